@@ -1,20 +1,51 @@
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
-use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Margin},
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Margin, Rect},
     style::{Color, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap, Clear, Table, Row, Cell, TableState},
+    symbols,
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph, Sparkline, Wrap, Clear, Table, Row, Cell, TableState},
     Frame,
 };
 use anyhow::Result;
 use unicode_width::UnicodeWidthStr;
 
-use crate::utils::errors::AppError;
-use crate::services::system_service::SystemService;
-use crate::services::fail2ban_client::Fail2banClient;
-use crate::services::file_monitor::LogMonitor;
+use crate::utils::errors::{AppError, ServiceError};
+use crate::utils::config_writer::{write_config_file, copy_config_file};
+use crate::utils::paths::fail2ban_paths;
+use crate::utils::privileges::Capabilities;
+use crate::services::system_service::{job_failure, SystemService};
+use crate::services::service_backend::{JobState, ServiceJob, UnitState};
+use crate::services::health::{DaemonInfo, HealthLevel, ServiceHealth, RECENT_ERROR_LIMIT};
+use crate::services::event_history::{from_log_clock, log_clock_now, next_window, window_label, EventHistory, EventKind, MAX_BAN_RECORDS, MAX_BUCKETS, STATS_WINDOWS};
+use crate::services::fail2ban_client::{client_command, Fail2banClient};
+use crate::services::audit_log::{export_csv, AuditAction, AuditEntry, AuditLog};
+use crate::services::config_validator::{ConfigValidator, IssueSeverity, ValidationIssue};
+use crate::services::definition_tree::{create_local_override, scan_definitions, Definition, DEFINITION_DIRS};
+use crate::services::file_monitor::{ban_event, BanAction, BanEvent, LogMonitor, ConfigFileWatcher};
+use crate::services::jail_templates::{JailDestination, NewJail};
+use crate::services::ban_set::{BanReconciler, BanSet};
+use crate::services::ban_query::{complete, common_prefix, replace_last_word, BanQuery, CompletionSource, QueryPresets};
+use crate::services::geoip::{GeoInfo, GeoIp};
+use crate::services::notifications::Notifier;
+use crate::services::reverse_dns::{Hostname, ReverseDns};
+use crate::services::statistics::{export_table, network_prefix, BanStatistics, StatsTable, TableData};
+use crate::services::subnets::{aggregate, next_prefix, plan_range_ban, whitelist_overlaps, RangeBan, SubnetGroup,
+    DEFAULT_IPV4_PREFIX, DEFAULT_IPV6_PREFIX, IPV4_PREFIXES, IPV6_PREFIXES};
+use crate::services::table_sort::{sort_jails, BannedIpColumn, JailColumn, SortOrder};
+use crate::utils::ini_syntax::{classify_line, IniToken};
+use crate::utils::text_buffer::{TextBuffer, Position, SearchMode, SearchField};
+use crate::utils::text_diff::{unified_diff, diff_to_string, three_way_merge, has_conflict_markers, DiffLine, DiffLineKind};
+
+/// Lines moved by PageUp/PageDown and kept in view by the editors
+const EDITOR_PAGE_LINES: usize = 20;
+// Give up on a service job that has not finished by then
+const SERVICE_JOB_TIMEOUT: Duration = Duration::from_secs(90);
+// Ban/Unban lines from the log keep the banned IP list current between full reloads
+const LOG_TAIL_INTERVAL: Duration = Duration::from_secs(2);
+const BAN_RECONCILE_INTERVAL: Duration = Duration::from_secs(120);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Screen {
@@ -26,6 +57,10 @@ pub enum Screen {
     Help,
     About,
     JailEditor,
+    Audit,
+    Health,
+    Statistics,
+    Subnets,
 }
 
 impl Screen {
@@ -39,6 +74,10 @@ impl Screen {
             Screen::Help => "Help",
             Screen::About => "About",
             Screen::JailEditor => "Jail Editor",
+            Screen::Audit => "Audit",
+            Screen::Health => "Health",
+            Screen::Statistics => "Statistics",
+            Screen::Subnets => "Subnets",
         }
     }
 }
@@ -50,6 +89,20 @@ pub enum DashboardFocus {
     BannedIPs,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ConfigFocus {
+    #[default]
+    Files,
+    Definitions,  // filter.d / action.d / jail.d browser
+}
+
+/// A visible row of the definition browser
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DefinitionRow {
+    Dir(&'static str),
+    Definition(usize),  // Index into ConfigManagementState::definitions
+}
+
 #[derive(Debug, Clone)]
 pub enum ServiceStatus {
     Running,
@@ -78,12 +131,22 @@ impl ServiceStatus {
     }
 }
 
+fn health_color(level: HealthLevel) -> Color {
+    match level {
+        HealthLevel::Healthy => Color::Green,
+        HealthLevel::Degraded => Color::Yellow,
+        HealthLevel::Critical => Color::Red,
+        HealthLevel::Stopped | HealthLevel::Unknown => Color::Gray,
+    }
+}
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)] // Fields will be used in jail management features (Epic 1)
 pub struct JailState {
     pub name: String,
     pub enabled: bool,
     pub banned_count: usize,
+    pub failed_count: usize,
     pub filter: String,
     pub action: String,
 }
@@ -127,6 +190,17 @@ pub enum ServiceAction {
     Reload,
 }
 
+impl ServiceAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ServiceAction::Start => "Starting",
+            ServiceAction::Stop => "Stopping",
+            ServiceAction::Restart => "Restarting",
+            ServiceAction::Reload => "Reloading",
+        }
+    }
+}
+
 #[derive(Debug)]
 #[allow(dead_code)] // Message variants needed for TEA pattern and planned features
 pub enum AppMessage {
@@ -147,10 +221,19 @@ pub enum AppMessage {
     PerformJailToggle(String, bool),  // jail_name, new_enabled
     OpenJailEditor(String),          // jail_name
     CloseJailEditor,
-    UpdateJailEditorContent(String), // new_content
     SaveJailConfiguration,
     JailConfigSaved(bool),          // success
     
+    // Save review (diff preview) for both editors
+    ReviewChanges,
+    ConfirmSave,
+    DiscardChanges,
+    CloseSaveReview,
+    
+    // External edits to the file open in an editor
+    ResolveExternalChange(ExternalChangeAction),
+    CloseExternalChangeDialog,
+    
     // IP management
     BanIP(String, Option<String>),
     UnbanIP(String),
@@ -181,6 +264,15 @@ pub enum AppMessage {
     BackupConfiguration,
     RestoreConfiguration,
     TestConfiguration,
+    EditExternally,               // $VISUAL/$EDITOR on the open or selected file
+    OpenNewJailWizard,
+    CloseNewJailWizard,
+    CreateNewJail,
+    
+    // Subnet aggregation
+    OpenRangeBanDialog,
+    CloseRangeBanDialog,
+    ConfirmRangeBan,
     
     // Monitoring
     LogUpdate(LogEntry),
@@ -191,6 +283,25 @@ pub enum AppMessage {
     Quit,
 }
 
+impl AppMessage {
+    /// What the message would change, if it is refused in read-only mode
+    fn privileged_action(&self) -> Option<&'static str> {
+        match self {
+            AppMessage::BanIP(..) | AppMessage::OpenBanDialog | AppMessage::ConfirmBan
+            | AppMessage::OpenRangeBanDialog | AppMessage::ConfirmRangeBan => Some("banning"),
+            AppMessage::UnbanIP(_) | AppMessage::OpenUnbanConfirmation(..) | AppMessage::ConfirmUnban => Some("unbanning"),
+            AppMessage::ServiceAction(_) => Some("service control"),
+            AppMessage::AddToWhitelist(_) | AppMessage::RemoveFromWhitelist(_) | AppMessage::OpenWhitelistDialog => Some("whitelist changes"),
+            AppMessage::ToggleJailEnabled(_) | AppMessage::SetJailEnabled(..) | AppMessage::PerformJailToggle(..) => Some("enabling jails"),
+            AppMessage::OpenJailEditor(_) | AppMessage::SaveJailConfiguration | AppMessage::OpenConfigEditor(_)
+            | AppMessage::SaveConfigFile | AppMessage::ReviewChanges | AppMessage::ConfirmSave
+            | AppMessage::EditExternally | AppMessage::OpenNewJailWizard | AppMessage::CreateNewJail => Some("editing"),
+            AppMessage::BackupConfiguration | AppMessage::RestoreConfiguration => Some("backup and restore"),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct IpManagementState {
     pub ban_dialog_open: bool,
@@ -229,20 +340,26 @@ pub struct AppState {
     pub fail2ban_service: ServiceStatus,
     pub jails: HashMap<String, JailState>,
     pub available_jails: Vec<JailConfig>,
-    pub banned_ips: Vec<BannedIP>,
+    pub banned_ips: BanSet,
     pub log_entries: Vec<LogEntry>,
     // Removed unused error_state field
     pub last_update: Instant,
     pub status_message: Option<(String, chrono::DateTime<chrono::Utc>)>,
     pub service_message: Option<String>,
     pub last_service_action: Option<(String, chrono::DateTime<chrono::Local>)>, // Action name and timestamp
+    pub service_unit: Option<UnitState>,  // The service manager's view of fail2ban, when reachable
     // Log filtering state
     pub log_filter: LogFilter,
     // Banned IP filtering state
     pub banned_ip_filter: BannedIpFilter,
+    pub filter_prompt: Option<FilterPrompt>,
+    // Column order of the dashboard tables, kept across refreshes
+    pub banned_ip_sort: SortOrder<BannedIpColumn>,
+    pub jail_sort: SortOrder<JailColumn>,
+    pub sort_headers: Vec<(Rect, SortHeader)>,  // Clickable header cells from the last draw
     // Pagination for banned IPs
     pub banned_ip_pagination: BannedIpPagination,
-    // Performance optimization - track last full IP refresh
+    // Last full reload of the banned IPs; None asks for one right away
     pub last_ip_full_refresh: Option<Instant>,
     // Cached filtered IPs to avoid re-filtering 18k items on every render
     pub cached_filtered_ips: Vec<BannedIP>,
@@ -276,6 +393,38 @@ pub struct AppState {
     pub jail_editor: JailEditorState,
     // Configuration management state
     pub config_management: ConfigManagementState,
+    // Pending external change to the file open in an editor
+    pub external_change: Option<ExternalChangeState>,
+    // Diff preview shown before an editor saves
+    pub save_review: Option<SaveReviewState>,
+    // Cut/copy buffer shared by both editors
+    pub editor_clipboard: String,
+    // New jail wizard (Configuration screen)
+    pub new_jail_wizard: Option<NewJailWizardState>,
+    // What the current user may do (read-only observer mode when not root)
+    pub capabilities: Capabilities,
+    // Audit screen (newest entry first)
+    pub audit_entries: Vec<AuditEntry>,
+    pub audit_selected_index: usize,
+    pub audit_table_state: TableState,
+    pub audit_error: Option<String>,
+    // Service manager state, socket ping, daemon settings and recent errors
+    pub health: ServiceHealth,
+    // Ban/Unban/Found counts from the log, charted on the dashboard and Statistics screen
+    pub event_history: EventHistory,
+    pub stats_window_hours: u32,
+    // Offender tables on the Statistics screen
+    pub stats_table: StatsTable,
+    pub stats_selected_index: usize,
+    pub stats_table_state: TableState,
+    // Address shown in the IP details dialog
+    pub ip_detail: Option<String>,
+    // Subnet aggregation screen
+    pub subnet_ipv4_prefix: u8,
+    pub subnet_ipv6_prefix: u8,
+    pub subnet_selected_index: usize,
+    pub subnet_table_state: TableState,
+    pub range_ban_dialog: Option<RangeBanDialogState>,
 }
 
 #[derive(Debug, Clone)]
@@ -283,11 +432,112 @@ pub struct JailEditorState {
     pub is_open: bool,
     pub jail_name: String,
     pub original_content: String,
-    pub current_content: String,
+    pub buffer: TextBuffer,
     pub backup_path: Option<String>,
-    pub cursor_position: usize,
-    pub scroll_offset: usize,
     pub modified: bool,
+    pub file_snapshot: String, // Whole jail.local as it was when the editor opened
+    pub lint_issues: Vec<ValidationIssue>,
+}
+
+/// Diff shown for confirmation before an editor writes its file
+#[derive(Debug, Clone)]
+pub struct SaveReviewState {
+    pub path: String,
+    pub diff: Vec<DiffLine>,
+    pub scroll_offset: usize,
+}
+
+/// Range ban about to replace the host bans of one subnet
+#[derive(Debug, Clone)]
+pub struct RangeBanDialogState {
+    pub group: SubnetGroup,
+    pub jails: Vec<String>,  // Jails banning hosts in the range first
+    pub jail_index: usize,
+}
+
+/// New jail being set up from a filter template
+#[derive(Debug, Clone)]
+pub struct NewJailWizardState {
+    pub filters: Vec<String>,  // Available in filter.d
+    pub filter_index: usize,
+    pub jail: NewJail,
+    pub field: NewJailField,
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NewJailField {
+    Filter,
+    Name,
+    Port,
+    LogPath,
+    Backend,
+    MaxRetry,
+    BanTime,
+    Destination,
+}
+
+impl NewJailField {
+    const ALL: [NewJailField; 8] = [
+        NewJailField::Filter,
+        NewJailField::Name,
+        NewJailField::Port,
+        NewJailField::LogPath,
+        NewJailField::Backend,
+        NewJailField::MaxRetry,
+        NewJailField::BanTime,
+        NewJailField::Destination,
+    ];
+    
+    fn label(self) -> &'static str {
+        match self {
+            NewJailField::Filter => "Filter",
+            NewJailField::Name => "Jail name",
+            NewJailField::Port => "Port",
+            NewJailField::LogPath => "Log path",
+            NewJailField::Backend => "Backend",
+            NewJailField::MaxRetry => "Max retry",
+            NewJailField::BanTime => "Ban time",
+            NewJailField::Destination => "Write to",
+        }
+    }
+    
+    /// Typed in rather than picked from a list
+    fn is_text(self) -> bool {
+        !matches!(self, NewJailField::Filter | NewJailField::Backend | NewJailField::Destination)
+    }
+    
+    fn step(self, delta: isize) -> Self {
+        let index = Self::ALL.iter().position(|&f| f == self).unwrap_or(0) as isize;
+        Self::ALL[(index + delta).rem_euclid(Self::ALL.len() as isize) as usize]
+    }
+}
+
+/// Backends offered by the wizard (cycled with ←/→)
+const JAIL_BACKENDS: [&str; 4] = ["auto", "systemd", "pyinotify", "polling"];
+
+/// Editor content waiting to be handed to $VISUAL/$EDITOR by the main loop,
+/// which owns the terminal
+#[derive(Debug, Clone)]
+pub struct ExternalEditRequest {
+    pub content: String,
+    pub file_name: String,
+    close_if_unchanged: bool, // Started from the Configuration screen rather than an open editor
+}
+
+/// The file open in an editor was changed on disk by someone else
+#[derive(Debug, Clone)]
+pub struct ExternalChangeState {
+    pub path: String,
+    pub disk_content: String,
+    pub dialog_open: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalChangeAction {
+    Merge,      // Three-way merge disk changes into the editor
+    Overwrite,  // Save the editor content anyway
+    LoadDisk,   // Discard editor changes and load the disk version
 }
 
 #[derive(Debug, Clone)]
@@ -318,10 +568,6 @@ impl LoadingModalState {
         }
     }
     
-    pub fn update_message(&mut self, message: String) {
-        self.message = message;
-    }
-    
     pub fn update_animated_dots(&mut self) {
         let elapsed = self.started_at.elapsed().as_millis();
         let dot_count = ((elapsed / 500) % 4) as usize; // Change every 500ms, cycle through 0-3 dots
@@ -336,13 +582,19 @@ pub struct ConfigManagementState {
     pub table_state: TableState,
     pub editor_open: bool,
     pub current_file_path: String,
-    pub current_file_content: String,
+    pub buffer: TextBuffer,
     pub original_content: String,
-    pub cursor_position: usize,
-    pub scroll_offset: usize,
     pub modified: bool,
-    pub cursor_blink_timer: std::time::Instant,
-    pub cursor_visible: bool,
+    pub lint_issues: Vec<ValidationIssue>, // Inline checks for the open file
+    // Results of the last dry-run validation
+    pub validation_issues: Vec<ValidationIssue>,
+    pub last_validated: Option<chrono::DateTime<chrono::Local>>,
+    // Definition browser
+    pub focus: ConfigFocus,
+    pub definitions: Vec<Definition>,
+    pub expanded_dirs: Vec<&'static str>,
+    pub definition_index: usize,
+    pub definition_table_state: TableState,
 }
 
 impl Default for JailEditorState {
@@ -351,42 +603,32 @@ impl Default for JailEditorState {
             is_open: false,
             jail_name: String::new(),
             original_content: String::new(),
-            current_content: String::new(),
+            buffer: TextBuffer::default(),
             backup_path: None,
-            cursor_position: 0,
-            scroll_offset: 0,
             modified: false,
+            file_snapshot: String::new(),
+            lint_issues: Vec::new(),
         }
     }
 }
 
 impl Default for ConfigManagementState {
     fn default() -> Self {
-        let config_files = vec![
+        // Resolved against --fail2ban-root
+        let config_file = |name: &str, description: &str, editable: bool| {
+            let path = fail2ban_paths().config_file(name);
             ConfigFile {
-                path: "/etc/fail2ban/jail.local".to_string(),
-                description: "Local jail overrides (user modifications)".to_string(),
-                exists: std::path::Path::new("/etc/fail2ban/jail.local").exists(),
-                editable: true,
-            },
-            ConfigFile {
-                path: "/etc/fail2ban/jail.conf".to_string(),
-                description: "Main jail configuration (system default)".to_string(),
-                exists: std::path::Path::new("/etc/fail2ban/jail.conf").exists(),
-                editable: false, // Read-only system file
-            },
-            ConfigFile {
-                path: "/etc/fail2ban/fail2ban.local".to_string(),
-                description: "Local daemon configuration overrides".to_string(),
-                exists: std::path::Path::new("/etc/fail2ban/fail2ban.local").exists(),
-                editable: true,
-            },
-            ConfigFile {
-                path: "/etc/fail2ban/fail2ban.conf".to_string(),
-                description: "fail2ban daemon configuration".to_string(),
-                exists: std::path::Path::new("/etc/fail2ban/fail2ban.conf").exists(),
-                editable: false, // Read-only system file
-            },
+                path: path.display().to_string(),
+                description: description.to_string(),
+                exists: path.exists(),
+                editable,
+            }
+        };
+        let config_files = vec![
+            config_file("jail.local", "Local jail overrides (user modifications)", true),
+            config_file("jail.conf", "Main jail configuration (system default)", false), // Read-only system file
+            config_file("fail2ban.local", "Local daemon configuration overrides", true),
+            config_file("fail2ban.conf", "fail2ban daemon configuration", false), // Read-only system file
         ];
         
         Self {
@@ -395,13 +637,17 @@ impl Default for ConfigManagementState {
             table_state: TableState::default(),
             editor_open: false,
             current_file_path: String::new(),
-            current_file_content: String::new(),
+            buffer: TextBuffer::default(),
             original_content: String::new(),
-            cursor_position: 0,
-            scroll_offset: 0,
             modified: false,
-            cursor_blink_timer: std::time::Instant::now(),
-            cursor_visible: true,
+            lint_issues: Vec::new(),
+            validation_issues: Vec::new(),
+            last_validated: None,
+            focus: ConfigFocus::Files,
+            definitions: Vec::new(),
+            expanded_dirs: vec!["filter.d"],
+            definition_index: 0,
+            definition_table_state: TableState::default(),
         }
     }
 }
@@ -427,42 +673,33 @@ impl Default for LogFilter {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct BannedIpFilter {
-    pub ip_starting_digit: Option<char>, // Filter by first digit of IP (1-9)
-    pub jail: Option<String>,           // Filter by specific jail
-    pub ban_age_hours: Option<u32>,     // Filter by ban age (recent vs old)
-    pub remaining_time: Option<RemainingTimeFilter>, // Filter by remaining ban time
+    pub query: Option<BanQuery>, // Typed filter expression, e.g. "jail:sshd age:<2h"
     pub version: u64, // Version number to track filter changes for caching
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum RemainingTimeFilter {
-    Soon,      // Unbans within 1 hour
-    Today,     // Unbans within 24 hours
-    ThisWeek,  // Unbans within 1 week
-    Permanent, // Permanent bans (no unban time)
+impl BannedIpFilter {
+    fn has_active_filters(&self) -> bool {
+        self.query.is_some()
+    }
 }
 
-impl Default for BannedIpFilter {
-    fn default() -> Self {
-        Self {
-            ip_starting_digit: None,
-            jail: None,
-            ban_age_hours: None,
-            remaining_time: None,
-            version: 0,
-        }
-    }
+/// A sortable column header on the dashboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortHeader {
+    Jail(JailColumn),
+    BannedIp(BannedIpColumn),
 }
 
-impl BannedIpFilter {
-    fn has_active_filters(&self) -> bool {
-        self.ip_starting_digit.is_some() ||
-        self.jail.is_some() ||
-        self.ban_age_hours.is_some() ||
-        self.remaining_time.is_some()
-    }
+/// The '/' prompt for typing a banned IP query
+#[derive(Debug, Clone, Default)]
+pub struct FilterPrompt {
+    pub input: String,
+    pub error: Option<String>,
+    pub completions: Vec<String>,
+    pub preset_index: Option<usize>, // Preset shown while browsing with Up/Down
+    pub naming: Option<String>,      // Name being typed after Ctrl+S
 }
 
 #[derive(Debug, Clone)]
@@ -568,16 +805,17 @@ impl OperationType {
         }
     }
     
-    pub fn estimated_duration(&self) -> Duration {
+    /// None for service jobs - their progress comes from the service manager
+    pub fn estimated_duration(&self) -> Option<Duration> {
         match self {
-            OperationType::ServiceRestart => Duration::from_secs(5),
-            OperationType::ServiceStart => Duration::from_secs(3),
-            OperationType::ServiceStop => Duration::from_secs(2),
-            OperationType::ServiceReload => Duration::from_secs(2),
-            OperationType::LogRefresh => Duration::from_millis(500),
-            OperationType::DataRefresh => Duration::from_secs(1),
-            OperationType::IpBan => Duration::from_millis(300),
-            OperationType::IpUnban => Duration::from_millis(300),
+            OperationType::ServiceRestart
+            | OperationType::ServiceStart
+            | OperationType::ServiceStop
+            | OperationType::ServiceReload => None,
+            OperationType::LogRefresh => Some(Duration::from_millis(500)),
+            OperationType::DataRefresh => Some(Duration::from_secs(1)),
+            OperationType::IpBan => Some(Duration::from_millis(300)),
+            OperationType::IpUnban => Some(Duration::from_millis(300)),
         }
     }
 }
@@ -592,14 +830,19 @@ impl Default for AppState {
             fail2ban_service: ServiceStatus::Running,
             jails,
             available_jails: Vec::new(),
-            banned_ips: Vec::new(),
+            banned_ips: BanSet::new(),
             log_entries: Vec::with_capacity(1000),
             last_update: Instant::now(),
             status_message: None,
             service_message: None,
             last_service_action: None,
+            service_unit: None,
             log_filter: LogFilter::default(),
             banned_ip_filter: BannedIpFilter::default(),
+            filter_prompt: None,
+            banned_ip_sort: SortOrder::default(),
+            jail_sort: SortOrder::default(),
+            sort_headers: Vec::new(),
             banned_ip_pagination: BannedIpPagination::default(),
             last_ip_full_refresh: None,
             cached_filtered_ips: Vec::new(),
@@ -624,6 +867,27 @@ impl Default for AppState {
             error_dialog: None,
             jail_editor: JailEditorState::default(),
             config_management: ConfigManagementState::default(),
+            external_change: None,
+            save_review: None,
+            editor_clipboard: String::new(),
+            new_jail_wizard: None,
+            capabilities: Capabilities::full(),
+            audit_entries: Vec::new(),
+            audit_selected_index: 0,
+            audit_table_state: TableState::default(),
+            audit_error: None,
+            health: ServiceHealth::default(),
+            event_history: EventHistory::new(),
+            stats_window_hours: 24,
+            stats_table: StatsTable::TopIps,
+            stats_selected_index: 0,
+            stats_table_state: TableState::default(),
+            ip_detail: None,
+            subnet_ipv4_prefix: DEFAULT_IPV4_PREFIX,
+            subnet_ipv6_prefix: DEFAULT_IPV6_PREFIX,
+            subnet_selected_index: 0,
+            subnet_table_state: TableState::default(),
+            range_ban_dialog: None,
         }
     }
 }
@@ -645,10 +909,26 @@ pub struct App {
     // Staggered refresh timers to prevent blocking
     last_service_check: Instant,
     last_jail_refresh: Instant,
-    last_ip_refresh: Instant,
     last_log_refresh: Instant,
     // Performance monitoring
     performance_stats: PerformanceStats,
+    // Watches the file open in the jail or config editor
+    editor_watcher: Option<ConfigFileWatcher>,
+    // Picked up by the main loop, which suspends the TUI while $EDITOR runs
+    external_edit_request: Option<ExternalEditRequest>,
+    // Append-only trail of administrative actions
+    audit_log: AuditLog,
+    // Service job submitted to the init system, polled from the event loop
+    service_job: Option<ServiceJob>,
+    // Notification rules fed with every new log line
+    notifier: Notifier,
+    // Country/ASN of banned addresses from local databases, cached per address
+    geoip: GeoIp,
+    // PTR names resolved in the background for the banned IPs on screen
+    rdns: ReverseDns,
+    filter_presets: QueryPresets,
+    // Full banned IP reloads, run in the background
+    ban_reconciler: BanReconciler,
 }
 
 #[derive(Debug, Clone)]
@@ -695,9 +975,17 @@ impl App {
             // Staggered refresh intervals to prevent blocking
             last_service_check: Instant::now().checked_sub(Duration::from_secs(10)).unwrap_or(Instant::now()),
             last_jail_refresh: Instant::now().checked_sub(Duration::from_secs(10)).unwrap_or(Instant::now()),
-            last_ip_refresh: Instant::now().checked_sub(Duration::from_secs(20)).unwrap_or(Instant::now()),
             last_log_refresh: Instant::now().checked_sub(Duration::from_secs(10)).unwrap_or(Instant::now()),
             performance_stats: PerformanceStats::default(),
+            editor_watcher: None,
+            external_edit_request: None,
+            audit_log: AuditLog::default(),
+            service_job: None,
+            notifier: Notifier::default(),
+            geoip: GeoIp::default(),
+            rdns: ReverseDns::default(),
+            filter_presets: QueryPresets::default(),
+            ban_reconciler: BanReconciler::new(),
         };
         
         // Perform initial load - service status and jail data needed for interface
@@ -710,7 +998,7 @@ impl App {
         // Immediately start loading banned IPs if service is running and jails are available
         if matches!(app.state.fail2ban_service, ServiceStatus::Running) && !app.state.jails.is_empty() {
             log::info!("Service is running with {} jails, starting immediate banned IP loading", app.state.jails.len());
-            app.start_banned_ip_loading();
+            app.start_ban_reconcile(true);
         }
         
         // Initialize dashboard states since we start on the dashboard
@@ -752,6 +1040,10 @@ impl App {
             }
         }
         
+        if self.service_job.is_some() {
+            self.poll_service_job();
+        }
+        
         // Staggered refresh system to prevent blocking
         let mut any_refresh_needed = false;
         
@@ -794,39 +1086,17 @@ impl App {
             any_refresh_needed = true;
         }
         
-        // IP data refresh - even more conservative for large datasets
-        let ip_refresh_interval = if is_massive_dataset {
-            Duration::from_secs(120) // 2 minutes for massive datasets
-        } else if is_large_dataset {
-            Duration::from_secs(60)  // 1 minute for large datasets
-        } else {
-            Duration::from_secs(30)  // 30 seconds for normal datasets
-        };
-        
-        if self.last_ip_refresh.elapsed() >= ip_refresh_interval {
-            // Use two-phase loading: first set loading state, then load on next cycle
-            if !self.state.is_loading_banned_ips {
-                self.start_banned_ip_loading();
-            } else {
-                self.continue_banned_ip_loading();
-                self.last_ip_refresh = Instant::now();
-            }
-            any_refresh_needed = true;
+        // Banned IPs - a full reload in the background now and then, to catch what the log missed
+        if self.ban_reconciler.is_running() {
+            self.poll_ban_reconcile();
+        } else if self.state.last_ip_full_refresh.map_or(true, |last| last.elapsed() >= BAN_RECONCILE_INTERVAL) {
+            any_refresh_needed |= self.start_ban_reconcile(false);
         }
         
-        // Log entries - reduced frequency for large datasets
-        let log_refresh_interval = if is_large_dataset {
-            Duration::from_secs(30)  // 30 seconds for large datasets
-        } else {
-            Duration::from_secs(10)  // 10 seconds for normal datasets
-        };
-        
-        if self.last_log_refresh.elapsed() >= log_refresh_interval {
-            if !is_massive_dataset {  // Skip log refresh for massive datasets unless explicitly requested
-                self.refresh_log_data();
-            }
+        // Log entries - tailed often, since Ban and Unban lines update the banned IPs in place
+        if self.last_log_refresh.elapsed() >= LOG_TAIL_INTERVAL {
+            self.refresh_log_data();
             self.last_log_refresh = Instant::now();
-            any_refresh_needed = true;
         }
         
         // Update refresh display state
@@ -854,16 +1124,20 @@ impl App {
             modal.update_animated_dots();
         }
         
-        // Update cursor blinking for config editor
-        if self.state.config_management.editor_open {
-            if self.state.config_management.cursor_blink_timer.elapsed() >= Duration::from_millis(500) {
-                self.state.config_management.cursor_visible = !self.state.config_management.cursor_visible;
-                self.state.config_management.cursor_blink_timer = Instant::now();
-            }
+        // Pick up changes made to the open config file by other tools
+        self.check_external_edits();
+        
+        // Collect finished reverse DNS lookups
+        if self.rdns.is_enabled() {
+            self.rdns.poll();
         }
         
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
+            let event = event::read()?;
+            if let Event::Mouse(mouse) = event {
+                self.handle_mouse(mouse);
+            }
+            if let Event::Key(key) = event {
                 // Debug key presses when on Dashboard with BannedIPs focus
                 if self.state.current_screen == Screen::Dashboard && self.state.dashboard_focus == DashboardFocus::BannedIPs {
                     log::debug!("Key pressed: {:?}, Screen: {:?}, Focus: {:?}", key.code, self.state.current_screen, self.state.dashboard_focus);
@@ -879,142 +1153,84 @@ impl App {
                             self.set_status_message("⚠ NO BANNED IPs TO EXPORT");
                         }
                     },
-                    // JAIL EDITOR KEY HANDLING (HIGHEST PRIORITY - must come first)
-                    KeyCode::Esc if self.state.jail_editor.is_open => {
-                        self.handle_message(AppMessage::CloseJailEditor);
+                    // SAVE REVIEW (diff preview on top of either editor)
+                    KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') if self.state.save_review.is_some() => {
+                        self.handle_message(AppMessage::ConfirmSave);
                     },
-                    KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) && self.state.jail_editor.is_open => {
-                        self.handle_message(AppMessage::SaveJailConfiguration);
-                    },
-                    KeyCode::Enter if self.state.jail_editor.is_open => {
-                        let mut content = self.state.jail_editor.current_content.clone();
-                        content.insert(self.state.jail_editor.cursor_position, '\n');
-                        self.state.jail_editor.cursor_position += 1;
-                        self.state.jail_editor.modified = content != self.state.jail_editor.original_content;
-                        self.handle_message(AppMessage::UpdateJailEditorContent(content));
-                        self.update_editor_scroll();
-                    },
-                    KeyCode::Char(c) if self.state.jail_editor.is_open => {
-                        let mut content = self.state.jail_editor.current_content.clone();
-                        content.insert(self.state.jail_editor.cursor_position, c);
-                        self.state.jail_editor.cursor_position += 1;
-                        self.state.jail_editor.modified = content != self.state.jail_editor.original_content;
-                        self.handle_message(AppMessage::UpdateJailEditorContent(content));
-                        self.update_editor_scroll();
-                    },
-                    KeyCode::Backspace if self.state.jail_editor.is_open => {
-                        if self.state.jail_editor.cursor_position > 0 {
-                            let mut content = self.state.jail_editor.current_content.clone();
-                            content.remove(self.state.jail_editor.cursor_position - 1);
-                            self.state.jail_editor.cursor_position -= 1;
-                            self.state.jail_editor.modified = content != self.state.jail_editor.original_content;
-                            self.handle_message(AppMessage::UpdateJailEditorContent(content));
-                            self.update_editor_scroll();
-                        }
+                    KeyCode::Char('d') | KeyCode::Char('D') if self.state.save_review.is_some() => {
+                        self.handle_message(AppMessage::DiscardChanges);
                     },
-                    KeyCode::Left if self.state.jail_editor.is_open => {
-                        let new_pos = self.move_cursor_left();
-                        if new_pos != self.state.jail_editor.cursor_position {
-                            self.state.jail_editor.cursor_position = new_pos;
-                            self.update_editor_scroll();
-                        }
+                    KeyCode::Esc if self.state.save_review.is_some() => {
+                        self.handle_message(AppMessage::CloseSaveReview);
                     },
-                    KeyCode::Right if self.state.jail_editor.is_open => {
-                        let new_pos = self.move_cursor_right();
-                        if new_pos != self.state.jail_editor.cursor_position {
-                            self.state.jail_editor.cursor_position = new_pos;
-                            self.update_editor_scroll();
-                        }
+                    KeyCode::Up if self.state.save_review.is_some() => {
+                        self.scroll_save_review(-1);
                     },
-                    KeyCode::Up if self.state.jail_editor.is_open => {
-                        let new_pos = self.move_cursor_up();
-                        if new_pos != self.state.jail_editor.cursor_position {
-                            self.state.jail_editor.cursor_position = new_pos;
-                            self.update_editor_scroll();
-                        }
+                    KeyCode::Down if self.state.save_review.is_some() => {
+                        self.scroll_save_review(1);
                     },
-                    KeyCode::Down if self.state.jail_editor.is_open => {
-                        let new_pos = self.move_cursor_down();
-                        if new_pos != self.state.jail_editor.cursor_position {
-                            self.state.jail_editor.cursor_position = new_pos;
-                            self.update_editor_scroll();
-                        }
+                    KeyCode::PageUp if self.state.save_review.is_some() => {
+                        self.scroll_save_review(-10);
+                    },
+                    KeyCode::PageDown if self.state.save_review.is_some() => {
+                        self.scroll_save_review(10);
+                    },
+                    _ if self.state.save_review.is_some() => {
+                        // Review is modal - swallow other keys so they don't reach the editor
                     },
                     
-                    // CONFIG EDITOR KEY HANDLING (SECOND HIGHEST PRIORITY)
-                    KeyCode::Esc if self.state.config_management.editor_open => {
-                        self.handle_message(AppMessage::CloseConfigEditor);
+                    // EXTERNAL CHANGE DIALOG (on top of either editor)
+                    KeyCode::Char('m') | KeyCode::Char('M') if self.is_external_change_dialog_open() => {
+                        self.handle_message(AppMessage::ResolveExternalChange(ExternalChangeAction::Merge));
                     },
-                    KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) && self.state.config_management.editor_open => {
-                        self.handle_message(AppMessage::SaveConfigFile);
-                    },
-                    KeyCode::Enter if self.state.config_management.editor_open => {
-                        let mut content = self.state.config_management.current_file_content.clone();
-                        content.insert(self.state.config_management.cursor_position, '\n');
-                        self.state.config_management.cursor_position += 1;
-                        self.state.config_management.modified = content != self.state.config_management.original_content;
-                        self.state.config_management.current_file_content = content;
-                        self.state.config_management.cursor_visible = true;
-                        self.state.config_management.cursor_blink_timer = std::time::Instant::now();
-                        self.update_config_editor_scroll();
-                    },
-                    KeyCode::Char(c) if self.state.config_management.editor_open => {
-                        let mut content = self.state.config_management.current_file_content.clone();
-                        content.insert(self.state.config_management.cursor_position, c);
-                        self.state.config_management.cursor_position += 1;
-                        self.state.config_management.modified = content != self.state.config_management.original_content;
-                        self.state.config_management.current_file_content = content;
-                        self.state.config_management.cursor_visible = true;
-                        self.state.config_management.cursor_blink_timer = std::time::Instant::now();
-                        self.update_config_editor_scroll();
-                    },
-                    KeyCode::Backspace if self.state.config_management.editor_open => {
-                        if self.state.config_management.cursor_position > 0 {
-                            let mut content = self.state.config_management.current_file_content.clone();
-                            content.remove(self.state.config_management.cursor_position - 1);
-                            self.state.config_management.cursor_position -= 1;
-                            self.state.config_management.modified = content != self.state.config_management.original_content;
-                            self.state.config_management.current_file_content = content;
-                            self.state.config_management.cursor_visible = true;
-                            self.state.config_management.cursor_blink_timer = std::time::Instant::now();
-                            self.update_config_editor_scroll();
-                        }
+                    KeyCode::Char('o') | KeyCode::Char('O') if self.is_external_change_dialog_open() => {
+                        self.handle_message(AppMessage::ResolveExternalChange(ExternalChangeAction::Overwrite));
                     },
-                    KeyCode::Left if self.state.config_management.editor_open => {
-                        let new_pos = self.move_config_cursor_left();
-                        if new_pos != self.state.config_management.cursor_position {
-                            self.state.config_management.cursor_position = new_pos;
-                            self.state.config_management.cursor_visible = true;
-                            self.state.config_management.cursor_blink_timer = std::time::Instant::now();
-                            self.update_config_editor_scroll();
-                        }
+                    KeyCode::Char('l') | KeyCode::Char('L') if self.is_external_change_dialog_open() => {
+                        self.handle_message(AppMessage::ResolveExternalChange(ExternalChangeAction::LoadDisk));
                     },
-                    KeyCode::Right if self.state.config_management.editor_open => {
-                        let new_pos = self.move_config_cursor_right();
-                        if new_pos != self.state.config_management.cursor_position {
-                            self.state.config_management.cursor_position = new_pos;
-                            self.state.config_management.cursor_visible = true;
-                            self.state.config_management.cursor_blink_timer = std::time::Instant::now();
-                            self.update_config_editor_scroll();
-                        }
+                    KeyCode::Esc if self.is_external_change_dialog_open() => {
+                        self.handle_message(AppMessage::CloseExternalChangeDialog);
                     },
-                    KeyCode::Up if self.state.config_management.editor_open => {
-                        let new_pos = self.move_config_cursor_up();
-                        if new_pos != self.state.config_management.cursor_position {
-                            self.state.config_management.cursor_position = new_pos;
-                            self.state.config_management.cursor_visible = true;
-                            self.state.config_management.cursor_blink_timer = std::time::Instant::now();
-                            self.update_config_editor_scroll();
-                        }
+                    _ if self.is_external_change_dialog_open() => {
+                        // Dialog is modal - swallow other keys so they don't reach the editor
                     },
-                    KeyCode::Down if self.state.config_management.editor_open => {
-                        let new_pos = self.move_config_cursor_down();
-                        if new_pos != self.state.config_management.cursor_position {
-                            self.state.config_management.cursor_position = new_pos;
-                            self.state.config_management.cursor_visible = true;
-                            self.state.config_management.cursor_blink_timer = std::time::Instant::now();
-                            self.update_config_editor_scroll();
-                        }
+                    
+                    // NEW JAIL WIZARD (Configuration screen)
+                    KeyCode::Esc if self.state.new_jail_wizard.is_some() => {
+                        self.handle_message(AppMessage::CloseNewJailWizard);
+                    },
+                    KeyCode::Enter if self.state.new_jail_wizard.is_some() => {
+                        self.handle_message(AppMessage::CreateNewJail);
+                    },
+                    _ if self.state.new_jail_wizard.is_some() => {
+                        self.handle_new_jail_wizard_key(key);
+                    },
+                    
+                    // BANNED IP QUERY PROMPT (Dashboard) - modal text entry
+                    _ if self.state.filter_prompt.is_some() => {
+                        self.handle_filter_prompt_key(key);
+                    },
+                    
+                    // JAIL EDITOR KEY HANDLING (HIGHEST PRIORITY - must come first)
+                    KeyCode::Esc if self.state.jail_editor.is_open && !self.is_editor_search_open() => {
+                        self.handle_message(AppMessage::CloseJailEditor);
+                    },
+                    
+                    // CONFIG EDITOR KEY HANDLING (SECOND HIGHEST PRIORITY)
+                    KeyCode::Esc if self.state.config_management.editor_open && !self.is_editor_search_open() => {
+                        self.handle_message(AppMessage::CloseConfigEditor);
+                    },
+                    
+                    // Shared by both editors
+                    KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) && self.is_editor_open() => {
+                        self.handle_message(AppMessage::ReviewChanges);
+                    },
+                    KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) && self.is_editor_open() && !self.is_editor_search_open() => {
+                        self.handle_message(AppMessage::EditExternally);
+                    },
+                    _ if self.is_editor_open() => {
+                        self.handle_editor_key(key);
                     },
                     
                     // GLOBAL HOTKEYS (only when NO editor is open)
@@ -1024,6 +1240,26 @@ impl App {
                     KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) && !self.state.jail_editor.is_open && !self.state.config_management.editor_open => {
                         self.should_quit = true;
                     },
+                    // Range ban dialog takes all other keys while open
+                    KeyCode::Esc if self.state.range_ban_dialog.is_some() => {
+                        self.handle_message(AppMessage::CloseRangeBanDialog);
+                    },
+                    KeyCode::Enter if self.state.range_ban_dialog.is_some() => {
+                        self.handle_message(AppMessage::ConfirmRangeBan);
+                    },
+                    KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down if self.state.range_ban_dialog.is_some() => {
+                        if let Some(ref mut dialog) = self.state.range_ban_dialog {
+                            let count = dialog.jails.len().max(1);
+                            let step = if matches!(key.code, KeyCode::Left | KeyCode::Up) { count - 1 } else { 1 };
+                            dialog.jail_index = (dialog.jail_index + step) % count;
+                        }
+                    },
+                    _ if self.state.range_ban_dialog.is_some() => {},
+                    // IP details dialog takes all other keys while open
+                    KeyCode::Esc | KeyCode::Enter if self.state.ip_detail.is_some() => {
+                        self.state.ip_detail = None;
+                    },
+                    _ if self.state.ip_detail.is_some() => {},
                     // Global IP ban dialog - 'B' key (accessible from anywhere except config screen and any editor)
                     KeyCode::Char('B') | KeyCode::Char('b') if !key.modifiers.contains(KeyModifiers::CONTROL) && !self.state.ip_management.ban_dialog_open && self.state.current_screen != Screen::Configuration && !self.state.jail_editor.is_open && !self.state.config_management.editor_open => {
                         self.handle_message(AppMessage::OpenBanDialog);
@@ -1045,9 +1281,6 @@ impl App {
                     KeyCode::Esc if self.state.jail_editor.is_open => {
                         self.handle_message(AppMessage::CloseJailEditor);
                     },
-                    KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) && self.state.jail_editor.is_open => {
-                        self.handle_message(AppMessage::SaveJailConfiguration);
-                    },
                     KeyCode::Esc if !self.state.jail_editor.is_open && !self.state.config_management.editor_open => {
                         // ESC returns to dashboard (disabled when any editor is open)
                         self.state.current_screen = Screen::Dashboard;
@@ -1062,6 +1295,11 @@ impl App {
                                 self.load_recent_logs();
                                 self.complete_operation(true, Some("✓ Logs refreshed".to_string()));
                             },
+                            Screen::Audit => self.load_audit_entries(),
+                            Screen::Health => {
+                                self.refresh_health(true);
+                                self.set_status_message(&format!("✓ Health re-checked: {}", self.state.health.headline()));
+                            },
                             _ => {
                                 // R key not applicable for this screen
                             }
@@ -1094,6 +1332,17 @@ impl App {
                         self.state.help_scroll_offset = 0;
                     },
                     
+                    // AUDIT SCREEN
+                    KeyCode::Up if self.state.current_screen == Screen::Audit => {
+                        self.select_audit_entry(self.state.audit_selected_index.saturating_sub(1));
+                    },
+                    KeyCode::Down if self.state.current_screen == Screen::Audit => {
+                        self.select_audit_entry(self.state.audit_selected_index + 1);
+                    },
+                    KeyCode::Char('x') | KeyCode::Char('X') if self.state.current_screen == Screen::Audit => {
+                        self.export_audit_log();
+                    },
+                    
                     // Single-key navigation (only when not conflicting and not in any editor or ban dialog)
                     KeyCode::Char('h') if !key.modifiers.contains(KeyModifiers::CONTROL) && !self.state.jail_editor.is_open && !self.state.config_management.editor_open && !self.state.ip_management.ban_dialog_open => {
                         self.state.current_screen = Screen::Help;
@@ -1120,6 +1369,22 @@ impl App {
                     KeyCode::Char('i') if !key.modifiers.contains(KeyModifiers::CONTROL) && !self.state.jail_editor.is_open && !self.state.config_management.editor_open && !self.state.ip_management.ban_dialog_open => {
                         self.state.current_screen = Screen::About;
                     },
+                    KeyCode::Char('k') if !key.modifiers.contains(KeyModifiers::CONTROL) && !self.state.jail_editor.is_open && !self.state.config_management.editor_open && !self.state.ip_management.ban_dialog_open => {
+                        self.state.current_screen = Screen::Audit;
+                        self.load_audit_entries();
+                    },
+                    KeyCode::Char('p') if !key.modifiers.contains(KeyModifiers::CONTROL) && !self.state.jail_editor.is_open && !self.state.config_management.editor_open && !self.state.ip_management.ban_dialog_open => {
+                        self.state.current_screen = Screen::Health;
+                        self.refresh_health(true);
+                    },
+                    KeyCode::Char('m') if !key.modifiers.contains(KeyModifiers::CONTROL) && !self.state.jail_editor.is_open && !self.state.config_management.editor_open && !self.state.ip_management.ban_dialog_open => {
+                        self.state.current_screen = Screen::Statistics;
+                    },
+                    // 'a' adds an entry on the Whitelist screen
+                    KeyCode::Char('a') if !key.modifiers.contains(KeyModifiers::CONTROL) && !self.state.jail_editor.is_open && !self.state.config_management.editor_open && !self.state.ip_management.ban_dialog_open && !self.state.ip_management.whitelist_dialog_open && self.state.current_screen != Screen::Whitelist => {
+                        self.state.current_screen = Screen::Subnets;
+                        self.load_whitelist_if_needed();
+                    },
                     KeyCode::Char('0') if self.state.current_screen == Screen::Logs && !self.state.jail_editor.is_open => {
                         self.clear_log_filters();
                     },
@@ -1128,17 +1393,16 @@ impl App {
                     KeyCode::Char('0') if self.state.current_screen == Screen::Dashboard && self.state.dashboard_focus == DashboardFocus::BannedIPs && !self.state.jail_editor.is_open => {
                         self.clear_banned_ip_filters();
                     },
-                    KeyCode::Char('1') if self.state.current_screen == Screen::Dashboard && self.state.dashboard_focus == DashboardFocus::BannedIPs && !self.state.jail_editor.is_open => {
-                        self.cycle_ip_digit_filter();
+                    KeyCode::Char('/') if self.state.current_screen == Screen::Dashboard && self.state.dashboard_focus == DashboardFocus::BannedIPs && !self.state.jail_editor.is_open => {
+                        self.open_filter_prompt();
                     },
-                    KeyCode::Char('2') if self.state.current_screen == Screen::Dashboard && self.state.dashboard_focus == DashboardFocus::BannedIPs && !self.state.jail_editor.is_open => {
-                        self.cycle_jail_filter();
-                    },
-                    KeyCode::Char('3') if self.state.current_screen == Screen::Dashboard && self.state.dashboard_focus == DashboardFocus::BannedIPs && !self.state.jail_editor.is_open => {
-                        self.cycle_ban_age_filter();
+                    
+                    // Table sorting (Dashboard, focused table) - headers can be clicked too
+                    KeyCode::Char('o') if self.state.current_screen == Screen::Dashboard && !self.state.jail_editor.is_open => {
+                        self.cycle_sort_column();
                     },
-                    KeyCode::Char('4') if self.state.current_screen == Screen::Dashboard && self.state.dashboard_focus == DashboardFocus::BannedIPs && !self.state.jail_editor.is_open => {
-                        self.cycle_remaining_time_filter();
+                    KeyCode::Char('O') if self.state.current_screen == Screen::Dashboard && !self.state.jail_editor.is_open => {
+                        self.reverse_sort();
                     },
                     
                     KeyCode::Char('f') | KeyCode::Char('F') if !key.modifiers.contains(KeyModifiers::CONTROL) && !self.state.jail_editor.is_open && !self.state.ip_management.ban_dialog_open => {
@@ -1147,6 +1411,7 @@ impl App {
                         self.update_operation_progress(25, Some("Refreshing service status...".to_string()));
                         // Use staggered refresh system instead of full refresh
                         self.refresh_service_status();
+                        self.state.last_ip_full_refresh = None;  // Banned IPs reload in the background
                         self.complete_operation(true, Some("✓ Data refreshed".to_string()));
                         
                         // Record refresh timestamp for service status display
@@ -1268,19 +1533,37 @@ impl App {
                         self.state.ip_management.whitelist_ip_input.pop();
                     },
                     // Configuration page navigation
-                    KeyCode::Up if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open => {
+                    KeyCode::Tab if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open => {
+                        self.state.config_management.focus = match self.state.config_management.focus {
+                            ConfigFocus::Files => ConfigFocus::Definitions,
+                            ConfigFocus::Definitions => ConfigFocus::Files,
+                        };
+                    },
+                    KeyCode::Up if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open && self.state.config_management.focus == ConfigFocus::Definitions => {
+                        self.select_definition_row(self.state.config_management.definition_index.saturating_sub(1));
+                    },
+                    KeyCode::Down if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open && self.state.config_management.focus == ConfigFocus::Definitions => {
+                        self.select_definition_row(self.state.config_management.definition_index + 1);
+                    },
+                    KeyCode::Enter | KeyCode::Right | KeyCode::Char('e') | KeyCode::Char('E') if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open && self.state.config_management.focus == ConfigFocus::Definitions => {
+                        self.activate_definition_row();
+                    },
+                    KeyCode::Left if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open && self.state.config_management.focus == ConfigFocus::Definitions => {
+                        self.collapse_definition_dir();
+                    },
+                    KeyCode::Up if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open && self.state.config_management.focus == ConfigFocus::Files => {
                         if self.state.config_management.selected_file_index > 0 {
                             self.state.config_management.selected_file_index -= 1;
                             self.state.config_management.table_state.select(Some(self.state.config_management.selected_file_index));
                         }
                     },
-                    KeyCode::Down if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open => {
+                    KeyCode::Down if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open && self.state.config_management.focus == ConfigFocus::Files => {
                         if self.state.config_management.selected_file_index < self.state.config_management.config_files.len().saturating_sub(1) {
                             self.state.config_management.selected_file_index += 1;
                             self.state.config_management.table_state.select(Some(self.state.config_management.selected_file_index));
                         }
                     },
-                    KeyCode::Enter if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open => {
+                    KeyCode::Enter if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open && self.state.config_management.focus == ConfigFocus::Files => {
                         let selected_file = &self.state.config_management.config_files[self.state.config_management.selected_file_index];
                         if selected_file.exists && selected_file.editable {
                             self.handle_message(AppMessage::OpenConfigEditor(selected_file.path.clone()));
@@ -1290,7 +1573,7 @@ impl App {
                             self.set_status_message(&format!("⚠ File is not editable: {}", selected_file.path));
                         }
                     },
-                    KeyCode::Char('e') | KeyCode::Char('E') if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open => {
+                    KeyCode::Char('e') | KeyCode::Char('E') if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open && self.state.config_management.focus == ConfigFocus::Files => {
                         let selected_file = &self.state.config_management.config_files[self.state.config_management.selected_file_index];
                         if selected_file.exists && selected_file.editable {
                             self.handle_message(AppMessage::OpenConfigEditor(selected_file.path.clone()));
@@ -1309,6 +1592,59 @@ impl App {
                     KeyCode::Char('t') | KeyCode::Char('T') if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open => {
                         self.handle_message(AppMessage::TestConfiguration);
                     },
+                    KeyCode::Char('v') | KeyCode::Char('V') if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open => {
+                        self.handle_message(AppMessage::EditExternally);
+                    },
+                    KeyCode::Char('n') | KeyCode::Char('N') if self.state.current_screen == Screen::Configuration && !self.state.config_management.editor_open => {
+                        self.handle_message(AppMessage::OpenNewJailWizard);
+                    },
+                    
+                    // STATISTICS SCREEN (after the dialogs, which take typed keys first)
+                    KeyCode::Char('2') if self.state.current_screen == Screen::Statistics => {
+                        self.state.stats_window_hours = next_window(self.state.stats_window_hours);
+                        self.set_status_message(&format!("✓ Showing last {}", window_label(self.state.stats_window_hours)));
+                    },
+                    KeyCode::Tab if self.state.current_screen == Screen::Statistics => {
+                        self.state.stats_table = self.state.stats_table.next();
+                        self.select_stats_row(0);
+                    },
+                    KeyCode::Up if self.state.current_screen == Screen::Statistics => {
+                        self.select_stats_row(self.state.stats_selected_index.saturating_sub(1));
+                    },
+                    KeyCode::Down if self.state.current_screen == Screen::Statistics => {
+                        self.select_stats_row(self.state.stats_selected_index + 1);
+                    },
+                    KeyCode::Enter if self.state.current_screen == Screen::Statistics => {
+                        let table = self.statistics_table();
+                        match table.ips.get(self.state.stats_selected_index) {
+                            Some(Some(ip)) => self.state.ip_detail = Some(ip.clone()),
+                            _ => self.set_status_message(&format!("⚠ No IP details for {} rows", self.state.stats_table.title())),
+                        }
+                    },
+                    KeyCode::Char('x') | KeyCode::Char('X') if self.state.current_screen == Screen::Statistics => {
+                        self.export_statistics_table();
+                    },
+                    
+                    // SUBNETS SCREEN
+                    KeyCode::Char('4') if self.state.current_screen == Screen::Subnets => {
+                        self.state.subnet_ipv4_prefix = next_prefix(&IPV4_PREFIXES, self.state.subnet_ipv4_prefix);
+                        self.select_subnet(0);
+                        self.set_status_message(&format!("✓ Grouping IPv4 by /{}", self.state.subnet_ipv4_prefix));
+                    },
+                    KeyCode::Char('6') if self.state.current_screen == Screen::Subnets => {
+                        self.state.subnet_ipv6_prefix = next_prefix(&IPV6_PREFIXES, self.state.subnet_ipv6_prefix);
+                        self.select_subnet(0);
+                        self.set_status_message(&format!("✓ Grouping IPv6 by /{}", self.state.subnet_ipv6_prefix));
+                    },
+                    KeyCode::Up if self.state.current_screen == Screen::Subnets => {
+                        self.select_subnet(self.state.subnet_selected_index.saturating_sub(1));
+                    },
+                    KeyCode::Down if self.state.current_screen == Screen::Subnets => {
+                        self.select_subnet(self.state.subnet_selected_index + 1);
+                    },
+                    KeyCode::Enter if self.state.current_screen == Screen::Subnets => {
+                        self.handle_message(AppMessage::OpenRangeBanDialog);
+                    },
                     
                     // Dashboard focus navigation
                     KeyCode::Tab if self.state.current_screen == Screen::Dashboard => {
@@ -1328,7 +1664,7 @@ impl App {
                                 if self.state.banned_ips.is_empty() && matches!(self.state.fail2ban_service, ServiceStatus::Running) {
                                     log::info!("User switched to banned IPs view - triggering on-demand IP data load");
                                     if !self.state.is_loading_banned_ips {
-                                        self.start_banned_ip_loading();
+                                        self.start_ban_reconcile(true);
                                     }
                                 }
                             },
@@ -1424,9 +1760,11 @@ impl App {
                             self.handle_message(AppMessage::SetJailEnabled(jail_name, !current_enabled));
                         }
                     },
-                    // Dashboard screen-specific ENTER handling (dialogs are handled globally above)
-                    KeyCode::Enter if self.state.current_screen == Screen::Dashboard => {
-                        // Dashboard-specific ENTER actions go here if needed in the future
+                    // Dashboard IP details (dialogs are handled globally above)
+                    KeyCode::Enter if self.state.current_screen == Screen::Dashboard && self.state.dashboard_focus == DashboardFocus::BannedIPs => {
+                        if let Some(banned_ip) = self.selected_banned_ip() {
+                            self.state.ip_detail = Some(banned_ip.ip);
+                        }
                     },
                     // Dashboard jail editor
                     KeyCode::Char('E') | KeyCode::Char('e') if self.state.current_screen == Screen::Dashboard && self.state.dashboard_focus == DashboardFocus::Jails => {
@@ -1438,16 +1776,12 @@ impl App {
                     },
                     // Dashboard whitelist management
                     KeyCode::Char('W') | KeyCode::Char('w') if self.state.current_screen == Screen::Dashboard && self.state.dashboard_focus == DashboardFocus::BannedIPs => {
-                        self.state.ip_management.whitelist_dialog_open = true;
-                        self.state.ip_management.whitelist_ip_input.clear();
+                        self.handle_message(AppMessage::OpenWhitelistDialog);
                     },
                     // Dashboard IP unbanning
                     KeyCode::Char('U') | KeyCode::Char('u') if self.state.current_screen == Screen::Dashboard && self.state.dashboard_focus == DashboardFocus::BannedIPs => {
-                        if !self.state.banned_ips.is_empty() && self.state.dashboard_banned_ip_selected_index < self.state.banned_ips.len() {
-                            let banned_ip = &self.state.banned_ips[self.state.dashboard_banned_ip_selected_index];
-                            self.state.ip_management.unban_confirmation_open = true;
-                            self.state.ip_management.ip_to_unban = Some(banned_ip.ip.clone());
-                            self.state.ip_management.jail_for_unban = Some(banned_ip.jail.clone());
+                        if let Some(banned_ip) = self.selected_banned_ip() {
+                            self.handle_message(AppMessage::OpenUnbanConfirmation(banned_ip.ip, banned_ip.jail));
                         }
                     },
                     // Dashboard service actions
@@ -1483,19 +1817,47 @@ impl App {
     
     fn refresh_service_status(&mut self) {
         // Lightweight service status check
-        match self.system_service.get_status() {
-            Ok(status) => {
-                log::info!("Service status check result: {:?}", status);
-                self.state.fail2ban_service = status;
+        match self.system_service.unit_state() {
+            Ok(unit) => {
+                log::info!("Service status check result: {} ({})", unit.active_state, unit.sub_state);
+                self.state.fail2ban_service = unit.service_status();
+                self.state.service_unit = Some(unit);
             },
             Err(e) => {
                 log::error!("Failed to get service status: {}", e);
                 self.state.fail2ban_service = ServiceStatus::Unknown;
+                self.state.service_unit = None;
             }
         }
+        self.refresh_health(false);
         self.state.last_update = Instant::now();
     }
     
+    /// Ping the socket and collect recent log errors. Daemon settings are read
+    /// again when `full` or when the socket starts answering after a gap.
+    fn refresh_health(&mut self, full: bool) {
+        let was_responding = self.state.health.responding();
+        self.state.health.service = self.state.fail2ban_service.clone();
+        self.state.health.ping = self.state.capabilities.socket.then(|| {
+            self.fail2ban_client.ping().map_err(|e| match e {
+                AppError::Service(ServiceError::CommunicationError(reason)) => reason,
+                e => e.to_string(),
+            })
+        });
+        
+        if !self.state.health.responding() {
+            self.state.health.daemon = DaemonInfo::default();
+        } else if full || !was_responding {
+            self.state.health.daemon = DaemonInfo::query(&self.fail2ban_client);
+        }
+        
+        match self.log_monitor.recent_errors(RECENT_ERROR_LIMIT) {
+            Ok(errors) => self.state.health.recent_errors = errors,
+            Err(e) => log::warn!("Failed to read recent log errors: {}", e),
+        }
+        self.state.health.checked_at = Some(chrono::Local::now());
+    }
+    
     fn refresh_jail_data(&mut self) {
         log::info!("Refreshing jail data - service status: {:?}", self.state.fail2ban_service);
         // Only refresh jail data if service is running
@@ -1532,259 +1894,104 @@ impl App {
         }
     }
     
-    fn refresh_ip_data(&mut self) {
-        let banned_ip_count = self.state.banned_ips.len();
-        let is_massive_dataset = banned_ip_count > 15000;
-        let is_large_dataset = banned_ip_count > 10000;
-        
-        // Dramatically more conservative refresh logic for large datasets
-        let force_refresh_interval = if is_massive_dataset {
-            Duration::from_secs(300) // 5 minutes for massive datasets (18k+ IPs)
-        } else if is_large_dataset {
-            Duration::from_secs(120) // 2 minutes for large datasets (10k+ IPs)
-        } else {
-            Duration::from_secs(60)  // 1 minute for normal datasets
-        };
+    /// Start a full reload of every jail's bans in the background; the loading
+    /// modal covers it when there is nothing to show yet
+    fn start_ban_reconcile(&mut self, show_modal: bool) -> bool {
+        if !matches!(self.state.fail2ban_service, ServiceStatus::Running) || self.state.jails.is_empty() {
+            // Clear data if service is not running
+            if !self.state.banned_ips.is_empty() {
+                self.state.banned_ips.clear();
+                self.banned_ips_changed();
+            }
+            return false;
+        }
         
-        let force_refresh = self.state.last_ip_full_refresh.map_or(
-            self.state.banned_ips.is_empty(), // Only if empty on first load
-            |last| last.elapsed() > force_refresh_interval
-        );
+        let jail_names: Vec<String> = self.state.jails.keys().cloned().collect();
+        let total_jails = jail_names.len();
+        if !self.ban_reconciler.start(jail_names) {
+            return false;
+        }
+        self.state.banned_ips.begin_reconcile(chrono::Utc::now());
+        self.state.is_loading_banned_ips = true;
+        log::info!("Reloading banned IPs from {} jails in the background", total_jails);
         
-        let should_refresh = self.state.banned_ips.is_empty() || force_refresh;
+        if show_modal {
+            self.state.loading_modal = Some(LoadingModalState::new(
+                "🔄 Loading Banned IPs".to_string(),
+                format!("Loading banned IPs from {} jails\n\nPlease wait", total_jails),
+            ));
+        }
+        true
+    }
+    
+    /// Take a finished reload, with the Ban/Unban lines logged meanwhile replayed on top
+    fn poll_ban_reconcile(&mut self) {
+        let Some(result) = self.ban_reconciler.poll() else {
+            return;
+        };
+        let had_modal = self.state.loading_modal.take().is_some();
+        self.state.is_loading_banned_ips = false;
+        self.state.last_ip_full_refresh = Some(Instant::now());
         
-        if matches!(self.state.fail2ban_service, ServiceStatus::Running) && 
-           !self.state.jails.is_empty() && should_refresh {
-            
-            // Show user-visible loading message
-            if is_massive_dataset {
-                self.set_status_message("🔄 Loading banned IPs... (Large dataset detected, this may take 10-15 seconds)");
-                log::info!("Loading banned IP data for {} jails (PERFORMANCE MODE: 18k+ IPs detected)...", self.state.jails.len());
-            } else if is_large_dataset {
-                self.set_status_message("🔄 Loading banned IPs... (this may take several seconds)");
-                log::info!("Loading banned IP data for {} jails (PERFORMANCE MODE: 10k+ IPs detected)...", self.state.jails.len());
-            } else {
-                self.set_status_message("🔄 Loading banned IPs...");
-                log::info!("Loading banned IP data for {} jails...", self.state.jails.len());
-            }
-            let start_time = Instant::now();
-            
-            let mut all_banned_ips = Vec::new();
-            let mut total_processed = 0;
-            let total_jails = self.state.jails.len();
-            
-            // Collect jail names to avoid borrowing issues
-            let jail_names: Vec<String> = self.state.jails.keys().cloned().collect();
-            
-            for (index, jail_name) in jail_names.iter().enumerate() {
-                // Update progress for large datasets
-                if is_large_dataset && total_jails > 3 {
-                    let progress_percent = ((index + 1) * 100) / total_jails;
-                    self.set_status_message(&format!("🔄 Loading banned IPs... ({}/{} jails, {}%)", 
-                                                     index + 1, total_jails, progress_percent));
+        match result {
+            Ok(snapshot) => {
+                let took = snapshot.took;
+                if !snapshot.failed_jails.is_empty() {
+                    log::warn!("Banned IP reload could not read jails {}; keeping their rows", snapshot.failed_jails.join(", "));
                 }
-                
-                match self.fail2ban_client.get_banned_ips(jail_name) {
-                    Ok(mut ips) => {
-                        total_processed += ips.len();
-                        all_banned_ips.append(&mut ips);
-                        log::debug!("Loaded {} IPs from jail {} ({}/{})", 
-                                   ips.len(), jail_name, index + 1, self.state.jails.len());
-                    },
-                    Err(e) => {
-                        log::warn!("Failed to get banned IPs for jail {}: {}", jail_name, e);
-                    }
+                let changes = self.state.banned_ips.finish_reconcile(snapshot);
+                self.banned_ips_changed();
+                log::info!("Reloaded {} banned IPs in {:.2}s ({} added, {} removed that the log did not show)",
+                          self.state.banned_ips.len(), took.as_secs_f32(), changes.added, changes.removed);
+                if had_modal {
+                    self.set_status_message(&format!("✅ Loaded {} banned IPs from {} jails in {:.1}s",
+                                                    self.state.banned_ips.len(), self.state.jails.len(), took.as_secs_f32()));
                 }
-            }
-            
-            let load_duration = start_time.elapsed();
-            log::info!("Loaded {} total banned IPs from {} jails in {:.2}s", 
-                      total_processed, self.state.jails.len(), load_duration.as_secs_f32());
-            
-            // Show sorting progress for large datasets
-            if is_large_dataset {
-                self.set_status_message("🔄 Sorting banned IPs...");
-            }
-            
-            // Sort banned IPs by IP address first, then by jail name
-            all_banned_ips.sort_by(|a, b| {
-                a.ip.cmp(&b.ip).then_with(|| a.jail.cmp(&b.jail))
-            });
-            
-            self.state.banned_ips = all_banned_ips;
-            self.state.last_ip_full_refresh = Some(Instant::now());
-            
-            // Update pagination with total count
-            self.state.banned_ip_pagination.update_total_items(self.state.banned_ips.len());
-            
-            // Show completion message
-            self.set_status_message(&format!("✅ Loaded {} banned IPs from {} jails in {:.1}s", 
-                                            total_processed, total_jails, load_duration.as_secs_f32()));
-        } else if !matches!(self.state.fail2ban_service, ServiceStatus::Running) {
-            // Clear data if service is not running
-            self.state.banned_ips.clear();
-            self.state.banned_ip_pagination.update_total_items(0);
+            },
+            Err(e) => {
+                self.state.banned_ips.abandon_reconcile();
+                log::warn!("Banned IP reload failed: {}", e);
+                if had_modal {
+                    self.set_status_message(&format!("✗ Failed to load banned IPs: {}", e));
+                }
+            },
         }
     }
     
-    fn start_banned_ip_loading(&mut self) {
-        let banned_ip_count = self.state.banned_ips.len();
-        let is_massive_dataset = banned_ip_count > 15000;
-        let is_large_dataset = banned_ip_count > 10000;
-        
-        // Check if refresh is actually needed before showing modal
-        let force_refresh_interval = if is_massive_dataset {
-            Duration::from_secs(300) // 5 minutes for massive datasets (18k+ IPs)
-        } else if is_large_dataset {
-            Duration::from_secs(120) // 2 minutes for large datasets (10k+ IPs)
-        } else {
-            Duration::from_secs(60)  // 1 minute for normal datasets
-        };
+    /// Ban and Unban lines change the banned IPs in place, in the order they were logged
+    fn apply_ban_events(&mut self, entries: &[LogEntry]) {
+        let mut events = entries.iter()
+            .filter_map(|entry| ban_event(&entry.message).map(|event| (from_log_clock(entry.timestamp), event)))
+            .collect::<Vec<_>>();
+        events.sort_by_key(|(at, _)| *at);
         
-        let force_refresh = self.state.last_ip_full_refresh.map_or(
-            self.state.banned_ips.is_empty(), // Only if empty on first load
-            |last| last.elapsed() > force_refresh_interval
-        );
-        
-        let should_refresh = self.state.banned_ips.is_empty() || force_refresh;
-        
-        // Only show modal if we actually need to refresh
-        if !should_refresh {
-            log::debug!("Skipping banned IP refresh - not needed yet (last refresh: {:?})", 
-                       self.state.last_ip_full_refresh.map(|t| t.elapsed()));
-            return;
+        let mut changed = false;
+        for (at, event) in &events {
+            changed |= self.apply_ban_event(event, *at);
+        }
+        if changed {
+            self.banned_ips_changed();
         }
-        
-        // Phase 1: Show full-screen loading modal - UI will redraw before next event
-        self.state.is_loading_banned_ips = true;
-        
-        // Show full-screen modal that's impossible to miss
-        let (title, message) = if is_massive_dataset {
-            (
-                "🔄 Loading Banned IPs".to_string(),
-                format!("Large dataset detected ({} existing IPs)\n\nThis operation may take 10-15 seconds\nPlease wait while we load all banned IPs", banned_ip_count)
-            )
-        } else if is_large_dataset {
-            (
-                "🔄 Loading Banned IPs".to_string(),
-                format!("Loading {} banned IPs\n\nThis may take several seconds\nPlease wait", banned_ip_count)
-            )
-        } else {
-            (
-                "🔄 Loading Banned IPs".to_string(),
-                "Loading banned IPs from all jails\n\nPlease wait".to_string()
-            )
-        };
-        
-        self.state.loading_modal = Some(LoadingModalState::new(title, message));
-        
-        log::info!("PHASE 1: Showing full-screen loading modal for {} dataset ({}+ IPs)", 
-                  if is_massive_dataset { "massive" } else if is_large_dataset { "large" } else { "normal" }, 
-                  banned_ip_count);
-        
-        // Force immediate UI refresh by returning - actual loading happens in next cycle
-        log::info!("PHASE 1 COMPLETE: Loading modal displayed, will load data on next event cycle");
     }
     
-    fn continue_banned_ip_loading(&mut self) {
-        // Phase 2: Actually perform the expensive loading operation
-        log::info!("PHASE 2: Starting actual banned IP loading operation");
-        
-        let banned_ip_count = self.state.banned_ips.len();
-        let is_massive_dataset = banned_ip_count > 15000;
-        let is_large_dataset = banned_ip_count > 10000;
-        
-        // Use the exact same loading logic as before, but now the loading message is already visible
-        let force_refresh_interval = if is_massive_dataset {
-            Duration::from_secs(300) // 5 minutes for massive datasets (18k+ IPs)
-        } else if is_large_dataset {
-            Duration::from_secs(120) // 2 minutes for large datasets (10k+ IPs)
-        } else {
-            Duration::from_secs(60)  // 1 minute for normal datasets
-        };
-        
-        let force_refresh = self.state.last_ip_full_refresh.map_or(
-            self.state.banned_ips.is_empty(), // Only if empty on first load
-            |last| last.elapsed() > force_refresh_interval
-        );
-        
-        let should_refresh = self.state.banned_ips.is_empty() || force_refresh;
-        
-        if matches!(self.state.fail2ban_service, ServiceStatus::Running) && 
-           !self.state.jails.is_empty() && should_refresh {
-            
-            let start_time = Instant::now();
-            
-            let mut all_banned_ips = Vec::new();
-            let mut total_processed = 0;
-            let total_jails = self.state.jails.len();
-            
-            // Collect jail names to avoid borrowing issues
-            let jail_names: Vec<String> = self.state.jails.keys().cloned().collect();
-            
-            for (index, jail_name) in jail_names.iter().enumerate() {
-                // Update modal progress for large datasets
-                if is_large_dataset && total_jails > 3 {
-                    let progress_percent = ((index + 1) * 100) / total_jails;
-                    if let Some(ref mut modal) = self.state.loading_modal {
-                        modal.update_message(format!("Loading banned IPs from jails ({}/{})\n\nProgress: {}%\n\nCurrently processing: {}", 
-                                                     index + 1, total_jails, progress_percent, jail_name));
-                        modal.progress = Some(progress_percent as u8);
-                    }
-                }
-                
-                match self.fail2ban_client.get_banned_ips(jail_name) {
-                    Ok(mut ips) => {
-                        total_processed += ips.len();
-                        all_banned_ips.append(&mut ips);
-                        log::debug!("Loaded {} IPs from jail {} ({}/{})", 
-                                   ips.len(), jail_name, index + 1, self.state.jails.len());
-                    },
-                    Err(e) => {
-                        log::warn!("Failed to get banned IPs for jail {}: {}", jail_name, e);
-                    }
-                }
-            }
-            
-            let load_duration = start_time.elapsed();
-            log::info!("Loaded {} total banned IPs from {} jails in {:.2}s", 
-                      total_processed, self.state.jails.len(), load_duration.as_secs_f32());
-            
-            // Show sorting progress in modal for large datasets
-            if is_large_dataset {
-                if let Some(ref mut modal) = self.state.loading_modal {
-                    modal.update_message(format!("Sorting {} banned IPs...\n\nThis may take a moment", total_processed));
-                    modal.progress = Some(95);
-                }
-            }
-            
-            // Sort banned IPs by IP address first, then by jail name
-            all_banned_ips.sort_by(|a, b| {
-                a.ip.cmp(&b.ip).then_with(|| a.jail.cmp(&b.jail))
-            });
-            
-            self.state.banned_ips = all_banned_ips;
-            self.state.last_ip_full_refresh = Some(Instant::now());
-            
-            // Update pagination with total count
-            self.state.banned_ip_pagination.update_total_items(self.state.banned_ips.len());
-            
-            // Show completion in modal briefly
-            if let Some(ref mut modal) = self.state.loading_modal {
-                modal.update_message(format!("✅ Successfully loaded {} banned IPs\nfrom {} jails in {:.1}s", 
-                                            total_processed, total_jails, load_duration.as_secs_f32()));
-                modal.progress = Some(100);
+    /// One Ban or Unban, also counted in its jail's bans; true when the list changed
+    fn apply_ban_event(&mut self, event: &BanEvent, at: chrono::DateTime<chrono::Utc>) -> bool {
+        if !self.state.banned_ips.apply(event, at) {
+            return false;
+        }
+        if let Some(jail) = self.state.jails.get_mut(&event.jail) {
+            match event.action {
+                BanAction::Ban => jail.banned_count += 1,
+                BanAction::Unban => jail.banned_count = jail.banned_count.saturating_sub(1),
             }
-        } else if !matches!(self.state.fail2ban_service, ServiceStatus::Running) {
-            // Clear data if service is not running
-            self.state.banned_ips.clear();
-            self.state.banned_ip_pagination.update_total_items(0);
         }
-        
-        // Reset loading state and clear modal
-        self.state.is_loading_banned_ips = false;
-        self.state.loading_modal = None;
-        log::info!("PHASE 2 COMPLETE: Banned IP loading finished, clearing modal");
+        true
+    }
+    
+    /// Rebuild the filtered list and the page count from the changed banned IPs
+    fn banned_ips_changed(&mut self) {
+        self.state.banned_ip_filter.version += 1;
+        self.state.banned_ip_pagination.update_total_items(self.state.banned_ips.len());
     }
     
     fn refresh_log_data(&mut self) {
@@ -1793,43 +2000,83 @@ impl App {
     }
     
     fn handle_service_action(&mut self, action: ServiceAction) {
-        // Start progress tracking
+        if self.deny_read_only("service control") {
+            return;
+        }
+        if self.service_job.is_some() {
+            self.set_status_message("⚠ A service job is still running");
+            return;
+        }
+        
         let operation_type = match action {
             ServiceAction::Start => OperationType::ServiceStart,
             ServiceAction::Stop => OperationType::ServiceStop,
             ServiceAction::Restart => OperationType::ServiceRestart,
             ServiceAction::Reload => OperationType::ServiceReload,
         };
-        
         self.start_operation(operation_type);
         
-        // Simulate progress for better user experience
-        self.update_operation_progress(25, Some("Preparing operation...".to_string()));
+        match self.system_service.submit(&action) {
+            Ok(job) => {
+                self.update_operation_progress(10, Some(format!("Submitted to {}...", self.system_service.backend().label())));
+                self.service_job = Some(job);
+            },
+            Err(e) => self.finish_service_job(&action, Err(e.to_string())),
+        }
+    }
+    
+    /// Follow the submitted job and finish the operation once it is done
+    fn poll_service_job(&mut self) {
+        let Some(job) = self.service_job.clone() else {
+            return;
+        };
         
-        let result = match action {
-            ServiceAction::Start => {
-                self.update_operation_progress(50, Some("Starting service...".to_string()));
-                self.system_service.start()
+        match self.system_service.job_state(&job) {
+            Ok(JobState::Waiting) => {
+                self.update_operation_progress(25, Some("Queued behind other systemd jobs...".to_string()));
             },
-            ServiceAction::Stop => {
-                self.update_operation_progress(50, Some("Stopping service...".to_string()));
-                self.system_service.stop()
+            Ok(JobState::Running) => {
+                let detail = match self.system_service.unit_state() {
+                    Ok(unit) => format!("{}: {} ({})", job.action.label(), unit.active_state, unit.sub_state),
+                    Err(_) => format!("{}...", job.action.label()),
+                };
+                self.update_operation_progress(50, Some(detail));
             },
-            ServiceAction::Restart => {
-                self.update_operation_progress(33, Some("Stopping service...".to_string()));
-                let stop_result = self.system_service.stop();
-                if stop_result.is_ok() {
-                    self.update_operation_progress(66, Some("Starting service...".to_string()));
-                }
-                self.system_service.restart()
+            Ok(JobState::Done) => {
+                self.service_job = None;
+                self.update_operation_progress(90, Some("Checking unit state...".to_string()));
+                let result = match self.system_service.unit_state() {
+                    Ok(unit) => {
+                        let outcome = if unit.satisfies(&job.action) { Ok(()) } else { Err(job_failure(&unit)) };
+                        self.state.fail2ban_service = unit.service_status();
+                        self.state.service_unit = Some(unit);
+                        outcome
+                    },
+                    Err(e) => Err(e.to_string()),
+                };
+                self.finish_service_job(&job.action, result);
             },
-            ServiceAction::Reload => {
-                self.update_operation_progress(50, Some("Reloading configuration...".to_string()));
-                self.system_service.reload()
+            Err(e) => {
+                self.service_job = None;
+                self.finish_service_job(&job.action, Err(e.to_string()));
             },
-        };
+        }
         
-        self.update_operation_progress(90, Some("Finalizing...".to_string()));
+        if self.service_job.is_some() && job.submitted_at.elapsed() > SERVICE_JOB_TIMEOUT {
+            self.service_job = None;
+            let error = format!("service job did not finish within {}s", SERVICE_JOB_TIMEOUT.as_secs());
+            self.finish_service_job(&job.action, Err(error));
+        }
+    }
+    
+    fn finish_service_job(&mut self, action: &ServiceAction, result: std::result::Result<(), String>) {
+        let audit_action = match action {
+            ServiceAction::Start => AuditAction::ServiceStart,
+            ServiceAction::Stop => AuditAction::ServiceStop,
+            ServiceAction::Restart => AuditAction::ServiceRestart,
+            ServiceAction::Reload => AuditAction::ServiceReload,
+        };
+        self.audit(AuditEntry::new(audit_action, "fail2ban").outcome(&result));
         
         match result {
             Ok(()) => {
@@ -1854,8 +2101,8 @@ impl App {
                 };
                 self.state.last_service_action = Some((action_name.to_string(), chrono::Local::now()));
                 
-                // Trigger targeted IP refresh after successful ban
-                self.last_ip_refresh = Instant::now().checked_sub(Duration::from_secs(4)).unwrap_or(Instant::now());
+                // Restored bans are not logged as Ban lines, so reload the list
+                self.state.last_ip_full_refresh = None;
             },
             Err(e) => {
                 let error_msg = format!("✗ Service action failed: {}", e);
@@ -1866,6 +2113,72 @@ impl App {
         }
     }
     
+    /// Switch to read-only observer mode unless fully privileged
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.state.capabilities = capabilities;
+        if capabilities.read_only() {
+            self.set_status_message(&format!("⚠ Read-only mode - unavailable: {}", capabilities.missing().join(", ")));
+        }
+    }
+    
+    /// Notification rules loaded from the settings file
+    pub fn set_notifier(&mut self, notifier: Notifier) {
+        self.notifier = notifier;
+    }
+    
+    pub fn set_geoip(&mut self, geoip: GeoIp) {
+        self.geoip = geoip;
+    }
+    
+    pub fn set_rdns(&mut self, rdns: ReverseDns) {
+        self.rdns = rdns;
+    }
+    
+    pub fn set_filter_presets(&mut self, presets: QueryPresets) {
+        self.filter_presets = presets;
+    }
+    
+    /// Use an explicitly chosen init system instead of the detected one
+    pub fn set_system_service(&mut self, system_service: SystemService) {
+        self.system_service = system_service;
+        self.refresh_service_status();
+    }
+    
+    /// Write the audit trail somewhere other than the default
+    pub fn set_audit_log(&mut self, audit_log: AuditLog) {
+        self.audit_log = audit_log;
+    }
+    
+    /// Record an administrative action; a failed write never blocks the action itself
+    fn audit(&mut self, entry: AuditEntry) {
+        log::info!("Audit: {} {} by {} - {}", entry.action.label(), entry.target, entry.user, entry.result_label());
+        if let Err(e) = self.audit_log.record(&entry) {
+            log::error!("Failed to write audit log {}: {}", self.audit_log.path().display(), e);
+            self.set_status_message(&format!("⚠ Audit log not written: {}", e));
+        }
+        if self.state.current_screen == Screen::Audit {
+            self.state.audit_entries.insert(0, entry);
+        }
+    }
+    
+    /// Refuse `action` in read-only mode; returns true if it was refused
+    fn deny_read_only(&mut self, action: &str) -> bool {
+        if !self.state.capabilities.read_only() {
+            return false;
+        }
+        self.set_status_message(&format!("⚠ Read-only mode: {} requires root", action));
+        true
+    }
+    
+    /// Hotkey style for actions that change fail2ban - greyed out when read-only
+    fn action_hotkey_style(&self) -> Style {
+        if self.state.capabilities.read_only() {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default().fg(Color::Rgb(0, 150, 255))
+        }
+    }
+    
     fn set_status_message(&mut self, message: &str) {
         self.state.status_message = Some((message.to_string(), chrono::Utc::now()));
     }
@@ -1880,15 +2193,21 @@ impl App {
         self.start_operation(OperationType::IpUnban);
         self.update_operation_progress(30, Some(format!("Unbanning {} from {}...", ip, jail)));
         
-        match self.fail2ban_client.unban_ip(jail, ip) {
+        let result = self.fail2ban_client.unban_ip(jail, ip);
+        self.audit(AuditEntry::new(AuditAction::Unban, format!("{} in {}", ip, jail)).outcome(&result));
+        
+        match result {
             Ok(()) => {
                 self.update_operation_progress(80, Some("Updating IP list...".to_string()));
                 
                 let success_msg = format!("✓ Successfully unbanned {} from {}", ip, jail);
                 self.complete_operation(true, Some(success_msg));
                 
-                // Trigger targeted IP refresh after operation
-                self.last_ip_refresh = Instant::now().checked_sub(Duration::from_secs(4)).unwrap_or(Instant::now());
+                // Show it right away rather than when the Unban line is tailed
+                let event = BanEvent { action: BanAction::Unban, jail: jail.to_string(), ip: ip.to_string() };
+                if self.apply_ban_event(&event, chrono::Utc::now()) {
+                    self.banned_ips_changed();
+                }
             },
             Err(e) => {
                 let error_msg = format!("✗ Failed to unban {}: {}", ip, e);
@@ -1906,6 +2225,7 @@ impl App {
         
         // Always use the jail's configured bantime (no custom duration support)
         let result = self.fail2ban_client.ban_ip(jail, ip);
+        self.audit(AuditEntry::new(AuditAction::Ban, format!("{} in {}", ip, jail)).outcome(&result));
         
         match result {
             Ok(()) => {
@@ -1914,8 +2234,11 @@ impl App {
                 let success_msg = format!("✓ Successfully banned {} in {} (using jail's configured bantime)", ip, jail);
                 self.complete_operation(true, Some(success_msg));
                 
-                // Trigger targeted IP refresh after operation
-                self.last_ip_refresh = Instant::now().checked_sub(Duration::from_secs(4)).unwrap_or(Instant::now());
+                // Show it right away rather than when the Ban line is tailed
+                let event = BanEvent { action: BanAction::Ban, jail: jail.to_string(), ip: ip.to_string() };
+                if self.apply_ban_event(&event, chrono::Utc::now()) {
+                    self.banned_ips_changed();
+                }
             },
             Err(e) => {
                 let error_msg = format!("✗ Failed to ban {}: {}", ip, e);
@@ -1925,6 +2248,56 @@ impl App {
         }
     }
     
+    /// Ban the range, then lift the host bans it now covers in the same jail
+    fn handle_range_ban(&mut self, plan: &RangeBan) {
+        let range = plan.range.to_string();
+        log::info!("Banning range {} in jail {} to replace {} host bans", range, plan.jail, plan.replaces.len());
+        
+        self.start_operation(OperationType::IpBan);
+        self.update_operation_progress(30, Some(format!("Banning {} in {}...", range, plan.jail)));
+        
+        let result = self.fail2ban_client.ban_ip(&plan.jail, &range);
+        self.audit(AuditEntry::new(AuditAction::Ban, format!("{} in {}", range, plan.jail))
+            .change(plan.replaces.join(" "), range.clone())
+            .outcome(&result));
+        if let Err(e) = result {
+            self.complete_operation(false, Some(format!("✗ Failed to ban {}: {}", range, e)));
+            log::error!("Failed to ban range {}: {}", range, e);
+            return;
+        }
+        
+        self.update_operation_progress(60, Some(format!("Removing {} host bans...", plan.replaces.len())));
+        let mut failed = 0;
+        for ip in &plan.replaces {
+            let result = self.fail2ban_client.unban_ip(&plan.jail, ip);
+            self.audit(AuditEntry::new(AuditAction::Unban, format!("{} in {}", ip, plan.jail)).outcome(&result));
+            if let Err(e) = result {
+                log::error!("Failed to unban {} after banning {}: {}", ip, range, e);
+                failed += 1;
+            }
+        }
+        
+        let message = if failed == 0 {
+            format!("✓ Banned {} in {}, replacing {} host bans", range, plan.jail, plan.replaces.len())
+        } else {
+            format!("⚠ Banned {} in {}, but {} of {} host bans could not be removed", range, plan.jail, failed, plan.replaces.len())
+        };
+        self.complete_operation(failed == 0, Some(message));
+        
+        // Reload the list rather than replay the range and every host it replaced
+        self.state.last_ip_full_refresh = None;
+    }
+    
+    fn subnet_groups(&self) -> Vec<SubnetGroup> {
+        aggregate(self.state.banned_ips.as_slice(), self.state.subnet_ipv4_prefix, self.state.subnet_ipv6_prefix)
+    }
+    
+    fn select_subnet(&mut self, index: usize) {
+        let groups = self.subnet_groups().len();
+        self.state.subnet_selected_index = index.min(groups.saturating_sub(1));
+        self.state.subnet_table_state.select(if groups == 0 { None } else { Some(self.state.subnet_selected_index) });
+    }
+    
     fn export_banned_ips_to_csv(&mut self) {
         use std::io::Write;
         
@@ -1999,7 +2372,102 @@ impl App {
         }
     }
     
+    fn load_audit_entries(&mut self) {
+        match self.audit_log.read_entries() {
+            Ok(mut entries) => {
+                entries.reverse();
+                self.state.audit_entries = entries;
+                self.state.audit_error = None;
+            },
+            Err(e) => {
+                log::error!("Failed to read audit log {}: {}", self.audit_log.path().display(), e);
+                self.state.audit_entries.clear();
+                self.state.audit_error = Some(format!("Cannot read {}: {}", self.audit_log.path().display(), e));
+            }
+        }
+        self.select_audit_entry(0);
+    }
+    
+    fn select_audit_entry(&mut self, index: usize) {
+        let last = self.state.audit_entries.len().saturating_sub(1);
+        self.state.audit_selected_index = index.min(last);
+        self.state.audit_table_state.select(if self.state.audit_entries.is_empty() { None } else { Some(self.state.audit_selected_index) });
+    }
+    
+    /// The selected Statistics table over the selected window
+    fn statistics_table(&self) -> TableData {
+        BanStatistics::collect(self.state.banned_ips.as_slice(), &self.state.event_history, self.state.stats_window_hours, log_clock_now())
+            .table(self.state.stats_table)
+    }
+    
+    fn select_stats_row(&mut self, index: usize) {
+        let rows = self.statistics_table().rows.len();
+        self.state.stats_selected_index = index.min(rows.saturating_sub(1));
+        self.state.stats_table_state.select(if rows == 0 { None } else { Some(self.state.stats_selected_index) });
+    }
+    
+    fn export_statistics_table(&mut self) {
+        let kind = self.state.stats_table;
+        let table = self.statistics_table();
+        if table.rows.is_empty() {
+            self.set_status_message(&format!("⚠ No {} rows to export", kind.title()));
+            return;
+        }
+        
+        let filename = format!("f2b-buxjr-{}_{}.csv", kind.slug(), chrono::Local::now().format("%Y%m%d_%H%M%S"));
+        let export_paths = [
+            std::path::Path::new("/tmp").join(&filename),
+            std::path::Path::new(".").join(&filename),
+        ];
+        
+        for path in &export_paths {
+            match export_table(&table, path) {
+                Ok(()) => {
+                    log::info!("Exported {} {} rows to {}", table.rows.len(), kind.title(), path.display());
+                    self.set_status_message(&format!("✓ Exported {} {} rows to {}", table.rows.len(), kind.title(), path.display()));
+                    return;
+                },
+                Err(e) => log::error!("Failed to export {} to {}: {}", kind.title(), path.display(), e),
+            }
+        }
+        self.set_status_message(&format!("✗ Failed to export {} - could not create file in any location", kind.title()));
+    }
+    
+    fn export_audit_log(&mut self) {
+        if self.state.audit_entries.is_empty() {
+            self.set_status_message("⚠ No audit entries to export");
+            return;
+        }
+        
+        // Oldest first in the export, like the log itself
+        let mut entries = self.state.audit_entries.clone();
+        entries.reverse();
+        let filename = format!("f2b-buxjr-audit_{}.csv", chrono::Local::now().format("%Y%m%d_%H%M%S"));
+        let export_paths = [
+            std::path::Path::new("/tmp").join(&filename),
+            std::path::Path::new(".").join(&filename),
+        ];
+        
+        for path in &export_paths {
+            match export_csv(&entries, path) {
+                Ok(()) => {
+                    log::info!("Exported {} audit entries to {}", entries.len(), path.display());
+                    self.set_status_message(&format!("✓ Exported {} audit entries to {}", entries.len(), path.display()));
+                    return;
+                },
+                Err(e) => log::error!("Failed to export audit log to {}: {}", path.display(), e),
+            }
+        }
+        self.set_status_message("✗ Failed to export audit log - could not create file in any location");
+    }
+    
     fn handle_message(&mut self, message: AppMessage) {
+        if let Some(action) = message.privileged_action() {
+            if self.deny_read_only(action) {
+                return;
+            }
+        }
+        
         match message {
             // IP management messages
             AppMessage::OpenBanDialog => {
@@ -2059,6 +2527,39 @@ impl App {
             AppMessage::ExportBannedIPs => {
                 self.export_banned_ips_to_csv();
             },
+            // Subnet aggregation messages
+            AppMessage::OpenRangeBanDialog => {
+                let groups = self.subnet_groups();
+                let Some(group) = groups.into_iter().nth(self.state.subnet_selected_index) else {
+                    self.set_status_message("⚠ No banned subnets");
+                    return;
+                };
+                
+                // Jails already banning hosts in the range first, then the other active jails
+                let mut jails = group.jails().into_iter().map(|(jail, _)| jail).collect::<Vec<_>>();
+                let mut others = self.state.jails.keys().filter(|jail| !jails.contains(jail)).cloned().collect::<Vec<_>>();
+                others.sort();
+                jails.extend(others);
+                if jails.is_empty() {
+                    self.set_status_message("⚠ No active jails found - check fail2ban service status");
+                    return;
+                }
+                self.state.range_ban_dialog = Some(RangeBanDialogState { group, jails, jail_index: 0 });
+            },
+            AppMessage::CloseRangeBanDialog => {
+                self.state.range_ban_dialog = None;
+            },
+            AppMessage::ConfirmRangeBan => {
+                if let Some(dialog) = self.state.range_ban_dialog.clone() {
+                    match plan_range_ban(&dialog.group, &dialog.jails[dialog.jail_index], &self.state.whitelist_ips) {
+                        Ok(plan) => {
+                            self.state.range_ban_dialog = None;
+                            self.handle_range_ban(&plan);
+                        },
+                        Err(e) => self.set_status_message(&format!("✗ Range ban refused: {}", e)),
+                    }
+                }
+            },
             // Whitelist management messages
             AppMessage::OpenWhitelistDialog => {
                 self.state.ip_management.whitelist_dialog_open = true;
@@ -2077,10 +2578,15 @@ impl App {
                 } else if self.state.whitelist_ips.contains(&ip) {
                     self.set_status_message(&format!("⚠ {} is already in the whitelist", ip));
                 } else {
+                    let before = self.state.whitelist_ips.join(" ");
                     self.state.whitelist_ips.push(ip.clone());
                     
                     // Save to fail2ban configuration
-                    match self.fail2ban_client.save_whitelist_ips(&self.state.whitelist_ips) {
+                    let result = self.fail2ban_client.save_whitelist_ips(&self.state.whitelist_ips);
+                    self.audit(AuditEntry::new(AuditAction::WhitelistAdd, ip.clone())
+                        .change(before, self.state.whitelist_ips.join(" "))
+                        .outcome(&result));
+                    match result {
                         Ok(()) => {
                             self.set_status_message(&format!("✓ {} added to whitelist and saved", ip));
                         },
@@ -2096,10 +2602,15 @@ impl App {
             },
             AppMessage::RemoveFromWhitelist(index) => {
                 if index < self.state.whitelist_ips.len() {
+                    let before = self.state.whitelist_ips.join(" ");
                     let removed_ip = self.state.whitelist_ips.remove(index);
                     
                     // Save to fail2ban configuration
-                    match self.fail2ban_client.save_whitelist_ips(&self.state.whitelist_ips) {
+                    let result = self.fail2ban_client.save_whitelist_ips(&self.state.whitelist_ips);
+                    self.audit(AuditEntry::new(AuditAction::WhitelistRemove, removed_ip.clone())
+                        .change(before, self.state.whitelist_ips.join(" "))
+                        .outcome(&result));
+                    match result {
                         Ok(()) => {
                             self.set_status_message(&format!("✓ {} removed from whitelist and saved", removed_ip));
                         },
@@ -2165,9 +2676,6 @@ impl App {
             AppMessage::CloseJailEditor => {
                 self.close_jail_editor();
             },
-            AppMessage::UpdateJailEditorContent(content) => {
-                self.state.jail_editor.current_content = content;
-            },
             AppMessage::SaveJailConfiguration => {
                 self.save_jail_configuration();
             },
@@ -2199,6 +2707,46 @@ impl App {
             AppMessage::TestConfiguration => {
                 self.test_configuration();
             },
+            AppMessage::EditExternally => {
+                self.request_external_edit();
+            },
+            AppMessage::OpenNewJailWizard => {
+                self.open_new_jail_wizard();
+            },
+            AppMessage::CloseNewJailWizard => {
+                self.state.new_jail_wizard = None;
+            },
+            AppMessage::CreateNewJail => {
+                self.create_new_jail();
+            },
+            AppMessage::ReviewChanges => {
+                self.open_save_review();
+            },
+            AppMessage::ConfirmSave => {
+                self.confirm_save_review();
+            },
+            AppMessage::DiscardChanges => {
+                self.state.save_review = None;
+                if self.state.jail_editor.is_open {
+                    self.state.jail_editor.modified = false;
+                    self.close_jail_editor();
+                } else if self.state.config_management.editor_open {
+                    self.state.config_management.modified = false;
+                    self.close_config_editor();
+                }
+                self.set_status_message("⚠ Changes discarded");
+            },
+            AppMessage::CloseSaveReview => {
+                self.state.save_review = None;
+            },
+            AppMessage::ResolveExternalChange(action) => {
+                self.resolve_external_change(action);
+            },
+            AppMessage::CloseExternalChangeDialog => {
+                if let Some(ref mut change) = self.state.external_change {
+                    change.dialog_open = false;
+                }
+            },
             // Other messages would be handled here
             _ => {
                 // For now, just log unhandled messages
@@ -2208,6 +2756,8 @@ impl App {
     }
     
     pub fn render(&mut self, frame: &mut Frame) {
+        self.state.sort_headers.clear();
+        
         let mut constraints = vec![
             Constraint::Length(3), // Header
             Constraint::Min(0),    // Content
@@ -2257,6 +2807,28 @@ impl App {
             self.render_whitelist_dialog(frame, frame.size());
         }
         
+        if self.is_external_change_dialog_open() {
+            self.render_external_change_dialog(frame, frame.size());
+        }
+        if self.state.save_review.is_some() {
+            self.render_save_review(frame, frame.size());
+        }
+        if self.state.new_jail_wizard.is_some() {
+            self.render_new_jail_wizard(frame, frame.size());
+        }
+        if let Some(ip) = self.state.ip_detail.clone() {
+            if self.rdns.is_enabled() {
+                self.rdns.lookup(&ip);
+            }
+            self.render_ip_detail(frame, frame.size());
+        }
+        if self.state.range_ban_dialog.is_some() {
+            self.render_range_ban_dialog(frame, frame.size());
+        }
+        if self.state.filter_prompt.is_some() {
+            self.render_filter_prompt(frame, frame.size());
+        }
+        
         // Render loading modal on top of EVERYTHING if present (highest priority)
         if let Some(ref modal) = self.state.loading_modal {
             self.render_loading_modal(frame, frame.size(), modal);
@@ -2283,10 +2855,13 @@ impl App {
         let version_number = env!("CARGO_PKG_VERSION");
         let service_status = format!("[{}]", self.state.fail2ban_service.symbol());
         let screen_title = format!(" {} ", self.state.current_screen.title());
+        let health_level = self.state.health.level();
+        let health_badge = if health_level == HealthLevel::Unknown { String::new() } else { format!(" {}", self.state.health.headline()) };
+        let read_only_badge = if self.state.capabilities.read_only() { " READ-ONLY " } else { "" };
         let datetime_str = now.format("%Y-%m-%d %H:%M:%S").to_string();
         
         // Calculate left side width based on actual spans
-        let mut left_side_width = version_prefix.len() + version_number.len() + 1 + service_status.len() + screen_title.len();
+        let mut left_side_width = version_prefix.len() + version_number.len() + 1 + service_status.len() + health_badge.width() + read_only_badge.len() + screen_title.len();
         
         // Add status message length to calculation if present
         if let Some(ref msg) = status_display {
@@ -2316,6 +2891,14 @@ impl App {
                 &service_status,
                 Style::default().fg(self.state.fail2ban_service.color())
             ),
+            Span::styled(
+                &health_badge,
+                Style::default().fg(health_color(health_level))
+            ),
+            Span::styled(
+                read_only_badge,
+                Style::default().fg(Color::Black).bg(Color::Yellow)
+            ),
             Span::styled(
                 &screen_title,
                 Style::default().fg(Color::White)
@@ -2364,6 +2947,10 @@ impl App {
             Screen::Logs => self.render_logs(frame, area),
             Screen::Settings => self.render_settings(frame, area),
             Screen::JailEditor => self.render_jail_editor(frame, area),
+            Screen::Audit => self.render_audit(frame, area),
+            Screen::Health => self.render_health(frame, area),
+            Screen::Statistics => self.render_statistics(frame, area),
+            Screen::Subnets => self.render_subnets(frame, area),
         }
     }
     
@@ -2382,11 +2969,12 @@ impl App {
             base_service_text.to_string()
         };
         
-        // Split layout: Service status, jails table, and Banned IPs table
+        // Split layout: Service status, activity sparklines, jails table, and Banned IPs table
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),   // Service status header
+                Constraint::Length(4),   // Ban/failure sparklines
                 Constraint::Min(8),      // Jails table
                 Constraint::Min(8),      // Banned IPs table
             ])
@@ -2395,32 +2983,64 @@ impl App {
         // Render service status header
         let service_title = Line::from(vec![
             Span::raw("Service Status - "),
-            Span::styled("R", Style::default().fg(Color::Rgb(0, 150, 255))),
+            Span::styled("R", self.action_hotkey_style()),
             Span::raw(":Restart | "),
-            Span::styled("S", Style::default().fg(Color::Rgb(0, 150, 255))),
+            Span::styled("S", self.action_hotkey_style()),
             Span::raw(":Start | "),
-            Span::styled("T", Style::default().fg(Color::Rgb(0, 150, 255))),
+            Span::styled("T", self.action_hotkey_style()),
             Span::raw(":Stop | "),
-            Span::styled("D", Style::default().fg(Color::Rgb(0, 150, 255))),
+            Span::styled("D", self.action_hotkey_style()),
             Span::raw(":Reload"),
         ]);
-        let service_header = Paragraph::new(vec![
-            Line::from(vec![
-                Span::styled(
-                    format!("{} {}", self.state.fail2ban_service.symbol(), service_text),
-                    Style::default().fg(self.state.fail2ban_service.color())
-                ),
-            ]),
-        ])
-        .block(Block::default().title(service_title).borders(Borders::ALL));
+        
+        // PID, uptime, memory and restarts as far as the service manager reports them
+        let mut service_spans = vec![
+            Span::styled(
+                format!("{} {}", self.state.fail2ban_service.symbol(), service_text),
+                Style::default().fg(self.state.fail2ban_service.color())
+            ),
+        ];
+        if let Some(ref unit) = self.state.service_unit {
+            service_spans.push(Span::styled(
+                format!("  [{}] {}", unit.sub_state, unit.summary()).trim_end().to_string(),
+                Style::default().fg(Color::Gray)
+            ));
+            if let Some(ref failure) = unit.failure {
+                service_spans.push(Span::styled(format!("  Last failure: {}", failure), Style::default().fg(Color::Red)));
+            }
+        }
+        
+        let service_header = Paragraph::new(vec![Line::from(service_spans)])
+            .block(Block::default().title(service_title).borders(Borders::ALL));
         
         frame.render_widget(service_header, chunks[0]);
         
+        self.render_activity_strip(frame, chunks[1]);
+        
         // Render jails table
-        self.render_jails_table(frame, chunks[1]);
+        self.render_jails_table(frame, chunks[2]);
         
         // Render Banned IPs table (same format as IP Management)
-        self.render_recent_activity_table(frame, chunks[2]);
+        self.render_recent_activity_table(frame, chunks[3]);
+    }
+    
+    /// Bans and failures over the Statistics screen's window, all jails
+    fn render_activity_strip(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        let window = self.state.stats_window_hours;
+        let now = log_clock_now();
+        for (rect, kind, label, color) in [(halves[0], EventKind::Ban, "Bans", Color::Red), (halves[1], EventKind::Found, "Failures", Color::Yellow)] {
+            let data = self.state.event_history.series(None, kind, window, rect.width.saturating_sub(2) as usize, now);
+            let title = format!("{} - last {}: {}", label, window_label(window), data.iter().sum::<u64>());
+            let sparkline = Sparkline::default()
+                .block(Block::default().borders(Borders::ALL).title(title))
+                .data(&data)
+                .style(Style::default().fg(color));
+            frame.render_widget(sparkline, rect);
+        }
     }
     
     fn render_jails_table(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
@@ -2437,15 +3057,19 @@ impl App {
                 Cell::from(""),
                 Cell::from(""),
                 Cell::from(""),
+                Cell::from(""),
             ]));
         } else {
             let sorted_jails = self.get_sorted_jails_for_display();
             
             for jail_config in sorted_jails.iter() {
-                // Get current ban count from runtime state if available
+                // Get current ban and failure counts from runtime state if available
                 let ban_count = self.state.jails.get(&jail_config.name)
                     .map(|js| js.banned_count)
                     .unwrap_or(0);
+                let failed_count = self.state.jails.get(&jail_config.name)
+                    .map(|js| js.failed_count)
+                    .unwrap_or(0);
                 
                 let status_symbol = if jail_config.enabled { "●" } else { "○" };
                 let status_color = if jail_config.enabled { Color::Green } else { Color::Red };
@@ -2466,6 +3090,7 @@ impl App {
                     Cell::from(format!("{} {}", status_symbol, status_text)).style(Style::default().fg(status_color)),
                     Cell::from(jail_config.name.clone()).style(Style::default().fg(Color::White)),
                     Cell::from(ban_count_text).style(Style::default().fg(Color::White)),
+                    Cell::from(failed_count.to_string()).style(Style::default().fg(if failed_count > 0 { Color::Yellow } else { Color::White })),
                     Cell::from(filter_display).style(Style::default().fg(Color::White)),
                     Cell::from(port_display).style(Style::default().fg(Color::White)),
                     Cell::from(jail_config.ban_time.clone()).style(Style::default().fg(Color::White)),
@@ -2475,40 +3100,46 @@ impl App {
             }
         }
         
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(12),  // Status
-                Constraint::Length(20),  // Jail Name
-                Constraint::Length(10),  // Bans
-                Constraint::Length(18),  // Filter
-                Constraint::Length(12),  // Port
-                Constraint::Length(12),  // Ban Time
-                Constraint::Length(12),  // Find Time
-                Constraint::Length(8),   // Max Retry
-            ]
-        )
-        .header(Row::new(vec![
-            Cell::from("Status").style(Style::default().fg(Color::Yellow)),
-            Cell::from("Jail Name").style(Style::default().fg(Color::Yellow)),
-            Cell::from("Bans").style(Style::default().fg(Color::Yellow)),
-            Cell::from("Filter").style(Style::default().fg(Color::Yellow)),
-            Cell::from("Port").style(Style::default().fg(Color::Yellow)),
-            Cell::from("Ban Time").style(Style::default().fg(Color::Yellow)),
-            Cell::from("Find Time").style(Style::default().fg(Color::Yellow)),
-            Cell::from("Retry").style(Style::default().fg(Color::Yellow)),
-        ]))
+        let widths = [
+            Constraint::Length(12),  // Status
+            Constraint::Length(20),  // Jail Name
+            Constraint::Length(10),  // Bans
+            Constraint::Length(8),   // Failed
+            Constraint::Length(18),  // Filter
+            Constraint::Length(12),  // Port
+            Constraint::Length(12),  // Ban Time
+            Constraint::Length(12),  // Find Time
+            Constraint::Length(8),   // Max Retry
+        ];
+        
+        // Sortable headers show the direction and can be clicked
+        let sort = self.state.jail_sort;
+        let sortable = [Some(JailColumn::Status), Some(JailColumn::Name), Some(JailColumn::Bans), Some(JailColumn::Failed), None, None, None, None, None];
+        let headers = ["Status", "Jail Name", "Bans", "Failed", "Filter", "Port", "Ban Time", "Find Time", "Retry"];
+        for (rect, column) in header_cells(area, &widths).into_iter().zip(sortable) {
+            if let Some(column) = column {
+                self.state.sort_headers.push((rect, SortHeader::Jail(column)));
+            }
+        }
+        
+        let table = Table::new(rows, widths)
+        .header(Row::new(headers.iter().zip(sortable).map(|(header, column)| {
+            let indicator = column.map_or("", |column| sort.indicator(column));
+            Cell::from(format!("{}{}", header, indicator)).style(Style::default().fg(Color::Yellow))
+        }).collect::<Vec<_>>()))
         .block(Block::default().borders(Borders::ALL).title(
             if self.state.dashboard_focus == DashboardFocus::Jails {
                 let active_count = self.state.available_jails.iter().filter(|j| j.enabled).count();
                 Line::from(vec![
                     Span::raw(format!("Jails ({} Total / {} Active) - ", self.state.available_jails.len(), active_count)),
-                    Span::styled("ENTER", Style::default().fg(Color::Rgb(0, 150, 255))),
+                    Span::styled("ENTER", self.action_hotkey_style()),
                     Span::raw(":En/Disable | "),
-                    Span::styled("E", Style::default().fg(Color::Rgb(0, 150, 255))),
+                    Span::styled("E", self.action_hotkey_style()),
                     Span::raw(":Edit | "),
                     Span::styled("↑↓", Style::default().fg(Color::Rgb(0, 150, 255))),
                     Span::raw(":Navigate | "),
+                    Span::styled("o/O", Style::default().fg(Color::Rgb(0, 150, 255))),
+                    Span::raw(":Sort | "),
                     Span::styled("TAB", Style::default().fg(Color::Rgb(0, 150, 255))),
                     Span::raw(":Switch Focus"),
                 ])
@@ -2537,7 +3168,7 @@ impl App {
         let mut rows = Vec::new();
         
         // Compute all needed data first to avoid borrowing conflicts
-        let all_filtered_ips = self.get_filtered_banned_ips().clone();
+        let all_filtered_ips = self.get_filtered_banned_ips().to_vec();
         let total_count = self.state.banned_ips.len();
         let filtered_count = all_filtered_ips.len();
         
@@ -2557,44 +3188,59 @@ impl App {
         
         // Compute filter spans inline to avoid borrowing issues
         let mut filter_spans = Vec::new();
-        if let Some(digit) = self.state.banned_ip_filter.ip_starting_digit {
-            filter_spans.push(Span::styled(format!("IP:{}", digit), Style::default().fg(Color::Yellow)));
-        }
-        if let Some(ref jail) = self.state.banned_ip_filter.jail {
-            if !filter_spans.is_empty() {
-                filter_spans.push(Span::styled(", ", Style::default().fg(Color::Gray)));
-            }
-            filter_spans.push(Span::styled(format!("Jail:{}", jail), Style::default().fg(Color::Yellow)));
+        if let Some(ref query) = self.state.banned_ip_filter.query {
+            filter_spans.push(Span::styled(query.text().to_string(), Style::default().fg(Color::Yellow)));
         }
-        if let Some(hours) = self.state.banned_ip_filter.ban_age_hours {
-            if !filter_spans.is_empty() {
-                filter_spans.push(Span::styled(", ", Style::default().fg(Color::Gray)));
+        
+        let dashboard_focus = self.state.dashboard_focus;
+        let banned_ip_selected_index = self.state.dashboard_banned_ip_selected_index;
+        
+        // Country and ASN columns only when a GeoIP database is configured
+        let geo_enabled = self.geoip.is_enabled();
+        let mut widths = vec![
+            Constraint::Length(16),  // IP Address
+            Constraint::Length(22),  // Jail (increased from 12 to 22)
+            Constraint::Length(20),  // Ban Date/Time
+            Constraint::Length(20),  // Unban Date/Time
+            Constraint::Min(16),     // Time Remaining (expanded to use space from removed reason)
+        ];
+        let mut headers = vec!["IP Address", "Jail", "Banned At", "Unbans At", "Remaining"];
+        if geo_enabled {
+            widths[4] = Constraint::Length(16);
+            widths.extend([Constraint::Length(8), Constraint::Min(20)]);
+            headers.extend(["Country", "ASN"]);
+        }
+        
+        // Hostnames are resolved lazily, only for the rows that fit on screen
+        let rdns_enabled = self.rdns.is_enabled();
+        let mut visible_rows = 0..0;
+        if rdns_enabled {
+            if let Some(last) = widths.last_mut() {
+                *last = Constraint::Length(if geo_enabled { 28 } else { 16 });
             }
-            let age_text = match hours {
-                1 => "Age:1h".to_string(),
-                24 => "Age:24h".to_string(), 
-                168 => "Age:1w".to_string(),
-                _ => format!("Age:{}h", hours),
-            };
-            filter_spans.push(Span::styled(age_text, Style::default().fg(Color::Yellow)));
-        }
-        if let Some(remaining_filter) = self.state.banned_ip_filter.remaining_time {
-            if !filter_spans.is_empty() {
-                filter_spans.push(Span::styled(", ", Style::default().fg(Color::Gray)));
+            widths.push(Constraint::Min(24));
+            headers.push("Hostname");
+            
+            let height = area.height.saturating_sub(3) as usize;  // Borders and header
+            let mut offset = self.state.dashboard_banned_ip_table_state.offset();
+            if dashboard_focus == DashboardFocus::BannedIPs {
+                offset = offset.min(banned_ip_selected_index).max((banned_ip_selected_index + 1).saturating_sub(height));
             }
-            let remaining_text = match remaining_filter {
-                RemainingTimeFilter::Soon => "Remaining:<1h",
-                RemainingTimeFilter::Today => "Remaining:<24h", 
-                RemainingTimeFilter::ThisWeek => "Remaining:<1w",
-                RemainingTimeFilter::Permanent => "Remaining:∞",
-            };
-            filter_spans.push(Span::styled(remaining_text, Style::default().fg(Color::Yellow)));
+            visible_rows = offset..offset + height;
         }
         
-        let dashboard_focus = self.state.dashboard_focus;
-        let banned_ip_selected_index = self.state.dashboard_banned_ip_selected_index;
+        // Sortable headers show the direction and can be clicked; Unbans At and Remaining share an order
+        let sort = self.state.banned_ip_sort;
+        let sortable = [BannedIpColumn::Ip, BannedIpColumn::Jail, BannedIpColumn::BannedAt, BannedIpColumn::Remaining, BannedIpColumn::Remaining];
+        for (rect, column) in header_cells(area, &widths).into_iter().zip(sortable) {
+            self.state.sort_headers.push((rect, SortHeader::BannedIp(column)));
+        }
+        let header = Row::new(headers.iter().enumerate().map(|(i, h)| {
+            let indicator = sortable.get(i).map_or("", |column| sort.indicator(*column));
+            Cell::from(format!("{}{}", h, indicator)).style(Style::default().fg(Color::Yellow))
+        }).collect::<Vec<_>>());
         
-        for banned_ip in &filtered_ips {
+        for (row_index, banned_ip) in filtered_ips.iter().enumerate() {
             let ban_time_local = banned_ip.ban_time.with_timezone(&chrono::Local);
             let ban_date = ban_time_local.format("%Y-%m-%d").to_string();
             let ban_time = ban_time_local.format("%H:%M:%S").to_string();
@@ -2632,13 +3278,32 @@ impl App {
                 "Permanent".to_string()
             };
             
-            rows.push(Row::new(vec![
+            let mut cells = vec![
                 Cell::from(banned_ip.ip.clone()).style(Style::default().fg(Color::White)),
                 Cell::from(banned_ip.jail.clone()).style(Style::default().fg(Color::White)),
                 Cell::from(format!("{} {}", ban_date, ban_time)).style(Style::default().fg(Color::White)),
                 Cell::from(unban_date_time).style(Style::default().fg(Color::White)),
                 Cell::from(unban_info).style(Style::default().fg(Color::White)),
-            ]));
+            ];
+            if geo_enabled {
+                let geo = self.geoip.cached(&banned_ip.ip);
+                cells.push(Cell::from(geo.country.clone().unwrap_or_else(|| "—".to_string())).style(Style::default().fg(Color::Cyan)));
+                cells.push(Cell::from(geo.asn_label().unwrap_or_else(|| "—".to_string())).style(Style::default().fg(Color::Gray)));
+            }
+            if rdns_enabled {
+                let hostname = if visible_rows.contains(&row_index) {
+                    self.rdns.lookup(&banned_ip.ip)
+                } else {
+                    self.rdns.peek(&banned_ip.ip)
+                };
+                cells.push(match hostname {
+                    Hostname::Found(name) => Cell::from(name).style(Style::default().fg(Color::White)),
+                    Hostname::Pending if visible_rows.contains(&row_index) => Cell::from("resolving…").style(Style::default().fg(Color::DarkGray)),
+                    Hostname::Pending => Cell::from(""),
+                    Hostname::NotFound => Cell::from("—").style(Style::default().fg(Color::Gray)),
+                });
+            }
+            rows.push(Row::new(cells));
         }
         
         // Use pre-computed values for titles with pagination info
@@ -2662,35 +3327,19 @@ impl App {
                     Cell::from(""),
                     Cell::from(""),
                 ])],
-                [
-                    Constraint::Length(16),  // IP Address
-                    Constraint::Length(22),  // Jail (increased from 12 to 22)
-                    Constraint::Length(20),  // Ban Date/Time
-                    Constraint::Length(20),  // Unban Date/Time
-                    Constraint::Min(16),     // Time Remaining (expanded to use space from removed reason)
-                ]
+                widths.clone(),
             )
-            .header(Row::new(vec![
-                Cell::from("IP Address").style(Style::default().fg(Color::Yellow)),
-                Cell::from("Jail").style(Style::default().fg(Color::Yellow)),
-                Cell::from("Banned At").style(Style::default().fg(Color::Yellow)),
-                Cell::from("Unbans At").style(Style::default().fg(Color::Yellow)),
-                Cell::from("Remaining").style(Style::default().fg(Color::Yellow)),
-            ]))
+            .header(header.clone())
             .block(Block::default().borders(Borders::ALL).title(
                 if dashboard_focus == DashboardFocus::BannedIPs {
                     let mut title_spans = vec![
                         Span::raw(count_text.clone()),
+                        Span::styled("/", Style::default().fg(Color::Rgb(0, 150, 255))),
+                        Span::raw(":Filter|"),
                         Span::styled("0", Style::default().fg(Color::Rgb(0, 150, 255))),
                         Span::raw(":Clr|"),
-                        Span::styled("1", Style::default().fg(Color::Rgb(0, 150, 255))),
-                        Span::raw(":IP|"),
-                        Span::styled("2", Style::default().fg(Color::Rgb(0, 150, 255))),
-                        Span::raw(":Jail|"),
-                        Span::styled("3", Style::default().fg(Color::Rgb(0, 150, 255))),
-                        Span::raw(":Age|"),
-                        Span::styled("4", Style::default().fg(Color::Rgb(0, 150, 255))),
-                        Span::raw(":Rem|"),
+                        Span::styled("o/O", Style::default().fg(Color::Rgb(0, 150, 255))),
+                        Span::raw(":Sort|"),
                         Span::styled(",.", Style::default().fg(Color::Rgb(0, 150, 255))),
                         Span::raw(":Pgs|"),
                         Span::styled("X", Style::default().fg(Color::Rgb(0, 150, 255))),
@@ -2728,38 +3377,22 @@ impl App {
         } else {
             Table::new(
                 rows,
-                [
-                    Constraint::Length(16),  // IP Address
-                    Constraint::Length(22),  // Jail (increased from 12 to 22)
-                    Constraint::Length(20),  // Ban Date/Time
-                    Constraint::Length(20),  // Unban Date/Time
-                    Constraint::Min(16),     // Time Remaining (expanded to use space from removed reason)
-                ]
+                widths,
             )
-            .header(Row::new(vec![
-                Cell::from("IP Address").style(Style::default().fg(Color::Yellow)),
-                Cell::from("Jail").style(Style::default().fg(Color::Yellow)),
-                Cell::from("Banned At").style(Style::default().fg(Color::Yellow)),
-                Cell::from("Unbans At").style(Style::default().fg(Color::Yellow)),
-                Cell::from("Remaining").style(Style::default().fg(Color::Yellow)),
-            ]))
+            .header(header)
             .block(Block::default().borders(Borders::ALL).title(
                 if dashboard_focus == DashboardFocus::BannedIPs {
                     let mut title_spans = vec![
                         Span::raw(count_text.clone()),
+                        Span::styled("/", Style::default().fg(Color::Rgb(0, 150, 255))),
+                        Span::raw(":Filter | "),
                         Span::styled("0", Style::default().fg(Color::Rgb(0, 150, 255))),
                         Span::raw(":Clear | "),
-                        Span::styled("1", Style::default().fg(Color::Rgb(0, 150, 255))),
-                        Span::raw(":IP | "),
-                        Span::styled("2", Style::default().fg(Color::Rgb(0, 150, 255))),
-                        Span::raw(":Jail | "),
-                        Span::styled("3", Style::default().fg(Color::Rgb(0, 150, 255))),
-                        Span::raw(":Age | "),
-                        Span::styled("4", Style::default().fg(Color::Rgb(0, 150, 255))),
-                        Span::raw(":Remaining | "),
+                        Span::styled("o/O", Style::default().fg(Color::Rgb(0, 150, 255))),
+                        Span::raw(":Sort | "),
                         Span::styled(",.", Style::default().fg(Color::Rgb(0, 150, 255))),
                         Span::raw(":Pages | "),
-                        Span::styled("U", Style::default().fg(Color::Rgb(0, 150, 255))),
+                        Span::styled("U", self.action_hotkey_style()),
                         Span::raw(":Unban | "),
                         Span::styled("X", Style::default().fg(Color::Rgb(0, 150, 255))),
                         Span::raw(":Export"),
@@ -2827,6 +3460,88 @@ impl App {
         for file in &mut self.state.config_management.config_files {
             file.exists = std::path::Path::new(&file.path).exists();
         }
+        
+        self.refresh_definitions();
+    }
+    
+    /// Rescan filter.d, action.d and jail.d (override pairs and which jails use them)
+    fn refresh_definitions(&mut self) {
+        self.state.config_management.definitions = scan_definitions(&ConfigValidator::new());
+        let index = self.state.config_management.definition_index;
+        self.select_definition_row(index);
+    }
+    
+    fn definition_rows(&self) -> Vec<DefinitionRow> {
+        let config = &self.state.config_management;
+        let mut rows = Vec::new();
+        for dir in DEFINITION_DIRS {
+            rows.push(DefinitionRow::Dir(dir));
+            if config.expanded_dirs.contains(&dir) {
+                rows.extend(config.definitions.iter().enumerate()
+                    .filter(|(_, d)| d.dir == dir)
+                    .map(|(i, _)| DefinitionRow::Definition(i)));
+            }
+        }
+        rows
+    }
+    
+    fn select_definition_row(&mut self, index: usize) {
+        let last = self.definition_rows().len().saturating_sub(1);
+        let config = &mut self.state.config_management;
+        config.definition_index = index.min(last);
+        config.definition_table_state.select(Some(config.definition_index));
+    }
+    
+    /// Expand/collapse a directory, or open a definition's .local (creating the override first)
+    fn activate_definition_row(&mut self) {
+        let rows = self.definition_rows();
+        let Some(&row) = rows.get(self.state.config_management.definition_index) else {
+            return;
+        };
+        
+        let definition = match row {
+            DefinitionRow::Dir(dir) => {
+                let expanded = &mut self.state.config_management.expanded_dirs;
+                match expanded.iter().position(|d| *d == dir) {
+                    Some(position) => { expanded.remove(position); },
+                    None => expanded.push(dir),
+                }
+                return;
+            },
+            DefinitionRow::Definition(index) => self.state.config_management.definitions[index].clone(),
+        };
+        if self.deny_read_only("editing") {
+            return;
+        }
+        
+        let validator = ConfigValidator::new();
+        let local_path = definition.local_path(validator.config_dir());
+        if !definition.has_local {
+            if let Err(e) = create_local_override(&definition, validator.config_dir()) {
+                self.set_status_message(&format!("✗ Could not create {}: {}", local_path.display(), e));
+                log::error!("Failed to create override for {}/{}: {}", definition.dir, definition.name, e);
+                return;
+            }
+            self.refresh_definitions();
+        }
+        
+        self.handle_message(AppMessage::OpenConfigEditor(local_path.display().to_string()));
+        if !definition.has_local && self.state.config_management.editor_open {
+            self.set_status_message(&format!("✓ Created override {} - the packaged .conf stays untouched", local_path.display()));
+        }
+    }
+    
+    /// ← on a definition jumps to its directory and folds it
+    fn collapse_definition_dir(&mut self) {
+        let rows = self.definition_rows();
+        let dir = match rows.get(self.state.config_management.definition_index) {
+            Some(DefinitionRow::Dir(dir)) => *dir,
+            Some(DefinitionRow::Definition(index)) => self.state.config_management.definitions[*index].dir,
+            None => return,
+        };
+        self.state.config_management.expanded_dirs.retain(|d| *d != dir);
+        let dir_row = self.definition_rows().iter().position(|row| *row == DefinitionRow::Dir(dir)).unwrap_or(0);
+        self.select_definition_row(dir_row);
     }
     
     fn render_help(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
@@ -2882,6 +3597,10 @@ impl App {
             Screen::Settings => self.add_settings_help(&mut help_lines),
             Screen::JailEditor => self.add_jail_editor_help(&mut help_lines),
             Screen::Whitelist => self.add_whitelist_help(&mut help_lines),
+            Screen::Audit => self.add_audit_help(&mut help_lines),
+            Screen::Health => self.add_health_help(&mut help_lines),
+            Screen::Statistics => self.add_statistics_help(&mut help_lines),
+            Screen::Subnets => self.add_subnets_help(&mut help_lines),
             _ => self.add_general_help(&mut help_lines),
         }
         
@@ -2897,7 +3616,9 @@ impl App {
             ("C", "Configuration", "W", "Whitelist"),
             ("G", "Settings & Performance", "I", "About & Version"),
             ("F", "Global Refresh", "Q", "Quit Application"),
-            ("B", "Ban IP Dialog", "", ""),
+            ("B", "Ban IP Dialog", "K", "Audit Trail"),
+            ("P", "Service Health", "M", "Statistics"),
+            ("A", "Subnets", "", ""),
             ("", "", "", ""),
         ];
         
@@ -2947,25 +3668,30 @@ impl App {
         lines.push(Line::raw("• [↑/↓] Navigate jail list"));
         lines.push(Line::raw("• [ENTER] Enable/disable selected jail"));
         lines.push(Line::raw("• [E] Edit jail configuration"));
+        lines.push(Line::raw("• [o] Sort by the next column: status, name, bans, failures • [O] Reverse"));
         lines.push(Line::raw(""));
         
         lines.push(Line::from(vec![
             Span::styled("🚫 IP Management (when Banned IPs panel focused):", Style::default().fg(Color::Cyan)),
         ]));
         lines.push(Line::raw("• [↑/↓] Navigate banned IP list"));
+        lines.push(Line::raw("• [ENTER] Show IP details: current bans, ban history, recent log lines"));
         lines.push(Line::raw("• [U] Unban selected IP"));
         lines.push(Line::raw("• [W] Open whitelist dialog"));
         lines.push(Line::raw("• [X] Export banned IPs to CSV"));
+        lines.push(Line::raw("• [o] Sort by the next column: IP, jail, ban time, remaining • [O] Reverse"));
+        lines.push(Line::raw("• Click a column header (▲/▼) to sort by it; click again to reverse"));
+        lines.push(Line::raw("• Ban/Unban lines from the log update the list live; [F] reloads it from fail2ban"));
         lines.push(Line::raw(""));
         
         lines.push(Line::from(vec![
             Span::styled("🔍 Banned IP Filtering (Banned IPs panel):", Style::default().fg(Color::Cyan)),
         ]));
-        lines.push(Line::raw("• [0] Clear all filters"));
-        lines.push(Line::raw("• [1] Cycle IP address filter"));
-        lines.push(Line::raw("• [2] Cycle jail name filter"));
-        lines.push(Line::raw("• [3] Cycle ban age filter"));
-        lines.push(Line::raw("• [4] Cycle remaining time filter"));
+        lines.push(Line::raw("• [/] Type a filter, e.g. jail:sshd ip:203.0.113.0/24 age:<2h remaining:>1d"));
+        lines.push(Line::raw("  Fields: jail, ip, age, remaining (or remaining:permanent), country, asn (GeoIP)"));
+        lines.push(Line::raw("  Terms must all match; combine with AND, OR, NOT and parentheses"));
+        lines.push(Line::raw("  [Tab] Complete • [↑/↓] Browse presets • [Ctrl+S] Save preset • [Ctrl+D] Delete preset"));
+        lines.push(Line::raw("• [0] Clear the filter"));
         lines.push(Line::raw(""));
         
         lines.push(Line::from(vec![
@@ -3003,11 +3729,17 @@ impl App {
         lines.push(Line::raw(""));
         
         let editor_commands = vec![
-            ("[Ctrl+S]", "Save and Close", "Save configuration, reload fail2ban, and close editor"),
+            ("[Ctrl+S]", "Review and Save", "Show a diff of jail.local, then save, reload fail2ban, and close editor"),
             ("[Esc]", "Cancel/Close", "Close editor (warns if unsaved changes)"),
-            ("[Arrow Keys]", "Navigate", "Move cursor within the configuration"),
+            ("[Arrow Keys]", "Navigate", "Move cursor; Ctrl+←/→ by word, Home/End, PgUp/PgDn, Ctrl+Home/End"),
+            ("[Shift+Move]", "Select", "Extend the selection; Ctrl+A selects everything"),
+            ("[Ctrl+C/X/V]", "Clipboard", "Copy, cut and paste the selection"),
+            ("[Ctrl+Z/Y]", "Undo/Redo", "Step back and forward through edits"),
+            ("[Ctrl+F]", "Find", "Incremental search; Enter/F3 next, ↑/Shift+F3 previous"),
+            ("[Ctrl+R]", "Replace", "Regex replace all; Tab switches between pattern and replacement"),
+            ("[Ctrl+E]", "External Editor", "Edit in $VISUAL/$EDITOR, then review the diff as usual"),
             ("[Enter]", "New Line", "Insert line break at cursor position"),
-            ("[Backspace]", "Delete", "Delete character before cursor"),
+            ("[Backspace/Del]", "Delete", "Delete character before/after cursor"),
         ];
         
         for (key, action, desc) in editor_commands {
@@ -3025,9 +3757,13 @@ impl App {
             Span::styled("⚠️  Safety Features:", Style::default().fg(Color::Red)),
         ]));
         lines.push(Line::raw("• Automatic backup created before editing"));
+        lines.push(Line::raw("• Changes shown as a diff before saving ([Enter] save, [Esc] keep editing, [D] discard)"));
+        lines.push(Line::raw("• ✗/⚠ in the gutter flag bad values, unknown or duplicate keys and missing filters/actions"));
         lines.push(Line::raw("• Configuration tested with fail2ban reload"));
         lines.push(Line::raw("• Automatic rollback if reload fails"));
         lines.push(Line::raw("• Changes reverted to maintain system stability"));
+        lines.push(Line::raw("• Edits made to jail.local by other tools are detected;"));
+        lines.push(Line::raw("  Ctrl+S then offers [M]erge, [L]oad disk version or [O]verwrite"));
         lines.push(Line::raw(""));
         lines.push(Line::raw("Edit jail settings like enabled, port, maxretry, bantime, etc."));
     }
@@ -3111,13 +3847,23 @@ impl App {
         lines.push(Line::raw("• [E] Edit selected configuration file"));
         lines.push(Line::raw("• [B] Backup current configuration"));
         lines.push(Line::raw("• [R] Restore configuration from backup"));
-        lines.push(Line::raw("• [T] Test configuration validity"));
+        lines.push(Line::raw("• [T] Test configuration (dry run, does not reload fail2ban)"));
+        lines.push(Line::raw("• [V] Edit selected file in $VISUAL/$EDITOR (Ctrl+E inside the editor)"));
+        lines.push(Line::raw("• [N] New jail from a filter.d template (sshd, nginx, postfix, dovecot defaults)"));
+        lines.push(Line::raw("• [TAB] Switch to the filter.d / action.d / jail.d browser"));
+        lines.push(Line::raw("• [ENTER] on a definition opens its .local, creating an override of the packaged .conf"));
+        lines.push(Line::raw("• [←/→] Fold and unfold directories"));
         lines.push(Line::raw(""));
         lines.push(Line::from(vec![
             Span::styled("⚠️  Best Practice:", Style::default().fg(Color::Yellow)),
         ]));
         lines.push(Line::raw("Always edit .local files, not .conf files"));
         lines.push(Line::raw("Local files override system defaults safely"));
+        lines.push(Line::raw("Ctrl+S in the editor shows a diff of your changes before writing"));
+        lines.push(Line::raw("The editor supports undo (Ctrl+Z), find (Ctrl+F) and regex replace (Ctrl+R)"));
+        lines.push(Line::raw("Lines marked ✗/⚠ in the editor gutter have problems; the message shows below"));
+        lines.push(Line::raw("If another tool changes an open file, the editor shows a banner"));
+        lines.push(Line::raw("and Ctrl+S offers a three-way merge before anything is written"));
     }
     
     fn add_settings_help(&self, lines: &mut Vec<Line>) {
@@ -3128,6 +3874,16 @@ impl App {
         lines.push(Line::raw("Monitor application performance and configure settings:"));
         lines.push(Line::raw(""));
         
+        lines.push(Line::from(vec![
+            Span::styled("🔒 Access:", Style::default().fg(Color::Cyan)),
+        ]));
+        lines.push(Line::raw("• Without root, f2b-buxjr runs read-only if the socket or log is readable"));
+        lines.push(Line::raw("• Greyed-out hotkeys (ban, unban, edit, service) need root"));
+        lines.push(Line::raw("• ✗ marks what the current user cannot reach"));
+        lines.push(Line::raw("• Service manager: init system used for start/stop (--service-manager)"));
+        lines.push(Line::raw("• Notification rules: ban/unban hooks from the settings file (--config)"));
+        lines.push(Line::raw(""));
+        
         lines.push(Line::from(vec![
             Span::styled("📊 Performance Metrics:", Style::default().fg(Color::Cyan)),
         ]));
@@ -3162,6 +3918,85 @@ impl App {
         lines.push(Line::raw("• Performance stats updated every 10 seconds"));
     }
     
+    fn add_audit_help(&self, lines: &mut Vec<Line>) {
+        lines.push(Line::from(vec![
+            Span::styled("📜 Audit Trail Help:", Style::default().fg(Color::Yellow)),
+        ]));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Every ban, unban, whitelist change, jail toggle, config save and"));
+        lines.push(Line::raw("service action taken through f2b-buxjr, newest first:"));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("• [↑/↓] Select an entry; its before/after values show below"));
+        lines.push(Line::raw("• [R] Reload from disk"));
+        lines.push(Line::raw("• [X] Export to CSV"));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("The user shown is the login behind sudo (SUDO_USER)."));
+        lines.push(Line::raw(format!("Entries are appended to {} (root only).", self.audit_log.path().display())));
+        lines.push(Line::raw(""));
+    }
+    
+    fn add_health_help(&self, lines: &mut Vec<Line>) {
+        lines.push(Line::from(vec![
+            Span::styled("🩺 Service Health Help:", Style::default().fg(Color::Yellow)),
+        ]));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Combines the service manager with what the daemon itself answers:"));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("• Healthy: running and the socket answers fail2ban-client ping"));
+        lines.push(Line::raw("• Degraded: ping slower than 1s, or ERROR log entries in the last hour"));
+        lines.push(Line::raw("• SOCKET DEAD: the service runs but the socket does not answer"));
+        lines.push(Line::raw("• [R] Re-check now, including version and database settings"));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("The header badge next to the service symbol shows the same summary."));
+        lines.push(Line::raw(""));
+    }
+    
+    fn add_statistics_help(&self, lines: &mut Vec<Line>) {
+        lines.push(Line::from(vec![
+            Span::styled("📈 Statistics Help:", Style::default().fg(Color::Yellow)),
+        ]));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Ban, Unban and Found lines from the fail2ban log over time:"));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("• [2] Cycle the window: last 1 hour, 24 hours, week"));
+        lines.push(Line::raw("• The chart shows all jails; each jail row has its own ban sparkline"));
+        lines.push(Line::raw("• The dashboard sparklines follow the same window"));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Tables below the chart, over the same window:"));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("• [Tab] Switch table: top IPs, /24 and /64 prefixes, jails, hours of day, repeat offenders"));
+        lines.push(Line::raw("• [↑/↓] Select a row"));
+        lines.push(Line::raw("• [ENTER] IP details for the selected address (or the busiest address of a prefix)"));
+        lines.push(Line::raw("• [X] Export the table to CSV"));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Bans come from the log plus current bans older than the log file."));
+        lines.push(Line::raw("Counts are kept in memory for one week, starting with the current log file."));
+        lines.push(Line::raw(""));
+    }
+    
+    fn add_subnets_help(&self, lines: &mut Vec<Line>) {
+        lines.push(Line::from(vec![
+            Span::styled("🧮 Subnets Help:", Style::default().fg(Color::Yellow)),
+        ]));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Banned IPs grouped by network, most banned hosts first:"));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("• [4] Cycle the IPv4 prefix length: /16, /20, /22, /24, /28"));
+        lines.push(Line::raw("• [6] Cycle the IPv6 prefix length: /32, /48, /56, /64"));
+        lines.push(Line::raw("• [↑/↓] Select a range"));
+        lines.push(Line::raw("• [ENTER] Replace the host bans with one range ban in a chosen jail"));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Density is the share of the range's addresses that are banned."));
+        lines.push(Line::raw("Ranges with 5 or more banned hosts are suggested for a range ban."));
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Range ban dialog:"));
+        lines.push(Line::raw("• [←/→] Choose the jail; jails already banning hosts in the range come first"));
+        lines.push(Line::raw("• [ENTER] Ban the range, then unban its hosts in that jail"));
+        lines.push(Line::raw("• Refused when the range overlaps a whitelist (ignoreip) entry"));
+        lines.push(Line::raw("• Host bans in other jails stay, as their actions may block other ports"));
+        lines.push(Line::raw(""));
+    }
+    
     fn add_whitelist_help(&self, lines: &mut Vec<Line>) {
         lines.push(Line::from(vec![
             Span::styled("🛡️  IP Whitelist Management Help:", Style::default().fg(Color::Yellow)),
@@ -3214,7 +4049,7 @@ impl App {
         ]));
         lines.push(Line::raw("• Run with 'sudo' for full functionality"));
         lines.push(Line::raw("• Use TAB to switch focus between panels"));
-        lines.push(Line::raw("• Use number keys (1-4) for quick filtering"));
+        lines.push(Line::raw("• Use number keys (1-4) to filter logs and / to filter banned IPs"));
         lines.push(Line::raw("• Use log filters to focus on specific events"));
         lines.push(Line::raw("• Monitor performance in Settings screen (G)"));
         lines.push(Line::raw("• Check banned IPs regularly for false positives"));
@@ -3331,185 +4166,783 @@ impl App {
         }
     }
     
-    fn render_ban_dialog(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
-        // Apply standard dialog clearing pattern
-        frame.render_widget(Clear, area);
-        
-        // Create a completely solid background using filled text to ensure no bleed-through
-        let overlay = " ".repeat((area.width * area.height) as usize);
-        let solid_background = Paragraph::new(overlay)
-            .style(Style::default().bg(Color::Black))
-            .wrap(Wrap { trim: false });
-        frame.render_widget(solid_background, area);
-        
-        // Create a centered popup (increased height to fit all content)
-        let popup_area = centered_rect(70, 60, area);
-        
-        // Then render the dialog border
-        let dialog_border = Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Yellow))
-            .title(" Manually Ban IP ");
-        frame.render_widget(dialog_border, popup_area);
-        
-        // Create inner area for content (accounting for borders)
-        let inner_area = popup_area.inner(&ratatui::layout::Margin { horizontal: 1, vertical: 1 });
-        
+    fn render_audit(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let hotkey_color = Color::Rgb(0, 150, 255);
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3),  // IP input
-                Constraint::Length(3),  // Jail selection
-                Constraint::Length(3),  // Bantime display
-                Constraint::Length(4),  // Buttons/instructions
+                Constraint::Min(8),      // Entries
+                Constraint::Length(9),   // Before/after of the selected entry
             ])
-            .split(inner_area);
+            .split(area);
         
-        // IP input
-        let ip_text = if self.state.ip_management.ban_ip_input.is_empty() {
-            "Type IP address here...".to_string()
-        } else {
-            format!("{}_", self.state.ip_management.ban_ip_input) // Add cursor
-        };
+        let rows: Vec<Row> = self.state.audit_entries.iter().map(|entry| {
+            let result_color = if entry.success { Color::Green } else { Color::Red };
+            Row::new(vec![
+                Cell::from(entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string()),
+                Cell::from(entry.user.clone()),
+                Cell::from(entry.action.label()),
+                Cell::from(entry.target.clone()),
+                Cell::from(entry.result_label()).style(Style::default().fg(result_color)),
+            ])
+        }).collect();
         
-        let ip_active = self.state.ip_management.ban_dialog_field_index == 0;
-        let ip_input = Paragraph::new(ip_text)
-            .style(if ip_active { 
-                Style::default().fg(Color::White).bg(Color::Blue) 
-            } else { 
-                Style::default().fg(Color::Gray).bg(Color::Black) 
-            })
-            .alignment(Alignment::Center)
-            .block(Block::default()
-                .title(" Enter IP Address ")
-                .borders(Borders::ALL)
-                .border_style(if ip_active {
-                    Style::default().fg(Color::Yellow)
-                } else {
-                    Style::default().fg(Color::Gray)
-                }));
-        frame.render_widget(ip_input, chunks[0]);
+        let title = Line::from(vec![
+            Span::raw(format!("Audit Trail ({} entries, {}) - ", self.state.audit_entries.len(), self.audit_log.path().display())),
+            Span::styled("↑↓", Style::default().fg(hotkey_color)),
+            Span::raw(":Navigate | "),
+            Span::styled("R", Style::default().fg(hotkey_color)),
+            Span::raw(":Reload | "),
+            Span::styled("X", Style::default().fg(hotkey_color)),
+            Span::raw(":Export | "),
+            Span::styled("ESC", Style::default().fg(hotkey_color)),
+            Span::raw(":Back"),
+        ]);
         
-        // Jail selection
-        let jail_count = self.state.jails.len();
-        let jail_active = self.state.ip_management.ban_dialog_field_index == 1;
-        let jail_text = if jail_count == 0 {
-            "No active jails available (check fail2ban service)".to_string()
-        } else {
-            let selected_jail = self.state.ip_management.selected_jail_for_ban
-                .as_ref()
-                .map(|s| s.as_str())
-                .unwrap_or("(none selected)");
-            
-            if jail_active {
-                format!("▶ {} ◀ (↑/↓ to change)", selected_jail)
-            } else {
-                selected_jail.to_string()
+        let table = Table::new(rows, [
+            Constraint::Length(20),  // Time
+            Constraint::Length(12),  // User
+            Constraint::Length(17),  // Action
+            Constraint::Min(30),     // Target
+            Constraint::Length(24),  // Result
+        ])
+        .header(Row::new(vec![
+            Cell::from("Time").style(Style::default().fg(Color::Yellow)),
+            Cell::from("User").style(Style::default().fg(Color::Yellow)),
+            Cell::from("Action").style(Style::default().fg(Color::Yellow)),
+            Cell::from("Target").style(Style::default().fg(Color::Yellow)),
+            Cell::from("Result").style(Style::default().fg(Color::Yellow)),
+        ]))
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().bg(Color::DarkGray));
+        frame.render_stateful_widget(table, chunks[0], &mut self.state.audit_table_state);
+        
+        // Details of the selected entry
+        let mut lines = Vec::new();
+        if let Some(ref error) = self.state.audit_error {
+            lines.push(Line::styled(error.clone(), Style::default().fg(Color::Red)));
+        } else if let Some(entry) = self.state.audit_entries.get(self.state.audit_selected_index) {
+            if let Some(ref error) = entry.error {
+                lines.push(Line::styled(format!("Error: {}", error), Style::default().fg(Color::Red)));
             }
-        };
-        let jail_selection = Paragraph::new(jail_text)
-            .style(if jail_active { 
-                Style::default().fg(Color::White).bg(Color::Blue) 
-            } else { 
-                Style::default().fg(Color::Gray) 
-            })
-            .alignment(Alignment::Center)
-            .block(Block::default()
-                .title("Jail")
-                .borders(Borders::ALL)
-                .border_style(if jail_active {
-                    Style::default().fg(Color::Yellow)
+            for line in entry.before.iter().flat_map(|b| b.lines()) {
+                lines.push(Line::styled(format!("- {}", line), Style::default().fg(Color::Red)));
+            }
+            for line in entry.after.iter().flat_map(|a| a.lines()) {
+                lines.push(Line::styled(format!("+ {}", line), Style::default().fg(Color::Green)));
+            }
+            for line in entry.diff.iter().flat_map(|d| d.lines()) {
+                let color = if line.starts_with("---") || line.starts_with("+++") {
+                    Color::Yellow
                 } else {
-                    Style::default().fg(Color::Gray)
-                }));
-        frame.render_widget(jail_selection, chunks[1]);
-        
-        // Bantime display (read-only, shows jail's configured bantime)
-        let (bantime_text, bantime_title) = if let Some(ref selected_jail) = self.state.ip_management.selected_jail_for_ban {
-            let bantime = self.get_jail_bantime(selected_jail);
-            (bantime, format!(" Ban Duration (jail configured: {}) ", selected_jail))
+                    match line.chars().next() {
+                        Some('@') => Color::Cyan,
+                        Some('+') => Color::Green,
+                        Some('-') => Color::Red,
+                        _ => Color::Gray,
+                    }
+                };
+                lines.push(Line::styled(line.to_string(), Style::default().fg(color)));
+            }
+            if lines.is_empty() {
+                lines.push(Line::styled("No before/after values for this action", Style::default().fg(Color::Gray)));
+            }
         } else {
-            ("Select a jail to see bantime".to_string(), " Ban Duration (jail configured) ".to_string())
-        };
-        let bantime_display = Paragraph::new(bantime_text)
-            .style(Style::default().fg(Color::Cyan))
-            .alignment(Alignment::Center)
-            .block(Block::default()
-                .title(bantime_title)
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)));
-        frame.render_widget(bantime_display, chunks[2]);
-        
-        // Instructions and buttons
-        let current_field = match self.state.ip_management.ban_dialog_field_index {
-            0 => "IP Address",
-            1 => "Jail (use ↑/↓)",
-            _ => "Unknown"
-        };
-        
-        let instructions = vec![
-            Line::from(vec![
-                Span::styled("Current field: ", Style::default().fg(Color::Gray)),
-                Span::styled(current_field, Style::default().fg(Color::Yellow)),
-            ]),
-            Line::from(vec![
-                Span::styled("[Tab]", Style::default().fg(Color::Green)),
-                Span::styled(" Next Field  ", Style::default().fg(Color::Gray)),
-                Span::styled("[Enter]", Style::default().fg(Color::Green)),
-                Span::styled(" Ban IP  ", Style::default().fg(Color::Gray)),
-                Span::styled("[Esc]", Style::default().fg(Color::Red)),
-                Span::styled(" Cancel", Style::default().fg(Color::Gray)),
-            ]),
-        ];
+            lines.push(Line::styled("No administrative actions recorded yet", Style::default().fg(Color::Gray)));
+        }
         
-        let buttons = Paragraph::new(instructions)
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
-        frame.render_widget(buttons, chunks[3]);
+        let details = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Change"))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(details, chunks[1]);
     }
     
-    fn render_unban_confirmation(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
-        // Apply standard dialog clearing pattern
-        frame.render_widget(Clear, area);
-        
-        // Create a completely solid background using filled text to ensure no bleed-through
-        let overlay = " ".repeat((area.width * area.height) as usize);
-        let solid_background = Paragraph::new(overlay)
-            .style(Style::default().bg(Color::Black))
-            .wrap(Wrap { trim: false });
-        frame.render_widget(solid_background, area);
-        
-        let popup_area = centered_rect(50, 40, area);
-        
-        // Then render the dialog border
-        let dialog_border = Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Red))
-            .title(" Confirm Unban ");
-        frame.render_widget(dialog_border, popup_area);
-        
+    fn render_statistics(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let hotkey_color = Color::Rgb(0, 150, 255);
+        let history = &self.state.event_history;
+        let window = self.state.stats_window_hours;
+        let now = log_clock_now();
+        let jails = history.jail_totals(window, now);
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3),  // Title
-                Constraint::Min(3),     // Message
-                Constraint::Length(4),  // Buttons - right size for instruction text
+                Constraint::Percentage(35),  // Event chart
+                Constraint::Length((jails.len().max(1) as u16 + 2).min(area.height / 4)),
+                Constraint::Min(8),          // Offender tables
             ])
-            .split(popup_area);
-        
-        let title = Paragraph::new("Confirm Unban")
-            .style(Style::default().fg(Color::Red))
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
-        frame.render_widget(title, chunks[0]);
-        
-        let default_ip = "Unknown".to_string();
-        let default_jail = "Unknown".to_string();
-        let ip = self.state.ip_management.ip_to_unban.as_ref().unwrap_or(&default_ip);
-        let jail = self.state.ip_management.jail_for_unban.as_ref().unwrap_or(&default_jail);
+            .split(area);
+        self.render_statistics_table(frame, chunks[2]);
+        let history = &self.state.event_history;
         
-        let message = Paragraph::new(format!("Unban IP {} from jail {}?", ip, jail))
-            .style(Style::default().fg(Color::White))
+        // All jails, one line per event kind
+        let points = (chunks[0].width.saturating_sub(12) as usize).clamp(10, 120);
+        let series = |kind: EventKind| history.series(None, kind, window, points, now)
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| (i as f64, count as f64))
+            .collect::<Vec<_>>();
+        let (bans, unbans, found) = (series(EventKind::Ban), series(EventKind::Unban), series(EventKind::Found));
+        let peak = bans.iter().chain(&unbans).chain(&found).map(|(_, count)| *count).fold(1.0, f64::max);
+        let datasets = vec![
+            Dataset::default().name("Found").marker(symbols::Marker::Braille).graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Yellow)).data(&found),
+            Dataset::default().name("Unban").marker(symbols::Marker::Braille).graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Green)).data(&unbans),
+            Dataset::default().name("Ban").marker(symbols::Marker::Braille).graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Red)).data(&bans),
+        ];
+        let totals = history.totals(window, now);
+        let title = Line::from(vec![
+            Span::raw(format!("Events - last {}: {} bans, {} unbans, {} found - ", window_label(window), totals.bans, totals.unbans, totals.found)),
+            Span::styled("2", Style::default().fg(hotkey_color)),
+            Span::raw(":Window | "),
+            Span::styled("ESC", Style::default().fg(hotkey_color)),
+            Span::raw(":Back"),
+        ]);
+        let axis_style = Style::default().fg(Color::Gray);
+        let chart = Chart::new(datasets)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .x_axis(Axis::default().style(axis_style).bounds([0.0, (points - 1) as f64])
+                .labels(vec![Span::raw(format!("-{}", window_label(window))), Span::raw("now")]))
+            .y_axis(Axis::default().style(axis_style).bounds([0.0, peak])
+                .labels(vec![Span::raw("0"), Span::raw(format!("{}", peak as u64))]));
+        frame.render_widget(chart, chunks[0]);
+        
+        // Busiest jails first, each with its own ban sparkline
+        let block = Block::default().borders(Borders::ALL).title(format!("Per Jail - last {}", window_label(window)));
+        let inner = block.inner(chunks[1]);
+        frame.render_widget(block, chunks[1]);
+        if jails.is_empty() {
+            let empty = Paragraph::new("No Ban, Unban or Found lines in this window").style(Style::default().fg(Color::Gray));
+            frame.render_widget(empty, inner);
+            return;
+        }
+        for (row, (jail, counts)) in jails.iter().take(inner.height as usize).enumerate() {
+            let line = ratatui::layout::Rect { y: inner.y + row as u16, height: 1, ..inner };
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(54), Constraint::Min(10)])
+                .split(line);
+            let label = Paragraph::new(Line::from(vec![
+                Span::styled(format!("{:<18.18}", jail), Style::default().fg(Color::Cyan)),
+                Span::styled(format!("{:>6} ban", counts.bans), Style::default().fg(Color::Red)),
+                Span::styled(format!("{:>6} unban", counts.unbans), Style::default().fg(Color::Green)),
+                Span::styled(format!("{:>7} found", counts.found), Style::default().fg(Color::Yellow)),
+            ]));
+            frame.render_widget(label, columns[0]);
+            let data = history.series(Some(jail), EventKind::Ban, window, columns[1].width as usize, now);
+            frame.render_widget(Sparkline::default().data(&data).style(Style::default().fg(Color::Red)), columns[1]);
+        }
+    }
+    
+    /// Top IPs, prefixes, jails, hours and repeat offenders, one at a time
+    fn render_statistics_table(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let hotkey_color = Color::Rgb(0, 150, 255);
+        let table = self.statistics_table();
+        let last = table.rows.len().saturating_sub(1);
+        self.state.stats_selected_index = self.state.stats_selected_index.min(last);
+        self.state.stats_table_state.select(if table.rows.is_empty() { None } else { Some(self.state.stats_selected_index) });
+        
+        let mut title = Vec::new();
+        for kind in StatsTable::ALL {
+            let style = if kind == self.state.stats_table {
+                Style::default().fg(Color::Black).bg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            title.push(Span::styled(format!(" {} ", kind.title()), style));
+        }
+        title.push(Span::raw(format!(" - last {} - ", window_label(self.state.stats_window_hours))));
+        for (key, action) in [("Tab", ":Table | "), ("↑↓", ":Select | "), ("ENTER", ":Details | "), ("X", ":Export")] {
+            title.push(Span::styled(key, Style::default().fg(hotkey_color)));
+            title.push(Span::raw(action));
+        }
+        let block = Block::default().borders(Borders::ALL).title(Line::from(title));
+        
+        if table.rows.is_empty() {
+            let empty = Paragraph::new("No bans in this window").style(Style::default().fg(Color::Gray)).block(block);
+            frame.render_widget(empty, area);
+            return;
+        }
+        
+        // Each column as wide as its widest cell
+        let widths = table.headers.iter().enumerate()
+            .map(|(column, header)| {
+                let widest = table.rows.iter().map(|row| row[column].width()).max().unwrap_or(0);
+                Constraint::Length(widest.max(header.width()) as u16 + 2)
+            })
+            .collect::<Vec<_>>();
+        let rows = table.rows.iter().map(|row| {
+            Row::new(row.iter().enumerate().map(|(column, cell)| {
+                let color = if column == 0 { Color::Cyan } else { Color::White };
+                Cell::from(cell.clone()).style(Style::default().fg(color))
+            }))
+        });
+        let widget = Table::new(rows, widths)
+            .header(Row::new(table.headers.iter().map(|header| Cell::from(*header).style(Style::default().fg(Color::Yellow)))))
+            .block(block)
+            .highlight_style(Style::default().bg(Color::DarkGray));
+        frame.render_stateful_widget(widget, area, &mut self.state.stats_table_state);
+    }
+    
+    fn render_subnets(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let hotkey_color = Color::Rgb(0, 150, 255);
+        let groups = self.subnet_groups();
+        let last = groups.len().saturating_sub(1);
+        self.state.subnet_selected_index = self.state.subnet_selected_index.min(last);
+        self.state.subnet_table_state.select(if groups.is_empty() { None } else { Some(self.state.subnet_selected_index) });
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(8),     // Ranges
+                Constraint::Length(5),  // Selected range
+            ])
+            .split(area);
+        
+        let suggested = groups.iter().filter(|group| group.is_suggested()).count();
+        let mut title = vec![Span::raw(format!(
+            "Subnets - IPv4 /{}, IPv6 /{} - {} ranges, {} suggested - ",
+            self.state.subnet_ipv4_prefix, self.state.subnet_ipv6_prefix, groups.len(), suggested,
+        ))];
+        for (key, action) in [("4", ":IPv4 | "), ("6", ":IPv6 | "), ("↑↓", ":Select | "), ("ENTER", ":Ban Range | "), ("ESC", ":Back")] {
+            title.push(Span::styled(key, Style::default().fg(hotkey_color)));
+            title.push(Span::raw(action));
+        }
+        let block = Block::default().borders(Borders::ALL).title(Line::from(title));
+        if groups.is_empty() {
+            let empty = Paragraph::new("No banned IPs").style(Style::default().fg(Color::Gray)).block(block);
+            frame.render_widget(empty, chunks[0]);
+            return;
+        }
+        
+        let rows = groups.iter().map(|group| {
+            let jails = group.jails().iter().map(|(jail, hosts)| format!("{}:{}", jail, hosts)).collect::<Vec<_>>().join(" ");
+            let range_jails = group.banned_as_range_in();
+            let (status, color) = if !range_jails.is_empty() {
+                (format!("range banned in {}", range_jails.join(" ")), Color::Green)
+            } else if group.is_suggested() {
+                ("⚠ suggest range ban".to_string(), Color::Yellow)
+            } else {
+                (String::new(), Color::Gray)
+            };
+            Row::new(vec![
+                Cell::from(group.range.to_string()).style(Style::default().fg(Color::Cyan)),
+                Cell::from(group.host_count().to_string()).style(Style::default().fg(Color::White)),
+                Cell::from(format_density(group.density())).style(Style::default().fg(Color::White)),
+                Cell::from(jails).style(Style::default().fg(Color::White)),
+                Cell::from(status).style(Style::default().fg(color)),
+            ])
+        });
+        let table = Table::new(rows, [
+            Constraint::Length(44),
+            Constraint::Length(7),
+            Constraint::Length(9),
+            Constraint::Min(20),
+            Constraint::Length(28),
+        ])
+        .header(Row::new(vec![
+            Cell::from("Range").style(Style::default().fg(Color::Yellow)),
+            Cell::from("Hosts").style(Style::default().fg(Color::Yellow)),
+            Cell::from("Density").style(Style::default().fg(Color::Yellow)),
+            Cell::from("Jails (hosts)").style(Style::default().fg(Color::Yellow)),
+            Cell::from("Status").style(Style::default().fg(Color::Yellow)),
+        ]))
+        .block(block)
+        .highlight_style(Style::default().bg(Color::DarkGray));
+        frame.render_stateful_widget(table, chunks[0], &mut self.state.subnet_table_state);
+        
+        // Hosts of the selected range and whether a range ban would hit the whitelist
+        let mut lines = Vec::new();
+        if let Some(group) = groups.get(self.state.subnet_selected_index) {
+            let hosts = group.host_bans.keys().map(|ip| ip.to_string()).collect::<Vec<_>>();
+            lines.push(Line::from(vec![
+                Span::raw("Hosts: "),
+                Span::styled(hosts.join(" "), Style::default().fg(Color::White)),
+            ]));
+            let overlaps = whitelist_overlaps(&group.range, &self.state.whitelist_ips);
+            lines.push(if overlaps.is_empty() {
+                Line::styled("Whitelist: ✓ no overlap", Style::default().fg(Color::Green))
+            } else {
+                Line::styled(format!("Whitelist: ✗ overlaps {} - no range ban possible", overlaps.join(", ")), Style::default().fg(Color::Red))
+            });
+        }
+        let details = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Selected Range"))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(details, chunks[1]);
+    }
+    
+    fn render_range_ban_dialog(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let Some(ref dialog) = self.state.range_ban_dialog else {
+            return;
+        };
+        
+        // Apply standard dialog clearing pattern
+        frame.render_widget(Clear, area);
+        let overlay = " ".repeat((area.width * area.height) as usize);
+        let solid_background = Paragraph::new(overlay)
+            .style(Style::default().bg(Color::Black))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(solid_background, area);
+        
+        let popup_area = centered_rect(60, 40, area);
+        let jail = &dialog.jails[dialog.jail_index];
+        let mut lines = vec![
+            Line::from(vec![
+                Span::raw("Range:  "),
+                Span::styled(dialog.group.range.to_string(), Style::default().fg(Color::Cyan)),
+                Span::raw(format!("  ({} banned hosts)", dialog.group.host_count())),
+            ]),
+            Line::from(vec![
+                Span::raw("Jail:   "),
+                Span::styled(format!("◀ {} ▶", jail), Style::default().fg(Color::Yellow)),
+            ]),
+            Line::raw(""),
+        ];
+        let confirm = match plan_range_ban(&dialog.group, jail, &self.state.whitelist_ips) {
+            Ok(plan) => {
+                lines.push(Line::raw(format!("Bans {} in {} and unbans the {} host(s) it covers there.", plan.range, plan.jail, plan.replaces.len())));
+                let elsewhere = dialog.group.host_count() - plan.replaces.len();
+                if elsewhere > 0 {
+                    lines.push(Line::styled(format!("{} host(s) banned only in other jails keep their bans.", elsewhere), Style::default().fg(Color::Gray)));
+                }
+                lines.push(Line::styled("Whitelist: ✓ no overlap", Style::default().fg(Color::Green)));
+                "[Enter] Ban range • [←/→] Jail • [Esc] Cancel"
+            },
+            Err(e) => {
+                lines.push(Line::styled(format!("✗ {}", e), Style::default().fg(Color::Red)));
+                "[Esc] Cancel"
+            },
+        };
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(confirm, Style::default().fg(Color::Gray)));
+        
+        let dialog_widget = Paragraph::new(lines)
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red))
+                .title(" Replace Host Bans with Range Ban "))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(dialog_widget, popup_area);
+    }
+    
+    fn render_filter_prompt(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let Some(ref prompt) = self.state.filter_prompt else {
+            return;
+        };
+        
+        let cursor = Span::styled("█", Style::default().fg(Color::Gray));
+        let mut input_line = vec![
+            Span::raw("Filter:  "),
+            Span::styled(prompt.input.clone(), Style::default().fg(Color::Cyan)),
+        ];
+        if prompt.naming.is_none() {
+            input_line.push(cursor.clone());
+        }
+        let mut lines = vec![Line::from(input_line)];
+        
+        let names = self.filter_presets.names();
+        if let Some(name) = prompt.preset_index.and_then(|i| names.get(i)) {
+            lines.push(Line::styled(
+                format!("Preset:  {} ({}/{})", name, prompt.preset_index.unwrap_or(0) + 1, names.len()),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        if let Some(ref name) = prompt.naming {
+            lines.push(Line::from(vec![
+                Span::raw("Save as: "),
+                Span::styled(name.clone(), Style::default().fg(Color::Yellow)),
+                cursor,
+            ]));
+        }
+        if let Some(ref error) = prompt.error {
+            lines.push(Line::styled(format!("✗ {}", error), Style::default().fg(Color::Red)));
+        }
+        if !prompt.completions.is_empty() {
+            lines.push(Line::styled(prompt.completions.join("  "), Style::default().fg(Color::Gray)));
+        }
+        
+        lines.push(Line::raw(""));
+        lines.push(Line::styled("Fields: jail:sshd  ip:203.0.113.0/24  age:<2h  remaining:>1d  remaining:permanent", Style::default().fg(Color::Gray)));
+        let mut combine = "Combine with AND, OR, NOT and ( ); a bare word matches part of the IP or jail".to_string();
+        if self.geoip.is_enabled() {
+            combine = format!("country:CN  asn:14061  •  {}", combine);
+        }
+        lines.push(Line::styled(combine, Style::default().fg(Color::Gray)));
+        lines.push(Line::raw(""));
+        let keys = if prompt.naming.is_some() {
+            "[Enter] Save preset • [Esc] Back"
+        } else {
+            "[Enter] Apply • [Tab] Complete • [↑/↓] Presets • [Ctrl+S] Save • [Ctrl+D] Delete • [Esc] Cancel"
+        };
+        lines.push(Line::styled(keys, Style::default().fg(Color::Gray)));
+        
+        // Just tall enough for its lines, so most of the table stays readable behind it
+        let width = centered_rect(70, 100, area).width;
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup_area = ratatui::layout::Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        frame.render_widget(Clear, popup_area);
+        
+        let dialog_widget = Paragraph::new(lines)
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Rgb(0, 150, 255)))
+                .title(" Filter Banned IPs "))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(dialog_widget, popup_area);
+    }
+    
+    fn render_ip_detail(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let ip = match self.state.ip_detail {
+            Some(ref ip) => ip,
+            None => return,
+        };
+        
+        // Apply standard dialog clearing pattern
+        frame.render_widget(Clear, area);
+        let overlay = " ".repeat((area.width * area.height) as usize);
+        let solid_background = Paragraph::new(overlay)
+            .style(Style::default().bg(Color::Black))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(solid_background, area);
+        
+        let popup_area = centered_rect(70, 70, area);
+        let section = |title: &'static str| Line::styled(title, Style::default().fg(Color::Yellow));
+        let field = |label: &str, value: String, color: Color| Line::from(vec![
+            Span::raw(format!("  {:<14}", label)),
+            Span::styled(value, Style::default().fg(color)),
+        ]);
+        
+        let mut lines = vec![
+            field("Address", ip.clone(), Color::Cyan),
+            field("Network", network_prefix(ip).unwrap_or_else(|| "—".to_string()), Color::White),
+            field("Whitelisted", if self.state.whitelist_ips.contains(ip) { "yes".to_string() } else { "no".to_string() }, Color::White),
+        ];
+        if self.geoip.is_enabled() {
+            let geo = self.geoip.lookup(ip);
+            let country = match (geo.country, geo.country_name) {
+                (Some(code), Some(name)) => format!("{} ({})", code, name),
+                (code, name) => code.or(name).unwrap_or_else(|| "unknown".to_string()),
+            };
+            lines.push(field("Country", country, Color::White));
+            lines.push(field("AS", geo.asn.map_or_else(|| "unknown".to_string(), |asn| format!("AS{}", asn)), Color::White));
+            lines.push(field("Organisation", geo.org.unwrap_or_else(|| "unknown".to_string()), Color::White));
+        }
+        if self.rdns.is_enabled() {
+            let hostname = match self.rdns.peek(ip) {
+                Hostname::Found(name) => name,
+                Hostname::Pending => "resolving…".to_string(),
+                Hostname::NotFound => "none".to_string(),
+            };
+            lines.push(field("Hostname", hostname, Color::White));
+        }
+        lines.push(Line::raw(""));
+        lines.push(section("Current Bans:"));
+        let current = self.state.banned_ips.iter().filter(|banned| &banned.ip == ip).collect::<Vec<_>>();
+        if current.is_empty() {
+            lines.push(Line::styled("  Not banned in any jail", Style::default().fg(Color::Gray)));
+        }
+        for banned in current {
+            let until = banned.unban_time
+                .map(|at| at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| "permanent".to_string());
+            lines.push(field(&banned.jail, format!("since {} until {}", banned.ban_time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"), until), Color::Red));
+        }
+        
+        // Always the longest window, whatever the Statistics screen shows
+        let window = STATS_WINDOWS[STATS_WINDOWS.len() - 1];
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(format!("Ban History (last {}):", window_label(window)), Style::default().fg(Color::Yellow)));
+        let statistics = BanStatistics::collect(self.state.banned_ips.as_slice(), &self.state.event_history, window, log_clock_now());
+        match statistics.offender(ip) {
+            Some(offender) => {
+                lines.push(field("Bans", format!("{} in {} jail(s)", offender.bans, offender.jails.len()), Color::White));
+                lines.push(field("First", offender.first.format("%Y-%m-%d %H:%M").to_string(), Color::White));
+                lines.push(field("Last", offender.last.format("%Y-%m-%d %H:%M").to_string(), Color::White));
+                for (jail, bans) in &offender.jails {
+                    lines.push(field(jail, format!("{} ban(s)", bans), Color::Cyan));
+                }
+            },
+            None => lines.push(Line::styled("  No bans recorded", Style::default().fg(Color::Gray))),
+        }
+        
+        lines.push(Line::raw(""));
+        lines.push(section("Recent Log Lines:"));
+        let recent = self.state.log_entries.iter().rev().filter(|entry| entry.message.contains(ip.as_str())).take(10).collect::<Vec<_>>();
+        if recent.is_empty() {
+            lines.push(Line::styled("  None in the loaded log", Style::default().fg(Color::Gray)));
+        }
+        for entry in recent.into_iter().rev() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {} ", entry.timestamp.format("%m-%d %H:%M:%S")), Style::default().fg(Color::Gray)),
+                Span::raw(entry.message.clone()),
+            ]));
+        }
+        
+        let dialog = Paragraph::new(lines)
+            .block(Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(format!(" IP Details: {} - [Esc] Close ", ip)))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(dialog, popup_area);
+    }
+    
+    fn render_health(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let hotkey_color = Color::Rgb(0, 150, 255);
+        let health = &self.state.health;
+        let level = health.level();
+        let section = |title: &'static str| Line::styled(title, Style::default().fg(Color::Yellow));
+        let field = |label: &str, value: String, color: Color| Line::from(vec![
+            Span::raw(format!("  {:<14}", label)),
+            Span::styled(value, Style::default().fg(color)),
+        ]);
+        let known = |value: &Option<String>| value.clone().unwrap_or_else(|| "—".to_string());
+        
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(format!("{:?}", level), Style::default().fg(health_color(level))),
+                Span::raw(format!("  {}", health.explanation())),
+            ]),
+            Line::raw(""),
+            section("Service Manager:"),
+            field("Backend", self.system_service.backend().label().to_string(), Color::Cyan),
+            field("State", format!("{} {:?}", self.state.fail2ban_service.symbol(), self.state.fail2ban_service), self.state.fail2ban_service.color()),
+        ];
+        if let Some(ref unit) = self.state.service_unit {
+            lines.push(field("Unit", format!("[{}] {}", unit.sub_state, unit.summary()).trim_end().to_string(), Color::Gray));
+            if let Some(ref failure) = unit.failure {
+                lines.push(field("Last failure", failure.clone(), Color::Red));
+            }
+        }
+        
+        lines.push(Line::raw(""));
+        lines.push(section("Socket:"));
+        lines.push(field("Path", fail2ban_paths().socket_path().display().to_string(), Color::White));
+        lines.push(match health.ping {
+            Some(Ok(latency)) => field("Ping", format!("✓ pong in {} ms", latency.as_millis()), if level == HealthLevel::Degraded { Color::Yellow } else { Color::Green }),
+            Some(Err(ref e)) => field("Ping", format!("✗ {}", e), Color::Red),
+            None => field("Ping", "not probed (no socket access)".to_string(), Color::Gray),
+        });
+        
+        lines.push(Line::raw(""));
+        lines.push(section("Daemon:"));
+        lines.push(field("Version", known(&health.daemon.version), Color::White));
+        lines.push(field("Database", known(&health.daemon.dbfile), Color::White));
+        lines.push(field("Purge age", known(&health.daemon.dbpurgeage), Color::White));
+        lines.push(field("Log level", known(&health.daemon.loglevel), Color::White));
+        lines.push(field("Log target", known(&health.daemon.logtarget), Color::White));
+        
+        lines.push(Line::raw(""));
+        lines.push(section("Recent Errors:"));
+        if health.recent_errors.is_empty() {
+            lines.push(Line::styled("  No ERROR entries in the log", Style::default().fg(Color::Gray)));
+        }
+        for entry in &health.recent_errors {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {} ", entry.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")), Style::default().fg(Color::Gray)),
+                Span::styled(entry.message.clone(), Style::default().fg(Color::Red)),
+            ]));
+        }
+        
+        let checked = health.checked_at.map(|t| t.format("%H:%M:%S").to_string()).unwrap_or_else(|| "never".to_string());
+        let title = Line::from(vec![
+            Span::raw(format!("Service Health (checked {}) - ", checked)),
+            Span::styled("R", Style::default().fg(hotkey_color)),
+            Span::raw(":Re-check | "),
+            Span::styled("ESC", Style::default().fg(hotkey_color)),
+            Span::raw(":Back"),
+        ]);
+        let widget = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(widget, area);
+    }
+    
+    fn render_ban_dialog(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        // Apply standard dialog clearing pattern
+        frame.render_widget(Clear, area);
+        
+        // Create a completely solid background using filled text to ensure no bleed-through
+        let overlay = " ".repeat((area.width * area.height) as usize);
+        let solid_background = Paragraph::new(overlay)
+            .style(Style::default().bg(Color::Black))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(solid_background, area);
+        
+        // Create a centered popup (increased height to fit all content)
+        let popup_area = centered_rect(70, 60, area);
+        
+        // Then render the dialog border
+        let dialog_border = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title(" Manually Ban IP ");
+        frame.render_widget(dialog_border, popup_area);
+        
+        // Create inner area for content (accounting for borders)
+        let inner_area = popup_area.inner(&ratatui::layout::Margin { horizontal: 1, vertical: 1 });
+        
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),  // IP input
+                Constraint::Length(3),  // Jail selection
+                Constraint::Length(3),  // Bantime display
+                Constraint::Length(4),  // Buttons/instructions
+            ])
+            .split(inner_area);
+        
+        // IP input
+        let ip_text = if self.state.ip_management.ban_ip_input.is_empty() {
+            "Type IP address here...".to_string()
+        } else {
+            format!("{}_", self.state.ip_management.ban_ip_input) // Add cursor
+        };
+        
+        let ip_active = self.state.ip_management.ban_dialog_field_index == 0;
+        let ip_input = Paragraph::new(ip_text)
+            .style(if ip_active { 
+                Style::default().fg(Color::White).bg(Color::Blue) 
+            } else { 
+                Style::default().fg(Color::Gray).bg(Color::Black) 
+            })
+            .alignment(Alignment::Center)
+            .block(Block::default()
+                .title(" Enter IP Address ")
+                .borders(Borders::ALL)
+                .border_style(if ip_active {
+                    Style::default().fg(Color::Yellow)
+                } else {
+                    Style::default().fg(Color::Gray)
+                }));
+        frame.render_widget(ip_input, chunks[0]);
+        
+        // Jail selection
+        let jail_count = self.state.jails.len();
+        let jail_active = self.state.ip_management.ban_dialog_field_index == 1;
+        let jail_text = if jail_count == 0 {
+            "No active jails available (check fail2ban service)".to_string()
+        } else {
+            let selected_jail = self.state.ip_management.selected_jail_for_ban
+                .as_ref()
+                .map(|s| s.as_str())
+                .unwrap_or("(none selected)");
+            
+            if jail_active {
+                format!("▶ {} ◀ (↑/↓ to change)", selected_jail)
+            } else {
+                selected_jail.to_string()
+            }
+        };
+        let jail_selection = Paragraph::new(jail_text)
+            .style(if jail_active { 
+                Style::default().fg(Color::White).bg(Color::Blue) 
+            } else { 
+                Style::default().fg(Color::Gray) 
+            })
+            .alignment(Alignment::Center)
+            .block(Block::default()
+                .title("Jail")
+                .borders(Borders::ALL)
+                .border_style(if jail_active {
+                    Style::default().fg(Color::Yellow)
+                } else {
+                    Style::default().fg(Color::Gray)
+                }));
+        frame.render_widget(jail_selection, chunks[1]);
+        
+        // Bantime display (read-only, shows jail's configured bantime)
+        let (bantime_text, bantime_title) = if let Some(ref selected_jail) = self.state.ip_management.selected_jail_for_ban {
+            let bantime = self.get_jail_bantime(selected_jail);
+            (bantime, format!(" Ban Duration (jail configured: {}) ", selected_jail))
+        } else {
+            ("Select a jail to see bantime".to_string(), " Ban Duration (jail configured) ".to_string())
+        };
+        let bantime_display = Paragraph::new(bantime_text)
+            .style(Style::default().fg(Color::Cyan))
+            .alignment(Alignment::Center)
+            .block(Block::default()
+                .title(bantime_title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)));
+        frame.render_widget(bantime_display, chunks[2]);
+        
+        // Instructions and buttons
+        let current_field = match self.state.ip_management.ban_dialog_field_index {
+            0 => "IP Address",
+            1 => "Jail (use ↑/↓)",
+            _ => "Unknown"
+        };
+        
+        let instructions = vec![
+            Line::from(vec![
+                Span::styled("Current field: ", Style::default().fg(Color::Gray)),
+                Span::styled(current_field, Style::default().fg(Color::Yellow)),
+            ]),
+            Line::from(vec![
+                Span::styled("[Tab]", Style::default().fg(Color::Green)),
+                Span::styled(" Next Field  ", Style::default().fg(Color::Gray)),
+                Span::styled("[Enter]", Style::default().fg(Color::Green)),
+                Span::styled(" Ban IP  ", Style::default().fg(Color::Gray)),
+                Span::styled("[Esc]", Style::default().fg(Color::Red)),
+                Span::styled(" Cancel", Style::default().fg(Color::Gray)),
+            ]),
+        ];
+        
+        let buttons = Paragraph::new(instructions)
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(buttons, chunks[3]);
+    }
+    
+    fn render_unban_confirmation(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        // Apply standard dialog clearing pattern
+        frame.render_widget(Clear, area);
+        
+        // Create a completely solid background using filled text to ensure no bleed-through
+        let overlay = " ".repeat((area.width * area.height) as usize);
+        let solid_background = Paragraph::new(overlay)
+            .style(Style::default().bg(Color::Black))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(solid_background, area);
+        
+        let popup_area = centered_rect(50, 40, area);
+        
+        // Then render the dialog border
+        let dialog_border = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Red))
+            .title(" Confirm Unban ");
+        frame.render_widget(dialog_border, popup_area);
+        
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),  // Title
+                Constraint::Min(3),     // Message
+                Constraint::Length(4),  // Buttons - right size for instruction text
+            ])
+            .split(popup_area);
+        
+        let title = Paragraph::new("Confirm Unban")
+            .style(Style::default().fg(Color::Red))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(title, chunks[0]);
+        
+        let default_ip = "Unknown".to_string();
+        let default_jail = "Unknown".to_string();
+        let ip = self.state.ip_management.ip_to_unban.as_ref().unwrap_or(&default_ip);
+        let jail = self.state.ip_management.jail_for_unban.as_ref().unwrap_or(&default_jail);
+        
+        let message = Paragraph::new(format!("Unban IP {} from jail {}?", ip, jail))
+            .style(Style::default().fg(Color::White))
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
         frame.render_widget(message, chunks[1]);
@@ -3618,11 +5051,19 @@ impl App {
             return;
         }
         
+        let validation_height = if self.state.config_management.last_validated.is_some() {
+            (self.state.config_management.validation_issues.len().max(1) as u16 + 3).min(12)
+        } else {
+            0
+        };
+        
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Min(5),     // Main table
-                Constraint::Length(5),  // Status info
+                Constraint::Length(self.state.config_management.config_files.len() as u16 + 3),  // Main table
+                Constraint::Min(6),                     // Definition browser
+                Constraint::Length(validation_height),  // Validation results
+                Constraint::Length(5),                  // Status info
             ])
             .split(area);
         
@@ -3656,21 +5097,30 @@ impl App {
         ])
         .header(header)
         .block(Block::default()
+            .border_style(self.config_focus_border(ConfigFocus::Files))
             .title(Line::from(vec![
                 Span::raw("Configuration Files - "),
-                Span::styled("E", Style::default().fg(Color::Rgb(0, 150, 255))),
+                Span::styled("E", self.action_hotkey_style()),
                 Span::raw(":Edit | "),
-                Span::styled("B", Style::default().fg(Color::Rgb(0, 150, 255))),
+                Span::styled("B", self.action_hotkey_style()),
                 Span::raw(":Backup | "),
-                Span::styled("R", Style::default().fg(Color::Rgb(0, 150, 255))),
+                Span::styled("R", self.action_hotkey_style()),
                 Span::raw(":Restore | "),
                 Span::styled("T", Style::default().fg(Color::Rgb(0, 150, 255))),
                 Span::raw(":Test | "),
+                Span::styled("V", self.action_hotkey_style()),
+                Span::raw(":$EDITOR | "),
+                Span::styled("N", self.action_hotkey_style()),
+                Span::raw(":New jail | "),
                 Span::styled("ESC", Style::default().fg(Color::Rgb(0, 150, 255))),
                 Span::raw(":Back"),
             ]))
             .borders(Borders::ALL))
-        .highlight_style(Style::default().bg(Color::Blue).fg(Color::White));
+        .highlight_style(if self.state.config_management.focus == ConfigFocus::Files {
+            Style::default().bg(Color::Blue).fg(Color::White)
+        } else {
+            Style::default()
+        });
         
         frame.render_stateful_widget(table, chunks[0], &mut self.state.config_management.table_state.clone());
         
@@ -3701,7 +5151,124 @@ impl App {
         let status_widget = Paragraph::new(status_lines)
             .block(Block::default().borders(Borders::ALL));
         
-        frame.render_widget(status_widget, chunks[1]);
+        frame.render_widget(status_widget, chunks[3]);
+        
+        self.render_definition_browser(frame, chunks[1]);
+        if validation_height > 0 {
+            self.render_validation_results(frame, chunks[2]);
+        }
+    }
+    
+    fn config_focus_border(&self, pane: ConfigFocus) -> Style {
+        if self.state.config_management.focus == pane {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default()
+        }
+    }
+    
+    fn render_definition_browser(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let config = &self.state.config_management;
+        let focused = config.focus == ConfigFocus::Definitions;
+        
+        let header = Row::new(vec![
+            Cell::from("Definition"),
+            Cell::from("Files"),
+            Cell::from("Used by (enabled jails)"),
+        ]).style(Style::default().fg(Color::Yellow));
+        
+        let rows: Vec<Row> = self.definition_rows().into_iter().map(|row| match row {
+            DefinitionRow::Dir(dir) => {
+                let marker = if config.expanded_dirs.contains(&dir) { "▾" } else { "▸" };
+                let count = config.definitions.iter().filter(|d| d.dir == dir).count();
+                Row::new(vec![
+                    Cell::from(Span::styled(format!("{} {}/", marker, dir), Style::default().fg(Color::Yellow))),
+                    Cell::from(Span::styled(format!("{} files", count), Style::default().fg(Color::Gray))),
+                    Cell::from(""),
+                ])
+            },
+            DefinitionRow::Definition(index) => {
+                let definition = &config.definitions[index];
+                let files_color = if definition.has_local { Color::Green } else { Color::Gray };
+                let used_by = if definition.used_by.is_empty() {
+                    Span::styled("-", Style::default().fg(Color::DarkGray))
+                } else {
+                    Span::styled(definition.used_by.join(", "), Style::default().fg(Color::White))
+                };
+                Row::new(vec![
+                    Cell::from(Span::styled(format!("    {}", definition.name), Style::default().fg(Color::Cyan))),
+                    Cell::from(Span::styled(definition.files_label(), Style::default().fg(files_color))),
+                    Cell::from(used_by),
+                ])
+            },
+        }).collect();
+        
+        let enter_hint = if focused { ":Open .local (creates override) | " } else { ":Focus | " };
+        let table = Table::new(rows, &[
+            Constraint::Length(34),      // Definition
+            Constraint::Length(12),      // Files
+            Constraint::Min(20),         // Used by
+        ])
+        .header(header)
+        .block(Block::default()
+            .title(Line::from(vec![
+                Span::raw("Filters, Actions & Drop-ins - "),
+                Span::styled(if focused { "ENTER" } else { "TAB" }, Style::default().fg(Color::Rgb(0, 150, 255))),
+                Span::raw(enter_hint),
+                Span::styled("←/→", Style::default().fg(Color::Rgb(0, 150, 255))),
+                Span::raw(":Fold | "),
+                Span::styled("V", Style::default().fg(Color::Rgb(0, 150, 255))),
+                Span::raw(":$EDITOR"),
+            ]))
+            .borders(Borders::ALL)
+            .border_style(self.config_focus_border(ConfigFocus::Definitions)))
+        .highlight_style(if focused {
+            Style::default().bg(Color::Blue).fg(Color::White)
+        } else {
+            Style::default()
+        });
+        
+        frame.render_stateful_widget(table, area, &mut config.definition_table_state.clone());
+    }
+    
+    fn render_validation_results(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let issues = &self.state.config_management.validation_issues;
+        let checked_at = self.state.config_management.last_validated
+            .map(|t| t.format("%H:%M:%S").to_string())
+            .unwrap_or_default();
+        
+        let mut lines = Vec::new();
+        if issues.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("✓", Style::default().fg(Color::Green)),
+                Span::raw(" No problems found"),
+            ]));
+        }
+        
+        let visible = area.height.saturating_sub(2) as usize;
+        for (i, issue) in issues.iter().enumerate() {
+            if i + 1 == visible && issues.len() > visible {
+                lines.push(Line::styled(format!("  ... and {} more (see log)", issues.len() - i), Style::default().fg(Color::Gray)));
+                break;
+            }
+            let (symbol, color) = match issue.severity {
+                IssueSeverity::Error => ("✗", Color::Red),
+                IssueSeverity::Warning => ("⚠", Color::Yellow),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(symbol, Style::default().fg(color)),
+                Span::raw(" "),
+                Span::styled(issue.location(), Style::default().fg(Color::Cyan)),
+                Span::raw(format!("  {}", issue.message)),
+            ]));
+        }
+        
+        let widget = Paragraph::new(lines)
+            .block(Block::default()
+                .title(format!("Validation Results (dry run at {})", checked_at))
+                .borders(Borders::ALL));
+        
+        frame.render_widget(widget, area);
     }
     
     fn render_logs(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
@@ -3940,6 +5507,63 @@ impl App {
             Line::raw(""),
         ];
         
+        // Access section - what read-only mode leaves out
+        settings_lines.push(Line::from(vec![
+            Span::styled("Access:", Style::default().fg(Color::Yellow)),
+        ]));
+        settings_lines.push(Line::raw(""));
+        let capabilities = self.state.capabilities;
+        for (label, available) in [
+            ("Ban/unban, editing, service control", capabilities.privileged),
+            ("fail2ban socket (live jail status)  ", capabilities.socket),
+            ("fail2ban log (ban history)          ", capabilities.log),
+            ("Configuration files                 ", capabilities.config),
+        ] {
+            let (symbol, color) = if available { ("✓", Color::Green) } else { ("✗", Color::Red) };
+            settings_lines.push(Line::from(vec![
+                Span::raw(format!("{}  ", label)),
+                Span::styled(symbol, Style::default().fg(color)),
+            ]));
+        }
+        if capabilities.read_only() {
+            settings_lines.push(Line::from(vec![
+                Span::styled("Read-only mode - run with sudo for full functionality", Style::default().fg(Color::Yellow)),
+            ]));
+        }
+        settings_lines.push(Line::from(vec![
+            Span::raw("Service manager:                      "),
+            Span::styled(self.system_service.backend().label(), Style::default().fg(Color::Cyan)),
+        ]));
+        settings_lines.push(Line::from(vec![
+            Span::raw("Notification rules:                   "),
+            Span::styled(self.notifier.rule_count().to_string(), Style::default().fg(Color::Cyan)),
+        ]));
+        let databases = self.geoip.describe();
+        settings_lines.push(Line::from(vec![
+            Span::raw("GeoIP databases:                      "),
+            if databases.is_empty() {
+                Span::styled("none", Style::default().fg(Color::Gray))
+            } else {
+                Span::styled(format!("{} ({} addresses cached)", databases.len(), self.geoip.cached_count()), Style::default().fg(Color::Cyan))
+            },
+        ]));
+        for database in databases {
+            settings_lines.push(Line::styled(format!("  {}", database), Style::default().fg(Color::Gray)));
+        }
+        settings_lines.push(Line::from(vec![
+            Span::raw("Reverse DNS:                          "),
+            if self.rdns.is_enabled() {
+                Span::styled(format!("on ({} cached, {} pending)", self.rdns.cached_count(), self.rdns.pending_count()), Style::default().fg(Color::Cyan))
+            } else {
+                Span::styled("off", Style::default().fg(Color::Gray))
+            },
+        ]));
+        settings_lines.push(Line::from(vec![
+            Span::raw("Banned IP filter presets:             "),
+            Span::styled(format!("{} saved in {}", self.filter_presets.names().len(), self.filter_presets.path().display()), Style::default().fg(Color::Cyan)),
+        ]));
+        settings_lines.push(Line::raw(""));
+        
         // Performance section
         settings_lines.push(Line::from(vec![
             Span::styled("Performance Metrics:", Style::default().fg(Color::Yellow)),
@@ -3996,6 +5620,15 @@ impl App {
             ),
         ]));
         
+        settings_lines.push(Line::from(vec![
+            Span::raw("Event History:   "),
+            Span::styled(
+                format!("{} / {} buckets, {} / {} bans", self.state.event_history.bucket_count(), MAX_BUCKETS,
+                    self.state.event_history.ban_record_count(), MAX_BAN_RECORDS),
+                Style::default().fg(Color::Cyan)
+            ),
+        ]));
+        
         settings_lines.push(Line::raw(""));
         
         // Application settings
@@ -4125,6 +5758,18 @@ impl App {
             Span::styled(" | ", Style::default().fg(Color::Gray)),
             Span::styled("W", Style::default().fg(hotkey_color)),
             Span::raw(":Whitelist"),
+            Span::styled(" | ", Style::default().fg(Color::Gray)),
+            Span::styled("K", Style::default().fg(hotkey_color)),
+            Span::raw(":Audit"),
+            Span::styled(" | ", Style::default().fg(Color::Gray)),
+            Span::styled("P", Style::default().fg(hotkey_color)),
+            Span::raw(":Health"),
+            Span::styled(" | ", Style::default().fg(Color::Gray)),
+            Span::styled("M", Style::default().fg(hotkey_color)),
+            Span::raw(":Stats"),
+            Span::styled(" | ", Style::default().fg(Color::Gray)),
+            Span::styled("A", Style::default().fg(hotkey_color)),
+            Span::raw(":Subnets"),
         ];
         
         // Add screen-specific shortcuts
@@ -4143,7 +5788,7 @@ impl App {
         // Add universal shortcuts
         footer_spans.extend(vec![
             Span::styled(" | ", Style::default().fg(Color::Gray)),
-            Span::styled("B", Style::default().fg(hotkey_color)),
+            Span::styled("B", self.action_hotkey_style()),
             Span::raw(":Ban IP"),
             Span::styled(" | ", Style::default().fg(Color::Gray)),
             Span::styled("ESC", Style::default().fg(hotkey_color)),
@@ -4190,7 +5835,10 @@ impl App {
                 ]),
             ];
             
-            if estimated_remaining.as_secs() > 0 {
+            if operation.estimated_completion.is_none() {
+                // Job-driven progress: the status text already says what the service manager is doing
+                progress_lines.push(Line::from(Span::styled(progress_bar, Style::default().fg(Color::Yellow))));
+            } else if estimated_remaining.as_secs() > 0 {
                 progress_lines.push(Line::from(vec![
                     Span::styled(progress_bar, Style::default().fg(Color::Yellow)),
                     Span::raw(format!(" ETA: {}s", estimated_remaining.as_secs())),
@@ -4210,6 +5858,9 @@ impl App {
     }
     
     fn load_recent_logs(&mut self) {
+        // Take the lines written since the last tail first, so their bans and
+        // notifications are not lost and they are not tailed again afterwards
+        self.update_log_entries();
         match self.log_monitor.get_recent_lines(100) {
             Ok(entries) => {
                 // Clear existing entries and add new ones (already sorted by timestamp, newest first)
@@ -4228,6 +5879,11 @@ impl App {
     fn update_log_entries(&mut self) {
         match self.log_monitor.tail_new_lines() {
             Ok(new_entries) => {
+                self.state.event_history.record(&new_entries);
+                self.apply_ban_events(&new_entries);
+                for notification in self.notifier.observe(&new_entries) {
+                    self.set_status_message(&format!("🔔 {}", notification.text()));
+                }
                 if !new_entries.is_empty() {
                     // Add new entries and re-sort to maintain chronological order (newest first)
                     self.state.log_entries.extend(new_entries);
@@ -4323,16 +5979,111 @@ impl App {
     
     fn get_sorted_jails_for_display(&self) -> Vec<JailConfig> {
         let mut sorted_jails = self.state.available_jails.clone();
-        sorted_jails.sort_by(|a, b| {
-            match (a.enabled, b.enabled) {
-                (true, false) => std::cmp::Ordering::Less,    // enabled first
-                (false, true) => std::cmp::Ordering::Greater, // disabled second
-                _ => a.name.cmp(&b.name),                     // alphabetical within same status
-            }
-        });
+        sort_jails(&mut sorted_jails, &self.state.jails, self.state.jail_sort);
         sorted_jails
     }
     
+    /// 'o' moves the focused table to its next sort column
+    fn cycle_sort_column(&mut self) {
+        let selected_jail = self.selected_dashboard_jail();
+        match self.state.dashboard_focus {
+            DashboardFocus::Jails => self.state.jail_sort.next_column(&JailColumn::ALL),
+            DashboardFocus::BannedIPs => self.state.banned_ip_sort.next_column(&BannedIpColumn::ALL),
+        }
+        self.apply_sort(self.state.dashboard_focus, selected_jail);
+    }
+    
+    /// 'O' flips the focused table between ascending and descending
+    fn reverse_sort(&mut self) {
+        let selected_jail = self.selected_dashboard_jail();
+        match self.state.dashboard_focus {
+            DashboardFocus::Jails => self.state.jail_sort.reverse(),
+            DashboardFocus::BannedIPs => self.state.banned_ip_sort.reverse(),
+        }
+        self.apply_sort(self.state.dashboard_focus, selected_jail);
+    }
+    
+    /// A header click sorts by that column, or flips the direction when it already is
+    fn sort_by_header(&mut self, header: SortHeader) {
+        let selected_jail = self.selected_dashboard_jail();
+        let table = match header {
+            SortHeader::Jail(column) => {
+                self.state.jail_sort.select(column);
+                DashboardFocus::Jails
+            },
+            SortHeader::BannedIp(column) => {
+                self.state.banned_ip_sort.select(column);
+                DashboardFocus::BannedIPs
+            },
+        };
+        self.state.dashboard_focus = table;
+        self.apply_sort(table, selected_jail);
+        self.initialize_dashboard_states();
+    }
+    
+    /// Clicks on the dashboard's column headers sort the tables
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) || self.is_dashboard_overlay_open() {
+            return;
+        }
+        let header = self.state.sort_headers.iter()
+            .find(|(rect, _)| rect.x <= mouse.column && mouse.column < rect.x + rect.width && rect.y == mouse.row)
+            .map(|(_, header)| *header);
+        if let Some(header) = header {
+            self.sort_by_header(header);
+        }
+    }
+    
+    /// Dialogs drawn over the dashboard tables, which then take no clicks
+    fn is_dashboard_overlay_open(&self) -> bool {
+        self.state.current_screen != Screen::Dashboard ||
+        self.state.error_dialog.is_some() ||
+        self.state.loading_modal.is_some() ||
+        self.state.ip_management.ban_dialog_open ||
+        self.state.ip_management.unban_confirmation_open ||
+        self.state.ip_management.whitelist_dialog_open ||
+        self.state.ip_detail.is_some() ||
+        self.state.range_ban_dialog.is_some() ||
+        self.state.filter_prompt.is_some() ||
+        self.state.jail_editor.is_open
+    }
+    
+    /// The row highlighted in the Banned IPs table, as filtered, sorted and paged
+    fn selected_banned_ip(&mut self) -> Option<BannedIP> {
+        let index = self.state.banned_ip_pagination.start_index() + self.state.dashboard_banned_ip_selected_index;
+        self.get_filtered_banned_ips().get(index).cloned()
+    }
+    
+    fn selected_dashboard_jail(&self) -> Option<String> {
+        self.get_sorted_jails_for_display()
+            .get(self.state.dashboard_jail_selected_index)
+            .map(|jail| jail.name.clone())
+    }
+    
+    fn apply_sort(&mut self, table: DashboardFocus, selected_jail: Option<String>) {
+        let direction = |descending: bool| if descending { "descending" } else { "ascending" };
+        match table {
+            DashboardFocus::Jails => {
+                // Keep the same jail selected wherever it moved to
+                if let Some(index) = self.get_sorted_jails_for_display().iter().position(|jail| Some(&jail.name) == selected_jail.as_ref()) {
+                    self.state.dashboard_jail_selected_index = index;
+                    self.state.dashboard_jail_table_state.select(Some(index));
+                }
+                let order = self.state.jail_sort;
+                self.set_status_message(&format!("✓ Jails sorted by {} ({})", order.column.label(), direction(order.descending)));
+            },
+            DashboardFocus::BannedIPs => {
+                let order = self.state.banned_ip_sort;
+                self.state.banned_ips.set_order(order);
+                self.state.banned_ip_filter.version += 1;  // Rebuild the filtered list in the new order
+                self.state.banned_ip_pagination.go_to_first_page();
+                self.state.dashboard_banned_ip_selected_index = 0;
+                self.state.dashboard_banned_ip_table_state.select(Some(0));
+                self.set_status_message(&format!("✓ Banned IPs sorted by {} ({})", order.column.label(), direction(order.descending)));
+            },
+        }
+    }
+    
     fn get_active_filters_spans(&self) -> Vec<Span> {
         let mut spans = Vec::new();
         let mut filter_count = 0;
@@ -4400,14 +6151,14 @@ impl App {
     
     // Removed unused get_banned_ip_filter_spans function - now computed inline
     
-    fn get_filtered_banned_ips(&mut self) -> &Vec<BannedIP> {
+    fn get_filtered_banned_ips(&mut self) -> &[BannedIP] {
         let is_massive_dataset = self.state.banned_ips.len() > 15000;
         
         // For massive datasets (18k+ IPs), skip expensive filtering entirely and return original data
         if is_massive_dataset && !self.state.banned_ip_filter.has_active_filters() {
             // Just return the original banned IPs without any expensive operations
             self.state.cached_filtered_ips = Vec::new(); // Clear cache to save memory
-            return &self.state.banned_ips;
+            return self.state.banned_ips.as_slice();
         }
         
         // Check if we need to recalculate the filtered results
@@ -4420,99 +6171,21 @@ impl App {
                        self.state.filter_cache_version, self.state.banned_ip_filter.version);
             let start_time = Instant::now();
             
-            // For massive datasets, use iterator-based filtering to avoid cloning entire vector
-            let filtered_ips: Vec<BannedIP> = if is_massive_dataset {
-                log::warn!("PERFORMANCE MODE: Using iterator-based filtering for {} IPs", self.state.banned_ips.len());
-                
-                // Use iterator chain instead of cloning, which is much more memory efficient
-                self.state.banned_ips.iter()
-                    .filter(|ip| {
-                        // Apply IP starting digit filter
-                        if let Some(digit) = self.state.banned_ip_filter.ip_starting_digit {
-                            if !ip.ip.starts_with(&digit.to_string()) {
-                                return false;
-                            }
-                        }
-                        
-                        // Apply jail filter
-                        if let Some(ref jail_filter) = self.state.banned_ip_filter.jail {
-                            if &ip.jail != jail_filter {
-                                return false;
-                            }
-                        }
-                        
-                        // Apply ban age filter
-                        if let Some(hours) = self.state.banned_ip_filter.ban_age_hours {
-                            let cutoff_time = chrono::Utc::now() - chrono::Duration::hours(hours as i64);
-                            if ip.ban_time < cutoff_time {
-                                return false;
-                            }
-                        }
-                        
-                        // Apply remaining time filter
-                        if let Some(remaining_filter) = self.state.banned_ip_filter.remaining_time {
-                            let now = chrono::Utc::now();
-                            match remaining_filter {
-                                RemainingTimeFilter::Soon => {
-                                    if let Some(unban_time) = ip.unban_time {
-                                        if unban_time > now + chrono::Duration::hours(1) {
-                                            return false;
-                                        }
-                                    } else {
-                                        return false; // Permanent bans don't qualify for "soon"
-                                    }
-                                }
-                                RemainingTimeFilter::Today => {
-                                    if let Some(unban_time) = ip.unban_time {
-                                        if unban_time > now + chrono::Duration::hours(24) {
-                                            return false;
-                                        }
-                                    } else {
-                                        return false; // Permanent bans don't qualify for "today"
-                                    }
-                                }
-                                RemainingTimeFilter::ThisWeek => {
-                                    if let Some(unban_time) = ip.unban_time {
-                                        if unban_time > now + chrono::Duration::weeks(1) {
-                                            return false;
-                                        }
-                                    } else {
-                                        return false; // Permanent bans don't qualify for "this week"
-                                    }
-                                }
-                                RemainingTimeFilter::Permanent => {
-                                    if ip.unban_time.is_some() {
-                                        return false; // Not a permanent ban
-                                    }
-                                }
-                            }
-                        }
-                        
-                        true
-                    })
-                    .cloned()
-                    .collect()
-            } else {
-                // For smaller datasets, use the original retain-based approach
-                let mut filtered_ips: Vec<BannedIP> = self.state.banned_ips.clone();
-                
-                // Apply IP starting digit filter
-                if let Some(digit) = self.state.banned_ip_filter.ip_starting_digit {
-                    filtered_ips.retain(|ip| ip.ip.starts_with(&digit.to_string()));
-                }
-                
-                // Apply jail filter
-                if let Some(ref jail_filter) = self.state.banned_ip_filter.jail {
-                    filtered_ips.retain(|ip| &ip.jail == jail_filter);
-                }
-                
-                // Apply ban age filter
-                if let Some(hours) = self.state.banned_ip_filter.ban_age_hours {
-                    let cutoff_time = chrono::Utc::now() - chrono::Duration::hours(hours as i64);
-                    filtered_ips.retain(|ip| ip.ban_time >= cutoff_time);
-                }
-                
-                filtered_ips
+            let filtered_ips: Vec<BannedIP> = match self.state.banned_ip_filter.query {
+                Some(ref query) => {
+                    if is_massive_dataset {
+                        log::warn!("PERFORMANCE MODE: Filtering {} IPs with '{}'", self.state.banned_ips.len(), query.text());
+                    }
+                    
+                    // Iterate instead of cloning the whole list first; GeoIP is only asked when a term needs it
+                    let now = chrono::Utc::now();
+                    let geoip = &mut self.geoip;
+                    self.state.banned_ips.iter()
+                        .filter(|ip| query.matches(ip, now, &mut |addr| geoip.cached(addr)))
+                        .cloned()
+                        .collect()
+                },
+                None => self.state.banned_ips.as_slice().to_vec(),
             };
             
             self.state.cached_filtered_ips = filtered_ips;
@@ -4700,110 +6373,191 @@ impl App {
     
     // Banned IP filtering methods
     fn clear_banned_ip_filters(&mut self) {
-        self.state.banned_ip_filter = BannedIpFilter::default();
-        self.state.banned_ip_filter.version += 1;
+        self.state.banned_ip_filter = BannedIpFilter {
+            query: None,
+            version: self.state.banned_ip_filter.version + 1,
+        };
         self.set_status_message("✓ Banned IP filters cleared");
     }
     
-    fn cycle_ip_digit_filter(&mut self) {
-        self.state.banned_ip_filter.ip_starting_digit = match self.state.banned_ip_filter.ip_starting_digit {
-            None => Some('1'),
-            Some('1') => Some('2'),
-            Some('2') => Some('3'),
-            Some('3') => Some('4'),
-            Some('4') => Some('5'),
-            Some('5') => Some('6'),
-            Some('6') => Some('7'),
-            Some('7') => Some('8'),
-            Some('8') => Some('9'),
-            _ => None,
-        };
+    fn set_banned_ip_query(&mut self, query: BanQuery) {
+        if query.uses_geo() && !self.geoip.is_enabled() {
+            self.set_status_message("⚠ No GeoIP database configured - country: and asn: match nothing");
+        } else {
+            self.set_status_message(&format!("✓ Filter: {}", query.text()));
+        }
+        self.state.banned_ip_filter.query = Some(query);
         self.state.banned_ip_filter.version += 1;
-        
-        let status = match self.state.banned_ip_filter.ip_starting_digit {
-            None => "✓ Showing all IP addresses",
-            Some(digit) => &format!("✓ Showing IPs starting with {}", digit),
-        };
-        self.set_status_message(status);
+        self.state.banned_ip_pagination.go_to_first_page();
+    }
+    
+    fn open_filter_prompt(&mut self) {
+        let input = self.state.banned_ip_filter.query.as_ref()
+            .map(|query| query.text().to_string())
+            .unwrap_or_default();
+        self.state.filter_prompt = Some(FilterPrompt {
+            input,
+            ..FilterPrompt::default()
+        });
     }
     
-    fn cycle_jail_filter(&mut self) {
-        let available_jails: Vec<String> = self.state.jails.keys().cloned().collect();
-        if available_jails.is_empty() {
-            self.set_status_message("⚠ No jails available for filtering");
+    fn handle_filter_prompt_key(&mut self, key: KeyEvent) {
+        let Some(mut prompt) = self.state.filter_prompt.take() else {
             return;
-        }
-        
-        self.state.banned_ip_filter.jail = match &self.state.banned_ip_filter.jail {
-            None => available_jails.get(0).cloned(),
-            Some(current_jail) => {
-                let current_index = available_jails.iter().position(|j| j == current_jail);
-                match current_index {
-                    Some(index) if index < available_jails.len() - 1 => {
-                        available_jails.get(index + 1).cloned()
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        
+        match key.code {
+            KeyCode::Esc if prompt.naming.is_some() => prompt.naming = None,
+            KeyCode::Esc => return,
+            KeyCode::Enter => {
+                if let Some(name) = prompt.naming.take() {
+                    match self.filter_presets.save(&name, &prompt.input) {
+                        Ok(()) => {
+                            self.set_status_message(&format!("✓ Saved filter preset '{}'", name.trim()));
+                            prompt.preset_index = self.filter_presets.names().iter().position(|n| n == name.trim());
+                        },
+                        Err(e) => prompt.error = Some(e.to_string()),
+                    }
+                } else if prompt.input.trim().is_empty() {
+                    self.clear_banned_ip_filters();
+                    return;
+                } else {
+                    match BanQuery::parse(&prompt.input) {
+                        Ok(query) => {
+                            self.set_banned_ip_query(query);
+                            return;
+                        },
+                        Err(e) => prompt.error = Some(e),
                     }
-                    _ => None,
                 }
-            }
-        };
-        self.state.banned_ip_filter.version += 1;
-        
-        let status = match &self.state.banned_ip_filter.jail {
-            None => "✓ Showing all jails",
-            Some(jail) => &format!("✓ Showing only jail: {}", jail),
-        };
-        self.set_status_message(status);
+            },
+            KeyCode::Char('s') if ctrl && prompt.naming.is_none() => {
+                match BanQuery::parse(&prompt.input) {
+                    Ok(_) => {
+                        let names = self.filter_presets.names();
+                        prompt.naming = Some(prompt.preset_index.and_then(|i| names.get(i).cloned()).unwrap_or_default());
+                        prompt.error = None;
+                    },
+                    Err(e) => prompt.error = Some(e),
+                }
+            },
+            KeyCode::Char('d') if ctrl && prompt.naming.is_none() => {
+                let names = self.filter_presets.names();
+                match prompt.preset_index.and_then(|i| names.get(i)) {
+                    Some(name) => match self.filter_presets.remove(name) {
+                        Ok(_) => {
+                            self.set_status_message(&format!("✓ Deleted filter preset '{}'", name));
+                            prompt.preset_index = None;
+                        },
+                        Err(e) => prompt.error = Some(e.to_string()),
+                    },
+                    None => prompt.error = Some("Pick a preset with ↑/↓ first".to_string()),
+                }
+            },
+            KeyCode::Up | KeyCode::Down if prompt.naming.is_none() => {
+                let names = self.filter_presets.names();
+                if names.is_empty() {
+                    prompt.error = Some("No saved presets - Ctrl+S saves the current filter".to_string());
+                } else {
+                    let last = names.len() - 1;
+                    let index = match (key.code, prompt.preset_index) {
+                        (KeyCode::Up, None | Some(0)) => last,
+                        (KeyCode::Up, Some(i)) => i - 1,
+                        (_, Some(i)) if i < last => i + 1,
+                        _ => 0,
+                    };
+                    prompt.input = self.filter_presets.get(&names[index]).unwrap_or_default().to_string();
+                    prompt.preset_index = Some(index);
+                    prompt.error = None;
+                    prompt.completions.clear();
+                }
+            },
+            KeyCode::Tab if prompt.naming.is_none() => {
+                let source = self.filter_completion_source();
+                let candidates = complete(&prompt.input, &source);
+                let typed = prompt.input.len() - replace_last_word(&prompt.input, "").len();
+                if candidates.len() == 1 {
+                    let mut word = candidates[0].clone();
+                    if !word.ends_with(':') {
+                        word.push(' ');
+                    }
+                    prompt.input = replace_last_word(&prompt.input, &word);
+                    prompt.completions.clear();
+                } else {
+                    let shared = common_prefix(&candidates);
+                    if shared.len() > typed {
+                        prompt.input = replace_last_word(&prompt.input, &shared);
+                    }
+                    prompt.completions = candidates;
+                }
+            },
+            KeyCode::Backspace => {
+                match prompt.naming {
+                    Some(ref mut name) => {
+                        name.pop();
+                    },
+                    None => {
+                        prompt.input.pop();
+                        prompt.preset_index = None;
+                    },
+                }
+                prompt.error = None;
+                prompt.completions.clear();
+            },
+            KeyCode::Char(c) if !ctrl => {
+                match prompt.naming {
+                    Some(ref mut name) => name.push(c),
+                    None => {
+                        prompt.input.push(c);
+                        prompt.preset_index = None;
+                    },
+                }
+                prompt.error = None;
+                prompt.completions.clear();
+            },
+            _ => {},
+        }
+        self.state.filter_prompt = Some(prompt);
     }
     
-    fn cycle_ban_age_filter(&mut self) {
-        self.state.banned_ip_filter.ban_age_hours = match self.state.banned_ip_filter.ban_age_hours {
-            None => Some(1),      // Last hour
-            Some(1) => Some(24),  // Last day  
-            Some(24) => Some(168), // Last week
-            _ => None,            // All times
-        };
-        self.state.banned_ip_filter.version += 1;
-        
-        let status = match self.state.banned_ip_filter.ban_age_hours {
-            None => "✓ Showing bans from all times",
-            Some(1) => "✓ Showing bans from last hour",
-            Some(24) => "✓ Showing bans from last day",
-            Some(168) => "✓ Showing bans from last week",
-            Some(hours) => &format!("✓ Showing bans from last {} hours", hours),
-        };
-        self.set_status_message(status);
+    /// Jails, countries and ASNs offered by Tab in the query prompt
+    fn filter_completion_source(&mut self) -> CompletionSource {
+        let mut jails: Vec<String> = self.state.jails.keys().cloned().collect();
+        jails.sort();
+        CompletionSource {
+            jails,
+            countries: self.banned_geo_values(|geo| geo.country.clone()),
+            asns: self.banned_geo_values(|geo| geo.asn),
+        }
     }
     
-    fn cycle_remaining_time_filter(&mut self) {
-        self.state.banned_ip_filter.remaining_time = match self.state.banned_ip_filter.remaining_time {
-            None => Some(RemainingTimeFilter::Soon),      // Within 1 hour
-            Some(RemainingTimeFilter::Soon) => Some(RemainingTimeFilter::Today),     // Within 24 hours  
-            Some(RemainingTimeFilter::Today) => Some(RemainingTimeFilter::ThisWeek), // Within 1 week
-            Some(RemainingTimeFilter::ThisWeek) => Some(RemainingTimeFilter::Permanent), // Permanent only
-            Some(RemainingTimeFilter::Permanent) => None, // All times
-        };
-        self.state.banned_ip_filter.version += 1;
-        
-        let status = match self.state.banned_ip_filter.remaining_time {
-            None => "✓ Showing all remaining times",
-            Some(RemainingTimeFilter::Soon) => "✓ Showing bans ending within 1 hour",
-            Some(RemainingTimeFilter::Today) => "✓ Showing bans ending within 24 hours",
-            Some(RemainingTimeFilter::ThisWeek) => "✓ Showing bans ending within 1 week",
-            Some(RemainingTimeFilter::Permanent) => "✓ Showing permanent bans only",
-        };
-        self.set_status_message(status);
+    /// Values of one GeoIP field among the banned IPs, most bans first
+    fn banned_geo_values<T: Ord + Clone>(&mut self, field: impl Fn(&GeoInfo) -> Option<T>) -> Vec<T> {
+        if !self.geoip.is_enabled() {
+            return Vec::new();
+        }
+        let mut counts: std::collections::BTreeMap<T, usize> = std::collections::BTreeMap::new();
+        for banned in &self.state.banned_ips {
+            if let Some(value) = field(&self.geoip.cached(&banned.ip)) {
+                *counts.entry(value).or_default() += 1;
+            }
+        }
+        let mut values = counts.into_iter().collect::<Vec<_>>();
+        values.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        values.into_iter().map(|(value, _)| value).collect()
     }
     
     fn start_operation(&mut self, operation_type: OperationType) {
         let now = Instant::now();
-        let estimated_completion = now + operation_type.estimated_duration();
+        let estimated_completion = operation_type.estimated_duration().map(|duration| now + duration);
         
         self.state.current_operation = Some(OperationProgress {
             operation_type: operation_type.clone(),
             progress_percent: 0,
             status_text: operation_type.display_name().to_string(),
             started_at: now,
-            estimated_completion: Some(estimated_completion),
+            estimated_completion,
         });
         
     }
@@ -4817,7 +6571,7 @@ impl App {
             }
             
             // Update estimated completion based on current progress
-            if progress_percent > 0 && progress_percent < 100 {
+            if operation.estimated_completion.is_some() && progress_percent > 0 && progress_percent < 100 {
                 let elapsed = operation.started_at.elapsed();
                 let estimated_total = elapsed.mul_f32(100.0 / progress_percent as f32);
                 operation.estimated_completion = Some(operation.started_at + estimated_total);
@@ -4942,6 +6696,7 @@ impl App {
                             name: jail_name.clone(),
                             enabled: true, // assume enabled if in list
                             banned_count: 0,
+                            failed_count: 0,
                             filter: "unknown".to_string(),
                             action: "unknown".to_string(),
                         });
@@ -5128,7 +6883,12 @@ impl App {
         self.update_operation_progress(60, Some("⚙️ Updating configuration and reloading fail2ban...".to_string()));
         
         let client = Fail2banClient::new();
-        match client.set_jail_enabled(&jail_name, new_enabled) {
+        let result = client.set_jail_enabled(&jail_name, new_enabled);
+        let action = if new_enabled { AuditAction::JailEnable } else { AuditAction::JailDisable };
+        self.audit(AuditEntry::new(action, jail_name.clone())
+            .change(format!("enabled = {}", !new_enabled), format!("enabled = {}", new_enabled))
+            .outcome(&result));
+        match result {
             Ok(()) => {
                 let action_text = if new_enabled { "enabled" } else { "disabled" };
                 self.complete_operation(true, Some(format!("✓ Jail '{}' {}", jail_name, action_text)));
@@ -5203,13 +6963,15 @@ impl App {
                     is_open: true,
                     jail_name: jail_name.clone(),
                     original_content: content.clone(),
-                    current_content: content,
+                    buffer: TextBuffer::from_text(&content),
                     backup_path,
-                    cursor_position: 0,
-                    scroll_offset: 0,
                     modified: false,
+                    file_snapshot: std::fs::read_to_string(jail_local_file()).unwrap_or_default(),
+                    lint_issues: Vec::new(),
                 };
+                self.refresh_editor_lint();
                 self.state.current_screen = Screen::JailEditor;
+                self.watch_editor_file(&jail_local_file());
             },
             Err(e) => {
                 log::error!("Failed to load jail configuration for {}: {}", jail_name, e);
@@ -5219,9 +6981,9 @@ impl App {
                     {}\n\n\
                     This may indicate:\n\
                     • Configuration file is missing or inaccessible\n\
-                    • Permission issues with /etc/fail2ban/\n\
+                    • Permission issues with {}/\n\
                     • Jail is not properly configured",
-                    jail_name, e
+                    jail_name, e, fail2ban_paths().root().display()
                 ));
             }
         }
@@ -5235,6 +6997,7 @@ impl App {
         }
         
         self.state.jail_editor = JailEditorState::default();
+        self.stop_watching_editor_file();
         self.state.current_screen = Screen::Dashboard;
         
         // Refresh jail data to reflect any changes made in the editor
@@ -5248,19 +7011,28 @@ impl App {
             return;
         }
         
+        let content = self.state.jail_editor.buffer.text();
+        if !self.ready_to_save(&content) {
+            return;
+        }
+        
         log::info!("Saving jail configuration for: {}", self.state.jail_editor.jail_name);
+        let audit_entry = AuditEntry::new(AuditAction::ConfigSave, format!("{} [{}]", jail_local_file(), self.state.jail_editor.jail_name))
+            .file_change(&self.state.jail_editor.original_content, &content);
         
         // Save the configuration
-        match self.write_jail_configuration(&self.state.jail_editor.jail_name, &self.state.jail_editor.current_content) {
+        match self.write_jail_configuration(&self.state.jail_editor.jail_name, &content) {
             Ok(()) => {
                 // Test fail2ban reload
                 match self.test_fail2ban_reload() {
                     Ok(()) => {
                         log::info!("Jail configuration saved and fail2ban reloaded successfully");
+                        self.audit(audit_entry);
                         self.handle_message(AppMessage::JailConfigSaved(true));
                     },
                     Err(e) => {
                         log::error!("fail2ban reload failed after configuration save: {}", e);
+                        self.audit(audit_entry.failed(format!("reload failed, rolled back: {}", e)));
                         // Rollback the configuration
                         self.rollback_jail_configuration();
                         self.handle_message(AppMessage::JailConfigSaved(false));
@@ -5269,15 +7041,16 @@ impl App {
             },
             Err(e) => {
                 log::error!("Failed to write jail configuration: {}", e);
+                self.audit(audit_entry.failed(e.to_string()));
                 self.state.error_dialog = Some(format!(
                     "Failed to Save Configuration\n\n\
                     Unable to save configuration for jail '{}':\n\n\
                     {}\n\n\
                     This may indicate:\n\
-                    • Permission denied to write /etc/fail2ban/jail.local\n\
+                    • Permission denied to write {}\n\
                     • Disk space issues\n\
                     • File system errors",
-                    self.state.jail_editor.jail_name, e
+                    self.state.jail_editor.jail_name, e, jail_local_file()
                 ));
             }
         }
@@ -5286,7 +7059,7 @@ impl App {
     /// Handle jail configuration save result
     fn handle_jail_config_saved(&mut self, success: bool) {
         if success {
-            self.state.jail_editor.original_content = self.state.jail_editor.current_content.clone();
+            self.state.jail_editor.original_content = self.state.jail_editor.buffer.text();
             self.state.jail_editor.modified = false;
             self.set_status_message("✓ Jail configuration saved successfully");
             // Close editor after successful save
@@ -5307,10 +7080,10 @@ impl App {
             // Reload the original content
             match self.load_jail_configuration(&self.state.jail_editor.jail_name) {
                 Ok(content) => {
-                    self.state.jail_editor.current_content = content.clone();
+                    self.state.jail_editor.buffer = TextBuffer::from_text(&content);
                     self.state.jail_editor.original_content = content;
                     self.state.jail_editor.modified = false;
-                    self.state.jail_editor.cursor_position = 0;
+                    self.refresh_editor_lint();
                 },
                 Err(e) => {
                     log::error!("Failed to reload jail configuration after rollback: {}", e);
@@ -5319,314 +7092,413 @@ impl App {
         }
     }
     
-    /// Render jail editor screen
-    fn render_jail_editor(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
-        if !self.state.jail_editor.is_open {
-            return;
-        }
-        
-        // Apply standard dialog clearing pattern for full-screen editor
-        frame.render_widget(Clear, area);
-        
-        let solid_background = Paragraph::new(" ".repeat((area.width * area.height) as usize))
-            .style(Style::default().bg(Color::Black))
-            .wrap(Wrap { trim: false });
-        frame.render_widget(solid_background, area);
-        
-        // Main layout: editor area + status bar
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3),      // Header
-                Constraint::Min(0),         // Editor content
-                Constraint::Length(3),      // Footer with shortcuts
-            ])
-            .split(area);
-        
-        // Header with jail name and status
-        let title = format!(" Editing Jail: {} {}", 
-            self.state.jail_editor.jail_name,
-            if self.state.jail_editor.modified { "[MODIFIED]" } else { "" }
-        );
-        let header = Paragraph::new(title)
-            .style(Style::default().fg(Color::Yellow).bg(Color::Black))
-            .block(Block::default().borders(Borders::ALL).title(" Jail Configuration Editor "));
-        frame.render_widget(header, chunks[0]);
-        
-        // Editor content area
-        let editor_area = chunks[1].inner(&Margin { horizontal: 1, vertical: 1 });
-        
-        // Split content into lines for display and calculate cursor position
-        let lines: Vec<String> = self.state.jail_editor.current_content.lines().map(|s| s.to_string()).collect();
-        let visible_lines = editor_area.height as usize;
-        
-        // Calculate cursor line and column using helper method
-        let (cursor_line, cursor_col) = self.get_jail_cursor_line_col();
-        
-        // Calculate scroll offset to keep cursor in view (this should be mutable)
-        let scroll_offset = if cursor_line < self.state.jail_editor.scroll_offset {
-            cursor_line
-        } else if cursor_line >= self.state.jail_editor.scroll_offset + visible_lines {
-            cursor_line.saturating_sub(visible_lines - 1)
+    /// Start watching the file open in an editor for changes made by other tools
+    fn watch_editor_file(&mut self, path: &str) {
+        self.state.external_change = None;
+        self.editor_watcher = match ConfigFileWatcher::new(path) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                log::warn!("External change detection unavailable for {}: {}", path, e);
+                None
+            }
+        };
+    }
+    
+    fn stop_watching_editor_file(&mut self) {
+        self.editor_watcher = None;
+        self.state.external_change = None;
+    }
+    
+    fn is_external_change_dialog_open(&self) -> bool {
+        self.state.external_change.as_ref().is_some_and(|c| c.dialog_open)
+    }
+    
+    /// Whole-file content the open editor started from
+    fn editor_base_content(&self) -> &str {
+        if self.state.jail_editor.is_open {
+            &self.state.jail_editor.file_snapshot
         } else {
-            self.state.jail_editor.scroll_offset
+            &self.state.config_management.original_content
+        }
+    }
+    
+    fn check_external_edits(&mut self) {
+        let Some(ref watcher) = self.editor_watcher else {
+            return;
         };
+        if !watcher.has_changed() {
+            return;
+        }
         
-        // Render visible lines with cursor
-        let mut display_lines = Vec::new();
-        let cursor_visible = (std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() / 500) % 2 == 0;
-            
-        for (i, line) in lines.iter().skip(scroll_offset).take(visible_lines).enumerate() {
-            let line_num = scroll_offset + i + 1;
-            let actual_line_idx = scroll_offset + i;
-            
-            // Check if this line contains the cursor
-            if actual_line_idx == cursor_line {
-                let cursor_pos = cursor_col.min(line.len());
-                
-                let mut line_spans = vec![
-                    Span::styled(format!("{:4} │ ", line_num), Style::default().fg(Color::Gray)),
-                ];
-                
-                // Add text before cursor
-                if cursor_pos > 0 {
-                    line_spans.push(Span::styled(
-                        line[..cursor_pos].to_string(), 
-                        Style::default().fg(Color::White)
-                    ));
-                }
-                
-                // Handle cursor rendering - either replace character at cursor or show at end
-                if cursor_pos < line.len() {
-                    // Cursor is on an existing character - replace it with styled version
-                    let char_at_cursor = line.chars().nth(cursor_pos).unwrap_or(' ');
-                    if cursor_visible {
-                        line_spans.push(Span::styled(
-                            "|".to_string(), 
-                            Style::default().fg(Color::Blue).bg(Color::White)
-                        ));
-                    } else {
-                        line_spans.push(Span::styled(
-                            char_at_cursor.to_string(), 
-                            Style::default().fg(Color::White)
-                        ));
-                    }
-                    
-                    // Add text after cursor (skip the character we just rendered)
-                    if cursor_pos + 1 < line.len() {
-                        line_spans.push(Span::styled(
-                            line[cursor_pos + 1..].to_string(), 
-                            Style::default().fg(Color::White)
-                        ));
-                    }
-                } else {
-                    // Cursor is at end of line - show pipe cursor
-                    if cursor_visible {
-                        line_spans.push(Span::styled(
-                            "|".to_string(), 
-                            Style::default().fg(Color::Blue).bg(Color::White)
-                        ));
-                    }
-                }
-                
-                display_lines.push(Line::from(line_spans));
-            } else {
-                display_lines.push(Line::from(vec![
-                    Span::styled(format!("{:4} │ ", line_num), Style::default().fg(Color::Gray)),
-                    Span::styled(line.clone(), Style::default().fg(Color::White)),
-                ]));
+        let path = watcher.file_path().display().to_string();
+        let disk_content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                // Mid-rename or unreadable for now; an empty file would look like a deletion
+                log::debug!("Could not read {} after a change, retrying: {}", path, e);
+                watcher.recheck();
+                return;
             }
+        };
+        if disk_content == self.editor_base_content() {
+            // Touched or rewritten with identical content (e.g. our own rollback)
+            self.state.external_change = None;
+            return;
         }
         
-        let editor_content = Paragraph::new(display_lines)
-            .style(Style::default().fg(Color::White))
-            .block(Block::default().borders(Borders::ALL).title(" Configuration Content "));
-        frame.render_widget(editor_content, chunks[1]);
-        
-        // Footer with shortcuts
-        let shortcuts = Paragraph::new("Ctrl+S: Save and Close | Esc: Close | Arrow keys: Navigate")
-            .style(Style::default().fg(Color::Gray))
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL).title(" Shortcuts "));
-        frame.render_widget(shortcuts, chunks[2]);
+        log::warn!("{} was changed on disk while open in the editor", path);
+        let dialog_open = self.is_external_change_dialog_open();
+        self.state.external_change = Some(ExternalChangeState { path: path.clone(), disk_content, dialog_open });
+        self.set_status_message(&format!("⚠ {} changed on disk - Ctrl+S to review before saving", path));
     }
     
-    /// Get cursor line and column from cursor position for jail editor
-    fn get_jail_cursor_line_col(&self) -> (usize, usize) {
-        let content = &self.state.jail_editor.current_content;
-        let cursor_pos = self.state.jail_editor.cursor_position;
-        let lines: Vec<&str> = content.lines().collect();
-        
-        if lines.is_empty() {
-            return (0, 0);
+    /// Shared save gate for both editors
+    fn ready_to_save(&mut self, content: &str) -> bool {
+        if has_conflict_markers(content) {
+            self.set_status_message("⚠ Resolve the <<<<<<< / >>>>>>> merge markers before saving");
+            return false;
         }
-        
-        let mut char_count = 0;
-        for (line_idx, line) in lines.iter().enumerate() {
-            let line_end = char_count + line.len();
-            
-            // Handle cursor at the exact position within or at end of this line
-            if cursor_pos <= line_end {
-                let col = cursor_pos - char_count;
-                return (line_idx, col);
-            }
-            
-            // Handle cursor at the newline character (beginning of next line)
-            if cursor_pos == line_end + 1 && line_idx < lines.len() - 1 {
-                return (line_idx + 1, 0);
-            }
-            
-            char_count += line.len() + 1; // +1 for newline
+        if let Some(ref mut change) = self.state.external_change {
+            change.dialog_open = true;
+            return false;
         }
-        
-        // Cursor is beyond all content - put it at end of last line
-        let last_line_idx = lines.len().saturating_sub(1);
-        let last_line_len = lines.get(last_line_idx).map(|l| l.len()).unwrap_or(0);
-        (last_line_idx, last_line_len)
+        true
     }
     
-    /// Update scroll offset to keep cursor in view
-    fn update_editor_scroll(&mut self) {
-        if !self.state.jail_editor.is_open {
+    fn resolve_external_change(&mut self, action: ExternalChangeAction) {
+        let Some(change) = self.state.external_change.take() else {
             return;
-        }
+        };
+        let base = self.editor_base_content().to_string();
+        let disk = change.disk_content;
         
-        let content_before_cursor = &self.state.jail_editor.current_content[..self.state.jail_editor.cursor_position];
-        let cursor_line = content_before_cursor.lines().count().saturating_sub(1);
+        if self.state.jail_editor.is_open {
+            self.resolve_jail_editor_change(action, &base, disk);
+        } else if self.state.config_management.editor_open {
+            self.resolve_config_editor_change(action, &base, disk);
+        }
+    }
+    
+    fn resolve_jail_editor_change(&mut self, action: ExternalChangeAction, base: &str, disk: String) {
+        let jail_name = self.state.jail_editor.jail_name.clone();
         
-        // Assume visible lines is around 20 (will be calculated properly in render)
-        let visible_lines = 20;
+        let section = match action {
+            ExternalChangeAction::Overwrite => {
+                self.state.jail_editor.file_snapshot = disk;
+                self.open_save_review();
+                return;
+            },
+            ExternalChangeAction::LoadDisk => self.extract_jail_section(&disk, &jail_name).map(|s| (s, 0)),
+            ExternalChangeAction::Merge => {
+                let current = self.state.jail_editor.buffer.text();
+                self.replace_jail_section(base, &jail_name, &current)
+                    .map(|ours| three_way_merge(base, &ours, &disk))
+                    .and_then(|merged| self.extract_jail_section(&merged.text, &jail_name).map(|s| (s, merged.conflicts)))
+            },
+        };
         
-        self.state.jail_editor.scroll_offset = if cursor_line < self.state.jail_editor.scroll_offset {
-            cursor_line
-        } else if cursor_line >= self.state.jail_editor.scroll_offset + visible_lines {
-            cursor_line.saturating_sub(visible_lines - 1)
-        } else {
-            self.state.jail_editor.scroll_offset
+        match section {
+            Ok((content, conflicts)) => {
+                let disk_section = self.extract_jail_section(&disk, &jail_name).unwrap_or_else(|_| content.clone());
+                let editor = &mut self.state.jail_editor;
+                // set_text keeps the pre-merge content on the undo stack
+                editor.buffer.set_text(&content);
+                editor.original_content = disk_section;
+                editor.modified = content != editor.original_content;
+                editor.file_snapshot = disk;
+                self.refresh_editor_lint();
+                self.report_external_change_result(action, conflicts);
+            },
+            Err(e) => {
+                self.state.jail_editor.file_snapshot = disk;
+                self.state.error_dialog = Some(format!(
+                    "Could Not Apply Changes From Disk\n\n\
+                    Jail '{}' could not be read from the updated jail.local:\n\n\
+                    {}\n\n\
+                    Your editor content was left unchanged.",
+                    jail_name, e
+                ));
+            }
+        }
+    }
+    
+    fn resolve_config_editor_change(&mut self, action: ExternalChangeAction, base: &str, disk: String) {
+        let editor = &mut self.state.config_management;
+        let conflicts = match action {
+            ExternalChangeAction::Overwrite => {
+                editor.original_content = disk;
+                self.open_save_review();
+                return;
+            },
+            ExternalChangeAction::LoadDisk => {
+                editor.buffer.set_text(&disk);
+                0
+            },
+            ExternalChangeAction::Merge => {
+                let merged = three_way_merge(base, &editor.buffer.text(), &disk);
+                editor.buffer.set_text(&merged.text);
+                merged.conflicts
+            },
         };
+        
+        editor.original_content = disk;
+        editor.modified = editor.buffer.text() != editor.original_content;
+        self.refresh_editor_lint();
+        self.report_external_change_result(action, conflicts);
     }
     
-    /// Move cursor up one line, maintaining column position when possible
-    fn move_cursor_up(&self) -> usize {
-        if !self.state.jail_editor.is_open {
-            return self.state.jail_editor.cursor_position;
+    fn report_external_change_result(&mut self, action: ExternalChangeAction, conflicts: usize) {
+        match action {
+            ExternalChangeAction::LoadDisk => self.set_status_message("✓ Loaded the version on disk"),
+            ExternalChangeAction::Merge if conflicts > 0 => self.set_status_message(&format!(
+                "⚠ Merged with {} conflict(s) - resolve the <<<<<<< markers, then save", conflicts)),
+            ExternalChangeAction::Merge => self.set_status_message("✓ Changes from disk merged into the editor"),
+            ExternalChangeAction::Overwrite => {},
         }
-        
-        let content = &self.state.jail_editor.current_content;
-        let lines: Vec<&str> = content.lines().collect();
-        if lines.is_empty() {
-            return 0;
+    }
+    
+    /// Editor title line, with a warning banner while an external change is pending
+    fn editor_header_line(&self, title: String) -> Line<'static> {
+        let mut spans = vec![Span::raw(title)];
+        if self.state.external_change.is_some() {
+            spans.push(Span::styled(
+                " ⚠ CHANGED ON DISK - Ctrl+S to review/merge ",
+                Style::default().fg(Color::Black).bg(Color::Yellow),
+            ));
+        }
+        Line::from(spans)
+    }
+    
+    /// Old and new whole-file content for whichever editor is open
+    fn pending_save_contents(&self) -> Option<(String, String, String)> {
+        if self.state.jail_editor.is_open {
+            let path = jail_local_file();
+            let old = std::fs::read_to_string(&path).unwrap_or_default();
+            // Section edits are shown as a diff of the whole file, as written by write_jail_configuration
+            match self.replace_jail_section(&old, &self.state.jail_editor.jail_name, &self.state.jail_editor.buffer.text()) {
+                Ok(new) => Some((path, old, new)),
+                Err(e) => {
+                    log::error!("Failed to prepare jail.local diff: {}", e);
+                    None
+                }
+            }
+        } else if self.state.config_management.editor_open {
+            Some((
+                self.state.config_management.current_file_path.clone(),
+                self.state.config_management.original_content.clone(),
+                self.state.config_management.buffer.text(),
+            ))
+        } else {
+            None
+        }
+    }
+    
+    /// Ctrl+S in either editor: show the diff before anything is written
+    fn open_save_review(&mut self) {
+        let content = if self.state.jail_editor.is_open {
+            self.state.jail_editor.buffer.text()
+        } else {
+            self.state.config_management.buffer.text()
+        };
+        if !self.ready_to_save(&content) {
+            return;
         }
         
-        // Get current position
-        let (current_line, current_col) = self.get_jail_cursor_line_col();
+        let Some((path, old, new)) = self.pending_save_contents() else {
+            self.set_status_message("⚠ Could not prepare changes for review");
+            return;
+        };
         
-        // If we're on the first line, can't move up
-        if current_line == 0 {
-            return self.state.jail_editor.cursor_position;
+        let diff = unified_diff(&old, &new, &format!("a{}", path), &format!("b{}", path), 3);
+        if diff.is_empty() {
+            self.set_status_message("✓ No changes to save");
+            return;
         }
         
-        // Move to previous line
-        let target_line_idx = current_line - 1;
-        let target_line = lines[target_line_idx];
-        let target_col = current_col.min(target_line.len());
+        self.state.save_review = Some(SaveReviewState { path, diff, scroll_offset: 0 });
+    }
+    
+    fn confirm_save_review(&mut self) {
+        let Some(review) = self.state.save_review.take() else {
+            return;
+        };
+        log::info!("Saving {} after review:\n{}", review.path, diff_to_string(&review.diff));
         
-        // Calculate new cursor position
-        let mut new_pos = 0;
-        for i in 0..target_line_idx {
-            new_pos += lines[i].len() + 1; // +1 for newline
+        if self.state.jail_editor.is_open {
+            self.save_jail_configuration();
+        } else if self.state.config_management.editor_open {
+            self.save_config_file();
         }
-        new_pos += target_col;
-        
-        new_pos.min(content.len())
     }
     
-    /// Move cursor down one line, maintaining column position when possible
-    fn move_cursor_down(&self) -> usize {
-        if !self.state.jail_editor.is_open {
-            return self.state.jail_editor.cursor_position;
+    fn scroll_save_review(&mut self, delta: isize) {
+        if let Some(ref mut review) = self.state.save_review {
+            let max_scroll = review.diff.len().saturating_sub(1);
+            review.scroll_offset = review.scroll_offset.saturating_add_signed(delta).min(max_scroll);
         }
+    }
+    
+    fn render_save_review(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let Some(ref review) = self.state.save_review else {
+            return;
+        };
         
-        let content = &self.state.jail_editor.current_content;
-        let lines: Vec<&str> = content.lines().collect();
-        if lines.is_empty() {
-            return 0;
-        }
+        let popup_area = centered_rect(85, 80, area);
+        frame.render_widget(Clear, popup_area);
         
-        // Get current position
-        let (current_line, current_col) = self.get_jail_cursor_line_col();
+        let solid_background = Paragraph::new(" ".repeat((popup_area.width * popup_area.height) as usize))
+            .style(Style::default().bg(Color::Black))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(solid_background, popup_area);
         
-        // If we're on the last line, can't move down
-        if current_line >= lines.len() - 1 {
-            return self.state.jail_editor.cursor_position;
-        }
+        let added = review.diff.iter().filter(|l| l.kind == DiffLineKind::Added).count();
+        let removed = review.diff.iter().filter(|l| l.kind == DiffLineKind::Removed).count();
+        let block = Block::default()
+            .title(format!(" Review Changes: {} (+{} -{}) ", review.path, added, removed))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        frame.render_widget(block, popup_area);
         
-        // Move to next line
-        let target_line_idx = current_line + 1;
-        let target_line = lines[target_line_idx];
-        let target_col = current_col.min(target_line.len());
+        let inner = popup_area.inner(&Margin { horizontal: 2, vertical: 1 });
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),     // Diff
+                Constraint::Length(2),  // Actions
+            ])
+            .split(inner);
         
-        // Calculate new cursor position
-        let mut new_pos = 0;
-        for i in 0..target_line_idx {
-            new_pos += lines[i].len() + 1; // +1 for newline
-        }
-        new_pos += target_col;
+        let diff_lines: Vec<Line> = review.diff.iter()
+            .skip(review.scroll_offset)
+            .take(chunks[0].height as usize)
+            .map(|diff_line| {
+                let color = match diff_line.kind {
+                    DiffLineKind::Added => Color::Green,
+                    DiffLineKind::Removed => Color::Red,
+                    DiffLineKind::Hunk => Color::Cyan,
+                    DiffLineKind::Header => Color::Yellow,
+                    DiffLineKind::Context => Color::Gray,
+                };
+                Line::styled(diff_line.text.clone(), Style::default().fg(color))
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(diff_lines).style(Style::default().bg(Color::Black)), chunks[0]);
         
-        new_pos.min(content.len())
+        let actions = Paragraph::new(vec![
+            Line::raw(""),
+            Line::from(vec![
+                Span::styled("ENTER", Style::default().fg(Color::Rgb(0, 150, 255))),
+                Span::raw(":Save and reload | "),
+                Span::styled("ESC", Style::default().fg(Color::Rgb(0, 150, 255))),
+                Span::raw(":Keep editing | "),
+                Span::styled("D", Style::default().fg(Color::Rgb(0, 150, 255))),
+                Span::raw(":Discard changes | "),
+                Span::styled("↑/↓", Style::default().fg(Color::Rgb(0, 150, 255))),
+                Span::raw(":Scroll"),
+            ]),
+        ]).style(Style::default().bg(Color::Black));
+        frame.render_widget(actions, chunks[1]);
     }
     
-    /// Move cursor left respecting line boundaries
-    fn move_cursor_left(&self) -> usize {
-        if !self.state.jail_editor.is_open {
-            return self.state.jail_editor.cursor_position;
-        }
+    /// Modal offered on save when the open file changed on disk
+    fn render_external_change_dialog(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let Some(ref change) = self.state.external_change else {
+            return;
+        };
         
-        let cursor_pos = self.state.jail_editor.cursor_position;
+        let popup_area = centered_rect(80, 70, area);
+        frame.render_widget(Clear, popup_area);
         
-        if cursor_pos == 0 {
-            return 0;
-        }
+        let solid_background = Paragraph::new(" ".repeat((popup_area.width * popup_area.height) as usize))
+            .style(Style::default().bg(Color::Black))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(solid_background, popup_area);
         
-        // Check if the character to the left is a newline
-        let content = &self.state.jail_editor.current_content;
-        let chars: Vec<char> = content.chars().collect();
+        let block = Block::default()
+            .title(" File Changed On Disk ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+        frame.render_widget(block, popup_area);
+        
+        let inner = popup_area.inner(&Margin { horizontal: 2, vertical: 1 });
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(change.path.clone(), Style::default().fg(Color::Cyan)),
+                Span::raw(" was modified by another process while you were editing it."),
+            ]),
+            Line::raw("Changes made on disk since you opened it:"),
+            Line::raw(""),
+        ];
         
-        // If moving left would cross a newline, don't move
-        if cursor_pos > 0 && chars.get(cursor_pos - 1) == Some(&'\n') {
-            return cursor_pos;
+        let diff = unified_diff(self.editor_base_content(), &change.disk_content, "opened", "on disk", 2);
+        let max_diff_lines = (inner.height as usize).saturating_sub(6);
+        for diff_line in diff.iter().take(max_diff_lines) {
+            let color = match diff_line.kind {
+                DiffLineKind::Added => Color::Green,
+                DiffLineKind::Removed => Color::Red,
+                DiffLineKind::Hunk => Color::Cyan,
+                DiffLineKind::Header => Color::Yellow,
+                DiffLineKind::Context => Color::Gray,
+            };
+            lines.push(Line::styled(diff_line.text.clone(), Style::default().fg(color)));
+        }
+        if diff.len() > max_diff_lines {
+            lines.push(Line::styled(format!("... {} more diff lines", diff.len() - max_diff_lines), Style::default().fg(Color::Gray)));
         }
         
-        // Otherwise, move left one character
-        cursor_pos - 1
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::styled("M", Style::default().fg(Color::Rgb(0, 150, 255))),
+            Span::raw(":Merge into editor | "),
+            Span::styled("L", Style::default().fg(Color::Rgb(0, 150, 255))),
+            Span::raw(":Load disk version | "),
+            Span::styled("O", Style::default().fg(Color::Rgb(0, 150, 255))),
+            Span::raw(":Overwrite with mine | "),
+            Span::styled("ESC", Style::default().fg(Color::Rgb(0, 150, 255))),
+            Span::raw(":Keep editing"),
+        ]));
+        
+        let paragraph = Paragraph::new(lines).style(Style::default().bg(Color::Black));
+        frame.render_widget(paragraph, inner);
     }
     
-    /// Move cursor right respecting line boundaries  
-    fn move_cursor_right(&self) -> usize {
+    /// Render jail editor screen
+    fn render_jail_editor(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
         if !self.state.jail_editor.is_open {
-            return self.state.jail_editor.cursor_position;
+            return;
         }
         
-        let content = &self.state.jail_editor.current_content;
-        let cursor_pos = self.state.jail_editor.cursor_position;
+        // Apply standard dialog clearing pattern for full-screen editor
+        frame.render_widget(Clear, area);
         
-        if cursor_pos >= content.len() {
-            return cursor_pos;
-        }
+        let solid_background = Paragraph::new(" ".repeat((area.width * area.height) as usize))
+            .style(Style::default().bg(Color::Black))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(solid_background, area);
         
-        // Check if the current character is a newline
-        let chars: Vec<char> = content.chars().collect();
+        // Main layout: editor area + status bar
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),      // Header
+                Constraint::Min(0),         // Editor content
+                Constraint::Length(3),      // Footer with shortcuts
+            ])
+            .split(area);
         
-        // If moving right would cross a newline, don't move
-        if chars.get(cursor_pos) == Some(&'\n') {
-            return cursor_pos;
-        }
+        // Header with jail name and status
+        let title = format!(" Editing Jail: {} {}", 
+            self.state.jail_editor.jail_name,
+            if self.state.jail_editor.modified { "[MODIFIED]" } else { "" }
+        );
+        let header = Paragraph::new(self.editor_header_line(title))
+            .style(Style::default().fg(Color::Yellow).bg(Color::Black))
+            .block(Block::default().borders(Borders::ALL).title(" Jail Configuration Editor "));
+        frame.render_widget(header, chunks[0]);
         
-        // Otherwise, move right one character
-        cursor_pos + 1
+        let editor = &self.state.jail_editor;
+        self.render_editor_buffer(frame, chunks[1], &editor.buffer, &editor.lint_issues, " Configuration Content ");
+        self.render_editor_footer(frame, chunks[2], &editor.buffer, &editor.lint_issues);
     }
     
     /// Create backup of jail configuration before editing
@@ -5634,7 +7506,7 @@ impl App {
         use std::fs;
         use chrono::Utc;
         
-        let jail_local_path = "/etc/fail2ban/jail.local";
+        let jail_local_path = &jail_local_file();
         let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
         let backup_path = format!("/tmp/jail_backup_{}_{}.conf", jail_name, timestamp);
         
@@ -5655,7 +7527,7 @@ impl App {
         use std::fs;
         
         // First try to load from jail.local
-        let jail_local_path = "/etc/fail2ban/jail.local";
+        let jail_local_path = &jail_local_file();
         let content = fs::read_to_string(jail_local_path)
             .map_err(|e| anyhow::anyhow!("Failed to read jail.local: {}", e))?;
         
@@ -5686,7 +7558,7 @@ impl App {
     fn write_jail_configuration(&self, jail_name: &str, new_content: &str) -> Result<()> {
         use std::fs;
         
-        let jail_local_path = "/etc/fail2ban/jail.local";
+        let jail_local_path = &jail_local_file();
         
         // Read the current jail.local file
         let current_content = fs::read_to_string(jail_local_path)
@@ -5695,8 +7567,8 @@ impl App {
         // Replace the jail section with new content
         let updated_content = self.replace_jail_section(&current_content, jail_name, new_content)?;
         
-        // Write back to jail.local
-        fs::write(jail_local_path, updated_content)
+        // Write back to jail.local (atomic, keeps mode/owner/line endings)
+        write_config_file(jail_local_path, &updated_content)
             .map_err(|e| anyhow::anyhow!("Failed to write jail.local: {}", e))?;
         
         log::info!("Successfully updated jail configuration for: {}", jail_name);
@@ -5737,12 +7609,10 @@ impl App {
     
     /// Test fail2ban reload to ensure configuration is valid
     fn test_fail2ban_reload(&self) -> Result<()> {
-        use std::process::Command;
-        
         log::info!("Testing fail2ban reload...");
         
-        let output = Command::new("fail2ban-client")
-            .args(&["reload"])
+        let output = client_command()
+            .args(["reload"])
             .output()
             .map_err(|e| anyhow::anyhow!("Failed to execute fail2ban-client: {}", e))?;
         
@@ -5758,12 +7628,10 @@ impl App {
     /// Rollback jail configuration to backup
     fn rollback_jail_configuration(&mut self) {
         if let Some(ref backup_path) = self.state.jail_editor.backup_path {
-            use std::fs;
-            
-            let jail_local_path = "/etc/fail2ban/jail.local";
+            let jail_local_path = &jail_local_file();
             
-            match fs::copy(backup_path, jail_local_path) {
-                Ok(_) => {
+            match copy_config_file(backup_path, jail_local_path) {
+                Ok(()) => {
                     log::info!("Successfully rolled back jail configuration from backup: {}", backup_path);
                     // Try to reload fail2ban again
                     if let Err(e) = self.test_fail2ban_reload() {
@@ -5898,14 +7766,12 @@ impl App {
         match std::fs::read_to_string(&file_path) {
             Ok(content) => {
                 self.state.config_management.editor_open = true;
-                self.state.config_management.current_file_path = file_path;
-                self.state.config_management.current_file_content = content.clone();
+                self.state.config_management.current_file_path = file_path.clone();
+                self.state.config_management.buffer = TextBuffer::from_text(&content);
                 self.state.config_management.original_content = content;
-                self.state.config_management.cursor_position = 0;
-                self.state.config_management.scroll_offset = 0;
                 self.state.config_management.modified = false;
-                self.state.config_management.cursor_blink_timer = std::time::Instant::now();
-                self.state.config_management.cursor_visible = true;
+                self.refresh_editor_lint();
+                self.watch_editor_file(&file_path);
                 self.set_status_message("✓ Configuration file opened for editing");
             },
             Err(e) => {
@@ -5920,15 +7786,13 @@ impl App {
             // TODO: Add confirmation dialog for unsaved changes
             self.set_status_message("⚠ Unsaved changes discarded");
         }
+        self.stop_watching_editor_file();
         self.state.config_management.editor_open = false;
         self.state.config_management.current_file_path.clear();
-        self.state.config_management.current_file_content.clear();
+        self.state.config_management.buffer = TextBuffer::default();
         self.state.config_management.original_content.clear();
-        self.state.config_management.cursor_position = 0;
-        self.state.config_management.scroll_offset = 0;
         self.state.config_management.modified = false;
-        self.state.config_management.cursor_blink_timer = std::time::Instant::now();
-        self.state.config_management.cursor_visible = true;
+        self.state.config_management.lint_issues.clear();
         
         // Return to Configuration screen
         self.state.current_screen = Screen::Configuration;
@@ -5936,9 +7800,17 @@ impl App {
     
     fn save_config_file(&mut self) {
         let file_path = self.state.config_management.current_file_path.clone();
-        let content = self.state.config_management.current_file_content.clone();
+        let content = self.state.config_management.buffer.text();
+        
+        if !self.ready_to_save(&content) {
+            return;
+        }
         
-        match std::fs::write(&file_path, &content) {
+        let result = write_config_file(&file_path, &content);
+        self.audit(AuditEntry::new(AuditAction::ConfigSave, file_path.clone())
+            .file_change(&self.state.config_management.original_content, &content)
+            .outcome(&result));
+        match result {
             Ok(()) => {
                 self.state.config_management.original_content = content;
                 self.state.config_management.modified = false;
@@ -5947,16 +7819,15 @@ impl App {
                 
                 // Reload fail2ban configuration
                 self.reload_fail2ban_configuration();
+                self.refresh_definitions();
                 
                 // Close editor and return to config page
+                self.stop_watching_editor_file();
                 self.state.config_management.editor_open = false;
                 self.state.config_management.current_file_path.clear();
-                self.state.config_management.current_file_content.clear();
+                self.state.config_management.buffer = TextBuffer::default();
                 self.state.config_management.original_content.clear();
-                self.state.config_management.cursor_position = 0;
-                self.state.config_management.scroll_offset = 0;
-                self.state.config_management.cursor_blink_timer = std::time::Instant::now();
-                self.state.config_management.cursor_visible = true;
+                self.state.config_management.lint_issues.clear();
                 
                 // Return to Configuration screen
                 self.state.current_screen = Screen::Configuration;
@@ -5971,7 +7842,9 @@ impl App {
     fn reload_fail2ban_configuration(&mut self) {
         // Use fail2ban-client to reload the configuration
         match std::process::Command::new("sudo")
-            .args(["fail2ban-client", "reload"])
+            .arg("fail2ban-client")
+            .args(fail2ban_paths().client_args())
+            .arg("reload")
             .output() {
             Ok(output) => {
                 if output.status.success() {
@@ -5989,26 +7862,22 @@ impl App {
     
     fn backup_configuration(&mut self) {
         let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-        let backup_dir = "/etc/fail2ban/backups";
+        let backup_dir = fail2ban_paths().backup_dir().display().to_string();
         
         // Create backup directory if it doesn't exist
-        if let Err(e) = std::fs::create_dir_all(backup_dir) {
+        if let Err(e) = std::fs::create_dir_all(&backup_dir) {
             self.set_status_message(&format!("⚠ Failed to create backup directory: {}", e));
             return;
         }
         
         let mut backup_count = 0;
-        let files_to_backup = [
-            ("/etc/fail2ban/jail.local", "jail.local"),
-            ("/etc/fail2ban/jail.conf", "jail.conf"),
-            ("/etc/fail2ban/fail2ban.local", "fail2ban.local"),
-            ("/etc/fail2ban/fail2ban.conf", "fail2ban.conf"),
-        ];
+        let files_to_backup = ["jail.local", "jail.conf", "fail2ban.local", "fail2ban.conf"];
         
-        for (source_path, filename) in &files_to_backup {
-            if std::path::Path::new(source_path).exists() {
+        for filename in &files_to_backup {
+            let source_path = fail2ban_paths().config_file(filename).display().to_string();
+            if std::path::Path::new(&source_path).exists() {
                 let backup_path = format!("{}/{}_{}", backup_dir, filename, timestamp);
-                match std::fs::copy(source_path, &backup_path) {
+                match std::fs::copy(&source_path, &backup_path) {
                     Ok(_) => {
                         backup_count += 1;
                         log::info!("Backed up {} to {}", source_path, backup_path);
@@ -6021,6 +7890,8 @@ impl App {
         }
         
         if backup_count > 0 {
+            self.audit(AuditEntry::new(AuditAction::ConfigBackup, backup_dir.clone())
+                .change("", format!("{} files, suffix _{}", backup_count, timestamp)));
             self.set_status_message(&format!("✓ {} configuration files backed up to {}", backup_count, backup_dir));
         } else {
             self.set_status_message("⚠ No configuration files found to backup");
@@ -6033,15 +7904,261 @@ impl App {
     }
     
     fn test_configuration(&mut self) {
-        match self.test_fail2ban_reload() {
-            Ok(()) => {
-                self.set_status_message("✓ Configuration test passed - fail2ban reload successful");
+        // Dry run only - the running daemon is never reloaded here
+        let issues = ConfigValidator::new().validate();
+        let error_count = issues.iter().filter(|i| i.severity == IssueSeverity::Error).count();
+        let warning_count = issues.len() - error_count;
+        
+        for issue in &issues {
+            log::info!("Configuration check {:?} at {}: {}", issue.severity, issue.location(), issue.message);
+        }
+        
+        if error_count > 0 {
+            self.set_status_message(&format!("✗ Configuration test failed: {} error(s), {} warning(s)", error_count, warning_count));
+        } else if warning_count > 0 {
+            self.set_status_message(&format!("⚠ Configuration test passed with {} warning(s)", warning_count));
+        } else {
+            self.set_status_message("✓ Configuration test passed - no problems found");
+        }
+        
+        self.state.config_management.validation_issues = issues;
+        self.state.config_management.last_validated = Some(chrono::Local::now());
+    }
+    
+    fn open_new_jail_wizard(&mut self) {
+        let filters = ConfigValidator::new().list_definitions("filter.d");
+        if filters.is_empty() {
+            self.set_status_message(&format!("⚠ No filters found in {}/filter.d", fail2ban_paths().root().display()));
+            return;
+        }
+        
+        // Start on sshd when it is available - the most common first jail
+        let filter_index = filters.iter().position(|f| f == "sshd").unwrap_or(0);
+        let jail = NewJail::from_filter(&filters[filter_index]);
+        self.state.new_jail_wizard = Some(NewJailWizardState {
+            filters,
+            filter_index,
+            jail,
+            field: NewJailField::Filter,
+            problems: Vec::new(),
+        });
+        self.validate_new_jail();
+    }
+    
+    fn validate_new_jail(&mut self) {
+        if let Some(ref mut wizard) = self.state.new_jail_wizard {
+            wizard.problems = wizard.jail.validate(&ConfigValidator::new());
+        }
+    }
+    
+    fn handle_new_jail_wizard_key(&mut self, key: KeyEvent) {
+        let Some(ref mut wizard) = self.state.new_jail_wizard else {
+            return;
+        };
+        
+        match (key.code, wizard.field) {
+            (KeyCode::Tab, _) => wizard.field = wizard.field.step(1),
+            (KeyCode::BackTab, _) => wizard.field = wizard.field.step(-1),
+            (KeyCode::Up | KeyCode::Down | KeyCode::PageUp | KeyCode::PageDown, NewJailField::Filter) => {
+                let delta: isize = match key.code {
+                    KeyCode::Up => -1,
+                    KeyCode::Down => 1,
+                    KeyCode::PageUp => -10,
+                    _ => 10,
+                };
+                let last = wizard.filters.len() - 1;
+                wizard.filter_index = (wizard.filter_index as isize + delta).clamp(0, last as isize) as usize;
+                let filter = wizard.filters[wizard.filter_index].clone();
+                wizard.jail.apply_filter(&filter);
+            },
+            (KeyCode::Char(c), NewJailField::Filter) => {
+                // Jump to the next filter starting with the typed letter
+                let count = wizard.filters.len();
+                if let Some(index) = (1..=count).map(|i| (wizard.filter_index + i) % count)
+                    .find(|&i| wizard.filters[i].starts_with(c)) {
+                    wizard.filter_index = index;
+                    let filter = wizard.filters[index].clone();
+                    wizard.jail.apply_filter(&filter);
+                }
+            },
+            (KeyCode::Left | KeyCode::Right | KeyCode::Char(' '), NewJailField::Backend) => {
+                let index = JAIL_BACKENDS.iter().position(|b| *b == wizard.jail.backend).unwrap_or(0);
+                let next = if key.code == KeyCode::Left { index + JAIL_BACKENDS.len() - 1 } else { index + 1 };
+                wizard.jail.backend = JAIL_BACKENDS[next % JAIL_BACKENDS.len()].to_string();
             },
+            (KeyCode::Left | KeyCode::Right | KeyCode::Char(' '), NewJailField::Destination) => {
+                wizard.jail.destination = match wizard.jail.destination {
+                    JailDestination::DropIn => JailDestination::JailLocal,
+                    JailDestination::JailLocal => JailDestination::DropIn,
+                };
+            },
+            (KeyCode::Down, _) => wizard.field = wizard.field.step(1),
+            (KeyCode::Up, _) => wizard.field = wizard.field.step(-1),
+            (KeyCode::Char(c), field) => {
+                if let Some(value) = Self::new_jail_text_field(&mut wizard.jail, field) {
+                    value.push(c);
+                }
+            },
+            (KeyCode::Backspace, field) => {
+                if let Some(value) = Self::new_jail_text_field(&mut wizard.jail, field) {
+                    value.pop();
+                }
+            },
+            _ => {},
+        }
+        
+        self.validate_new_jail();
+    }
+    
+    /// Free-text fields of the wizard (filter, backend and destination are picked instead)
+    fn new_jail_text_field(jail: &mut NewJail, field: NewJailField) -> Option<&mut String> {
+        match field {
+            NewJailField::Name => Some(&mut jail.name),
+            NewJailField::Port => Some(&mut jail.port),
+            NewJailField::LogPath => Some(&mut jail.logpath),
+            NewJailField::MaxRetry => Some(&mut jail.maxretry),
+            NewJailField::BanTime => Some(&mut jail.bantime),
+            NewJailField::Filter | NewJailField::Backend | NewJailField::Destination => None,
+        }
+    }
+    
+    fn create_new_jail(&mut self) {
+        self.validate_new_jail();
+        let Some(wizard) = self.state.new_jail_wizard.clone() else {
+            return;
+        };
+        if let Some(problem) = wizard.problems.first() {
+            self.set_status_message(&format!("✗ Cannot create jail: {}", problem));
+            return;
+        }
+        
+        let jail = wizard.jail;
+        let name = jail.name.trim().to_string();
+        let result = jail.write(ConfigValidator::new().config_dir());
+        let target = match result {
+            Ok(ref path) => format!("{} [{}]", path.display(), name),
+            Err(_) => name.clone(),
+        };
+        self.audit(AuditEntry::new(AuditAction::JailCreate, target).change("", jail.section_text()).outcome(&result));
+        let path = match result {
+            Ok(path) => path,
             Err(e) => {
-                self.set_status_message(&format!("⚠ Configuration test failed: {}", e));
-                log::error!("Configuration test failed: {}", e);
+                log::error!("Failed to create jail {}: {}", name, e);
+                self.state.error_dialog = Some(format!("Failed to create jail '{}':\n\n{}", name, e));
+                return;
             }
+        };
+        self.state.new_jail_wizard = None;
+        self.refresh_definitions();
+        
+        if !matches!(self.state.fail2ban_service, ServiceStatus::Running) {
+            self.set_status_message(&format!("✓ Jail '{}' written to {} - it starts with fail2ban", name, path.display()));
+            return;
+        }
+        
+        match self.fail2ban_client.start_jail(&name) {
+            Ok(()) => {
+                log::info!("Started new jail {}", name);
+                self.set_status_message(&format!("✓ Jail '{}' created in {} and started", name, path.display()));
+            },
+            Err(e) => {
+                log::error!("New jail {} did not start: {}", name, e);
+                self.set_status_message(&format!("⚠ Jail '{}' written to {} but did not start: {}", name, path.display(), e));
+            },
+        }
+        self.refresh_jail_data();
+    }
+    
+    fn render_new_jail_wizard(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let Some(ref wizard) = self.state.new_jail_wizard else {
+            return;
+        };
+        
+        let popup_area = centered_rect(70, 80, area);
+        frame.render_widget(Clear, popup_area);
+        
+        let solid_background = Paragraph::new(" ".repeat((popup_area.width * popup_area.height) as usize))
+            .style(Style::default().bg(Color::Black))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(solid_background, popup_area);
+        
+        let block = Block::default()
+            .title(" New Jail ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        frame.render_widget(block, popup_area);
+        
+        let inner = popup_area.inner(&Margin { horizontal: 2, vertical: 1 });
+        let jail = &wizard.jail;
+        let config_dir = ConfigValidator::new().config_dir().to_path_buf();
+        let mut lines = Vec::new();
+        
+        for field in NewJailField::ALL {
+            let active = field == wizard.field;
+            let value = match field {
+                NewJailField::Filter => format!("{}  ({} of {})", jail.filter, wizard.filter_index + 1, wizard.filters.len()),
+                NewJailField::Name => jail.name.clone(),
+                NewJailField::Port => jail.port.clone(),
+                NewJailField::LogPath if jail.backend == "systemd" => format!("{} (journal is used)", jail.logpath),
+                NewJailField::LogPath => jail.logpath.clone(),
+                NewJailField::Backend => jail.backend.clone(),
+                NewJailField::MaxRetry => jail.maxretry.clone(),
+                NewJailField::BanTime => jail.bantime.clone(),
+                NewJailField::Destination => jail.target_path(&config_dir).display().to_string(),
+            };
+            let hint = match field {
+                NewJailField::Filter if active => "  ↑/↓ or type a letter",
+                NewJailField::Backend | NewJailField::Destination if active => "  ←/→ to change",
+                _ => "",
+            };
+            let cursor = if active && field.is_text() { "_" } else { "" };
+            let value_style = if active {
+                Style::default().fg(Color::White).bg(Color::Blue)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(if active { "▶ " } else { "  " }, Style::default().fg(Color::Yellow)),
+                Span::styled(format!("{:<10} ", field.label()), Style::default().fg(Color::Yellow)),
+                Span::styled(format!("{}{}", value, cursor), value_style),
+                Span::styled(hint, Style::default().fg(Color::DarkGray)),
+            ]));
+        }
+        
+        lines.push(Line::raw(""));
+        lines.push(Line::styled("Section to be written:", Style::default().fg(Color::Yellow)));
+        for text in jail.section_text().lines() {
+            lines.push(Line::styled(format!("  {}", text), Style::default().fg(Color::Cyan)));
+        }
+        
+        lines.push(Line::raw(""));
+        if wizard.problems.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("✓", Style::default().fg(Color::Green)),
+                Span::raw(" Ready to create"),
+            ]));
         }
+        for problem in &wizard.problems {
+            lines.push(Line::from(vec![
+                Span::styled("✗", Style::default().fg(Color::Red)),
+                Span::raw(format!(" {}", problem)),
+            ]));
+        }
+        
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::styled("TAB", Style::default().fg(Color::Rgb(0, 150, 255))),
+            Span::raw(":Next field | "),
+            Span::styled("ENTER", Style::default().fg(Color::Rgb(0, 150, 255))),
+            Span::raw(":Create and start | "),
+            Span::styled("ESC", Style::default().fg(Color::Rgb(0, 150, 255))),
+            Span::raw(":Cancel"),
+        ]));
+        
+        let paragraph = Paragraph::new(lines)
+            .style(Style::default().bg(Color::Black))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, inner);
     }
     
     fn render_config_editor(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
@@ -6072,240 +8189,448 @@ impl App {
             self.state.config_management.current_file_path,
             if self.state.config_management.modified { "[MODIFIED]" } else { "" }
         );
-        let header = Paragraph::new(title)
+        let header = Paragraph::new(self.editor_header_line(title))
             .style(Style::default().fg(Color::Yellow).bg(Color::Black))
             .block(Block::default().borders(Borders::ALL).title(" Configuration File Editor "));
         frame.render_widget(header, chunks[0]);
         
-        // Editor content area
-        let editor_area = chunks[1].inner(&Margin { horizontal: 1, vertical: 1 });
-        
-        // Split content into lines for display and calculate cursor position
-        let lines: Vec<String> = self.state.config_management.current_file_content.lines().map(|s| s.to_string()).collect();
-        let visible_lines = editor_area.height as usize;
-        
-        // Calculate cursor line and column using helper method
-        let (cursor_line, cursor_col) = self.get_config_cursor_line_col();
+        let editor = &self.state.config_management;
+        self.render_editor_buffer(frame, chunks[1], &editor.buffer, &editor.lint_issues, "");
+        self.render_editor_footer(frame, chunks[2], &editor.buffer, &editor.lint_issues);
+    }
+    
+    /// Editor content with a line-number and lint gutter, INI highlighting, selection and search matches
+    fn render_editor_buffer(&self, frame: &mut Frame, area: ratatui::layout::Rect, buffer: &TextBuffer, issues: &[ValidationIssue], title: &str) {
+        let visible_lines = area.height.saturating_sub(2).max(1) as usize;
+        let cursor = buffer.cursor();
+        let selection = buffer.selection();
         
-        // Calculate scroll offset to keep cursor in view
-        let scroll_offset = if cursor_line < self.state.config_management.scroll_offset {
-            cursor_line
-        } else if cursor_line >= self.state.config_management.scroll_offset + visible_lines {
-            cursor_line.saturating_sub(visible_lines - 1)
+        // Keep the cursor in view even if the terminal is smaller than the page size used for scrolling
+        let scroll_offset = if cursor.line < buffer.scroll_offset {
+            cursor.line
+        } else if cursor.line >= buffer.scroll_offset + visible_lines {
+            cursor.line + 1 - visible_lines
         } else {
-            self.state.config_management.scroll_offset
+            buffer.scroll_offset
         };
         
-        // Render visible lines with cursor
-        let mut display_lines = Vec::new();
         let cursor_visible = (std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_millis() / 500) % 2 == 0;
+        
+        let normal = Style::default().fg(Color::White);
+        let selected = Style::default().fg(Color::Black).bg(Color::Cyan);
+        let matched = Style::default().fg(Color::Black).bg(Color::Yellow);
+        let cursor_style = Style::default().fg(Color::Blue).bg(Color::White);
+        
+        let mut display_lines = Vec::new();
+        for (line_idx, line) in buffer.lines().iter().enumerate().skip(scroll_offset).take(visible_lines) {
+            let gutter_style = if line_idx == cursor.line {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            // Issues are sorted with errors first for each line
+            let marker = match issues.iter().find(|i| i.line == Some(line_idx + 1)).map(|i| i.severity) {
+                Some(IssueSeverity::Error) => Span::styled("✗", Style::default().fg(Color::Red)),
+                Some(IssueSeverity::Warning) => Span::styled("⚠", Style::default().fg(Color::Yellow)),
+                None => Span::raw(" "),
+            };
+            let mut spans = vec![marker, Span::styled(format!("{:4} │ ", line_idx + 1), gutter_style)];
             
-        for (i, line) in lines.iter().skip(scroll_offset).take(visible_lines).enumerate() {
-            let line_num = scroll_offset + i + 1;
-            let actual_line_idx = scroll_offset + i;
-            
-            // Check if this line contains the cursor
-            if actual_line_idx == cursor_line {
-                let cursor_pos = cursor_col.min(line.len());
-                
-                let mut line_spans = vec![
-                    Span::styled(format!("{:4} │ ", line_num), Style::default().fg(Color::Gray)),
-                ];
-                
-                // Add text before cursor
-                if cursor_pos > 0 {
-                    line_spans.push(Span::styled(
-                        line[..cursor_pos].to_string(), 
-                        Style::default().fg(Color::White)
-                    ));
-                }
-                
-                // Handle cursor rendering - either replace character at cursor or show at end
-                if cursor_pos < line.len() {
-                    // Cursor is on an existing character - replace it with styled version
-                    let char_at_cursor = line.chars().nth(cursor_pos).unwrap_or(' ');
-                    if cursor_visible {
-                        line_spans.push(Span::styled(
-                            "|".to_string(), 
-                            Style::default().fg(Color::Blue).bg(Color::White)
-                        ));
-                    } else {
-                        line_spans.push(Span::styled(
-                            char_at_cursor.to_string(), 
-                            Style::default().fg(Color::White)
-                        ));
-                    }
-                    
-                    // Add text after cursor (skip the character we just rendered)
-                    if cursor_pos + 1 < line.len() {
-                        line_spans.push(Span::styled(
-                            line[cursor_pos + 1..].to_string(), 
-                            Style::default().fg(Color::White)
-                        ));
-                    }
+            let matches = buffer.line_matches(line_idx);
+            let text = line.trim_end_matches('\r');
+            let tokens = classify_line(text);
+            let chars: Vec<char> = text.chars().collect();
+            let style_at = |col: usize| {
+                let here = Position::new(line_idx, col);
+                if selection.is_some_and(|(start, end)| here >= start && here < end) {
+                    selected
+                } else if matches.iter().any(|&(start, end)| col >= start && col < end) {
+                    matched
                 } else {
-                    // Cursor is at end of line - show pipe cursor
-                    if cursor_visible {
-                        line_spans.push(Span::styled(
-                            "|".to_string(), 
-                            Style::default().fg(Color::Blue).bg(Color::White)
-                        ));
-                    }
+                    ini_token_style(tokens[col])
                 }
-                
-                display_lines.push(Line::from(line_spans));
-            } else {
-                display_lines.push(Line::from(vec![
-                    Span::styled(format!("{:4} │ ", line_num), Style::default().fg(Color::Gray)),
-                    Span::styled(line.clone(), Style::default().fg(Color::White)),
-                ]));
+            };
+            
+            // Group characters into runs of the same style; the cursor replaces the character under it
+            let mut run = String::new();
+            let mut run_style = normal;
+            for (col, c) in chars.iter().enumerate() {
+                let is_cursor = line_idx == cursor.line && col == cursor.col && cursor_visible;
+                let style = if is_cursor { cursor_style } else { style_at(col) };
+                if style != run_style && !run.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut run), run_style));
+                }
+                run_style = style;
+                run.push(if is_cursor { '|' } else { *c });
+            }
+            if !run.is_empty() {
+                spans.push(Span::styled(run, run_style));
             }
+            if line_idx == cursor.line && cursor.col >= chars.len() && cursor_visible {
+                spans.push(Span::styled("|", cursor_style));
+            }
+            
+            display_lines.push(Line::from(spans));
         }
         
+        let mut block = Block::default().borders(Borders::ALL);
+        if !title.is_empty() {
+            block = block.title(title.to_string());
+        }
         let editor_widget = Paragraph::new(display_lines)
-            .block(Block::default().borders(Borders::ALL))
-            .wrap(Wrap { trim: false });
+            .style(normal)
+            .block(block);
+        frame.render_widget(editor_widget, area);
+    }
+    
+    /// Cursor position and shortcuts, or the search/replace prompt while it is open.
+    /// Lint findings for the cursor line are shown in the border title.
+    fn render_editor_footer(&self, frame: &mut Frame, area: ratatui::layout::Rect, buffer: &TextBuffer, issues: &[ValidationIssue]) {
+        let hotkey = Style::default().fg(Color::Rgb(0, 150, 255));
+        let line = match buffer.search {
+            Some(ref search) => {
+                let field_style = |field: SearchField| {
+                    if search.field == field {
+                        Style::default().fg(Color::Black).bg(Color::White)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }
+                };
+                let mut spans = match search.mode {
+                    SearchMode::Find => vec![
+                        Span::styled(" Find: ", Style::default().fg(Color::Yellow)),
+                        Span::styled(format!("{}_", search.query), field_style(SearchField::Query)),
+                    ],
+                    SearchMode::Replace => vec![
+                        Span::styled(" Regex: ", Style::default().fg(Color::Yellow)),
+                        Span::styled(format!("{}_", search.query), field_style(SearchField::Query)),
+                        Span::styled("  Replace with: ", Style::default().fg(Color::Yellow)),
+                        Span::styled(format!("{}_", search.replacement), field_style(SearchField::Replacement)),
+                    ],
+                };
+                let help = match search.mode {
+                    SearchMode::Find => "Next | ↑:Previous | Esc:Done",
+                    SearchMode::Replace => "Replace all | Tab:Switch field | Esc:Cancel",
+                };
+                spans.push(Span::raw("   "));
+                spans.push(Span::styled("Enter", hotkey));
+                spans.push(Span::styled(format!(":{}", help), Style::default().fg(Color::Gray)));
+                Line::from(spans)
+            },
+            None => {
+                let cursor = buffer.cursor();
+                Line::from(vec![
+                    Span::styled(format!(" Ln {}, Col {} | {} lines | ", cursor.line + 1, cursor.col + 1, buffer.lines().len()), Style::default().fg(Color::Gray)),
+                    Span::styled("Ctrl+S", hotkey),
+                    Span::styled(":Save ", Style::default().fg(Color::Gray)),
+                    Span::styled("Ctrl+Z/Y", hotkey),
+                    Span::styled(":Undo/Redo ", Style::default().fg(Color::Gray)),
+                    Span::styled("Ctrl+F", hotkey),
+                    Span::styled(":Find ", Style::default().fg(Color::Gray)),
+                    Span::styled("Ctrl+R", hotkey),
+                    Span::styled(":Replace ", Style::default().fg(Color::Gray)),
+                    Span::styled("Ctrl+C/X/V", hotkey),
+                    Span::styled(":Copy/Cut/Paste ", Style::default().fg(Color::Gray)),
+                    Span::styled("Esc", hotkey),
+                    Span::styled(":Close", Style::default().fg(Color::Gray)),
+                ])
+            }
+        };
         
-        frame.render_widget(editor_widget, chunks[1]);
+        let cursor_line = buffer.cursor().line + 1;
+        let title = match issues.iter().find(|i| i.line == Some(cursor_line)) {
+            Some(issue) if issue.severity == IssueSeverity::Error => Span::styled(format!(" ✗ {} ", issue.message), Style::default().fg(Color::Red)),
+            Some(issue) => Span::styled(format!(" ⚠ {} ", issue.message), Style::default().fg(Color::Yellow)),
+            None => Span::raw(" Shortcuts "),
+        };
         
-        // Footer with shortcuts and cursor position
-        let (cursor_line, cursor_col) = self.get_config_cursor_line_col();
-        let footer_text = format!(" Line {}, Col {} | {} lines total | Ctrl+S:Save | Esc:Close ", 
-            cursor_line + 1, cursor_col + 1, lines.len());
+        let footer = Paragraph::new(line)
+            .style(Style::default().bg(Color::Black))
+            .block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(footer, area);
+    }
+    
+    /// Queue the open editor's content (or the selected file) for $VISUAL/$EDITOR
+    fn request_external_edit(&mut self) {
+        let close_if_unchanged = !self.is_editor_open();
+        if close_if_unchanged && self.state.config_management.focus == ConfigFocus::Definitions {
+            let rows = self.definition_rows();
+            if !matches!(rows.get(self.state.config_management.definition_index), Some(DefinitionRow::Definition(_))) {
+                self.set_status_message("⚠ Select a filter, action or jail.d file to edit");
+                return;
+            }
+            self.activate_definition_row();
+            if !self.state.config_management.editor_open {
+                return;
+            }
+        } else if close_if_unchanged {
+            let selected_file = self.state.config_management.config_files[self.state.config_management.selected_file_index].clone();
+            if !selected_file.exists {
+                self.set_status_message(&format!("⚠ File does not exist: {}", selected_file.path));
+                return;
+            }
+            if !selected_file.editable {
+                self.set_status_message(&format!("⚠ File is not editable: {}", selected_file.path));
+                return;
+            }
+            self.open_config_editor(selected_file.path);
+            if !self.state.config_management.editor_open {
+                return;
+            }
+        }
         
-        let footer = Paragraph::new(footer_text)
-            .style(Style::default().fg(Color::Gray).bg(Color::Black))
-            .block(Block::default().borders(Borders::ALL));
-        frame.render_widget(footer, chunks[2]);
+        let (content, file_name) = if self.state.jail_editor.is_open {
+            (self.state.jail_editor.buffer.text(), format!("jail-{}.local", self.state.jail_editor.jail_name))
+        } else {
+            let path = std::path::Path::new(&self.state.config_management.current_file_path);
+            let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "config.local".to_string());
+            (self.state.config_management.buffer.text(), file_name)
+        };
+        self.external_edit_request = Some(ExternalEditRequest { content, file_name, close_if_unchanged });
+    }
+    
+    /// Called by the main loop before it suspends the TUI
+    pub fn take_external_edit_request(&mut self) -> Option<ExternalEditRequest> {
+        self.external_edit_request.take()
     }
     
-    // Configuration editor cursor movement methods
-    fn move_config_cursor_left(&self) -> usize {
-        if self.state.config_management.cursor_position > 0 {
-            self.state.config_management.cursor_position - 1
+    /// Feed the edited copy into the open editor and continue with the normal review and save
+    pub fn finish_external_edit(&mut self, request: ExternalEditRequest, result: crate::utils::errors::Result<String>) {
+        let edited = match result {
+            Ok(edited) => edited,
+            Err(e) => {
+                log::error!("External edit of {} failed: {}", request.file_name, e);
+                if request.close_if_unchanged {
+                    self.close_config_editor();
+                }
+                self.set_status_message(&format!("✗ External editor failed: {}", e));
+                return;
+            }
+        };
+        
+        // Most editors add a final newline the section body never had
+        let edited = if request.content.ends_with('\n') {
+            edited.as_str()
         } else {
-            0
+            edited.strip_suffix('\n').unwrap_or(&edited)
+        };
+        if edited == request.content {
+            if request.close_if_unchanged {
+                self.close_config_editor();
+            }
+            self.set_status_message("✓ No changes made in the external editor");
+            return;
+        }
+        
+        if let Some(buffer) = self.active_editor_buffer_mut() {
+            buffer.set_text(edited);
+        }
+        self.refresh_editor_state();
+        
+        // Same path as Ctrl+S: checks, diff review, then backup/write/reload on confirm
+        self.open_save_review();
+        let issues = if self.state.jail_editor.is_open {
+            &self.state.jail_editor.lint_issues
+        } else {
+            &self.state.config_management.lint_issues
+        };
+        let errors = issues.iter().filter(|i| i.severity == IssueSeverity::Error).count();
+        if errors > 0 {
+            self.set_status_message(&format!("⚠ {} problem(s) flagged in the edited file - see the editor gutter", errors));
         }
     }
     
-    fn move_config_cursor_right(&self) -> usize {
-        let max_pos = self.state.config_management.current_file_content.len();
-        if self.state.config_management.cursor_position < max_pos {
-            self.state.config_management.cursor_position + 1
+    /// Update the modified flag and lint after the open editor's buffer changed
+    fn refresh_editor_state(&mut self) {
+        if self.state.jail_editor.is_open {
+            let editor = &mut self.state.jail_editor;
+            editor.modified = editor.buffer.text() != editor.original_content;
         } else {
-            max_pos
+            let editor = &mut self.state.config_management;
+            editor.modified = editor.buffer.text() != editor.original_content;
         }
+        self.refresh_editor_lint();
     }
     
-    fn move_config_cursor_up(&self) -> usize {
-        let content = &self.state.config_management.current_file_content;
-        let lines: Vec<&str> = content.lines().collect();
-        let current_pos = self.state.config_management.cursor_position;
-        
-        // Find current line and column
-        let mut pos = 0;
-        for (line_idx, line) in lines.iter().enumerate() {
-            let line_end = pos + line.len() + if line_idx < lines.len() - 1 { 1 } else { 0 }; // +1 for \n
-            if current_pos <= line_end {
-                if line_idx > 0 {
-                    let col = current_pos - pos;
-                    let prev_line = lines[line_idx - 1];
-                    let prev_line_start = pos - prev_line.len() - 1;
-                    return (prev_line_start + col.min(prev_line.len())).max(0);
-                }
-                break;
-            }
-            pos = line_end;
+    /// Re-run the inline checks for whichever editor is open
+    fn refresh_editor_lint(&mut self) {
+        let validator = ConfigValidator::new();
+        if self.state.jail_editor.is_open {
+            let editor = &mut self.state.jail_editor;
+            editor.lint_issues = validator.lint_content(&editor.buffer.text(), &jail_local_file(), Some(&editor.jail_name));
+        } else if self.state.config_management.editor_open {
+            let editor = &mut self.state.config_management;
+            editor.lint_issues = validator.lint_content(&editor.buffer.text(), &editor.current_file_path, None);
         }
-        current_pos
     }
     
-    fn move_config_cursor_down(&self) -> usize {
-        let content = &self.state.config_management.current_file_content;
-        let lines: Vec<&str> = content.lines().collect();
-        let current_pos = self.state.config_management.cursor_position;
-        
-        // Find current line and column
-        let mut pos = 0;
-        for (line_idx, line) in lines.iter().enumerate() {
-            let line_end = pos + line.len() + if line_idx < lines.len() - 1 { 1 } else { 0 }; // +1 for \n
-            if current_pos <= line_end {
-                if line_idx < lines.len() - 1 {
-                    let col = current_pos - pos;
-                    let next_line = lines[line_idx + 1];
-                    let next_line_start = line_end;
-                    return next_line_start + col.min(next_line.len());
-                }
-                break;
-            }
-            pos = line_end;
-        }
-        current_pos
+    fn is_editor_open(&self) -> bool {
+        self.state.jail_editor.is_open || self.state.config_management.editor_open
     }
     
-    fn update_config_editor_scroll(&mut self) {
-        let content = &self.state.config_management.current_file_content;
-        let lines: Vec<&str> = content.lines().collect();
-        let current_pos = self.state.config_management.cursor_position;
-        
-        // Find current line
-        let mut pos = 0;
-        let mut current_line = 0;
-        for (line_idx, line) in lines.iter().enumerate() {
-            let line_end = pos + line.len() + if line_idx < lines.len() - 1 { 1 } else { 0 };
-            if current_pos <= line_end {
-                current_line = line_idx;
-                break;
-            }
-            pos = line_end;
+    fn active_editor_buffer_mut(&mut self) -> Option<&mut TextBuffer> {
+        if self.state.jail_editor.is_open {
+            Some(&mut self.state.jail_editor.buffer)
+        } else if self.state.config_management.editor_open {
+            Some(&mut self.state.config_management.buffer)
+        } else {
+            None
         }
-        
-        let visible_lines = 20; // Approximate visible lines
-        if current_line < self.state.config_management.scroll_offset {
-            self.state.config_management.scroll_offset = current_line;
-        } else if current_line >= self.state.config_management.scroll_offset + visible_lines {
-            self.state.config_management.scroll_offset = current_line - visible_lines + 1;
+    }
+    
+    fn is_editor_search_open(&self) -> bool {
+        if self.state.jail_editor.is_open {
+            self.state.jail_editor.buffer.search.is_some()
+        } else {
+            self.state.config_management.editor_open && self.state.config_management.buffer.search.is_some()
         }
     }
     
-    fn get_config_cursor_line_col(&self) -> (usize, usize) {
-        let content = &self.state.config_management.current_file_content;
-        let cursor_pos = self.state.config_management.cursor_position;
-        let lines: Vec<&str> = content.lines().collect();
+    /// Editing keys shared by the jail and config editors
+    fn handle_editor_key(&mut self, key: KeyEvent) {
+        let clipboard = self.state.editor_clipboard.clone();
+        let Some(buffer) = self.active_editor_buffer_mut() else {
+            return;
+        };
         
-        if lines.is_empty() {
-            return (0, 0);
-        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        let mut copied = None;
+        let mut status = None;
         
-        let mut char_count = 0;
-        for (line_idx, line) in lines.iter().enumerate() {
-            let line_end = char_count + line.len();
-            
-            // Handle cursor at the exact position within or at end of this line
-            if cursor_pos <= line_end {
-                let col = cursor_pos - char_count;
-                return (line_idx, col);
+        if let Some(ref mut search) = buffer.search {
+            let field = match search.field {
+                SearchField::Query => &mut search.query,
+                SearchField::Replacement => &mut search.replacement,
+            };
+            let mode = search.mode;
+            let query_changed = search.field == SearchField::Query;
+            match key.code {
+                KeyCode::Esc => buffer.close_search(),
+                KeyCode::Tab | KeyCode::BackTab if mode == SearchMode::Replace => {
+                    search.field = match search.field {
+                        SearchField::Query => SearchField::Replacement,
+                        SearchField::Replacement => SearchField::Query,
+                    };
+                },
+                KeyCode::Enter if mode == SearchMode::Replace => match buffer.replace_all() {
+                    Ok(count) => {
+                        buffer.close_search();
+                        status = Some(if count == 0 {
+                            "⚠ No matches to replace".to_string()
+                        } else {
+                            format!("✓ Replaced {} match(es) - Ctrl+Z to undo", count)
+                        });
+                    },
+                    // Keep the prompt open so the pattern can be fixed
+                    Err(e) => status = Some(format!("✗ Invalid regex: {}", e)),
+                },
+                KeyCode::Enter | KeyCode::Down | KeyCode::F(3) if !shift => status = (!buffer.search_next(true)).then(|| "⚠ No matches".to_string()),
+                KeyCode::Up | KeyCode::F(3) => status = (!buffer.search_next(false)).then(|| "⚠ No matches".to_string()),
+                KeyCode::Backspace => {
+                    field.pop();
+                    if query_changed && mode == SearchMode::Find {
+                        buffer.search_update();
+                    }
+                },
+                KeyCode::Char(c) if !ctrl => {
+                    field.push(c);
+                    if query_changed && mode == SearchMode::Find && !buffer.search_update() {
+                        status = Some("⚠ No matches".to_string());
+                    }
+                },
+                _ => {},
             }
-            
-            // Handle cursor at the newline character (beginning of next line)
-            if cursor_pos == line_end + 1 && line_idx < lines.len() - 1 {
-                return (line_idx + 1, 0);
+        } else {
+            match key.code {
+                KeyCode::Char('z') if ctrl => status = (!buffer.undo()).then(|| "⚠ Nothing to undo".to_string()),
+                KeyCode::Char('y') if ctrl => status = (!buffer.redo()).then(|| "⚠ Nothing to redo".to_string()),
+                KeyCode::Char('f') if ctrl => buffer.open_search(SearchMode::Find),
+                KeyCode::Char('r') if ctrl => buffer.open_search(SearchMode::Replace),
+                KeyCode::Char('a') if ctrl => buffer.select_all(),
+                KeyCode::Char('c') if ctrl => copied = buffer.selected_text(),
+                KeyCode::Char('x') if ctrl => copied = buffer.cut(),
+                KeyCode::Char('v') if ctrl && !clipboard.is_empty() => buffer.insert_str(&clipboard),
+                KeyCode::Char(_) if ctrl => {},
+                KeyCode::Char(c) => buffer.insert_char(c),
+                KeyCode::Enter => buffer.insert_newline(),
+                KeyCode::Backspace => buffer.backspace(),
+                KeyCode::Delete => buffer.delete(),
+                KeyCode::F(3) => status = (!buffer.search_next(!shift)).then(|| "⚠ No matches".to_string()),
+                KeyCode::Left if ctrl => buffer.word_left(shift),
+                KeyCode::Right if ctrl => buffer.word_right(shift),
+                KeyCode::Left => buffer.move_left(shift),
+                KeyCode::Right => buffer.move_right(shift),
+                KeyCode::Up => buffer.move_lines(-1, shift),
+                KeyCode::Down => buffer.move_lines(1, shift),
+                KeyCode::PageUp => buffer.move_lines(-(EDITOR_PAGE_LINES as isize), shift),
+                KeyCode::PageDown => buffer.move_lines(EDITOR_PAGE_LINES as isize, shift),
+                KeyCode::Home if ctrl => buffer.move_to_start(shift),
+                KeyCode::End if ctrl => buffer.move_to_end(shift),
+                KeyCode::Home => buffer.move_home(shift),
+                KeyCode::End => buffer.move_end(shift),
+                _ => {},
             }
-            
-            char_count += line.len() + 1; // +1 for newline
+        }
+        buffer.ensure_visible(EDITOR_PAGE_LINES);
+        
+        if let Some(text) = copied {
+            let lines = text.lines().count().max(1);
+            self.state.editor_clipboard = text;
+            status = Some(format!("✓ {} line(s) copied", lines));
         }
         
-        // Cursor is beyond all content - put it at end of last line
-        let last_line_idx = lines.len().saturating_sub(1);
-        let last_line_len = lines.get(last_line_idx).map(|l| l.len()).unwrap_or(0);
-        (last_line_idx, last_line_len)
+        self.refresh_editor_state();
+        
+        if let Some(message) = status {
+            self.set_status_message(&message);
+        }
+    }
+}
+
+/// Colours for INI syntax highlighting in the editors
+fn ini_token_style(token: IniToken) -> Style {
+    match token {
+        IniToken::Section => Style::default().fg(Color::Yellow),
+        IniToken::Key => Style::default().fg(Color::Cyan),
+        IniToken::Separator => Style::default().fg(Color::Gray),
+        IniToken::Comment => Style::default().fg(Color::DarkGray),
+        IniToken::Interpolation => Style::default().fg(Color::Magenta),
+        IniToken::Tag => Style::default().fg(Color::Green),
+        IniToken::Value | IniToken::Plain => Style::default().fg(Color::White),
     }
 }
 
+/// jail.local under the configured fail2ban root
+fn jail_local_file() -> String {
+    fail2ban_paths().jail_local().display().to_string()
+}
+
+/// Screen cells of a bordered table's header row, one per column, laid out
+/// the way `Table` places them (left-aligned, one space apart)
+fn header_cells(area: Rect, widths: &[Constraint]) -> Vec<Rect> {
+    let row = Rect {
+        x: area.x + 1,
+        y: area.y + 1,
+        width: area.width.saturating_sub(2),
+        height: 1,
+    };
+    Layout::horizontal(widths.iter().copied()).flex(Flex::Start).spacing(1).split(row).to_vec()
+}
+
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
+/// Share of a range that is banned, readable down to sparse IPv6 ranges
+fn format_density(density: f64) -> String {
+    if density >= 0.001 {
+        format!("{:.1}%", density * 100.0)
+    } else {
+        "<0.1%".to_string()
+    }
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: ratatui::layout::Rect) -> ratatui::layout::Rect {
     use ratatui::layout::{Constraint, Layout, Direction};
     
@@ -0,0 +1,83 @@
+use f2b_buxjr::services::audit_log::{export_csv, AuditAction, AuditEntry, AuditLog};
+use std::os::unix::fs::PermissionsExt;
+
+fn main() {
+    println!("=== f2b-buxjr Audit Log Test ===");
+    println!();
+    
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-audit-{}", std::process::id()));
+    let audit_log = AuditLog::new(dir.join("audit/audit.log"));
+    
+    // Recording
+    println!("1. Recording Entries...");
+    std::env::set_var("SUDO_USER", "alice");
+    let ban = AuditEntry::new(AuditAction::Ban, "203.0.113.7 in sshd");
+    report(ban.user == "alice", "real user taken from SUDO_USER");
+    
+    let failed: Result<(), String> = Err("jail not found".to_string());
+    let entries = [
+        ban,
+        AuditEntry::new(AuditAction::WhitelistAdd, "10.0.0.0/8").change("127.0.0.1", "127.0.0.1 10.0.0.0/8"),
+        AuditEntry::new(AuditAction::ConfigSave, "/etc/fail2ban/jail.local")
+            .file_change("[sshd]\nmaxretry = 5\nbantime = 1h\n", "[sshd]\nmaxretry = 3\nbantime = 1h\n"),
+        AuditEntry::new(AuditAction::Unban, "198.51.100.1 in nginx, \"test\"").outcome(&failed),
+    ];
+    let mut written = true;
+    for entry in &entries {
+        if let Err(e) = audit_log.record(entry) {
+            println!("   ✗ Failed to record: {}", e);
+            written = false;
+        }
+    }
+    report(written, "entries appended");
+    
+    let mode = |path: &std::path::Path| std::fs::metadata(path).map(|m| m.permissions().mode() & 0o777).unwrap_or(0);
+    report(mode(&dir.join("audit")) == 0o700, "audit directory created 0700");
+    report(mode(audit_log.path()) == 0o600, "audit file created 0600");
+    
+    // Reading back
+    println!("\n2. Reading Entries...");
+    let _ = std::fs::OpenOptions::new().append(true).open(audit_log.path())
+        .and_then(|mut f| std::io::Write::write_all(&mut f, b"not json\n"));
+    match audit_log.read_entries() {
+        Ok(read) => {
+            report(read.len() == 4 && read[..] == entries[..], "entries round-trip, malformed line skipped");
+            let diff = read[2].diff.as_deref().unwrap_or_default();
+            report(diff.starts_with("--- a/etc/fail2ban/jail.local\n+++ b/etc/fail2ban/jail.local\n@@ -1,3 +1,3 @@\n"), "config save records where the change was made");
+            report(diff.ends_with(" [sshd]\n-maxretry = 5\n+maxretry = 3\n bantime = 1h") && read[2].before.is_none(), "config save keeps the diff with its context");
+            report(!read[3].success && read[3].result_label() == "failed: jail not found", "failure recorded with its error");
+        },
+        Err(e) => println!("   ✗ Failed to read: {}", e),
+    }
+    report(AuditLog::new(dir.join("missing.log")).read_entries().is_ok_and(|e| e.is_empty()), "missing log reads as empty");
+    
+    // Symlinks are refused
+    let link = dir.join("link.log");
+    let _ = std::os::unix::fs::symlink(dir.join("elsewhere.log"), &link);
+    report(AuditLog::new(&link).record(&entries[0]).is_err(), "symlinked audit path refused");
+    
+    // Export
+    println!("\n3. Exporting CSV...");
+    let csv_path = dir.join("export.csv");
+    match export_csv(&entries, &csv_path) {
+        Ok(()) => {
+            let csv = std::fs::read_to_string(&csv_path).unwrap_or_default();
+            report(csv.starts_with("Timestamp,User,Action,Target,Before,After,Diff,Result\n"), "CSV header written");
+            report(csv.contains(",alice,Unban,\"198.51.100.1 in nginx, \"\"test\"\"\","), "commas and quotes escaped");
+            report(mode(&csv_path) == 0o600, "export created 0600");
+        },
+        Err(e) => println!("   ✗ Export failed: {}", e),
+    }
+    report(export_csv(&entries, &csv_path).is_err(), "existing export never overwritten");
+    
+    let _ = std::fs::remove_dir_all(&dir);
+    println!("\n=== Audit Log Test Complete ===");
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,121 @@
+use chrono::{Duration, Utc};
+
+use f2b_buxjr::app::BannedIP;
+use f2b_buxjr::services::ban_query::{common_prefix, complete, replace_last_word, BanQuery, CompletionSource, QueryPresets};
+use f2b_buxjr::services::geoip::GeoInfo;
+
+fn main() {
+    println!("=== f2b-buxjr Ban Query Test ===");
+    println!();
+    
+    let now = Utc::now();
+    let ban = |ip: &str, jail: &str, age_minutes: i64, remaining_minutes: Option<i64>| BannedIP {
+        ip: ip.to_string(),
+        jail: jail.to_string(),
+        ban_time: now - Duration::minutes(age_minutes),
+        unban_time: remaining_minutes.map(|minutes| now + Duration::minutes(minutes)),
+        reason: String::new(),
+    };
+    let bans = [
+        ban("203.0.113.7", "sshd", 30, Some(30)),             // Fresh, ends soon
+        ban("203.0.113.0/28", "sshd", 3 * 60, Some(3 * 24 * 60)),
+        ban("198.51.100.20", "nginx-http-auth", 2 * 24 * 60, None),
+        ban("2001:db8::5", "postfix", 10, Some(50)),
+        ban("192.0.2.44", "nginx-botsearch", 5, Some(2 * 24 * 60)),
+    ];
+    let geo = |ip: &str| match ip {
+        "203.0.113.7" | "203.0.113.0/28" => GeoInfo { country: Some("CN".to_string()), asn: Some(4134), ..GeoInfo::default() },
+        "198.51.100.20" => GeoInfo { country: Some("RU".to_string()), asn: Some(14061), ..GeoInfo::default() },
+        _ => GeoInfo::default(),
+    };
+    let matching = |text: &str| -> Option<Vec<&str>> {
+        let query = BanQuery::parse(text).ok()?;
+        Some(bans.iter().filter(|b| query.matches(b, now, &mut |ip| geo(ip))).map(|b| b.ip.as_str()).collect())
+    };
+    
+    // Single terms
+    println!("1. Matching Single Terms...");
+    report(matching("jail:sshd") == Some(vec!["203.0.113.7", "203.0.113.0/28"]), "jail matched exactly");
+    report(matching("jail:nginx-*") == Some(vec!["198.51.100.20", "192.0.2.44"]), "jail wildcard");
+    report(matching("JAIL:SSHD").is_some_and(|ips| ips.len() == 2), "field names and values ignore case");
+    report(matching("ip:203.0.113.0/24") == Some(vec!["203.0.113.7", "203.0.113.0/28"]), "addresses and ranges inside a CIDR range");
+    report(matching("ip:203.0.113.0/30") == Some(vec![]), "wider range not inside a narrower one");
+    report(matching("ip:198.51.") == Some(vec!["198.51.100.20"]), "address prefix");
+    report(matching("ip:*.44") == Some(vec!["192.0.2.44"]), "address wildcard");
+    report(matching("2001:db8::/32") == Some(vec!["2001:db8::5"]), "bare IPv6 range is an address term");
+    report(matching("age:<1h") == Some(vec!["203.0.113.7", "2001:db8::5", "192.0.2.44"]), "age below");
+    report(matching("age:>=1d") == Some(vec!["198.51.100.20"]), "age at least");
+    report(matching("remaining:<1h") == Some(vec!["203.0.113.7", "2001:db8::5"]), "remaining below");
+    report(matching("remaining:>1d") == Some(vec!["203.0.113.0/28", "198.51.100.20", "192.0.2.44"]), "permanent bans never end");
+    report(matching("remaining:permanent") == Some(vec!["198.51.100.20"]), "permanent only");
+    report(matching("country:cn") == Some(vec!["203.0.113.7", "203.0.113.0/28"]), "country");
+    report(matching("asn:AS14061") == matching("asn:14061") && matching("asn:14061") == Some(vec!["198.51.100.20"]), "ASN with or without AS");
+    report(matching("botsearch") == Some(vec!["192.0.2.44"]) && matching("113").is_some_and(|ips| ips.len() == 2), "bare words match address or jail");
+    
+    // Combining
+    println!("\n2. Combining Terms...");
+    report(matching("jail:sshd ip:203.0.113.0/24 age:<2h remaining:<1d") == Some(vec!["203.0.113.7"]), "adjacent terms must all match");
+    report(matching("jail:postfix OR country:RU") == Some(vec!["198.51.100.20", "2001:db8::5"]), "OR");
+    report(matching("jail:sshd AND NOT age:<1h") == Some(vec!["203.0.113.0/28"]), "AND NOT");
+    report(matching("!jail:sshd !jail:nginx-*") == Some(vec!["2001:db8::5"]), "! negates");
+    report(matching("age:<1h (jail:sshd OR jail:postfix)") == Some(vec!["203.0.113.7", "2001:db8::5"]), "parentheses group");
+    report(matching("jail:postfix or jail:sshd and age:>1h") == Some(vec!["203.0.113.0/28", "2001:db8::5"]), "AND binds tighter than OR");
+    let query = BanQuery::parse("  jail:sshd   age:<2h ");
+    report(query.as_ref().is_ok_and(|q| q.text() == "jail:sshd   age:<2h" && !q.uses_geo()), "text kept, no GeoIP needed");
+    report(BanQuery::parse("NOT country:CN").is_ok_and(|q| q.uses_geo()), "country terms need GeoIP");
+    
+    // Errors
+    println!("\n3. Rejecting Bad Queries...");
+    let error = |text: &str| BanQuery::parse(text).err().unwrap_or_default();
+    report(error("jial:sshd").contains("unknown field 'jial'"), "unknown field named");
+    report(error("age:2x").contains("not a duration"), "bad duration");
+    report(error("age:<99999999999999h").contains("not a duration") && error("remaining:>9999999999999999s").contains("not a duration"), "duration too large");
+    report(error("asn:foo").contains("not an AS number"), "bad AS number");
+    report(error("jail:").contains("needs a value"), "missing value");
+    report(error("(jail:sshd").contains("missing ')'"), "unbalanced parenthesis");
+    report(error("jail:sshd OR").contains("ends too early") && error("AND jail:sshd").contains("unexpected"), "dangling keywords");
+    report(BanQuery::parse("   ").is_err(), "empty query");
+    
+    // Completion
+    println!("\n4. Completing...");
+    let source = CompletionSource {
+        jails: vec!["nginx-botsearch".to_string(), "nginx-http-auth".to_string(), "sshd".to_string()],
+        countries: vec!["CN".to_string(), "RU".to_string()],
+        asns: vec![4134, 14061],
+    };
+    report(complete("ja", &source) == vec!["jail:"], "field name");
+    report(complete("jail:sshd a", &source) == vec!["age:", "asn:", "AND"], "fields and keywords for the last word");
+    report(complete("jail:ng", &source) == vec!["jail:nginx-botsearch", "jail:nginx-http-auth"], "jail values");
+    report(complete("(country:", &source) == vec!["(country:CN", "(country:RU"], "parenthesis kept");
+    report(complete("asn:1", &source) == vec!["asn:14061"], "ASN values");
+    report(complete("jail:sshd ", &source).len() == 9, "everything after a space");
+    report(complete("jail:sshd", &source).is_empty(), "complete word offers nothing");
+    report(common_prefix(&complete("jail:ng", &source)) == "jail:nginx-", "common prefix");
+    report(replace_last_word("age:<1h jail:ng", "jail:nginx-") == "age:<1h jail:nginx-", "last word replaced");
+    
+    // Presets
+    println!("\n5. Saving Presets...");
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-query-{}", std::process::id()));
+    let path = dir.join("f2b-buxjr").join("filters.toml");
+    let mut presets = QueryPresets::load(&path).unwrap_or_default();
+    report(presets.names().is_empty(), "missing file means no presets");
+    report(presets.save("fresh ssh", "jail:sshd age:<1h ").is_ok() && presets.save("long", "remaining:>1d").is_ok(), "presets saved");
+    report(presets.save("broken", "jial:sshd").is_err() && presets.save(" ", "jail:sshd").is_err(), "bad query or empty name refused");
+    let reloaded = QueryPresets::load(&path).unwrap_or_default();
+    report(reloaded.names() == vec!["fresh ssh", "long"] && reloaded.get("fresh ssh") == Some("jail:sshd age:<1h"), "presets read back in name order");
+    report(presets.remove("long").is_ok_and(|removed| removed) && QueryPresets::load(&path).is_ok_and(|p| p.names().len() == 1), "preset deleted");
+    report(presets.remove("missing").is_ok_and(|removed| !removed), "deleting an unknown preset changes nothing");
+    let _ = std::fs::write(&path, "[presets]\nbad = 1\n");
+    report(QueryPresets::load(&path).is_err(), "malformed file rejected");
+    let _ = std::fs::remove_dir_all(&dir);
+    
+    println!("\n=== Ban Query Test Complete ===");
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Local, Utc};
+
+use f2b_buxjr::app::BannedIP;
+use f2b_buxjr::services::ban_set::{BanReconciler, BanSet, BanSnapshot, ReconcileChanges};
+use f2b_buxjr::services::event_history::from_log_clock;
+use f2b_buxjr::services::file_monitor::{ban_event, BanAction, BanEvent, LogMonitor};
+use f2b_buxjr::services::table_sort::{BannedIpColumn, SortOrder};
+
+fn main() {
+    println!("=== f2b-buxjr Ban Set Test ===");
+    println!();
+    
+    let now = Utc::now();
+    let ban = |ip: &str, jail: &str, age_minutes: i64| BannedIP {
+        ip: ip.to_string(),
+        jail: jail.to_string(),
+        ban_time: now - Duration::minutes(age_minutes),
+        unban_time: Some(now - Duration::minutes(age_minutes) + Duration::hours(1)),
+        reason: "Active ban".to_string(),
+    };
+    let event = |action: BanAction, ip: &str, jail: &str| BanEvent {
+        action,
+        jail: jail.to_string(),
+        ip: ip.to_string(),
+    };
+    let snapshot = |bans: Vec<BannedIP>| BanSnapshot {
+        bans,
+        bantimes: HashMap::from([("sshd".to_string(), Some(Duration::hours(1))), ("recidive".to_string(), None)]),
+        ..BanSnapshot::default()
+    };
+    let rows = |set: &BanSet| set.iter().map(|b| format!("{}@{}", b.ip, b.jail)).collect::<Vec<_>>().join(" ");
+    
+    // Full reloads
+    println!("1. Reloading...");
+    let mut set = BanSet::new();
+    report(!set.apply(&event(BanAction::Ban, "192.0.2.1", "sshd"), now) && set.is_empty(), "events ignored before the first reload");
+    set.begin_reconcile(now - Duration::minutes(1));
+    report(set.is_reconciling(), "reload in progress");
+    let changes = set.finish_reconcile(snapshot(vec![ban("10.0.0.100", "sshd", 5), ban("9.9.9.9", "sshd", 20), ban("10.0.0.2", "sshd", 10), ban("9.9.9.9", "sshd", 30)]));
+    report(rows(&set) == "9.9.9.9@sshd 10.0.0.2@sshd 10.0.0.100@sshd", "rows sorted, duplicates dropped");
+    report(changes == ReconcileChanges { added: 3, removed: 0 } && !set.is_reconciling(), "first reload adds everything");
+    report(set.contains("10.0.0.2", "sshd") && !set.contains("10.0.0.2", "nginx"), "looked up by address and jail");
+    
+    // Live events
+    println!("\n2. Applying Log Events...");
+    report(set.apply(&event(BanAction::Ban, "10.0.0.50", "sshd"), now), "ban added");
+    report(rows(&set) == "9.9.9.9@sshd 10.0.0.2@sshd 10.0.0.50@sshd 10.0.0.100@sshd", "inserted in sorted position");
+    let added = set.iter().find(|b| b.ip == "10.0.0.50");
+    report(added.is_some_and(|b| b.ban_time == now && b.unban_time == Some(now + Duration::hours(1))), "unban time from the jail's bantime");
+    report(!set.apply(&event(BanAction::Ban, "10.0.0.50", "sshd"), now), "repeated ban changes nothing");
+    report(set.apply(&event(BanAction::Ban, "10.0.0.50", "recidive"), now) && set.iter().any(|b| b.jail == "recidive" && b.unban_time.is_none()), "same address in another jail, permanent bantime");
+    report(set.apply(&event(BanAction::Unban, "10.0.0.2", "sshd"), now) && !set.contains("10.0.0.2", "sshd"), "unban removes the row");
+    report(!set.apply(&event(BanAction::Unban, "203.0.113.9", "sshd"), now), "unban of an unknown address changes nothing");
+    report(!set.apply(&event(BanAction::Ban, "203.0.113.9", "sshd"), now - Duration::hours(2)) && !set.contains("203.0.113.9", "sshd"), "events from before the reload ignored");
+    report(set.len() == 4, "row count kept");
+    
+    // Order
+    println!("\n3. Changing Order...");
+    set.set_order(SortOrder { column: BannedIpColumn::BannedAt, descending: true });
+    report(rows(&set) == "10.0.0.50@sshd 10.0.0.50@recidive 10.0.0.100@sshd 9.9.9.9@sshd", "re-sorted");
+    report(set.apply(&event(BanAction::Ban, "198.51.100.1", "sshd"), now + Duration::seconds(5)) && set.iter().next().is_some_and(|b| b.ip == "198.51.100.1"), "new ban placed in the current order");
+    report(set.apply(&event(BanAction::Unban, "10.0.0.100", "sshd"), now) && rows(&set) == "198.51.100.1@sshd 10.0.0.50@sshd 10.0.0.50@recidive 9.9.9.9@sshd", "unban found in the current order");
+    
+    // Reconciling
+    println!("\n4. Reconciling...");
+    let began = now + Duration::minutes(1);
+    set.begin_reconcile(began);
+    report(set.apply(&event(BanAction::Ban, "192.0.2.7", "sshd"), began + Duration::seconds(3)), "events still apply during a reload");
+    set.apply(&event(BanAction::Unban, "9.9.9.9", "sshd"), began + Duration::seconds(4));
+    // Read before the two events above, and missing a ban the log never showed
+    let changes = set.finish_reconcile(snapshot(vec![ban("9.9.9.9", "sshd", 20), ban("10.0.0.50", "sshd", 0), ban("172.16.0.1", "sshd", 3)]));
+    report(set.contains("192.0.2.7", "sshd") && !set.contains("9.9.9.9", "sshd"), "events seen during the reload replayed");
+    report(set.contains("172.16.0.1", "sshd") && !set.contains("198.51.100.1", "sshd") && !set.contains("10.0.0.50", "recidive"), "reload wins over older events");
+    report(changes == ReconcileChanges { added: 1, removed: 2 }, "corrections counted");
+    report(set.iter().next().is_some_and(|b| b.ip == "192.0.2.7"), "order kept across reloads");
+    set.apply(&event(BanAction::Ban, "10.0.0.60", "recidive"), began + Duration::seconds(5));
+    set.begin_reconcile(began + Duration::minutes(1));
+    let changes = set.finish_reconcile(BanSnapshot { failed_jails: vec!["recidive".to_string()], ..snapshot(vec![ban("192.0.2.7", "sshd", 0), ban("10.0.0.50", "sshd", 0), ban("172.16.0.1", "sshd", 3)]) });
+    report(set.contains("10.0.0.60", "recidive") && set.len() == 4 && changes == ReconcileChanges::default(), "jail that could not be read keeps its rows");
+    set.apply(&event(BanAction::Unban, "10.0.0.60", "recidive"), began + Duration::minutes(2));
+    set.begin_reconcile(began);
+    set.abandon_reconcile();
+    report(!set.is_reconciling() && set.finish_reconcile(BanSnapshot::default()) == ReconcileChanges::default() && set.len() == 3, "abandoned reload leaves the rows alone");
+    set.clear();
+    report(set.is_empty() && !set.apply(&event(BanAction::Ban, "192.0.2.8", "sshd"), began + Duration::minutes(1)), "cleared set waits for a reload");
+    
+    let mut reconciler = BanReconciler::new();
+    report(!reconciler.is_running() && reconciler.poll().is_none(), "nothing to collect before a reload starts");
+    
+    // Following the log
+    println!("\n5. Following the Log...");
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-ban-set-{}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    let path = dir.join("fail2ban.log");
+    let line = |verb: &str, ip: &str| format!("{} fail2ban.actions [812]: NOTICE  [sshd] {} {}\n", Local::now().format("%Y-%m-%d %H:%M:%S,%3f"), verb, ip);
+    let follow = |monitor: &mut LogMonitor, set: &mut BanSet| {
+        for entry in monitor.tail_new_lines().unwrap_or_default().iter().rev() {
+            if let Some(event) = ban_event(&entry.message) {
+                set.apply(&event, from_log_clock(entry.timestamp));
+            }
+        }
+    };
+    let mut live = BanSet::new();
+    live.begin_reconcile(Utc::now() - Duration::minutes(1));
+    live.finish_reconcile(BanSnapshot::default());
+    let _ = std::fs::write(&path, line("Ban", "192.0.2.20"));
+    let mut monitor = LogMonitor::new(&path.display().to_string());
+    follow(&mut monitor, &mut live);
+    report(live.contains("192.0.2.20", "sshd"), "tailed ban applied");
+    std::thread::sleep(std::time::Duration::from_millis(20));  // A newer modification time
+    let _ = std::fs::OpenOptions::new().append(true).open(&path).and_then(|mut file| {
+        std::io::Write::write_all(&mut file, format!("{}{}", line("Ban", "192.0.2.21"), line("Unban", "192.0.2.20")).as_bytes())
+    });
+    // What the Logs screen reads when it opens
+    report(monitor.get_recent_lines(100).is_ok_and(|entries| entries.len() == 3), "recent lines read");
+    follow(&mut monitor, &mut live);
+    report(live.contains("192.0.2.21", "sshd") && !live.contains("192.0.2.20", "sshd"), "lines written before the Logs screen opened still tailed");
+    let _ = std::fs::remove_dir_all(&dir);
+    
+    println!("\n=== Ban Set Test Complete ===");
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -1,3 +1,6 @@
+use std::time::Duration;
+
+use f2b_buxjr::app::ServiceAction;
 use f2b_buxjr::services::system_service::SystemService;
 use f2b_buxjr::services::fail2ban_client::Fail2banClient;
 
@@ -109,8 +112,8 @@ fn main() {
     println!("\n5. Testing Service Control...");
     
     // Test configuration reload (safest operation)
-    match system_service.reload() {
-        Ok(()) => {
+    match system_service.run(&ServiceAction::Reload, Duration::from_secs(30)) {
+        Ok(_) => {
             println!("   ✓ Configuration reload successful");
         },
         Err(e) => {
@@ -0,0 +1,126 @@
+use f2b_buxjr::services::config_validator::{parse_time_spec, ConfigValidator, IssueSeverity};
+
+fn main() {
+    env_logger::init();
+    
+    println!("=== f2b-buxjr Configuration Validator Test ===");
+    println!();
+    
+    // Test time spec parsing
+    println!("1. Testing Time Spec Parsing...");
+    let time_cases = [
+        ("600", Some(600)),
+        ("10m", Some(600)),
+        ("1h 30m", Some(5400)),
+        ("1w", Some(604800)),
+        ("-1", Some(-1)),
+        ("10 minutes", Some(600)),
+        ("ten", None),
+        ("5x", None),
+        ("99999999999999999", None),
+        ("99999999999999999y", None),
+    ];
+    for (input, expected) in time_cases {
+        let parsed = parse_time_spec(input).map(|d| d.num_seconds());
+        let symbol = if parsed == expected { "✓" } else { "✗" };
+        println!("   {} '{}' -> {:?} (expected {:?})", symbol, input, parsed, expected);
+    }
+    
+    // Build a fixture configuration directory (or use the one given on the command line)
+    println!("\n2. Preparing Configuration Directory...");
+    let config_dir = match std::env::args().nth(1) {
+        Some(dir) => {
+            println!("   Using {}", dir);
+            std::path::PathBuf::from(dir)
+        },
+        None => {
+            let dir = std::env::temp_dir().join(format!("f2b-buxjr-validator-{}", std::process::id()));
+            if let Err(e) = create_fixture(&dir) {
+                println!("   ✗ Failed to create fixture: {}", e);
+                return;
+            }
+            println!("   ✓ Fixture created at {}", dir.display());
+            dir
+        }
+    };
+    
+    // Run the static checks (fail2ban-client --test is skipped so the fixture result is stable)
+    println!("\n3. Running Built-in Checks...");
+    let validator = ConfigValidator::with_config_dir(&config_dir);
+    let issues = validator.check_configuration();
+    for issue in &issues {
+        let symbol = match issue.severity {
+            IssueSeverity::Error => "✗",
+            IssueSeverity::Warning => "⚠",
+        };
+        println!("   {} {} {}", symbol, issue.location(), issue.message);
+    }
+    
+    if std::env::args().nth(1).is_none() {
+        let expected = [
+            "filter 'missing-filter' not found",
+            "action 'missing-action' not found",
+            "log file not found",
+            "invalid time value for bantime",
+        ];
+        println!("\n4. Checking Expected Findings...");
+        for needle in expected {
+            if issues.iter().any(|i| i.message.contains(needle)) {
+                println!("   ✓ Reported: {}", needle);
+            } else {
+                println!("   ✗ Missing: {}", needle);
+            }
+        }
+        if issues.iter().any(|i| i.message.contains("[sshd]")) {
+            println!("   ✗ Valid sshd jail was reported");
+        } else {
+            println!("   ✓ Valid sshd jail passed");
+        }
+        
+        // Inline lint as used by the editors (a jail section body without its header)
+        println!("\n5. Testing Editor Lint...");
+        let body = "enabled = true\nfilter = missing-filter\nbantime = 10x\nbantme = 1h\nenabled = false\naction = iptables-multiport[name=ssh]";
+        let lint = validator.lint_content(body, "jail.local", Some("sshd"));
+        let lint_expected = [
+            (2, "filter 'missing-filter' not found"),
+            (3, "invalid time value for bantime"),
+            (4, "unknown key 'bantme'"),
+            (5, "duplicate key 'enabled'"),
+        ];
+        for (line, needle) in lint_expected {
+            if lint.iter().any(|i| i.line == Some(line) && i.message.contains(needle)) {
+                println!("   ✓ Line {}: {}", line, needle);
+            } else {
+                println!("   ✗ Line {} missing: {}", line, needle);
+            }
+        }
+        if lint.iter().any(|i| i.line == Some(6)) {
+            println!("   ✗ Existing action was reported");
+        } else {
+            println!("   ✓ Existing action passed");
+        }
+        let _ = std::fs::remove_dir_all(&config_dir);
+    }
+    
+    println!("\n=== Configuration Validator Test Complete ===");
+}
+
+fn create_fixture(dir: &std::path::Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir.join("filter.d"))?;
+    std::fs::create_dir_all(dir.join("action.d"))?;
+    std::fs::create_dir_all(dir.join("jail.d"))?;
+    
+    let log_file = dir.join("auth.log");
+    std::fs::write(&log_file, "")?;
+    std::fs::write(dir.join("filter.d/sshd.conf"), "[Definition]\nfailregex = ^Failed\n")?;
+    std::fs::write(dir.join("action.d/iptables-multiport.conf"), "[Definition]\n")?;
+    
+    std::fs::write(dir.join("jail.conf"), format!(
+        "[DEFAULT]\nbantime = 10m\nfindtime = 10m\nbanaction = iptables-multiport\naction = %(banaction)s[name=%(__name__)s]\n\n\
+         [sshd]\nlogpath = {}\n", log_file.display()))?;
+    std::fs::write(dir.join("jail.local"),
+        "[sshd]\nenabled = true\n\n\
+         [broken]\nenabled = true\nfilter = missing-filter\naction = missing-action\nlogpath = /nonexistent/f2b-buxjr.log\nbantime = ten minutes\n")?;
+    
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use std::os::unix::fs::PermissionsExt;
+
+use f2b_buxjr::utils::config_writer::{create_config_file, write_config_file};
+
+fn main() {
+    env_logger::init();
+    
+    println!("=== f2b-buxjr Config Writer Test ===");
+    println!();
+    
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-writer-{}", std::process::id()));
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        println!("✗ Failed to create test directory: {}", e);
+        return;
+    }
+    
+    // Test mode and trailing newline preservation
+    println!("1. Testing Mode and Trailing Newline...");
+    let path = dir.join("jail.local");
+    std::fs::write(&path, "[sshd]\nenabled = false\n").unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+    
+    // Callers build content with lines().join("\n"), which drops the final newline
+    match write_config_file(&path, "[sshd]\nenabled = true") {
+        Ok(()) => {
+            let content = std::fs::read_to_string(&path).unwrap();
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+            report(content == "[sshd]\nenabled = true\n", "trailing newline kept");
+            report(mode == 0o640, &format!("mode preserved ({:o})", mode));
+        },
+        Err(e) => println!("   ✗ Write failed: {}", e),
+    }
+    
+    // Test CRLF preservation
+    println!("\n2. Testing Line Ending Preservation...");
+    let crlf_path = dir.join("fail2ban.local");
+    std::fs::write(&crlf_path, "[Definition]\r\nloglevel = INFO\r\n").unwrap();
+    match write_config_file(&crlf_path, "[Definition]\nloglevel = DEBUG") {
+        Ok(()) => {
+            let content = std::fs::read_to_string(&crlf_path).unwrap();
+            report(content == "[Definition]\r\nloglevel = DEBUG\r\n", "CRLF line endings kept");
+        },
+        Err(e) => println!("   ✗ Write failed: {}", e),
+    }
+    
+    // Test new file creation and temp file cleanup
+    println!("\n3. Testing New File and Temp Cleanup...");
+    let new_path = dir.join("new.local");
+    match write_config_file(&new_path, "[DEFAULT]\nignoreip = 127.0.0.1/8") {
+        Ok(()) => {
+            let content = std::fs::read_to_string(&new_path).unwrap();
+            report(content.ends_with('\n'), "new file ends with newline");
+        },
+        Err(e) => println!("   ✗ Write failed: {}", e),
+    }
+    let leftovers = std::fs::read_dir(&dir).unwrap()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+        .count();
+    report(leftovers == 0, "no temp files left behind");
+    
+    // Test writing through a symlink
+    println!("\n4. Testing Symlinked Config Files...");
+    let managed = dir.join("managed");
+    let linked = dir.join("linked");
+    let _ = std::fs::create_dir_all(&managed).and_then(|_| std::fs::create_dir_all(&linked));
+    std::fs::write(managed.join("jail.local"), "[sshd]\nenabled = false\n").unwrap();
+    let link = linked.join("jail.local");
+    let _ = std::os::unix::fs::symlink("../managed/jail.local", &link);
+    match write_config_file(&link, "[sshd]\nenabled = true") {
+        Ok(()) => {
+            let still_link = std::fs::symlink_metadata(&link).is_ok_and(|m| m.file_type().is_symlink());
+            report(still_link, "symlink kept");
+            report(std::fs::read_to_string(managed.join("jail.local")).is_ok_and(|c| c == "[sshd]\nenabled = true\n"), "file it points at updated");
+        },
+        Err(e) => println!("   ✗ Write failed: {}", e),
+    }
+    let dangling = linked.join("new.local");
+    let _ = std::os::unix::fs::symlink("../managed/new.local", &dangling);
+    let written = write_config_file(&dangling, "[DEFAULT]\n");
+    report(written.is_ok() && managed.join("new.local").is_file() && std::fs::symlink_metadata(&dangling).is_ok_and(|m| m.file_type().is_symlink()), "dangling symlink's target created");
+    
+    // Test creating a file that must not exist yet
+    println!("\n5. Testing Create-Only Writes...");
+    let created = dir.join("sshd.local");
+    report(create_config_file(&created, "[Definition]").is_ok() && std::fs::read_to_string(&created).is_ok_and(|c| c == "[Definition]\n"), "new file created");
+    let refused = create_config_file(&created, "[Definition]\nmaxlines = 2");
+    report(refused.is_err() && std::fs::read_to_string(&created).is_ok_and(|c| c == "[Definition]\n"), "existing file left alone");
+    let leftovers = std::fs::read_dir(&dir).unwrap()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+        .count();
+    report(leftovers == 0, "no temp files left behind");
+    
+    let _ = std::fs::remove_dir_all(&dir);
+    println!("\n=== Config Writer Test Complete ===");
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,80 @@
+use f2b_buxjr::services::config_validator::ConfigValidator;
+use f2b_buxjr::services::definition_tree::{create_local_override, scan_definitions};
+
+fn main() {
+    println!("=== f2b-buxjr Definition Browser Test ===");
+    println!();
+    
+    println!("1. Preparing Configuration Directory...");
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-definitions-{}", std::process::id()));
+    if let Err(e) = create_fixture(&dir) {
+        println!("   ✗ Failed to create fixture: {}", e);
+        return;
+    }
+    println!("   ✓ Fixture created at {}", dir.display());
+    let validator = ConfigValidator::with_config_dir(&dir);
+    
+    // Override pairs and references
+    println!("\n2. Scanning Definitions...");
+    let definitions = scan_definitions(&validator);
+    let find = |dir: &str, name: &str| definitions.iter().find(|d| d.dir == dir && d.name == name).cloned();
+    
+    let sshd = find("filter.d", "sshd");
+    report(sshd.as_ref().is_some_and(|d| d.files_label() == "conf+local"), "sshd filter has a conf+local pair");
+    report(sshd.as_ref().is_some_and(|d| d.used_by == ["sshd"]), "sshd filter used by the enabled sshd jail");
+    
+    let nginx = find("filter.d", "nginx-http-auth");
+    report(nginx.as_ref().is_some_and(|d| d.files_label() == "conf" && d.used_by.is_empty()), "disabled jail does not count as a user");
+    
+    let iptables = find("action.d", "iptables-multiport");
+    report(iptables.as_ref().is_some_and(|d| d.used_by == ["sshd"]), "default banaction resolved through interpolation");
+    
+    let drop_in = find("jail.d", "defaults-debian");
+    report(drop_in.as_ref().is_some_and(|d| d.used_by == ["sshd"]), "jail.d file lists the jails it defines");
+    
+    // Creating an override
+    println!("\n3. Creating a .local Override...");
+    if let Some(nginx) = nginx {
+        match create_local_override(&nginx, &dir) {
+            Ok(path) => {
+                let content = std::fs::read_to_string(&path).unwrap_or_default();
+                report(path == dir.join("filter.d/nginx-http-auth.local"), "override written next to the .conf");
+                report(content.contains("\n[Definition]\n# failregex = ^auth failed <HOST>\n"), "packaged settings kept commented out");
+                let conf = std::fs::read_to_string(dir.join("filter.d/nginx-http-auth.conf")).unwrap_or_default();
+                report(conf == "[Definition]\nfailregex = ^auth failed <HOST>\n", "packaged .conf left untouched");
+                report(create_local_override(&nginx, &dir).is_err(), "existing override is never replaced");
+            },
+            Err(e) => println!("   ✗ Override failed: {}", e),
+        }
+    }
+    
+    // Filter files are linted for duplicates only
+    let lint = validator.lint_content("[Definition]\nfailregex = <HOST>\nignoreregex =\nfailregex = x", &dir.join("filter.d/x.local").display().to_string(), None);
+    report(lint.len() == 1 && lint[0].message.contains("duplicate key"), "filter.d lint skips jail key checks");
+    
+    let _ = std::fs::remove_dir_all(&dir);
+    println!("\n=== Definition Browser Test Complete ===");
+}
+
+fn create_fixture(dir: &std::path::Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir.join("filter.d"))?;
+    std::fs::create_dir_all(dir.join("action.d"))?;
+    std::fs::create_dir_all(dir.join("jail.d"))?;
+    
+    std::fs::write(dir.join("filter.d/sshd.conf"), "[Definition]\nfailregex = ^Failed\n")?;
+    std::fs::write(dir.join("filter.d/sshd.local"), "[Definition]\nmaxlines = 2\n")?;
+    std::fs::write(dir.join("filter.d/nginx-http-auth.conf"), "[Definition]\nfailregex = ^auth failed <HOST>\n")?;
+    std::fs::write(dir.join("action.d/iptables-multiport.conf"), "[Definition]\n")?;
+    std::fs::write(dir.join("jail.conf"),
+        "[DEFAULT]\nbanaction = iptables-multiport\naction = %(banaction)s[name=%(__name__)s]\n\n[sshd]\nport = ssh\n\n[nginx-http-auth]\nport = http\n")?;
+    std::fs::write(dir.join("jail.d/defaults-debian.conf"), "[sshd]\nenabled = true\n")?;
+    Ok(())
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
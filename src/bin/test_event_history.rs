@@ -0,0 +1,107 @@
+use chrono::{DateTime, Duration, Utc};
+
+use f2b_buxjr::app::LogEntry;
+use f2b_buxjr::services::event_history::{log_clock_now, next_window, window_label, EventHistory, EventKind, MAX_BUCKETS, STATS_WINDOWS};
+use f2b_buxjr::services::file_monitor::{found_event, LogMonitor};
+
+fn main() {
+    println!("=== f2b-buxjr Event History Test ===");
+    println!();
+    
+    let now = f2b_buxjr::services::event_history::log_clock_now();
+    
+    // Classifying log lines
+    println!("1. Recording Log Lines...");
+    report(found_event("fail2ban.filter  [812]: INFO    [sshd] Found 203.0.113.7 - 2025-07-19 16:16:35") == Some(("sshd".to_string(), "203.0.113.7".to_string())), "Found line parsed");
+    let mut history = EventHistory::new();
+    history.record(&[
+        entry(now - Duration::minutes(2), "fail2ban.actions [812]: NOTICE  [sshd] Ban 203.0.113.7"),
+        entry(now - Duration::minutes(2), "fail2ban.actions [812]: NOTICE  [sshd] Ban 203.0.113.8"),
+        entry(now - Duration::minutes(1), "fail2ban.actions [812]: NOTICE  [sshd] Unban 203.0.113.7"),
+        entry(now - Duration::minutes(1), "fail2ban.filter  [812]: INFO    [nginx-http-auth] Found 198.51.100.4 - 2025-07-19"),
+        entry(now - Duration::minutes(1), "fail2ban.actions [812]: NOTICE  [sshd] Restore Ban 192.0.2.1"),
+        entry(now - Duration::minutes(1), "fail2ban.server [812]: INFO    Reload finished."),
+    ]);
+    let totals = history.totals(1, now);
+    report(totals.bans == 2 && totals.unbans == 1 && totals.found == 1, "bans, unbans and failures counted; other lines ignored");
+    report(history.bucket_count() == 3, "one bucket per minute and jail");
+    
+    // Windows and bins
+    println!("\n2. Building Series...");
+    let mut history = EventHistory::new();
+    for hours_ago in 0..168 {
+        history.record_event("sshd", EventKind::Ban, now - Duration::hours(hours_ago));
+    }
+    history.record_event("postfix", EventKind::Ban, now - Duration::minutes(30));
+    history.record_event("postfix", EventKind::Found, now - Duration::minutes(30));
+    report(history.series(None, EventKind::Ban, 1, 60, now).iter().sum::<u64>() == 2, "1h window");
+    report(history.series(None, EventKind::Ban, 24, 24, now).iter().sum::<u64>() == 25, "24h window");
+    report(history.series(None, EventKind::Ban, 168, 7, now) == vec![24, 24, 24, 24, 24, 24, 25], "week in daily bins, oldest first");
+    report(history.series(Some("postfix"), EventKind::Ban, 24, 10, now).iter().sum::<u64>() == 1, "one jail only");
+    report(history.series(None, EventKind::Found, 1, 4, now) == vec![0, 1, 0, 0], "failure lands in its bin");
+    report(history.series(None, EventKind::Ban, 1, 0, now).is_empty(), "zero width gives no points");
+    report(history.series(None, EventKind::Ban, 1, 60, now + Duration::hours(2)).iter().sum::<u64>() == 0, "old events leave the window");
+    
+    let jails = history.jail_totals(24, now);
+    report(jails.first().is_some_and(|(jail, counts)| jail == "sshd" && counts.bans == 24), "busiest jail first");
+    report(jails.get(1).is_some_and(|(jail, counts)| jail == "postfix" && counts.found == 1), "quieter jail after it");
+    
+    // Bounds
+    println!("\n3. Bounding Memory...");
+    history.prune(now + Duration::hours(24));
+    report(history.series(None, EventKind::Ban, 168, 1, now + Duration::hours(24)) == vec![145], "events older than a week dropped");
+    let mut crowded = EventHistory::new();
+    for minute in 0..(MAX_BUCKETS as i64 + 500) {
+        crowded.record_event("sshd", EventKind::Found, now - Duration::minutes(minute % (6 * 24 * 60)) - Duration::seconds(minute / (6 * 24 * 60)));
+    }
+    for jail in 0..(MAX_BUCKETS / 1000 + 10) {
+        for minute in 0..1000 {
+            crowded.record_event(&format!("jail{}", jail), EventKind::Ban, now - Duration::minutes(minute));
+        }
+    }
+    crowded.prune(now);
+    report(crowded.bucket_count() == MAX_BUCKETS, "bucket count capped");
+    report(crowded.series(None, EventKind::Ban, 1, 1, now)[0] > 0, "cap drops the oldest buckets first");
+    
+    // Window choices
+    println!("\n4. Cycling Windows...");
+    report(STATS_WINDOWS == [1, 24, 168], "1h / 24h / week like the log time filter");
+    report(next_window(1) == 24 && next_window(24) == 168 && next_window(168) == 1, "windows cycle");
+    report(window_label(1) == "1h" && window_label(24) == "24h" && window_label(168) == "7d", "window labels");
+    
+    // Lines written just before the Logs screen reads the recent entries
+    println!("\n5. Tailing Around the Logs Screen...");
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-event-history-{}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    let log = dir.join("fail2ban.log");
+    let line = |ip: &str| format!("{} fail2ban.actions [812]: NOTICE  [sshd] Ban {}\n", chrono::Local::now().format("%Y-%m-%d %H:%M:%S,%3f"), ip);
+    let _ = std::fs::write(&log, line("203.0.113.90"));
+    let mut monitor = LogMonitor::new(&log.display().to_string());
+    let mut tailed = EventHistory::new();
+    tailed.record(&monitor.tail_new_lines().unwrap_or_default());
+    std::thread::sleep(std::time::Duration::from_millis(20));  // A newer modification time
+    let _ = std::fs::OpenOptions::new().append(true).open(&log).and_then(|mut file| std::io::Write::write_all(&mut file, line("203.0.113.91").as_bytes()));
+    report(monitor.get_recent_lines(100).is_ok_and(|entries| entries.len() == 2), "recent lines read");
+    tailed.record(&monitor.tail_new_lines().unwrap_or_default());
+    report(tailed.totals(1, log_clock_now()).bans == 2 && tailed.ban_record_count() == 2, "ban written before the read still counted");
+    let _ = std::fs::remove_dir_all(&dir);
+    
+    println!("\n=== Event History Test Complete ===");
+}
+
+fn entry(timestamp: DateTime<Utc>, message: &str) -> LogEntry {
+    LogEntry {
+        timestamp,
+        level: "NOTICE".to_string(),
+        message: message.to_string(),
+        jail: None,
+    }
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
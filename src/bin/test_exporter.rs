@@ -0,0 +1,141 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::fs::PermissionsExt;
+
+use f2b_buxjr::services::exporter::Exporter;
+use f2b_buxjr::services::fail2ban_client::{parse_jail_counters, JailCounters};
+use f2b_buxjr::services::file_monitor::{ban_event, BanAction, LogMonitor};
+use f2b_buxjr::services::notifications::Notifier;
+
+/// Answers the way fail2ban-client 0.11 does
+const FAKE_CLIENT: &str = r#"#!/bin/sh
+case "$*" in
+    ping) echo "Server replied: pong" ;;
+    status) printf 'Status\n|- Number of jail:\t2\n`- Jail list:\tsshd, nginx-http-auth\n' ;;
+    "status sshd") printf 'Status for the jail: sshd\n|- Filter\n|  |- Currently failed:\t3\n|  |- Total failed:\t120\n|  `- File list:\t/var/log/auth.log\n`- Actions\n   |- Currently banned:\t2\n   |- Total banned:\t17\n   `- Banned IP list:\t203.0.113.7 198.51.100.4\n' ;;
+    "status nginx-http-auth") printf 'Status for the jail: nginx-http-auth\n|- Filter\n|  |- Currently failed:\t0\n|  |- Total failed:\t4\n`- Actions\n   |- Currently banned:\t0\n   |- Total banned:\t1\n' ;;
+    *) exit 1 ;;
+esac
+"#;
+
+const LOG: &str = "\
+2025-07-19 16:16:35,393 fail2ban.actions        [812]: NOTICE  [sshd] Ban 203.0.113.7
+2025-07-19 16:16:40,001 fail2ban.actions        [812]: NOTICE  [sshd] Ban 198.51.100.4
+2025-07-19 16:17:02,514 fail2ban.actions        [812]: NOTICE  [nginx-http-auth] Restore Ban 192.0.2.9
+2025-07-19 16:26:35,400 fail2ban.actions        [812]: NOTICE  [sshd] Unban 203.0.113.7
+2025-07-19 16:27:00,000 fail2ban.filter         [812]: INFO    [sshd] Found 203.0.113.7 - 2025-07-19 16:27:00
+";
+
+fn main() {
+    println!("=== f2b-buxjr Exporter Test ===");
+    println!();
+    
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-exporter-{}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    
+    // Parsing
+    println!("1. Parsing fail2ban Output...");
+    let counters = parse_jail_counters("Status for the jail: sshd\n|- Filter\n|  |- Currently failed:\t3\n|  |- Total failed:\t120\n`- Actions\n   |- Currently banned:\t2\n   |- Total banned:\t17\n");
+    report(counters == JailCounters { currently_failed: 3, total_failed: 120, currently_banned: 2, total_banned: 17 }, "jail status counters");
+    report(parse_jail_counters("garbage") == JailCounters::default(), "unknown output counts as zero");
+    let ban = ban_event("fail2ban.actions [812]: NOTICE  [sshd] Ban 203.0.113.7");
+    report(ban.as_ref().is_some_and(|e| e.action == BanAction::Ban && e.jail == "sshd" && e.ip == "203.0.113.7"), "ban with jail, not the PID");
+    report(ban_event("fail2ban.actions [812]: NOTICE  [sshd] Unban 203.0.113.7").is_some_and(|e| e.action == BanAction::Unban), "unban");
+    report(ban_event("fail2ban.actions [812]: NOTICE  [sshd] Restore Ban 203.0.113.7").is_none(), "restored ban not counted");
+    
+    // Serving through a fake client
+    println!("\n2. Serving Metrics...");
+    let bin = dir.join("bin");
+    let installed = std::fs::create_dir_all(&bin)
+        .and_then(|_| std::fs::write(bin.join("fail2ban-client"), FAKE_CLIENT))
+        .and_then(|_| std::fs::set_permissions(bin.join("fail2ban-client"), std::fs::Permissions::from_mode(0o755)));
+    report(installed.is_ok(), "fake fail2ban-client installed");
+    std::env::set_var("PATH", format!("{}:{}", bin.display(), std::env::var("PATH").unwrap_or_default()));
+    
+    let log_path = dir.join("fail2ban.log");
+    let _ = std::fs::write(&log_path, LOG);
+    let listener = match TcpListener::bind("127.0.0.1:0") {
+        Ok(listener) => listener,
+        Err(e) => {
+            println!("   ✗ Failed to bind: {}", e);
+            return;
+        }
+    };
+    let addr = listener.local_addr().map(|a| a.to_string()).unwrap_or_default();
+    let exporter = Exporter::new(LogMonitor::new(&log_path.display().to_string()), Notifier::default());
+    std::thread::spawn(move || exporter.serve(listener));
+    
+    match get(&addr, "/metrics") {
+        Ok(response) => {
+            report(response.starts_with("HTTP/1.1 200 OK"), "200 for /metrics");
+            report(response.contains("Content-Type: text/plain; version=0.0.4"), "Prometheus content type");
+            report(response.contains("\nfail2ban_up 1\n"), "server up");
+            report(response.contains("fail2ban_jail_banned_current{jail=\"sshd\"} 2\n"), "currently banned per jail");
+            report(response.contains("fail2ban_jail_banned_total{jail=\"sshd\"} 17\n"), "total banned per jail");
+            report(response.contains("fail2ban_jail_failed_current{jail=\"sshd\"} 3\n"), "currently failed per jail");
+            report(response.contains("fail2ban_jail_failed_total{jail=\"nginx-http-auth\"} 4\n"), "total failed for a second jail");
+            report(response.contains("fail2ban_log_bans_total{jail=\"sshd\"} 2\n"), "ban events from the log");
+            report(response.contains("fail2ban_log_unbans_total{jail=\"sshd\"} 1\n"), "unban events from the log");
+            report(!response.contains("fail2ban_log_bans_total{jail=\"nginx-http-auth\"}"), "restored bans not counted");
+            report(response.contains("# TYPE fail2ban_jail_banned_total counter"), "counters typed");
+            report(response.contains("fail2ban_exporter_scrape_duration_seconds "), "scrape duration");
+        },
+        Err(e) => println!("   ✗ Request failed: {}", e),
+    }
+    
+    let appended = std::fs::OpenOptions::new().append(true).open(&log_path)
+        .and_then(|mut f| f.write_all(b"2025-07-19 16:30:00,000 fail2ban.actions        [812]: NOTICE  [sshd] Ban 192.0.2.50\n"));
+    report(appended.is_ok(), "log line appended");
+    match get(&addr, "/metrics") {
+        Ok(response) => report(response.contains("fail2ban_log_bans_total{jail=\"sshd\"} 3\n"), "counter grows with new log lines only"),
+        Err(e) => println!("   ✗ Request failed: {}", e),
+    }
+    report(get(&addr, "/nothing").is_ok_and(|r| r.starts_with("HTTP/1.1 404")), "404 for unknown paths");
+    // A client that connects and never sends a request
+    let idle = TcpStream::connect(&addr);
+    let started = std::time::Instant::now();
+    let scraped = get(&addr, "/metrics").is_ok_and(|r| r.starts_with("HTTP/1.1 200 OK"));
+    report(idle.is_ok() && scraped && started.elapsed() < std::time::Duration::from_secs(2), "idle client does not hold up a scrape");
+    let oversized = format!("GET /{}", "a".repeat(8187));
+    report(send(&addr, oversized.as_bytes()).is_ok_and(|r| r.starts_with("HTTP/1.1 431")), "endless request line cut off");
+    let crowd: Vec<_> = (0..16).filter_map(|_| TcpStream::connect(&addr).ok()).collect();
+    report(send(&addr, b"").is_ok_and(|r| r.starts_with("HTTP/1.1 503")), "connections beyond the limit refused");
+    drop(crowd);
+    drop(idle);
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    report(get(&addr, "/metrics").is_ok_and(|r| r.starts_with("HTTP/1.1 200 OK")), "served again once connections close");
+    
+    // Server down
+    println!("\n3. Reporting a Stopped Server...");
+    let _ = std::fs::write(bin.join("fail2ban-client"), "#!/bin/sh\necho 'Failed to access socket path' >&2\nexit 255\n");
+    match get(&addr, "/metrics") {
+        Ok(response) => {
+            report(response.contains("\nfail2ban_up 0\n"), "server down");
+            report(!response.contains("fail2ban_jail_banned_current{"), "no stale jail samples");
+        },
+        Err(e) => println!("   ✗ Request failed: {}", e),
+    }
+    
+    let _ = std::fs::remove_dir_all(&dir);
+    println!("\n=== Exporter Test Complete ===");
+}
+
+fn get(addr: &str, path: &str) -> std::io::Result<String> {
+    send(addr, format!("GET {} HTTP/1.1\r\nHost: {}\r\nAccept: */*\r\n\r\n", path, addr).as_bytes())
+}
+
+fn send(addr: &str, request: &[u8]) -> std::io::Result<String> {
+    let mut stream = TcpStream::connect(addr)?;
+    stream.write_all(request)?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    Ok(response)
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use f2b_buxjr::services::file_monitor::ConfigFileWatcher;
+use f2b_buxjr::utils::text_diff::{has_conflict_markers, three_way_merge};
+
+const BASE: &str = "[sshd]\nenabled = true\nport = ssh\nmaxretry = 5\nbantime = 1h\n";
+
+fn main() {
+    println!("=== f2b-buxjr External Changes Test ===");
+    println!();
+    
+    // Merging
+    println!("1. Merging Editor and Disk Changes...");
+    let ours = BASE.replace("port = ssh", "port = 2222");
+    let theirs = BASE.replace("bantime = 1h", "bantime = 1d");
+    let merged = three_way_merge(BASE, &ours, &theirs);
+    report(merged.conflicts == 0 && merged.text == "[sshd]\nenabled = true\nport = 2222\nmaxretry = 5\nbantime = 1d\n", "separate edits both kept");
+    report(!has_conflict_markers(&merged.text), "clean merge has no markers");
+    let same = three_way_merge(BASE, &ours, &ours);
+    report(same.conflicts == 0 && same.text == ours, "same edit on both sides taken once");
+    let deleted = three_way_merge(BASE, BASE, &BASE.replace("maxretry = 5\n", ""));
+    report(deleted.conflicts == 0 && !deleted.text.contains("maxretry"), "deletion on disk kept when the editor left the line alone");
+    
+    // Conflicts
+    println!("\n2. Marking Conflicts...");
+    let merged = three_way_merge(BASE, &BASE.replace("maxretry = 5", "maxretry = 3"), &BASE.replace("maxretry = 5", "maxretry = 10"));
+    report(merged.conflicts == 1, "overlapping edits conflict");
+    report(merged.text.contains("<<<<<<< editor\nmaxretry = 3\n=======\nmaxretry = 10\n>>>>>>> on disk\n"), "both versions between markers");
+    report(merged.text.starts_with("[sshd]\nenabled = true\nport = ssh\n") && merged.text.ends_with("bantime = 1h\n"), "untouched lines around the conflict kept");
+    let adjacent = three_way_merge(BASE, &ours, &BASE.replace("maxretry = 5", "maxretry = 3"));
+    report(adjacent.conflicts == 1, "edits to neighbouring lines conflict");
+    report(has_conflict_markers(&merged.text), "markers detected");
+    report(!has_conflict_markers("# <<<<<<< in a comment\nport = ssh\n") && !has_conflict_markers("<<<<<<<<\n"), "marker-like text not mistaken for markers");
+    
+    // Watching
+    println!("\n3. Watching the Open File...");
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-external-changes-{}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    let path = dir.join("jail.local");
+    let _ = std::fs::write(&path, BASE);
+    let watcher = match ConfigFileWatcher::new(&path.display().to_string()) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            println!("   ✗ Watcher not started: {}", e);
+            let _ = std::fs::remove_dir_all(&dir);
+            return;
+        }
+    };
+    report(!watcher.has_changed(), "nothing changed yet");
+    let _ = std::fs::write(dir.join("other.conf"), "[DEFAULT]\n");
+    std::thread::sleep(Duration::from_millis(200));
+    report(!watcher.has_changed(), "other files in the directory ignored");
+    // How editors and config management tools save: write a copy, rename it over the file
+    let copy = dir.join(".jail.local.swp");
+    let renamed = std::fs::write(&copy, theirs.as_bytes()).and_then(|_| std::fs::rename(&copy, &path));
+    std::thread::sleep(Duration::from_millis(200));
+    report(renamed.is_ok() && watcher.has_changed(), "file renamed over the open one detected");
+    report(!watcher.has_changed(), "each change reported once");
+    watcher.recheck();
+    report(watcher.has_changed() && !watcher.has_changed(), "unreadable change reported again on the next check");
+    let _ = std::fs::remove_dir_all(&dir);
+    
+    println!("\n=== External Changes Test Complete ===");
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
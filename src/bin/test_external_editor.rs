@@ -0,0 +1,76 @@
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use f2b_buxjr::utils::external_editor::{edit_in_external_editor, editor_command};
+
+/// Records its arguments and the file's mode, then edits the file the way a user would
+const FAKE_EDITOR: &str = r#"#!/bin/sh
+for file; do :; done
+echo "$@" > "$(dirname "$0")/args"
+stat -c %a "$file" > "$(dirname "$0")/mode"
+sed -i 's/bantime = 1h/bantime = 1d/' "$file"
+echo "maxretry = 3" >> "$file"
+"#;
+
+fn main() {
+    println!("=== f2b-buxjr External Editor Test ===");
+    println!();
+    
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-external-editor-{}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    let install = |name: &str, script: &str| {
+        let path = dir.join(name);
+        let _ = std::fs::write(&path, script);
+        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755));
+        path.display().to_string()
+    };
+    let editor = install("editor", FAKE_EDITOR);
+    let failing = install("failing", "#!/bin/sh\nexit 3\n");
+    let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap_or_default().trim().to_string();
+    
+    // Choosing the editor
+    println!("1. Choosing the Editor...");
+    std::env::set_var("VISUAL", format!("{} --wait", editor));
+    std::env::set_var("EDITOR", "nano");
+    report(editor_command() == [editor.clone(), "--wait".to_string()], "$VISUAL first, with its arguments");
+    std::env::set_var("VISUAL", "  ");
+    report(editor_command() == ["nano"], "blank $VISUAL falls back to $EDITOR");
+    std::env::remove_var("VISUAL");
+    std::env::remove_var("EDITOR");
+    report(editor_command() == ["vi"], "vi when neither is set");
+    
+    // Editing
+    println!("\n2. Editing a Temp Copy...");
+    std::env::set_var("VISUAL", format!("{} --wait", editor));
+    let edited = edit_in_external_editor("[sshd]\nbantime = 1h\n", "jail.local");
+    report(edited.as_deref().is_ok_and(|text| text == "[sshd]\nbantime = 1d\nmaxretry = 3\n"), "edited text read back");
+    let args = read("args");
+    let temp = args.split_whitespace().last().unwrap_or_default().to_string();
+    report(args.starts_with("--wait ") && temp.ends_with("-jail.local"), "editor arguments kept, copy named after the file");
+    report(read("mode") == "600", "copy readable by the owner only");
+    report(!temp.is_empty() && !Path::new(&temp).exists(), "copy removed afterwards");
+    
+    // Failures
+    println!("\n3. Handling Editor Failures...");
+    std::env::set_var("VISUAL", &failing);
+    let failed = edit_in_external_editor("[sshd]\n", "jail.local");
+    report(failed.as_ref().is_err_and(|e| e.to_string().contains("exited with")), "non-zero exit is an error");
+    std::env::set_var("VISUAL", dir.join("missing").display().to_string());
+    report(edit_in_external_editor("[sshd]\n", "jail.local").is_err_and(|e| e.to_string().contains("could not start")), "missing editor is an error");
+    let leftover = std::fs::read_dir(std::env::temp_dir()).map(|entries| entries
+        .filter_map(|e| e.ok())
+        .any(|e| e.file_name().to_string_lossy().starts_with(&format!("f2b-buxjr-{}-", std::process::id()))));
+    report(leftover.is_ok_and(|found| !found), "no copies left behind after failures");
+    std::env::remove_var("VISUAL");
+    
+    let _ = std::fs::remove_dir_all(&dir);
+    println!("\n=== External Editor Test Complete ===");
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
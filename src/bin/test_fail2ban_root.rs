@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use f2b_buxjr::services::config_validator::ConfigValidator;
+use f2b_buxjr::services::fail2ban_client::Fail2banClient;
+use f2b_buxjr::services::file_monitor::LogMonitor;
+use f2b_buxjr::utils::paths::{fail2ban_paths, Fail2banPaths};
+
+fn main() {
+    println!("=== f2b-buxjr Fail2ban Root Test ===");
+    println!();
+    
+    // Everything below runs against a fixture instead of /etc/fail2ban
+    println!("1. Preparing Fixture Root...");
+    let root = std::env::temp_dir().join(format!("f2b-buxjr-root-{}", std::process::id()));
+    if let Err(e) = create_fixture(&root) {
+        println!("   ✗ Failed to create fixture: {}", e);
+        return;
+    }
+    println!("   ✓ Fixture created at {}", root.display());
+    
+    let log_file = root.join("fail2ban.log");
+    let socket = root.join("fail2ban.sock");
+    let installed = Fail2banPaths::new(Some(root.clone()), Some(log_file.clone()), Some(socket.clone())).install();
+    report(installed, "paths installed before first use");
+    
+    println!("\n2. Testing Path Resolution...");
+    let paths = fail2ban_paths();
+    report(paths.jail_local() == root.join("jail.local"), "jail.local resolved under the root");
+    report(paths.backup_dir() == root.join("backups"), "backups resolved under the root");
+    report(LogMonitor::get_fail2ban_log_path() == log_file.display().to_string(), "log monitor follows --log-file");
+    let expected_args = vec!["-c".to_string(), root.display().to_string(), "-s".to_string(), socket.display().to_string()];
+    report(paths.client_args() == expected_args, "fail2ban-client gets -c and -s");
+    report(Fail2banPaths::default().client_args().is_empty(), "default paths add no client options");
+    report(ConfigValidator::new().config_dir() == root.as_path(), "validator checks the fixture root");
+    
+    // Config pipeline: reads and writes stay inside the fixture
+    println!("\n3. Testing Config Pipeline...");
+    let client = Fail2banClient::new();
+    match client.get_all_available_jails() {
+        Ok(jails) => report(jails.iter().any(|j| j.name == "sshd" && !j.enabled), "jails read from the fixture jail.local"),
+        Err(e) => println!("   ✗ Reading jails failed: {}", e),
+    }
+    
+    // The reload afterwards fails without a server - the write must still land in the fixture
+    let _ = client.set_jail_enabled("sshd", true);
+    let content = std::fs::read_to_string(root.join("jail.local")).unwrap_or_default();
+    report(content.contains("[sshd]\nenabled = true"), "jail toggle written to the fixture jail.local");
+    
+    match client.get_whitelist_ips() {
+        Ok(ips) => report(ips.contains(&"10.0.0.0/8".to_string()), "whitelist read from the fixture"),
+        Err(e) => println!("   ✗ Reading whitelist failed: {}", e),
+    }
+    
+    let issues = ConfigValidator::new().check_configuration();
+    report(issues.iter().all(|i| i.file.starts_with(&root.display().to_string())), "validation findings point into the fixture");
+    
+    let _ = std::fs::remove_dir_all(&root);
+    println!("\n=== Fail2ban Root Test Complete ===");
+}
+
+fn create_fixture(root: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(root.join("filter.d"))?;
+    std::fs::write(root.join("filter.d/sshd.conf"), "[Definition]\nfailregex = ^Failed\n")?;
+    std::fs::write(root.join("fail2ban.log"), "")?;
+    std::fs::write(root.join("jail.conf"), "[DEFAULT]\nbantime = 10m\n")?;
+    std::fs::write(root.join("jail.local"), format!(
+        "[DEFAULT]\nignoreip = 127.0.0.1/8 10.0.0.0/8\n\n[sshd]\nenabled = false\nport = ssh\nlogpath = {}\n",
+        root.join("fail2ban.log").display()))?;
+    Ok(())
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
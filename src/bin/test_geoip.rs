@@ -0,0 +1,207 @@
+use std::net::IpAddr;
+
+use f2b_buxjr::services::geoip::GeoIp;
+use f2b_buxjr::utils::mmdb::{Mmdb, MmdbValue};
+use f2b_buxjr::utils::settings::Settings;
+
+const COUNTRY_CSV: &str = "\
+\"0\",\"16777215\",\"-\",\"-\"
+\"3405803776\",\"3405804031\",\"AU\",\"Australia\"
+\"3758096128\",\"3758096383\",\"KR\",\"Korea (Republic of)\"
+\"3758096384\",\"3758096639\",\"IR\",\"Iran, Islamic Republic of\"
+";
+
+const ASN_CSV: &str = "\
+\"281474309472256\",\"281474309472511\",\"216.58.192.0/24\",\"15169\",\"Google LLC\"
+\"281470849515520\",\"281470849515775\",\"10.0.0.0/24\",\"-\",\"-\"
+";
+
+fn main() {
+    println!("=== f2b-buxjr GeoIP Test ===");
+    println!();
+    
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-geoip-{}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    
+    // MaxMind DB
+    println!("1. Reading a MaxMind Database...");
+    let db = match Mmdb::from_bytes(build_mmdb()) {
+        Ok(db) => db,
+        Err(e) => {
+            println!("   ✗ Database rejected: {}", e);
+            return;
+        }
+    };
+    report(db.database_type() == "Test-Country-ASN", "metadata read");
+    let record = db.lookup(addr("203.0.113.77")).ok().flatten();
+    report(record.as_ref().and_then(|r| r.get(&["country", "iso_code"])).and_then(MmdbValue::as_str) == Some("CN"), "IPv4 address found in an IPv6 tree");
+    report(record.as_ref().and_then(|r| r.get(&["autonomous_system_number"])).and_then(MmdbValue::as_u64) == Some(14061), "numbers decoded");
+    report(record.as_ref().and_then(|r| r.get(&["autonomous_system_organization"])).and_then(MmdbValue::as_str) == Some("DigitalOcean, LLC"), "pointers followed");
+    report(db.lookup(addr("2001:db8:ffff::1")).ok().flatten().is_some(), "IPv6 address found");
+    report(db.lookup(addr("198.51.100.1")).is_ok_and(|r| r.is_none()), "address outside every network not found");
+    report(Mmdb::from_bytes(b"not a database".to_vec()).is_err(), "file without metadata rejected");
+    let tree_past_end = |node_count: u64| Mmdb::from_bytes(with_node_count(build_mmdb(), node_count)).err();
+    report(tree_past_end(100_000).as_deref() == Some("search tree runs past the end of the file"), "node count larger than the file rejected");
+    report(tree_past_end(u64::MAX).as_deref() == Some("search tree runs past the end of the file"), "node count that overflows the tree size rejected");
+    
+    let path = dir.join("test.mmdb");
+    let _ = std::fs::write(&path, build_mmdb());
+    let mut geoip = GeoIp::default();
+    report(geoip.add_database(&path).is_ok() && geoip.is_enabled(), "database file loaded");
+    let info = geoip.lookup("203.0.113.77");
+    report(info.country.as_deref() == Some("CN") && info.country_name.as_deref() == Some("China"), "country code and name");
+    report(info.asn_label().as_deref() == Some("AS14061 DigitalOcean, LLC"), "ASN and organisation");
+    report(geoip.lookup("2001:db8::5").country.as_deref() == Some("DE"), "registered country used when there is no country");
+    report(geoip.lookup("198.51.100.1").is_empty(), "unknown address has no location");
+    report(geoip.lookup("203.0.113.0/24").is_empty() && geoip.lookup("mail.example.com").is_empty(), "ranges and hostnames not looked up");
+    report(geoip.cached("203.0.113.77") == info && geoip.cached("203.0.113.77") == info && geoip.cached_count() == 1, "answers cached per address");
+    
+    // IP2Location CSV
+    println!("\n2. Reading IP2Location CSV Files...");
+    let _ = std::fs::write(dir.join("country.csv"), COUNTRY_CSV);
+    let _ = std::fs::write(dir.join("asn.csv"), ASN_CSV);
+    let mut csv = GeoIp::default();
+    report(csv.add_database(&dir.join("country.csv")).is_ok() && csv.add_database(&dir.join("asn.csv")).is_ok(), "country and ASN files loaded");
+    report(csv.lookup("203.0.113.5").country.as_deref() == Some("AU"), "IPv4 range matched");
+    report(csv.lookup("224.0.0.9").country_name.as_deref() == Some("Iran, Islamic Republic of"), "quoted commas kept");
+    report(csv.lookup("216.58.192.10").asn == Some(15169), "IPv4 address matched in IPv6 numbering");
+    report(csv.lookup("0.1.2.3").is_empty() && csv.lookup("10.0.0.1").is_empty(), "placeholder rows ignored");
+    report(csv.lookup("203.0.114.1").country.is_none(), "gaps between ranges not matched");
+    let _ = std::fs::write(dir.join("empty.csv"), "from,to,country\n");
+    report(csv.add_database(&dir.join("empty.csv")).is_err(), "file without ranges rejected");
+    report(csv.describe().len() == 2 && csv.describe()[0].starts_with("IP2Location CSV, 3 ranges"), "databases described");
+    
+    // Settings
+    println!("\n3. Configuring Databases...");
+    let settings = Settings::parse("[geoip]\ndatabases = [\"/var/lib/GeoIP/GeoLite2-ASN.mmdb\"]\n");
+    report(settings.is_ok_and(|s| s.geoip.databases.len() == 1), "databases listed in the settings file");
+    report(Settings::parse("").is_ok_and(|s| s.geoip.databases.is_empty()), "no databases by default");
+    report(Settings::parse("[geoip]\ndatabase = \"x.mmdb\"\n").is_err(), "misspelt key rejected");
+    let settings = Settings::parse(&format!("[geoip]\ndatabases = [\"{}\"]\n", dir.join("missing.mmdb").display())).unwrap_or_default();
+    report(GeoIp::open(&settings.geoip).is_err(), "missing database is an error");
+    report(GeoIp::open(&Settings::default().geoip).is_ok_and(|g| !g.is_enabled()), "no databases, no enrichment");
+    
+    let _ = std::fs::remove_dir_all(&dir);
+    
+    println!("\n=== GeoIP Test Complete ===");
+}
+
+fn addr(text: &str) -> IpAddr {
+    text.parse().unwrap_or(IpAddr::from([0, 0, 0, 0]))
+}
+
+/// An IPv6 tree with 24-bit records: 203.0.113.0/24 and 2001:db8::/32
+fn build_mmdb() -> Vec<u8> {
+    // Data section: the organisation string first, then records pointing at it
+    let mut data = string("DigitalOcean, LLC");
+    let first = data.len();
+    data.extend(map(&[
+        ("country", map(&[("iso_code", string("CN")), ("names", map(&[("en", string("China"))]))])),
+        ("autonomous_system_number", uint32(14061)),
+        ("autonomous_system_organization", vec![0x20, 0x00]),  // Pointer to offset 0
+    ]));
+    let second = data.len();
+    data.extend(map(&[("registered_country", map(&[("iso_code", string("DE"))]))]));
+    
+    let mut nodes: Vec<[Option<Child>; 2]> = vec![[None, None]];
+    insert(&mut nodes, u32::from_be_bytes([203, 0, 113, 0]) as u128, 120, Child::Data(first));
+    insert(&mut nodes, 0x2001_0db8u128 << 96, 32, Child::Data(second));
+    
+    let node_count = nodes.len();
+    let mut file = Vec::new();
+    for node in &nodes {
+        for child in node {
+            let record = match child {
+                None => node_count,
+                Some(Child::Node(index)) => *index,
+                Some(Child::Data(offset)) => node_count + 16 + offset,
+            };
+            file.extend(&(record as u32).to_be_bytes()[1..]);
+        }
+    }
+    file.extend([0u8; 16]);
+    file.extend(data);
+    file.extend(b"\xAB\xCD\xEFMaxMind.com");
+    file.extend(map(&[
+        ("node_count", uint32(node_count as u32)),
+        ("record_size", vec![0xA1, 24]),  // uint16
+        ("ip_version", vec![0xA1, 6]),
+        ("database_type", string("Test-Country-ASN")),
+    ]));
+    file
+}
+
+/// The same file with its metadata claiming `node_count` nodes
+fn with_node_count(mut file: Vec<u8>, node_count: u64) -> Vec<u8> {
+    let key = string("node_count");
+    let Some(start) = file.windows(key.len()).rposition(|window| window == key) else {
+        return file;
+    };
+    let value = start + key.len();
+    file.splice(value..value + uint32(0).len(), uint64(node_count));
+    file
+}
+
+#[derive(Clone, Copy)]
+enum Child {
+    Node(usize),
+    Data(usize),
+}
+
+fn insert(nodes: &mut Vec<[Option<Child>; 2]>, bits: u128, len: u32, data: Child) {
+    let mut node = 0;
+    for i in 0..len {
+        let bit = ((bits >> (127 - i)) & 1) as usize;
+        if i == len - 1 {
+            nodes[node][bit] = Some(data);
+            return;
+        }
+        node = match nodes[node][bit] {
+            Some(Child::Node(next)) => next,
+            _ => {
+                nodes.push([None, None]);
+                nodes[node][bit] = Some(Child::Node(nodes.len() - 1));
+                nodes.len() - 1
+            },
+        };
+    }
+}
+
+fn string(text: &str) -> Vec<u8> {
+    let mut bytes = if text.len() < 29 {
+        vec![0x40 | text.len() as u8]
+    } else {
+        vec![0x40 | 29, (text.len() - 29) as u8]
+    };
+    bytes.extend(text.as_bytes());
+    bytes
+}
+
+fn uint32(value: u32) -> Vec<u8> {
+    let mut bytes = vec![0xC4];
+    bytes.extend(value.to_be_bytes());
+    bytes
+}
+
+fn uint64(value: u64) -> Vec<u8> {
+    let mut bytes = vec![0x08, 0x02];  // Extended type 9
+    bytes.extend(value.to_be_bytes());
+    bytes
+}
+
+fn map(entries: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    let mut bytes = vec![0xE0 | entries.len() as u8];
+    for (key, value) in entries {
+        bytes.extend(string(key));
+        bytes.extend(value);
+    }
+    bytes
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,50 @@
+use f2b_buxjr::utils::ini_syntax::{classify_line, IniToken};
+
+fn main() {
+    println!("=== f2b-buxjr INI Syntax Test ===");
+    println!();
+    
+    // One letter per character, so expected classes line up under the text
+    let classes = |line: &str| classify_line(line).iter().map(|token| match token {
+        IniToken::Plain => '.',
+        IniToken::Section => 'S',
+        IniToken::Key => 'K',
+        IniToken::Separator => '=',
+        IniToken::Value => 'V',
+        IniToken::Comment => 'C',
+        IniToken::Interpolation => 'I',
+        IniToken::Tag => 'T',
+    }).collect::<String>();
+    
+    // Structure
+    println!("1. Classifying Lines...");
+    report(classes("[sshd]") == "SSSSSS", "section header");
+    report(classes("bantime = 1h") == "KKKKKKKK=VVV", "key, separator and value");
+    report(classes("port: ssh") == "KKKK=VVVV", "colon separator");
+    report(classes("url = http://x") == "KKKK=VVVVVVVVV", "only the first separator splits");
+    report(classes("# comment = no") == "CCCCCCCCCCCCCC" && classes("; note") == "CCCCCC", "# and ; comments");
+    report(classes("  # indented") == "..CCCCCCCCCC", "indented comment");
+    report(classes("    /var/log/auth.log") == "....VVVVVVVVVVVVVVVVV", "indented line continues the value");
+    report(classes(" [not a section]").chars().all(|c| c == '.' || c == 'V'), "indented bracket is not a section");
+    report(classes("no separator here") == "................." && classes("").is_empty(), "plain text and empty lines");
+    report(classify_line("név = é").len() == "név = é".chars().count(), "one class per character");
+    
+    // Inside values
+    println!("\n2. Classifying Values...");
+    report(classes("logpath = %(sshd_log)s") == "KKKKKKKK=VIIIIIIIIIIII", "interpolation");
+    report(classes("action = %(action_)s[name=x]") == "KKKKKKK=VIIIIIIIIIIIVVVVVVVV", "interpolation followed by text");
+    report(classes("x = %(open") == "KK=VVVVVVV" && classes("x = %(name)") == "KK=VVVVVVVV", "unfinished interpolation left as value");
+    report(classes("failregex = ^<HOST> fail") == "KKKKKKKKKK=VVTTTTTTVVVVV", "tag");
+    report(classes("  <F-USER> from <ip>") == "..TTTTTTTTVVVVVVTTTT", "tags on a continuation line");
+    report(classes("x = a < b > c") == "KK=VVVVVVVVVV" && classes("x = <>") == "KK=VVV", "comparisons and empty brackets are not tags");
+    
+    println!("\n=== INI Syntax Test Complete ===");
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,123 @@
+use f2b_buxjr::services::config_validator::ConfigValidator;
+use f2b_buxjr::services::jail_templates::{template_for, JailDestination, NewJail};
+
+fn main() {
+    println!("=== f2b-buxjr Jail Template Test ===");
+    println!();
+    
+    // Service defaults
+    println!("1. Testing Service Templates...");
+    report(template_for("sshd").port == "ssh", "sshd uses port 'ssh'");
+    report(template_for("nginx-http-auth").port == "http,https", "nginx filters use http,https");
+    report(template_for("postfix").port.contains("submission"), "postfix covers submission");
+    report(template_for("dovecot").port.contains("imaps"), "dovecot covers imaps");
+    let unknown = template_for("some-custom-filter");
+    report(unknown.port.is_empty() && unknown.logpath.is_empty() && unknown.backend == "auto", "unknown filters get an empty template");
+    
+    // Fixture directory with one filter, one log and an existing local jail
+    println!("\n2. Preparing Configuration Directory...");
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-templates-{}", std::process::id()));
+    if let Err(e) = create_fixture(&dir) {
+        println!("   ✗ Failed to create fixture: {}", e);
+        return;
+    }
+    println!("   ✓ Fixture created at {}", dir.display());
+    let validator = ConfigValidator::with_config_dir(&dir);
+    let log_file = dir.join("auth.log").display().to_string();
+    
+    report(validator.list_definitions("filter.d") == ["myapp", "sshd"], "filter.d lists both filters once");
+    report(validator.local_jail_names() == ["myapp"], "only jail.local/jail.d sections count as taken");
+    
+    // Validation
+    println!("\n3. Testing Validation...");
+    let mut jail = NewJail::from_filter("sshd");
+    jail.logpath = log_file.clone();
+    jail.backend = "auto".to_string();
+    report(jail.validate(&validator).is_empty(), "sshd with an existing log passes");
+    
+    let mut bad = jail.clone();
+    bad.name = "my jail".to_string();
+    report(has_problem(&bad, &validator, "may only use"), "spaces in the name are rejected");
+    
+    let mut bad = jail.clone();
+    bad.name = "myapp".to_string();
+    report(has_problem(&bad, &validator, "already defined"), "an existing local jail name is rejected");
+    
+    let mut bad = jail.clone();
+    bad.logpath = "/nonexistent/f2b-buxjr.log".to_string();
+    report(has_problem(&bad, &validator, "log file not found"), "a missing log file is rejected");
+    
+    let mut journal = bad.clone();
+    journal.backend = "systemd".to_string();
+    report(journal.validate(&validator).is_empty(), "systemd backend does not need a log file");
+    journal.backend = "systemd[journalflags=1]".to_string();
+    report(journal.validate(&validator).is_empty(), "systemd backend options accepted");
+    
+    let mut bad = jail.clone();
+    bad.backend = "inotify".to_string();
+    report(has_problem(&bad, &validator, "unknown backend 'inotify'"), "an unknown backend is rejected");
+    
+    let mut bad = jail.clone();
+    bad.bantime = "ten".to_string();
+    report(has_problem(&bad, &validator, "bantime"), "an invalid bantime is rejected");
+    
+    let mut bad = jail.clone();
+    bad.filter = "nginx-http-auth".to_string();
+    report(has_problem(&bad, &validator, "not found in filter.d"), "a filter missing from filter.d is rejected");
+    
+    // Renaming is kept when the filter changes
+    let mut renamed = jail.clone();
+    renamed.name = "ssh-alt".to_string();
+    renamed.apply_filter("myapp");
+    report(renamed.name == "ssh-alt" && renamed.filter == "myapp", "custom name survives a filter change");
+    
+    // Writing
+    println!("\n4. Testing Write...");
+    match jail.write(&dir) {
+        Ok(path) => {
+            let written = std::fs::read_to_string(&path).unwrap_or_default();
+            report(path == dir.join("jail.d/sshd.local"), "drop-in written to jail.d/sshd.local");
+            report(written.starts_with("[sshd]\nenabled = true\nfilter = sshd\n"), "drop-in holds the enabled section");
+        },
+        Err(e) => println!("   ✗ Drop-in write failed: {}", e),
+    }
+    report(has_problem(&jail, &validator, "already"), "the same jail cannot be created twice");
+    
+    let mut local = NewJail::from_filter("myapp");
+    local.name = "myapp-strict".to_string();
+    local.logpath = log_file;
+    local.destination = JailDestination::JailLocal;
+    match local.write(&dir) {
+        Ok(_) => {
+            let content = std::fs::read_to_string(dir.join("jail.local")).unwrap_or_default();
+            report(content.starts_with("[myapp]\nenabled = true\n\n[myapp-strict]\n"), "section appended to jail.local after a blank line");
+        },
+        Err(e) => println!("   ✗ jail.local write failed: {}", e),
+    }
+    
+    let _ = std::fs::remove_dir_all(&dir);
+    println!("\n=== Jail Template Test Complete ===");
+}
+
+fn has_problem(jail: &NewJail, validator: &ConfigValidator, needle: &str) -> bool {
+    jail.validate(validator).iter().any(|p| p.contains(needle))
+}
+
+fn create_fixture(dir: &std::path::Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir.join("filter.d"))?;
+    std::fs::write(dir.join("auth.log"), "")?;
+    std::fs::write(dir.join("filter.d/sshd.conf"), "[Definition]\nfailregex = ^Failed\n")?;
+    std::fs::write(dir.join("filter.d/myapp.conf"), "[Definition]\nfailregex = ^denied <HOST>\n")?;
+    std::fs::write(dir.join("filter.d/myapp.local"), "[Definition]\n")?;
+    std::fs::write(dir.join("jail.conf"), "[DEFAULT]\nbantime = 10m\n\n[sshd]\nport = ssh\n")?;
+    std::fs::write(dir.join("jail.local"), "[myapp]\nenabled = true\n")?;
+    Ok(())
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,240 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::os::unix::net::UnixDatagram;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use f2b_buxjr::app::LogEntry;
+use f2b_buxjr::services::file_monitor::{BanAction, LogMonitor};
+use f2b_buxjr::services::notifications::{deliver, Notification, Notifier};
+use f2b_buxjr::utils::settings::{NotificationSettings, Settings};
+
+const SETTINGS: &str = r#"
+[notifications]
+syslog_socket = "/run/test.sock"
+
+[[notifications.rules]]
+name = "sshd burst"
+jail = "sshd"
+threshold = 2
+window_secs = 300
+cooldown_secs = 600
+webhook = "https://hooks.example.com/services/T000/B000"
+
+[[notifications.rules]]
+name = "any unban"
+event = "unban"
+cooldown_secs = 0
+syslog = true
+"#;
+
+fn main() {
+    println!("=== f2b-buxjr Notification Test ===");
+    println!();
+    
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-notifications-{}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    
+    // Settings file
+    println!("1. Loading Rules from the Settings File...");
+    let settings = match Settings::parse(SETTINGS) {
+        Ok(settings) => settings,
+        Err(e) => {
+            println!("   ✗ Settings rejected: {}", e);
+            return;
+        }
+    };
+    let rules = &settings.notifications.rules;
+    report(rules.len() == 2 && settings.notifications.syslog_socket == "/run/test.sock", "rules and syslog socket read");
+    report(rules[1].threshold == 0 && rules[1].window_secs == 300 && rules[1].jail.is_none(), "defaults filled in");
+    report(Settings::parse("[[notifications.rules]]\nname = \"mute\"\n").is_err_and(|e| e.contains("needs a webhook")), "rule without a channel rejected");
+    report(Settings::parse("[notifications]\nsyslog_sock = \"/dev/log\"\n").is_err(), "misspelt key rejected");
+    report(Settings::load(&dir.join("missing.toml")).is_ok_and(|s| s == Settings::default()), "missing settings file means defaults");
+    
+    // Rule evaluation
+    println!("\n2. Evaluating Rules...");
+    let start = Instant::now();
+    let mut notifier = Notifier::new(settings.notifications.clone());
+    report(notifier.observe_at(&[ban("sshd", "192.0.2.1"), ban("sshd", "192.0.2.2"), ban("sshd", "192.0.2.3")], start).is_empty(), "lines already in the log are history");
+    report(notifier.observe_at(&[ban("sshd", "203.0.113.1"), ban("sshd", "203.0.113.2")], start).is_empty(), "threshold reached but not passed stays quiet");
+    report(notifier.observe_at(&[ban("sshd", "203.0.113.2")], start).is_empty(), "same address again counts once");
+    report(notifier.observe_at(&[ban("nginx-http-auth", "203.0.113.9")], start).is_empty(), "other jails ignored");
+    let fired = notifier.observe_at(&[ban("sshd", "203.0.113.3")], start + Duration::from_secs(10));
+    report(fired.len() == 1 && fired[0].rule == "sshd burst" && fired[0].addresses.len() == 3, "more than the threshold within the window fires");
+    report(fired.first().is_some_and(|n| n.text().contains("3 bans in sshd within 5m")), "message names count, jail and window");
+    
+    for i in 0..3 {
+        let _ = notifier.observe_at(&[ban("sshd", &format!("198.51.100.{}", i))], start + Duration::from_secs(20));
+    }
+    let after_cooldown = start + Duration::from_secs(700);
+    let fired_again = (0..3)
+        .flat_map(|i| notifier.observe_at(&[ban("sshd", &format!("198.51.100.{}", 10 + i))], after_cooldown))
+        .collect::<Vec<_>>();
+    report(fired_again.len() == 1 && fired_again[0].suppressed == 1, "cooldown suppresses and reports it later");
+    
+    let mut slow = Notifier::new(settings.notifications.clone());
+    let _ = slow.observe_at(&[], start);
+    let _ = slow.observe_at(&[ban("sshd", "203.0.113.1"), ban("sshd", "203.0.113.2")], start);
+    report(slow.observe_at(&[ban("sshd", "203.0.113.3")], start + Duration::from_secs(301)).is_empty(), "events outside the window expire");
+    
+    let unbans = notifier.observe_at(&[unban("sshd", "203.0.113.1"), unban("postfix", "203.0.113.5")], after_cooldown);
+    report(unbans.len() == 2 && unbans.iter().all(|n| n.action == BanAction::Unban), "unban rule fires for any jail");
+    
+    // Delivery
+    println!("\n3. Delivering...");
+    let notification = Notification {
+        rule: "sshd burst".to_string(),
+        action: BanAction::Ban,
+        jails: vec!["sshd".to_string()],
+        addresses: vec!["203.0.113.1".to_string(), "203.0.113.2".to_string()],
+        window_secs: 300,
+        suppressed: 0,
+    };
+    let payload = notification.payload();
+    report(payload["text"].is_string() && payload["username"] == "f2b-buxjr" && payload["count"] == 2, "Slack/Mattermost compatible payload");
+    
+    match TcpListener::bind("127.0.0.1:0") {
+        Ok(listener) => {
+            let url = format!("http://{}/hooks/test", listener.local_addr().map(|a| a.to_string()).unwrap_or_default());
+            let (sender, received) = mpsc::channel();
+            std::thread::spawn(move || {
+                if let Ok((stream, _)) = listener.accept() {
+                    let _ = sender.send(read_request(stream));
+                }
+            });
+            let mut rule = rules[0].clone();
+            rule.webhook = Some(url);
+            let results = deliver(&rule, "/nonexistent", &notification);
+            report(results.len() == 1 && results[0].is_ok(), "webhook POST succeeded");
+            match received.recv_timeout(Duration::from_secs(5)) {
+                Ok((head, body)) => {
+                    report(head.starts_with("POST /hooks/test "), "POSTed to the webhook path");
+                    report(head.to_lowercase().contains("content-type: application/json"), "sent as JSON");
+                    let json: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+                    report(json["addresses"][1] == "203.0.113.2" && json["rule"] == "sshd burst", "payload received intact");
+                },
+                Err(_) => println!("   ✗ Stand-in never received the webhook"),
+            }
+        },
+        Err(e) => println!("   ✗ Failed to bind: {}", e),
+    }
+    
+    let mut rule = rules[0].clone();
+    rule.webhook = Some("http://127.0.0.1:9/unreachable".to_string());
+    report(deliver(&rule, "/nonexistent", &notification).first().is_some_and(|r| r.is_err()), "unreachable webhook reported");
+    
+    let output = dir.join("command.out");
+    let mut rule = rules[0].clone();
+    rule.webhook = None;
+    rule.command = Some(format!("echo \"$F2B_RULE|$F2B_EVENT|$F2B_JAILS|$F2B_COUNT|$F2B_ADDRESSES\" > {}", output.display()));
+    let results = deliver(&rule, "/nonexistent", &notification);
+    report(results.len() == 1 && results[0].is_ok(), "command ran");
+    report(std::fs::read_to_string(&output).is_ok_and(|o| o.trim() == "sshd burst|ban|sshd|2|203.0.113.1 203.0.113.2"), "command got the event in F2B_* variables");
+    rule.command = Some("exit 3".to_string());
+    report(deliver(&rule, "/nonexistent", &notification).first().is_some_and(|r| r.is_err()), "failing command reported");
+    
+    let socket = dir.join("syslog.sock");
+    match UnixDatagram::bind(&socket) {
+        Ok(syslog) => {
+            let _ = syslog.set_read_timeout(Some(Duration::from_secs(5)));
+            let mut rule = rules[1].clone();
+            rule.syslog = true;
+            let results = deliver(&rule, &socket.display().to_string(), &notification);
+            report(results.len() == 1 && results[0].is_ok(), "syslog datagram sent");
+            let mut buf = [0u8; 2048];
+            match syslog.recv(&mut buf) {
+                Ok(len) => {
+                    let line = String::from_utf8_lossy(&buf[..len]).to_string();
+                    report(line.starts_with("<36>") && line.contains("f2b-buxjr[") && line.contains("2 bans in sshd"), "auth.warning message with the text");
+                },
+                Err(e) => println!("   ✗ No syslog message: {}", e),
+            }
+        },
+        Err(e) => println!("   ✗ Failed to bind syslog stand-in: {}", e),
+    }
+    
+    // Background delivery from observe
+    println!("\n4. Delivering from the Log Tail...");
+    let output = dir.join("observed.out");
+    let mut background = NotificationSettings::default();
+    let mut rule = rules[1].clone();
+    rule.syslog = false;
+    rule.command = Some(format!("echo \"$F2B_ADDRESSES\" >> {}", output.display()));
+    background.rules.push(rule);
+    let mut notifier = Notifier::new(background);
+    let _ = notifier.observe(&[]);
+    report(notifier.observe(&[unban("sshd", "203.0.113.77")]).len() == 1, "rule fired from observe");
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !output.exists() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    std::thread::sleep(Duration::from_millis(100));
+    report(std::fs::read_to_string(&output).is_ok_and(|o| o.trim() == "203.0.113.77"), "delivered in the background");
+    
+    // Lines written just before the Logs screen reads the recent entries
+    println!("\n5. Tailing Around the Logs Screen...");
+    let log = dir.join("fail2ban.log");
+    let line = |verb: &str, ip: &str| format!("{} fail2ban.actions        [812]: NOTICE  [sshd] {} {}\n", chrono::Local::now().format("%Y-%m-%d %H:%M:%S,%3f"), verb, ip);
+    let _ = std::fs::write(&log, line("Ban", "203.0.113.90"));
+    let mut monitor = LogMonitor::new(&log.display().to_string());
+    let mut notifier = Notifier::new(settings.notifications.clone());
+    let _ = notifier.observe_at(&monitor.tail_new_lines().unwrap_or_default(), start);
+    std::thread::sleep(Duration::from_millis(20));  // A newer modification time
+    let _ = std::fs::OpenOptions::new().append(true).open(&log).and_then(|mut file| file.write_all(line("Unban", "203.0.113.90").as_bytes()));
+    report(monitor.get_recent_lines(100).is_ok_and(|entries| entries.len() == 2), "recent lines read");
+    let fired = notifier.observe_at(&monitor.tail_new_lines().unwrap_or_default(), start);
+    report(fired.len() == 1 && fired[0].addresses == vec!["203.0.113.90"], "unban written before the read still notified");
+    
+    let _ = std::fs::remove_dir_all(&dir);
+    println!("\n=== Notification Test Complete ===");
+}
+
+fn entry(message: String) -> LogEntry {
+    LogEntry {
+        timestamp: chrono::Utc::now(),
+        level: "NOTICE".to_string(),
+        message,
+        jail: None,
+    }
+}
+
+fn ban(jail: &str, ip: &str) -> LogEntry {
+    entry(format!("fail2ban.actions        [812]: NOTICE  [{}] Ban {}", jail, ip))
+}
+
+fn unban(jail: &str, ip: &str) -> LogEntry {
+    entry(format!("fail2ban.actions        [812]: NOTICE  [{}] Unban {}", jail, ip))
+}
+
+/// Request head and body as the webhook stand-in saw them
+fn read_request(stream: std::net::TcpStream) -> (String, String) {
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(_) => return (String::new(), String::new()),
+    };
+    let mut reader = BufReader::new(stream);
+    let mut head = String::new();
+    let mut length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+            length = value.trim().parse().unwrap_or(0);
+        }
+        head.push_str(&line);
+    }
+    let mut body = vec![0; length];
+    let _ = reader.read_exact(&mut body);
+    let _ = writer.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+    (head, String::from_utf8_lossy(&body).to_string())
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,49 @@
+use f2b_buxjr::utils::paths::Fail2banPaths;
+use f2b_buxjr::utils::privileges::{Capabilities, PrivilegeStatus};
+
+fn main() {
+    println!("=== f2b-buxjr Read-only Mode Test ===");
+    println!();
+    
+    println!("1. Preparing Fixture Root...");
+    let root = std::env::temp_dir().join(format!("f2b-buxjr-readonly-{}", std::process::id()));
+    if let Err(e) = std::fs::create_dir_all(&root) {
+        println!("   ✗ Failed to create fixture: {}", e);
+        return;
+    }
+    println!("   ✓ Fixture created at {}", root.display());
+    let paths = Fail2banPaths::new(Some(root.clone()), Some(root.join("fail2ban.log")), Some(root.join("fail2ban.sock")));
+    
+    // Privileged users always get everything
+    println!("\n2. Testing Privileged Users...");
+    report(Capabilities::for_status(PrivilegeStatus::Root, &paths) == Capabilities::full(), "root gets full capabilities");
+    report(Capabilities::for_status(PrivilegeStatus::Sudo, &paths) == Capabilities::full(), "sudo gets full capabilities");
+    report(Capabilities::full().missing().is_empty(), "nothing missing with full capabilities");
+    
+    // Nothing readable yet - startup must still be refused
+    println!("\n3. Testing Regular User Probe...");
+    let nothing = Capabilities::for_status(PrivilegeStatus::User, &paths);
+    report(nothing.read_only(), "regular users are read-only");
+    report(!nothing.can_observe(), "no socket and no log means nothing to observe");
+    report(nothing.missing().len() == 4, "all four capabilities listed as missing");
+    
+    // A readable log is enough to observe
+    let _ = std::fs::write(root.join("fail2ban.log"), "");
+    let _ = std::fs::write(root.join("jail.conf"), "[DEFAULT]\n");
+    let observer = Capabilities::probe(&paths);
+    report(observer.log && observer.config && !observer.socket, "log and configuration readable, socket missing");
+    report(observer.can_observe(), "readable log allows read-only mode");
+    let missing = observer.missing();
+    report(missing.iter().any(|m| m.contains("socket")) && !missing.iter().any(|m| m.contains("log")), "missing list names the socket only");
+    
+    let _ = std::fs::remove_dir_all(&root);
+    println!("\n=== Read-only Mode Test Complete ===");
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,113 @@
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use f2b_buxjr::services::reverse_dns::{parse_hosts_line, resolve, Hostname, ReverseDns};
+use f2b_buxjr::utils::settings::{RdnsSettings, Settings};
+
+/// Stands in for the system resolver; records every address it is asked about
+const RESOLVER: &str = r#"#!/bin/sh
+echo "$1" >> "$(dirname "$0")/queries.log"
+case "$1" in
+    192.0.2.1) echo "192.0.2.1       crawl-192-0-2-1.googlebot.com." ;;
+    192.0.2.2) echo "192.0.2.2       scanner.example.net" ;;
+    192.0.2.9) sleep 5 ;;
+    *) exit 2 ;;
+esac
+"#;
+
+fn main() {
+    println!("=== f2b-buxjr Reverse DNS Test ===");
+    println!();
+    
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-rdns-{}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    let script = dir.join("resolver.sh");
+    let _ = std::fs::write(&script, RESOLVER);
+    let command = format!("sh {}", script.display());
+    
+    // Resolver output
+    println!("1. Asking the Resolver...");
+    let addr = |text: &str| text.parse::<IpAddr>().unwrap_or(IpAddr::from([0, 0, 0, 0]));
+    report(parse_hosts_line("192.0.2.1  host.example.com alias\n", addr("192.0.2.1")).as_deref() == Some("host.example.com"), "first name taken");
+    report(parse_hosts_line("192.0.2.1  192.0.2.1\n", addr("192.0.2.1")).is_none(), "address echoed back is no name");
+    report(parse_hosts_line("", addr("192.0.2.1")).is_none(), "empty output is no name");
+    report(resolve("getent hosts", addr("127.0.0.1"), Duration::from_secs(5)).is_some(), "system resolver reads /etc/hosts");
+    report(resolve(&command, addr("192.0.2.1"), Duration::from_secs(5)).as_deref() == Some("crawl-192-0-2-1.googlebot.com"), "trailing dot dropped");
+    report(resolve(&command, addr("198.51.100.1"), Duration::from_secs(5)).is_none(), "failing lookup has no name");
+    let started = Instant::now();
+    report(resolve(&command, addr("192.0.2.9"), Duration::from_millis(300)).is_none() && started.elapsed() < Duration::from_secs(3), "slow lookup cut off at the timeout");
+    report(resolve("/nonexistent/resolver", addr("192.0.2.1"), Duration::from_secs(1)).is_none(), "missing command has no name");
+    
+    // Background lookups
+    println!("\n2. Resolving in the Background...");
+    let settings = RdnsSettings {
+        enabled: true,
+        command: command.clone(),
+        workers: 2,
+        timeout_ms: 1000,
+        ttl_secs: 3600,
+    };
+    let mut rdns = ReverseDns::new(settings.clone());
+    let _ = std::fs::remove_file(dir.join("queries.log"));
+    report(rdns.lookup("192.0.2.1") == Hostname::Pending, "first lookup pending");
+    for ip in ["192.0.2.2", "198.51.100.1", "198.51.100.2", "198.51.100.3"] {
+        rdns.lookup(ip);
+    }
+    report(rdns.pending_count() == 5, "lookups queued beyond the workers");
+    report(wait_for(&mut rdns, 5), "all lookups finish");
+    report(rdns.lookup("192.0.2.1") == Hostname::Found("crawl-192-0-2-1.googlebot.com".to_string()), "name cached");
+    report(rdns.peek("198.51.100.1") == Hostname::NotFound, "failure cached");
+    rdns.lookup("192.0.2.1");
+    rdns.lookup("198.51.100.1");
+    report(queries(&dir).len() == 5, "cached answers not asked again");
+    report(rdns.lookup("192.0.2.0/24") == Hostname::NotFound && rdns.lookup("mail.example.com") == Hostname::NotFound, "ranges and hostnames not looked up");
+    report(rdns.peek("192.0.2.2") == Hostname::Found("scanner.example.net".to_string()) && rdns.cached_count() == 5, "peek reads the cache");
+    
+    let mut expiring = ReverseDns::new(RdnsSettings { ttl_secs: 0, ..settings.clone() });
+    expiring.lookup("192.0.2.1");
+    wait_for(&mut expiring, 1);
+    report(expiring.lookup("192.0.2.1") == Hostname::Pending, "expired names looked up again");
+    
+    let mut slow = ReverseDns::new(RdnsSettings { timeout_ms: 200, ..settings.clone() });
+    slow.lookup("192.0.2.9");
+    report(wait_for(&mut slow, 1) && slow.peek("192.0.2.9") == Hostname::NotFound, "timeout cached as no name");
+    
+    let mut off = ReverseDns::default();
+    report(!off.is_enabled() && off.lookup("192.0.2.1") == Hostname::NotFound && off.pending_count() == 0, "disabled by default");
+    
+    // Settings
+    println!("\n3. Configuring Reverse DNS...");
+    let parsed = Settings::parse("[rdns]\nenabled = true\nworkers = 8\n");
+    report(parsed.is_ok_and(|s| s.rdns.enabled && s.rdns.workers == 8 && s.rdns.command == "getent hosts" && s.rdns.timeout_ms == 2000), "settings read, defaults filled in");
+    report(Settings::parse("[rdns]\nworkers = 0\n").is_err(), "zero workers rejected");
+    report(Settings::parse("[rdns]\ncommand = \" \"\n").is_err(), "empty command rejected");
+    report(Settings::parse("[rdns]\nttl = 60\n").is_err(), "misspelt key rejected");
+    
+    let _ = std::fs::remove_dir_all(&dir);
+    
+    println!("\n=== Reverse DNS Test Complete ===");
+}
+
+/// Poll until `count` answers arrived, giving up after a few seconds
+fn wait_for(rdns: &mut ReverseDns, count: usize) -> bool {
+    let started = Instant::now();
+    let mut collected = 0;
+    while collected < count && started.elapsed() < Duration::from_secs(10) {
+        collected += rdns.poll();
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    collected == count
+}
+
+fn queries(dir: &Path) -> Vec<String> {
+    std::fs::read_to_string(dir.join("queries.log")).unwrap_or_default().lines().map(str::to_string).collect()
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,135 @@
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::time::Duration;
+
+use f2b_buxjr::app::{ServiceAction, ServiceStatus};
+use f2b_buxjr::services::service_backend::ServiceBackend;
+use f2b_buxjr::services::system_service::SystemService;
+use f2b_buxjr::utils::paths::Fail2banPaths;
+
+/// One fake for every init tool; it keeps fail2ban's state in $FAKE_STATE
+const FAKE_TOOL: &str = r#"#!/bin/sh
+state=$(cat "$FAKE_STATE" 2>/dev/null || echo stopped)
+case "$(basename "$0")" in
+    rc-service|service) verb=$2 ;;
+    sv) verb=$1 ;;
+    fail2ban-client) eval "verb=\${$#}" ;;
+esac
+case "$verb" in
+    start) [ "$state" = broken ] && { echo "fail2ban: ERROR: No file(s) found for glob /var/log/auth.log" >&2; exit 1; }
+           echo started > "$FAKE_STATE" ;;
+    stop) echo stopped > "$FAKE_STATE" ;;
+    restart) echo started > "$FAKE_STATE" ;;
+    reload) [ "$state" = started ] || { echo "fail2ban is not running" >&2; exit 1; } ;;
+    status|ping)
+        case "$(basename "$0"):$state" in
+            rc-service:started) echo " * status: started" ;;
+            rc-service:*) echo " * status: stopped"; exit 3 ;;
+            service:started) echo "fail2ban is running." ;;
+            service:*) echo "fail2ban is not running."; exit 3 ;;
+            sv:started) echo "run: fail2ban: (pid 4321) 7260s; run: log: (pid 4320) 7260s" ;;
+            sv:*) echo "down: fail2ban: 3s, normally up" ;;
+            fail2ban-client:started) echo "Server replied: pong" ;;
+            fail2ban-client:*) echo "Failed to access socket path" >&2; exit 255 ;;
+        esac ;;
+esac
+"#;
+
+fn main() {
+    println!("=== f2b-buxjr Service Backend Test ===");
+    println!();
+    
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-backends-{}", std::process::id()));
+    let bin = dir.join("bin");
+    let state = dir.join("state");
+    if let Err(e) = install_fakes(&bin) {
+        println!("   ✗ Failed to install fake init tools: {}", e);
+        return;
+    }
+    std::env::set_var("PATH", format!("{}:{}", bin.display(), std::env::var("PATH").unwrap_or_default()));
+    std::env::set_var("FAKE_STATE", &state);
+    Fail2banPaths::new(None, None, Some(dir.join("fail2ban.sock"))).install();
+    
+    // Detection
+    println!("1. Detecting Init Systems...");
+    let detect = |name: &str, paths: &[&str]| {
+        let root = dir.join(name);
+        for path in paths {
+            let _ = std::fs::create_dir_all(root.join(path));
+        }
+        ServiceBackend::detect_at(&root, "fail2ban")
+    };
+    report(detect("systemd", &["run/systemd/system", "etc/init.d/fail2ban"]) == ServiceBackend::Systemd, "booted systemd wins over leftover init scripts");
+    report(detect("openrc", &["run/openrc", "etc/init.d/fail2ban"]) == ServiceBackend::OpenRc, "OpenRC from /run/openrc");
+    report(detect("runit", &["etc/service/fail2ban", "etc/init.d/fail2ban"]) == ServiceBackend::Runit, "runit from its service directory");
+    report(detect("sysv", &["etc/init.d/fail2ban"]) == ServiceBackend::SysV, "SysV from the init script");
+    report(detect("container", &[]) == ServiceBackend::Direct, "no init system falls back to fail2ban-client");
+    report(ServiceBackend::from_name("openrc") == Some(ServiceBackend::OpenRc) && ServiceBackend::from_name("upstart").is_none(), "--service-manager names parsed");
+    
+    // Status parsing
+    println!("\n2. Parsing Status Output...");
+    let crashed = ServiceBackend::OpenRc.parse_status(Some(32), " * status: crashed");
+    report(matches!(crashed.service_status(), ServiceStatus::Failed) && crashed.failure.is_some(), "OpenRC crashed maps to Failed");
+    let dead = ServiceBackend::SysV.parse_status(Some(1), "");
+    report(matches!(dead.service_status(), ServiceStatus::Failed), "LSB exit 1 (dead, pid file left) maps to Failed");
+    let running = ServiceBackend::Runit.parse_status(Some(0), "run: fail2ban: (pid 4321) 7260s; run: log: (pid 4320) 7260s");
+    report(running.main_pid == 4321 && running.summary() == "PID 4321, up 2h 1m", "runit PID and uptime parsed");
+    report(!ServiceBackend::Direct.parse_status(Some(255), "").satisfies(&ServiceAction::Start), "no ping reply is not running");
+    
+    // Driving each backend through the fakes
+    for backend in [ServiceBackend::OpenRc, ServiceBackend::SysV, ServiceBackend::Runit, ServiceBackend::Direct] {
+        println!("\n3. Controlling through {}...", backend.label());
+        let _ = std::fs::write(&state, "stopped");
+        let service = SystemService::with_backend(backend, "fail2ban");
+        report(matches!(service.get_status(), Ok(ServiceStatus::Stopped)), "stopped service reported");
+        
+        let started = service.run(&ServiceAction::Start, Duration::from_secs(5));
+        report(started.as_ref().is_ok_and(|s| matches!(s.service_status(), ServiceStatus::Running)), "start");
+        report(service.run(&ServiceAction::Reload, Duration::from_secs(5)).is_ok(), "reload");
+        report(service.run(&ServiceAction::Restart, Duration::from_secs(5)).is_ok(), "restart");
+        report(service.run(&ServiceAction::Stop, Duration::from_secs(5)).is_ok(), "stop");
+        
+        match service.run(&ServiceAction::Reload, Duration::from_secs(5)) {
+            Ok(_) => println!("   ✗ Reload of a stopped service reported as success"),
+            Err(e) => report(e.to_string().contains("not running"), "failed command reports its error output"),
+        }
+        let _ = std::fs::write(&state, "broken");
+        match service.run(&ServiceAction::Start, Duration::from_secs(5)) {
+            Ok(_) => println!("   ✗ Failed start reported as success"),
+            Err(e) => report(e.to_string().contains("No file(s) found"), "failed start carries fail2ban's message"),
+        }
+    }
+    
+    // PID file fallback for backends that do not report a PID
+    println!("\n4. Reading the PID File...");
+    let _ = std::fs::write(&state, "started");
+    let _ = std::fs::write(dir.join("fail2ban.pid"), "812\n");
+    match SystemService::with_backend(ServiceBackend::OpenRc, "fail2ban").unit_state() {
+        Ok(unit) => report(unit.main_pid == 812 && unit.uptime().is_some() && unit.restarts.is_none(), "PID and uptime from fail2ban.pid"),
+        Err(e) => println!("   ✗ Failed to read state: {}", e),
+    }
+    
+    let _ = std::fs::remove_dir_all(&dir);
+    println!("\n=== Service Backend Test Complete ===");
+}
+
+fn install_fakes(bin: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(bin)?;
+    let fake = bin.join("fake-init-tool");
+    std::fs::write(&fake, FAKE_TOOL)?;
+    std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755))?;
+    for tool in ["rc-service", "service", "sv", "fail2ban-client"] {
+        let link = bin.join(tool);
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&fake, link)?;
+    }
+    Ok(())
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
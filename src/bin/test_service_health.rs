@@ -0,0 +1,136 @@
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::time::Duration;
+
+use f2b_buxjr::app::{LogEntry, ServiceStatus};
+use f2b_buxjr::services::fail2ban_client::{parse_setting_output, Fail2banClient};
+use f2b_buxjr::services::file_monitor::LogMonitor;
+use f2b_buxjr::services::health::{DaemonInfo, HealthLevel, ServiceHealth};
+
+/// Answers the way fail2ban-client 0.11 does
+const FAKE_CLIENT: &str = r#"#!/bin/sh
+case "$*" in
+    ping) echo "Server replied: pong" ;;
+    version) echo "1.0.2" ;;
+    "get dbfile") printf 'Current database file is set to:\n`- /var/lib/fail2ban/fail2ban.sqlite3\n' ;;
+    "get dbpurgeage") printf 'Current database purge age is set to:\n`- 86400seconds\n' ;;
+    "get loglevel") echo "Current logging level is 'NOTICE'" ;;
+    "get logtarget") printf 'Current logging target is:\n`- /var/log/fail2ban.log\n' ;;
+    *) exit 1 ;;
+esac
+"#;
+
+fn main() {
+    println!("=== f2b-buxjr Service Health Test ===");
+    println!();
+    
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-health-{}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    
+    // Setting output
+    println!("1. Parsing fail2ban-client Output...");
+    report(parse_setting_output("Current logging level is 'INFO'\n") == "INFO", "quoted value");
+    report(parse_setting_output("Current logging target is:\n`- SYSLOG\n") == "SYSLOG", "value on a `- line");
+    report(parse_setting_output("Fail2Ban v0.10.2\n") == "Fail2Ban v0.10.2", "plain single line kept");
+    
+    // Levels
+    println!("\n2. Judging Health...");
+    let health = |service: ServiceStatus, ping: Option<Result<Duration, String>>, errors: Vec<LogEntry>| ServiceHealth {
+        service,
+        ping,
+        recent_errors: errors,
+        ..ServiceHealth::default()
+    };
+    let fast = Some(Ok(Duration::from_millis(40)));
+    let dead = Some(Err("Failed to access socket path: /var/run/fail2ban/fail2ban.sock".to_string()));
+    
+    let healthy = health(ServiceStatus::Running, fast.clone(), Vec::new());
+    report(healthy.level() == HealthLevel::Healthy && healthy.headline() == "ok 40ms", "running and answering is healthy");
+    let socket_dead = health(ServiceStatus::Running, dead.clone(), Vec::new());
+    report(socket_dead.level() == HealthLevel::Critical && socket_dead.headline() == "SOCKET DEAD", "running with a dead socket is critical");
+    report(socket_dead.explanation().contains("Failed to access socket path"), "explanation carries the ping error");
+    let slow = health(ServiceStatus::Running, Some(Ok(Duration::from_millis(1500))), Vec::new());
+    report(slow.level() == HealthLevel::Degraded && slow.headline() == "slow 1.5s", "slow ping is degraded");
+    let old_error = error_entry(chrono::Utc::now() - chrono::Duration::hours(3));
+    let new_error = error_entry(chrono::Utc::now() - chrono::Duration::minutes(5));
+    report(health(ServiceStatus::Running, fast.clone(), vec![old_error]).level() == HealthLevel::Healthy, "errors older than an hour ignored");
+    let erroring = health(ServiceStatus::Running, fast.clone(), vec![new_error]);
+    report(erroring.level() == HealthLevel::Degraded && erroring.headline() == "1 error", "recent error is degraded");
+    report(health(ServiceStatus::Stopped, dead.clone(), Vec::new()).level() == HealthLevel::Stopped, "stopped service with no socket is stopped");
+    report(health(ServiceStatus::Failed, None, Vec::new()).level() == HealthLevel::Critical, "failed unit is critical");
+    report(health(ServiceStatus::Unknown, fast, Vec::new()).level() == HealthLevel::Healthy, "container without a service manager judged by the socket");
+    report(health(ServiceStatus::Running, None, Vec::new()).level() == HealthLevel::Unknown, "unprobed socket is unknown");
+    
+    // Recent errors from the log
+    println!("\n3. Collecting Recent Errors...");
+    let log_path = dir.join("fail2ban.log");
+    let written = write_log(&log_path);
+    report(written.is_ok(), "log written");
+    let monitor = LogMonitor::new(&log_path.display().to_string());
+    match monitor.recent_errors(3) {
+        Ok(errors) => {
+            report(errors.len() == 3, "limited to the requested count");
+            report(errors.iter().all(|e| e.level == "ERROR"), "only ERROR entries");
+            report(errors.first().is_some_and(|e| e.message.contains("error 20")), "newest first");
+        },
+        Err(e) => println!("   ✗ Failed to read errors: {}", e),
+    }
+    report(LogMonitor::new(&dir.join("missing.log").display().to_string()).recent_errors(3).is_ok_and(|e| e.is_empty()), "missing log has no errors");
+    
+    // Daemon probe through a fake client
+    println!("\n4. Probing the Daemon...");
+    let bin = dir.join("bin");
+    let installed = std::fs::create_dir_all(&bin)
+        .and_then(|_| std::fs::write(bin.join("fail2ban-client"), FAKE_CLIENT))
+        .and_then(|_| std::fs::set_permissions(bin.join("fail2ban-client"), std::fs::Permissions::from_mode(0o755)));
+    report(installed.is_ok(), "fake fail2ban-client installed");
+    std::env::set_var("PATH", format!("{}:{}", bin.display(), std::env::var("PATH").unwrap_or_default()));
+    
+    let client = Fail2banClient::new();
+    report(client.ping().is_ok(), "ping answered");
+    let daemon = DaemonInfo::query(&client);
+    report(daemon.version.as_deref() == Some("1.0.2"), "version read");
+    report(daemon.dbfile.as_deref() == Some("/var/lib/fail2ban/fail2ban.sqlite3"), "dbfile read");
+    report(daemon.dbpurgeage.as_deref() == Some("86400seconds"), "dbpurgeage read");
+    report(daemon.loglevel.as_deref() == Some("NOTICE"), "loglevel read");
+    report(daemon.logtarget.as_deref() == Some("/var/log/fail2ban.log"), "logtarget read");
+    
+    let _ = std::fs::write(bin.join("fail2ban-client"), "#!/bin/sh\necho 'Failed to access socket path: /tmp/x.sock. Is fail2ban running?' >&2\nexit 255\n");
+    match client.ping() {
+        Ok(_) => println!("   ✗ Dead socket answered ping"),
+        Err(e) => report(e.to_string().contains("Is fail2ban running?"), "dead socket reported with the client's reason"),
+    }
+    
+    let _ = std::fs::remove_dir_all(&dir);
+    println!("\n=== Service Health Test Complete ===");
+}
+
+fn error_entry(timestamp: chrono::DateTime<chrono::Utc>) -> LogEntry {
+    LogEntry {
+        timestamp,
+        level: "ERROR".to_string(),
+        message: "fail2ban.actions [812]: ERROR Failed to execute ban jail 'sshd'".to_string(),
+        jail: Some("sshd".to_string()),
+    }
+}
+
+/// A log larger than the scanned tail, with multi-byte text around the cut
+fn write_log(path: &std::path::Path) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    for i in 0..6000 {
+        writeln!(file, "2025-07-19 16:16:35,393 fail2ban.filter  [812]: INFO    [sshd] Found 203.0.113.{} - ünïcödé", i % 250)?;
+    }
+    for i in 1..=20 {
+        writeln!(file, "2025-07-19 16:17:{:02},000 fail2ban.actions [812]: ERROR   Failed to execute ban: error {}", i, i)?;
+        writeln!(file, "2025-07-19 16:17:{:02},500 fail2ban.filter  [812]: INFO    [sshd] Found 198.51.100.{}", i, i)?;
+    }
+    Ok(())
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,157 @@
+use std::os::unix::fs::PermissionsExt;
+
+use chrono::{DateTime, Duration, Timelike, Utc};
+
+use f2b_buxjr::app::{BannedIP, LogEntry};
+use f2b_buxjr::services::event_history::{log_clock_now, EventHistory, MAX_BAN_RECORDS};
+use f2b_buxjr::services::statistics::{export_table, network_prefix, BanStatistics, StatsTable, TOP_ROWS};
+
+fn main() {
+    println!("=== f2b-buxjr Statistics Test ===");
+    println!();
+    
+    let now = log_clock_now();
+    
+    // Ban records from the log
+    println!("1. Remembering Banned Addresses...");
+    let mut history = EventHistory::new();
+    history.record(&[
+        ban(now - Duration::minutes(50), "sshd", "203.0.113.7"),
+        ban(now - Duration::minutes(40), "sshd", "203.0.113.7"),
+        ban(now - Duration::minutes(30), "postfix", "203.0.113.7"),
+        ban(now - Duration::minutes(20), "sshd", "203.0.113.8"),
+        ban(now - Duration::minutes(10), "sshd", "2001:db8:1:2::5"),
+        entry(now - Duration::minutes(5), "fail2ban.actions [812]: NOTICE  [sshd] Unban 203.0.113.8"),
+        entry(now - Duration::minutes(5), "fail2ban.filter  [812]: INFO    [postfix] Found 198.51.100.4 - 2025-07-19"),
+        ban(now - Duration::hours(30), "sshd", "192.0.2.1"),
+    ]);
+    report(history.ban_record_count() == 6, "one record per Ban line");
+    report(history.ban_records(24, now).count() == 5, "window limits the records");
+    report(history.totals(1, now).bans == 5, "bans still counted in the buckets");
+    history.prune(now + Duration::hours(24 * 7));
+    report(history.ban_record_count() == 0, "records older than a week dropped");
+    
+    let mut crowded = EventHistory::new();
+    for i in 0..(MAX_BAN_RECORDS + 10) {
+        crowded.record_ban("sshd", &format!("10.0.{}.{}", i / 256 % 256, i % 256), now - Duration::seconds((MAX_BAN_RECORDS + 10 - i) as i64));
+    }
+    crowded.prune(now);
+    report(crowded.ban_record_count() == MAX_BAN_RECORDS, "record count capped");
+    
+    // Aggregation
+    println!("\n2. Aggregating Offenders...");
+    let mut history = EventHistory::new();
+    history.record(&[
+        ban(now - Duration::minutes(50), "sshd", "203.0.113.7"),
+        ban(now - Duration::minutes(40), "sshd", "203.0.113.7"),
+        ban(now - Duration::minutes(30), "postfix", "203.0.113.7"),
+        ban(now - Duration::minutes(20), "sshd", "203.0.113.8"),
+        ban(now - Duration::minutes(15), "sshd", "198.51.100.9"),
+        ban(now - Duration::minutes(14), "sshd", "198.51.100.9"),
+        ban(now - Duration::minutes(10), "sshd", "2001:db8:1:2::5"),
+        entry(now - Duration::minutes(5), "fail2ban.actions [812]: NOTICE  [sshd] Unban 203.0.113.8"),
+        entry(now - Duration::minutes(5), "fail2ban.filter  [812]: INFO    [postfix] Found 198.51.100.4 - 2025-07-19"),
+    ]);
+    let banned = vec![
+        current("203.0.113.8", "sshd", Utc::now() - Duration::minutes(20)),  // Same ban as in the log
+        current("192.0.2.50", "recidive", Utc::now() - Duration::hours(2)),  // From before the log
+        current("192.0.2.51", "recidive", Utc::now() - Duration::days(3)),   // Outside the window
+    ];
+    let stats = BanStatistics::collect(&banned, &history, 24, now);
+    report(stats.total_bans() == 8, "log bans plus current bans missing from the log");
+    report(stats.offender("203.0.113.8").is_some_and(|o| o.bans == 1), "current ban seen in the log counted once");
+    report(stats.offender("192.0.2.51").is_none(), "current ban outside the window left out");
+    report(stats.offender("203.0.113.7").is_some_and(|o| o.jails.len() == 2 && o.last - o.first == Duration::minutes(20)), "jails and first/last ban per address");
+    
+    let top = stats.table(StatsTable::TopIps);
+    report(top.headers == vec!["IP", "Bans", "Jails", "Last Ban"], "top IP columns");
+    report(top.rows[0][0] == "203.0.113.7" && top.rows[0][1] == "3" && top.rows[0][2] == "postfix sshd", "most banned address first");
+    report(top.rows[1][0] == "198.51.100.9" && top.ips[1].as_deref() == Some("198.51.100.9"), "rows drill down to their address");
+    
+    let prefixes = stats.table(StatsTable::Prefixes);
+    report(prefixes.rows[0][0] == "203.0.113.0/24" && prefixes.rows[0][1] == "4" && prefixes.rows[0][2] == "2", "addresses grouped by /24");
+    report(prefixes.ips[0].as_deref() == Some("203.0.113.7"), "prefix drills down to its busiest address");
+    report(prefixes.rows.iter().any(|row| row[0] == "2001:db8:1:2::/64"), "IPv6 grouped by /64");
+    report(network_prefix("not an address").is_none(), "unparsable address has no prefix");
+    
+    let jails = stats.table(StatsTable::Jails);
+    report(jails.rows[0] == vec!["sshd", "6", "4", "1", "1", "0"], "busiest jail first with addresses, current bans, unbans");
+    report(jails.rows.iter().any(|row| row[0] == "postfix" && row[5] == "1"), "failures per jail");
+    report(jails.rows.iter().any(|row| row[0] == "recidive" && row[1] == "1"), "jail known only from current bans");
+    report(jails.ips.iter().all(|ip| ip.is_none()), "jail rows have no address");
+    
+    let hours = stats.table(StatsTable::Hours);
+    let hour = (now - Duration::minutes(14)).hour() as usize;
+    report(hours.rows.len() == 24 && hours.rows[0][0] == "00:00", "one row per hour of day");
+    report(hours.rows.iter().map(|row| row[1].parse::<u64>().unwrap_or(0)).sum::<u64>() == 8, "hours add up to the bans");
+    report(hours.rows[hour][1] != "0", "bans land in their hour");
+    
+    let repeat = stats.table(StatsTable::RepeatOffenders);
+    report(repeat.rows.len() == 2, "only addresses banned more than once");
+    report(repeat.rows[0][0] == "203.0.113.7", "banned in several jails first");
+    
+    let mut many = EventHistory::new();
+    for i in 0..(TOP_ROWS + 5) {
+        many.record_ban("sshd", &format!("10.{}.0.1", i), now - Duration::minutes(1));
+    }
+    let stats_many = BanStatistics::collect(&[], &many, 1, now);
+    report(stats_many.table(StatsTable::TopIps).rows.len() == TOP_ROWS, "top IPs limited");
+    report(stats_many.table(StatsTable::Prefixes).rows.len() == TOP_ROWS, "top prefixes limited");
+    
+    // Tables and export
+    println!("\n3. Switching and Exporting Tables...");
+    let mut visited = vec![StatsTable::TopIps];
+    while visited.len() <= StatsTable::ALL.len() {
+        visited.push(visited[visited.len() - 1].next());
+    }
+    report(visited[..StatsTable::ALL.len()] == StatsTable::ALL, "Tab cycles through all tables");
+    report(visited[StatsTable::ALL.len()] == StatsTable::TopIps, "cycle wraps around");
+    
+    let dir = std::env::temp_dir().join(format!("f2b-buxjr-statistics-{}", std::process::id()));
+    let _ = std::fs::create_dir_all(&dir);
+    let path = dir.join(format!("f2b-buxjr-{}.csv", StatsTable::TopIps.slug()));
+    match export_table(&top, &path) {
+        Ok(()) => {
+            let content = std::fs::read_to_string(&path).unwrap_or_default();
+            report(content.starts_with("IP,Bans,Jails,Last Ban\n203.0.113.7,3,postfix sshd,"), "CSV header and rows");
+            report(content.lines().count() == top.rows.len() + 1, "every row exported");
+            report(std::fs::metadata(&path).is_ok_and(|m| m.permissions().mode() & 0o777 == 0o600), "export is 0600");
+        },
+        Err(e) => println!("   ✗ Export failed: {}", e),
+    }
+    report(export_table(&top, &path).is_err(), "existing file not overwritten");
+    let _ = std::fs::remove_dir_all(&dir);
+    
+    println!("\n=== Statistics Test Complete ===");
+}
+
+fn entry(timestamp: DateTime<Utc>, message: &str) -> LogEntry {
+    LogEntry {
+        timestamp,
+        level: "NOTICE".to_string(),
+        message: message.to_string(),
+        jail: None,
+    }
+}
+
+fn ban(timestamp: DateTime<Utc>, jail: &str, ip: &str) -> LogEntry {
+    entry(timestamp, &format!("fail2ban.actions [812]: NOTICE  [{}] Ban {}", jail, ip))
+}
+
+fn current(ip: &str, jail: &str, ban_time: DateTime<Utc>) -> BannedIP {
+    BannedIP {
+        ip: ip.to_string(),
+        jail: jail.to_string(),
+        ban_time,
+        unban_time: None,
+        reason: String::new(),
+    }
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,99 @@
+use std::net::IpAddr;
+
+use f2b_buxjr::app::BannedIP;
+use f2b_buxjr::services::subnets::{aggregate, next_prefix, plan_range_ban, whitelist_overlaps, IPV4_PREFIXES, IPV6_PREFIXES, SUGGEST_MIN_HOSTS};
+use f2b_buxjr::utils::cidr::Cidr;
+
+fn main() {
+    println!("=== f2b-buxjr Subnet Aggregation Test ===");
+    println!();
+    
+    // Ranges
+    println!("1. Parsing Ranges...");
+    let range = Cidr::parse("203.0.113.77/24");
+    report(range.is_some_and(|r| r.to_string() == "203.0.113.0/24"), "host bits cleared");
+    report(Cidr::parse("203.0.113.7").is_some_and(|r| r.is_host() && r.prefix_len() == 32), "bare IPv4 address is a /32");
+    report(Cidr::parse("2001:db8::1").is_some_and(|r| r.is_host() && r.prefix_len() == 128), "bare IPv6 address is a /128");
+    report(Cidr::parse("2001:db8:1:2:3::/48").is_some_and(|r| r.to_string() == "2001:db8:1::/48"), "IPv6 range");
+    report(Cidr::parse("10.0.0.0/33").is_none() && Cidr::parse("mail.example.com").is_none(), "invalid length and hostnames rejected");
+    report(range.is_some_and(|r| r.size() == 256), "range size");
+    report(Cidr::parse("::/0").is_some_and(|r| r.size() == u128::MAX), "size saturates for huge IPv6 ranges");
+    let inside: IpAddr = "203.0.113.200".parse().unwrap_or(IpAddr::from([0, 0, 0, 0]));
+    let outside: IpAddr = "203.0.114.1".parse().unwrap_or(IpAddr::from([0, 0, 0, 0]));
+    report(range.is_some_and(|r| r.contains(&inside) && !r.contains(&outside)), "contains");
+    let wide = Cidr::parse("203.0.0.0/16");
+    let other = Cidr::parse("198.51.100.0/24");
+    report(range.zip(wide).is_some_and(|(r, w)| r.overlaps(&w) && w.overlaps(&r)), "nested ranges overlap");
+    report(range.zip(other).is_some_and(|(r, o)| !r.overlaps(&o)), "disjoint ranges do not");
+    report(range.zip(Cidr::parse("::/0")).is_some_and(|(r, v6)| !r.overlaps(&v6)), "IPv4 and IPv6 never overlap");
+    
+    // Grouping
+    println!("\n2. Grouping Bans...");
+    let mut banned = Vec::new();
+    for host in 1..=6 {
+        banned.push(ban(&format!("203.0.113.{}", host), "sshd"));
+    }
+    banned.push(ban("203.0.113.1", "postfix"));
+    banned.push(ban("203.0.113.9", "postfix"));
+    banned.push(ban("198.51.100.4", "sshd"));
+    banned.push(ban("2001:db8:1:2::5", "sshd"));
+    banned.push(ban("2001:db8:1:2::6", "sshd"));
+    banned.push(ban("192.0.2.0/24", "recidive"));
+    banned.push(ban("192.0.2.7", "sshd"));
+    banned.push(ban("not-an-address", "sshd"));
+    
+    let groups = aggregate(&banned, 24, 64);
+    report(groups.len() == 4, "one group per range, unparsable entries skipped");
+    let busiest = &groups[0];
+    report(busiest.range.to_string() == "203.0.113.0/24" && busiest.host_count() == 7, "most banned hosts first, hosts counted once");
+    report(busiest.jails() == vec![("sshd".to_string(), 6), ("postfix".to_string(), 2)], "hosts per jail");
+    report((busiest.density() - 7.0 / 256.0).abs() < 1e-9, "density is the banned share of the range");
+    report(busiest.is_suggested() && SUGGEST_MIN_HOSTS == 5, "dense range suggested");
+    report(groups.iter().any(|g| g.range.to_string() == "2001:db8:1:2::/64" && g.host_count() == 2), "IPv6 grouped by /64");
+    
+    let covered = groups.iter().find(|g| g.range.to_string() == "192.0.2.0/24");
+    report(covered.is_some_and(|g| g.banned_as_range_in() == vec!["recidive".to_string()] && !g.is_suggested()), "existing range ban shown, not suggested again");
+    report(aggregate(&banned, 28, 64).iter().all(|g| g.range.to_string() != "192.0.2.0/28" || g.range_bans.is_empty()), "wider range bans left out of narrower groups");
+    report(aggregate(&banned, 16, 32).iter().any(|g| g.range.to_string() == "203.0.0.0/16" && g.host_count() == 7), "other prefix lengths");
+    
+    report(next_prefix(&IPV4_PREFIXES, 24) == 28 && next_prefix(&IPV4_PREFIXES, 28) == 16, "IPv4 prefix cycles");
+    report(next_prefix(&IPV6_PREFIXES, 64) == 32 && next_prefix(&IPV6_PREFIXES, 7) == 32, "IPv6 prefix cycles, unknown restarts");
+    
+    // Replacing host bans
+    println!("\n3. Planning Range Bans...");
+    let whitelist = vec!["127.0.0.1/8".to_string(), "::1".to_string(), "gateway.example.com".to_string()];
+    match plan_range_ban(busiest, "sshd", &whitelist) {
+        Ok(plan) => {
+            report(plan.range.to_string() == "203.0.113.0/24" && plan.jail == "sshd", "range and jail");
+            report(plan.replaces.len() == 6 && !plan.replaces.contains(&"203.0.113.9".to_string()), "only host bans in the chosen jail replaced");
+        },
+        Err(e) => println!("   ✗ Plan refused: {}", e),
+    }
+    report(plan_range_ban(busiest, "postfix", &whitelist).is_ok_and(|plan| plan.replaces.len() == 2), "other jail replaces its own hosts");
+    
+    let whitelist = vec!["127.0.0.1".to_string(), "203.0.113.50".to_string()];
+    report(whitelist_overlaps(&busiest.range, &whitelist) == vec!["203.0.113.50".to_string()], "whitelisted host inside the range found");
+    report(plan_range_ban(busiest, "sshd", &whitelist).is_err_and(|e| e.contains("203.0.113.50")), "range ban refused over the whitelist");
+    report(whitelist_overlaps(&busiest.range, &["203.0.0.0/16".to_string()]).len() == 1, "wider whitelist range found");
+    report(whitelist_overlaps(&busiest.range, &["127.0.0.1/8 ::1".to_string()]).is_empty(), "unrelated entries ignored");
+    
+    println!("\n=== Subnet Aggregation Test Complete ===");
+}
+
+fn ban(ip: &str, jail: &str) -> BannedIP {
+    BannedIP {
+        ip: ip.to_string(),
+        jail: jail.to_string(),
+        ban_time: chrono::Utc::now(),
+        unban_time: None,
+        reason: String::new(),
+    }
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
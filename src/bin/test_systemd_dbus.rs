@@ -0,0 +1,279 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use f2b_buxjr::app::{ServiceAction, ServiceStatus};
+use f2b_buxjr::services::system_service::SystemService;
+use f2b_buxjr::services::service_backend::{format_uptime, JobState};
+use f2b_buxjr::services::systemd_dbus::SystemdController;
+use zbus::blocking::{Connection, ConnectionBuilder};
+use zbus::dbus_interface;
+use zbus::zvariant::OwnedObjectPath;
+
+// Run under a private session bus: dbus-run-session -- cargo run --bin test_systemd_dbus
+const BUS_NAME: &str = "org.freedesktop.systemd1";
+const UNIT_PATH: &str = "/org/freedesktop/systemd1/unit/fail2ban_2eservice";
+
+/// Stand-in for the state systemd keeps about fail2ban.service
+struct FakeUnit {
+    active_state: String,
+    sub_state: String,
+    main_pid: u32,
+    active_enter: u64,
+    restarts: u32,
+    result: String,
+    exit_status: i32,
+    fail_next_start: bool,
+    jobs: Vec<FakeJob>,
+    next_job: u32,
+}
+
+struct FakeJob {
+    id: u32,
+    kind: &'static str,
+    polls_left: u32,  // ListJobs calls before the job finishes
+}
+
+impl FakeUnit {
+    fn running() -> Self {
+        Self {
+            active_state: "active".to_string(),
+            sub_state: "running".to_string(),
+            main_pid: 812,
+            active_enter: micros_ago(3 * 3600 + 12 * 60),
+            restarts: 0,
+            result: "success".to_string(),
+            exit_status: 0,
+            fail_next_start: false,
+            jobs: Vec::new(),
+            next_job: 1,
+        }
+    }
+    
+    fn finish(&mut self, kind: &str) {
+        match kind {
+            "stop" => self.set("inactive", "dead", 0),
+            "reload" => {},
+            _ if self.fail_next_start => {
+                self.fail_next_start = false;
+                self.set("failed", "failed", 0);
+                self.result = "exit-code".to_string();
+                self.exit_status = 255;
+            },
+            _ => {
+                self.set("active", "running", 4242);
+                self.active_enter = micros_ago(0);
+                self.result = "success".to_string();
+                self.exit_status = 0;
+            },
+        }
+    }
+    
+    fn set(&mut self, active: &str, sub: &str, pid: u32) {
+        self.active_state = active.to_string();
+        self.sub_state = sub.to_string();
+        self.main_pid = pid;
+    }
+}
+
+fn micros_ago(seconds: i64) -> u64 {
+    (chrono::Local::now() - chrono::Duration::seconds(seconds)).timestamp_micros() as u64
+}
+
+fn object_path(path: &str) -> OwnedObjectPath {
+    OwnedObjectPath::try_from(path.to_string()).expect("valid object path")
+}
+
+struct Manager(Arc<Mutex<FakeUnit>>);
+
+impl Manager {
+    fn queue(&self, name: &str, kind: &'static str) -> zbus::fdo::Result<OwnedObjectPath> {
+        if name != "fail2ban.service" {
+            return Err(zbus::fdo::Error::Failed(format!("Unit {} not found.", name)));
+        }
+        let mut unit = self.0.lock().unwrap();
+        let id = unit.next_job;
+        unit.next_job += 1;
+        unit.jobs.push(FakeJob { id, kind, polls_left: 2 });
+        Ok(object_path(&format!("/org/freedesktop/systemd1/job/{}", id)))
+    }
+}
+
+#[dbus_interface(name = "org.freedesktop.systemd1.Manager")]
+impl Manager {
+    fn load_unit(&self, name: &str) -> zbus::fdo::Result<OwnedObjectPath> {
+        if name != "fail2ban.service" {
+            return Err(zbus::fdo::Error::Failed(format!("Unit {} not found.", name)));
+        }
+        Ok(object_path(UNIT_PATH))
+    }
+    
+    fn start_unit(&self, name: &str, _mode: &str) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.queue(name, "start")
+    }
+    
+    fn stop_unit(&self, name: &str, _mode: &str) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.queue(name, "stop")
+    }
+    
+    fn restart_unit(&self, name: &str, _mode: &str) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.queue(name, "restart")
+    }
+    
+    fn reload_unit(&self, name: &str, _mode: &str) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.queue(name, "reload")
+    }
+    
+    /// Every call moves each job one step: waiting, then running, then gone
+    fn list_jobs(&self) -> Vec<(u32, String, String, String, OwnedObjectPath, OwnedObjectPath)> {
+        let mut unit = self.0.lock().unwrap();
+        let finished: Vec<_> = unit.jobs.iter().filter(|job| job.polls_left == 0).map(|job| job.kind).collect();
+        unit.jobs.retain(|job| job.polls_left > 0);
+        for kind in finished {
+            unit.finish(kind);
+        }
+        
+        let mut listing = Vec::new();
+        for job in unit.jobs.iter_mut() {
+            let state = if job.polls_left == 2 { "waiting" } else { "running" };
+            job.polls_left -= 1;
+            listing.push((
+                job.id,
+                "fail2ban.service".to_string(),
+                job.kind.to_string(),
+                state.to_string(),
+                object_path(&format!("/org/freedesktop/systemd1/job/{}", job.id)),
+                object_path(UNIT_PATH),
+            ));
+        }
+        listing
+    }
+}
+
+struct Unit(Arc<Mutex<FakeUnit>>);
+
+#[dbus_interface(name = "org.freedesktop.systemd1.Unit")]
+impl Unit {
+    #[dbus_interface(property)]
+    fn active_state(&self) -> String {
+        self.0.lock().unwrap().active_state.clone()
+    }
+    
+    #[dbus_interface(property)]
+    fn sub_state(&self) -> String {
+        self.0.lock().unwrap().sub_state.clone()
+    }
+    
+    #[dbus_interface(property)]
+    fn active_enter_timestamp(&self) -> u64 {
+        self.0.lock().unwrap().active_enter
+    }
+}
+
+struct Service(Arc<Mutex<FakeUnit>>);
+
+#[dbus_interface(name = "org.freedesktop.systemd1.Service")]
+impl Service {
+    #[dbus_interface(property, name = "MainPID")]
+    fn main_pid(&self) -> u32 {
+        self.0.lock().unwrap().main_pid
+    }
+    
+    #[dbus_interface(property)]
+    fn memory_current(&self) -> u64 {
+        25_270_272
+    }
+    
+    #[dbus_interface(property, name = "NRestarts")]
+    fn n_restarts(&self) -> u32 {
+        self.0.lock().unwrap().restarts
+    }
+    
+    #[dbus_interface(property)]
+    fn result(&self) -> String {
+        self.0.lock().unwrap().result.clone()
+    }
+    
+    #[dbus_interface(property)]
+    fn exec_main_status(&self) -> i32 {
+        self.0.lock().unwrap().exit_status
+    }
+}
+
+fn serve(unit: &Arc<Mutex<FakeUnit>>) -> zbus::Result<Connection> {
+    ConnectionBuilder::session()?
+        .name(BUS_NAME)?
+        .serve_at("/org/freedesktop/systemd1", Manager(unit.clone()))?
+        .serve_at(UNIT_PATH, Unit(unit.clone()))?
+        .serve_at(UNIT_PATH, Service(unit.clone()))?
+        .build()
+}
+
+fn main() {
+    println!("=== f2b-buxjr systemd D-Bus Test ===");
+    println!();
+    
+    let unit = Arc::new(Mutex::new(FakeUnit::running()));
+    let (_server, client) = match serve(&unit).and_then(|server| Ok((server, Connection::session()?))) {
+        Ok(connections) => connections,
+        Err(e) => {
+            println!("   ⚠ No session bus ({}) - run under dbus-run-session; skipping", e);
+            return;
+        }
+    };
+    let service = SystemService::with_controller(SystemdController::with_connection(client.clone(), "fail2ban"));
+    
+    // Unit state
+    println!("1. Reading Unit State...");
+    match service.unit_state() {
+        Ok(state) => {
+            report(matches!(state.service_status(), ServiceStatus::Running), "active unit maps to Running");
+            report(state.main_pid == 812 && state.sub_state == "running", "main PID and sub-state read");
+            report(state.uptime().is_some_and(|up| up >= Duration::from_secs(3 * 3600)), "uptime from ActiveEnterTimestamp");
+            report(state.summary() == "PID 812, up 3h 12m, 24.1 MB, 0 restarts", "summary lists PID, uptime, memory and restarts");
+            report(state.failure.is_none(), "no failure while the last run succeeded");
+        },
+        Err(e) => println!("   ✗ Failed to read unit state: {}", e),
+    }
+    report(format_uptime(Duration::from_secs(2 * 86400 + 5 * 3600)) == "2d 5h", "uptime in days and hours");
+    let missing = SystemService::with_controller(SystemdController::with_connection(client, "nonexistent"));
+    report(missing.unit_state().is_err(), "unknown unit reported as an error");
+    
+    // Job progress
+    println!("\n2. Following a Job...");
+    match service.submit(&ServiceAction::Stop) {
+        Ok(job) => {
+            let states: Vec<_> = (0..3).filter_map(|_| service.job_state(&job).ok()).collect();
+            report(states == [JobState::Waiting, JobState::Running, JobState::Done], "job goes waiting, running, done");
+            let stopped = service.unit_state().map(|s| s.satisfies(&ServiceAction::Stop) && matches!(s.service_status(), ServiceStatus::Stopped));
+            report(stopped.unwrap_or(false), "unit stopped once the job is done");
+        },
+        Err(e) => println!("   ✗ Failed to submit stop job: {}", e),
+    }
+    
+    // Running to completion
+    println!("\n3. Running Actions...");
+    let started = service.run(&ServiceAction::Start, Duration::from_secs(5));
+    report(started.as_ref().is_ok_and(|s| s.main_pid == 4242), "start runs to completion");
+    report(service.run(&ServiceAction::Reload, Duration::from_secs(5)).is_ok(), "reload keeps the unit active");
+    
+    unit.lock().unwrap().fail_next_start = true;
+    match service.run(&ServiceAction::Restart, Duration::from_secs(5)) {
+        Ok(_) => println!("   ✗ Failed restart reported as success"),
+        Err(e) => report(e.to_string().contains("exit-code (exit status 255)"), "failed restart reports the unit's result"),
+    }
+    let failed = service.unit_state();
+    report(failed.as_ref().is_ok_and(|s| matches!(s.service_status(), ServiceStatus::Failed) && s.failure.is_some()), "failed unit keeps its failure reason");
+    
+    unit.lock().unwrap().jobs.push(FakeJob { id: 99, kind: "start", polls_left: u32::MAX });
+    report(service.run(&ServiceAction::Start, Duration::from_millis(300)).is_ok(), "jobs for other units do not block completion");
+    
+    println!("\n=== systemd D-Bus Test Complete ===");
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
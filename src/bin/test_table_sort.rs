@@ -0,0 +1,123 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+
+use f2b_buxjr::app::{BannedIP, JailConfig, JailState};
+use f2b_buxjr::services::table_sort::{compare_ips, sort_banned_ips, sort_jails, BannedIpColumn, JailColumn, SortOrder};
+
+fn main() {
+    println!("=== f2b-buxjr Table Sort Test ===");
+    println!();
+    
+    // Address order
+    println!("1. Ordering Addresses...");
+    report(compare_ips("10.0.0.2", "10.0.0.100") == Ordering::Less, "10.0.0.2 before 10.0.0.100");
+    report(compare_ips("9.9.9.9", "10.0.0.1") == Ordering::Less, "numeric, not by text");
+    report(compare_ips("255.255.255.255", "::1") == Ordering::Less, "IPv4 before IPv6");
+    report(compare_ips("2001:db8::2", "2001:db8::10") == Ordering::Less, "IPv6 numeric");
+    report(compare_ips("203.0.113.0", "203.0.113.0/24") == Ordering::Less && compare_ips("203.0.113.0/24", "203.0.113.1") == Ordering::Less, "range right after its network address");
+    report(compare_ips("2001:db8::1", "mail.example.com") == Ordering::Less, "hostnames last");
+    
+    // Banned IPs
+    println!("\n2. Sorting Banned IPs...");
+    let now = Utc::now();
+    let ban = |ip: &str, jail: &str, age_hours: i64, remaining_hours: Option<i64>| BannedIP {
+        ip: ip.to_string(),
+        jail: jail.to_string(),
+        ban_time: now - Duration::hours(age_hours),
+        unban_time: remaining_hours.map(|hours| now + Duration::hours(hours)),
+        reason: String::new(),
+    };
+    let mut ips = vec![
+        ban("10.0.0.100", "sshd", 1, Some(5)),
+        ban("2001:db8::1", "nginx", 4, Some(1)),
+        ban("10.0.0.2", "sshd", 3, None),
+        ban("9.9.9.9", "nginx", 2, Some(9)),
+        ban("10.0.0.2", "nginx", 5, Some(2)),
+    ];
+    let order = |ips: &[BannedIP]| ips.iter().map(|b| format!("{}@{}", b.ip, b.jail)).collect::<Vec<_>>().join(" ");
+    let by = |column: BannedIpColumn, descending: bool| SortOrder { column, descending };
+    
+    sort_banned_ips(&mut ips, SortOrder::default());
+    report(order(&ips) == "9.9.9.9@nginx 10.0.0.2@nginx 10.0.0.2@sshd 10.0.0.100@sshd 2001:db8::1@nginx", "by address, then jail, by default");
+    sort_banned_ips(&mut ips, by(BannedIpColumn::Ip, true));
+    report(order(&ips) == "2001:db8::1@nginx 10.0.0.100@sshd 10.0.0.2@sshd 10.0.0.2@nginx 9.9.9.9@nginx", "descending");
+    sort_banned_ips(&mut ips, by(BannedIpColumn::Jail, false));
+    report(order(&ips) == "9.9.9.9@nginx 10.0.0.2@nginx 2001:db8::1@nginx 10.0.0.2@sshd 10.0.0.100@sshd", "by jail, addresses within");
+    sort_banned_ips(&mut ips, by(BannedIpColumn::BannedAt, true));
+    report(order(&ips) == "10.0.0.100@sshd 9.9.9.9@nginx 10.0.0.2@sshd 2001:db8::1@nginx 10.0.0.2@nginx", "newest bans first");
+    sort_banned_ips(&mut ips, by(BannedIpColumn::Remaining, false));
+    report(order(&ips) == "2001:db8::1@nginx 10.0.0.2@nginx 10.0.0.100@sshd 9.9.9.9@nginx 10.0.0.2@sshd", "ending soonest first, permanent last");
+    ips.push(BannedIP { unban_time: Some(now - Duration::hours(1)), ..ban("192.0.2.1", "sshd", 1, None) });
+    sort_banned_ips(&mut ips, by(BannedIpColumn::Remaining, false));
+    report(ips.last().is_some_and(|b| b.ip == "192.0.2.1"), "unban time equal to the ban time is permanent");
+    
+    // Jails
+    println!("\n3. Sorting Jails...");
+    let jail = |name: &str, enabled: bool| JailConfig {
+        name: name.to_string(),
+        enabled,
+        filter: name.to_string(),
+        port: String::new(),
+        protocol: String::new(),
+        log_path: String::new(),
+        max_retry: 5,
+        find_time: "10m".to_string(),
+        ban_time: "1h".to_string(),
+        action: String::new(),
+    };
+    let mut jails = vec![jail("sshd", true), jail("apache-auth", false), jail("postfix", true), jail("nginx", true)];
+    let mut states = HashMap::new();
+    for (name, banned_count, failed_count) in [("sshd", 12, 3), ("postfix", 2, 40), ("nginx", 12, 0)] {
+        states.insert(name.to_string(), JailState {
+            name: name.to_string(),
+            enabled: true,
+            banned_count,
+            failed_count,
+            filter: name.to_string(),
+            action: String::new(),
+        });
+    }
+    let names = |jails: &[JailConfig]| jails.iter().map(|j| j.name.as_str()).collect::<Vec<_>>().join(" ");
+    let by = |column: JailColumn, descending: bool| SortOrder { column, descending };
+    
+    sort_jails(&mut jails, &states, SortOrder::default());
+    report(names(&jails) == "nginx postfix sshd apache-auth", "enabled first, then by name, by default");
+    sort_jails(&mut jails, &states, by(JailColumn::Name, false));
+    report(names(&jails) == "apache-auth nginx postfix sshd", "by name");
+    sort_jails(&mut jails, &states, by(JailColumn::Bans, true));
+    report(names(&jails) == "sshd nginx postfix apache-auth", "most bans first, ties by name reversed");
+    sort_jails(&mut jails, &states, by(JailColumn::Failed, true));
+    report(names(&jails) == "postfix sshd nginx apache-auth", "most failures first");
+    sort_jails(&mut jails, &states, by(JailColumn::Bans, false));
+    report(jails.first().is_some_and(|j| j.name == "apache-auth"), "stopped jails count as no bans");
+    
+    // Choosing the order
+    println!("\n4. Choosing Columns...");
+    let mut order = SortOrder::<BannedIpColumn>::default();
+    report(order.indicator(BannedIpColumn::Ip) == " ▲" && order.indicator(BannedIpColumn::Jail).is_empty(), "indicator on the sorted column only");
+    order.select(BannedIpColumn::Ip);
+    report(order.descending && order.indicator(BannedIpColumn::Ip) == " ▼", "same column again reverses");
+    order.select(BannedIpColumn::Remaining);
+    report(order.column == BannedIpColumn::Remaining && !order.descending, "another column starts ascending");
+    order.next_column(&BannedIpColumn::ALL);
+    report(order.column == BannedIpColumn::Ip, "next column wraps around");
+    order.reverse();
+    report(order.descending, "reverse flips the direction");
+    let mut jail_order = SortOrder::<JailColumn>::default();
+    for _ in 0..3 {
+        jail_order.next_column(&JailColumn::ALL);
+    }
+    report(jail_order.column == JailColumn::Failed, "jail columns in order");
+    
+    println!("\n=== Table Sort Test Complete ===");
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,94 @@
+use f2b_buxjr::utils::text_buffer::{Position, SearchMode, TextBuffer};
+
+fn main() {
+    println!("=== f2b-buxjr Text Buffer Test ===");
+    println!();
+    
+    let sample = "[sshd]\nenabled = false\nport = ssh\n";
+    
+    // Round trip keeps the content byte for byte
+    println!("1. Testing Round Trip...");
+    let buffer = TextBuffer::from_text(sample);
+    report(buffer.text() == sample, "text() returns the original content");
+    report(buffer.lines().len() == 4, "trailing newline kept as an empty last line");
+    
+    // Typing, undo and redo
+    println!("\n2. Testing Undo/Redo...");
+    let mut buffer = TextBuffer::from_text(sample);
+    buffer.move_lines(1, false);
+    buffer.move_end(false);
+    for _ in 0.."false".len() {
+        buffer.backspace();
+    }
+    for c in "true".chars() {
+        buffer.insert_char(c);
+    }
+    report(buffer.lines()[1] == "enabled = true", "edited line reads 'enabled = true'");
+    buffer.undo();
+    report(buffer.lines()[1] == "enabled = ", "undo removes the typed word in one step");
+    buffer.undo();
+    report(buffer.text() == sample, "second undo restores the deleted word");
+    buffer.redo();
+    buffer.redo();
+    report(buffer.lines()[1] == "enabled = true", "redo replays both steps");
+    
+    // Motions
+    println!("\n3. Testing Motions...");
+    let mut buffer = TextBuffer::from_text("    maxretry = 5\nbantime = 1h");
+    buffer.move_home(false);
+    report(buffer.cursor() == Position::new(0, 4), "Home goes to the indentation first");
+    buffer.move_home(false);
+    report(buffer.cursor() == Position::new(0, 0), "second Home goes to column 0");
+    buffer.move_home(false);
+    buffer.word_right(false);
+    report(buffer.cursor() == Position::new(0, 13), "word motion skips 'maxretry '");
+    buffer.move_end(false);
+    buffer.move_lines(1, false);
+    report(buffer.cursor() == Position::new(1, 12), "Down clamps the column to the shorter line");
+    buffer.move_right(false);
+    report(buffer.cursor() == Position::new(1, 12), "Right stops at the end of the buffer");
+    
+    // Selection with cut and paste
+    println!("\n4. Testing Selection, Cut and Paste...");
+    let mut buffer = TextBuffer::from_text("a\nb\nc");
+    buffer.move_lines(1, true);
+    buffer.move_lines(1, true);
+    let cut = buffer.cut();
+    report(cut.as_deref() == Some("a\nb\n"), "cut returns the two selected lines");
+    report(buffer.text() == "c", "selection removed from the buffer");
+    buffer.move_end(false);
+    buffer.insert_str("\nx\ny");
+    report(buffer.text() == "c\nx\ny" && buffer.cursor() == Position::new(2, 1), "multi-line paste leaves the cursor after it");
+    
+    // Search and regex replace
+    println!("\n5. Testing Search and Replace...");
+    let mut buffer = TextBuffer::from_text("[sshd]\nBantime = 10m\n[nginx]\nbantime = 1h");
+    buffer.open_search(SearchMode::Find);
+    if let Some(ref mut search) = buffer.search {
+        search.query = "bantime".to_string();
+    }
+    report(buffer.search_update() && buffer.cursor() == Position::new(1, 7), "lowercase query matches 'Bantime'");
+    report(buffer.search_next(true) && buffer.cursor() == Position::new(3, 7), "next match found on line 4");
+    buffer.close_search();
+    report(buffer.search_next(true) && buffer.cursor() == Position::new(1, 7), "F3 wraps around with the last query");
+    
+    buffer.open_search(SearchMode::Replace);
+    if let Some(ref mut search) = buffer.search {
+        search.query = r"(?i)bantime = (\d+)(\w)".to_string();
+        search.replacement = "bantime = ${1}${2}".to_string();
+    }
+    report(buffer.replace_all().ok() == Some(2), "regex replace hits both jails");
+    report(buffer.lines()[1] == "bantime = 10m", "capture groups expanded in the replacement");
+    buffer.undo();
+    report(buffer.lines()[1] == "Bantime = 10m", "replace all is undone in one step");
+    
+    println!("\n=== Text Buffer Test Complete ===");
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -0,0 +1,61 @@
+use f2b_buxjr::utils::text_diff::{diff_to_string, unified_diff, DiffLineKind};
+
+fn main() {
+    println!("=== f2b-buxjr Text Diff Test ===");
+    println!();
+    
+    let old: String = (1..=12).map(|n| format!("line {}\n", n)).collect();
+    let kinds = |text: &str, new: &str, context: usize| unified_diff(text, new, "a/jail.local", "b/jail.local", context)
+        .iter()
+        .map(|l| match l.kind {
+            DiffLineKind::Header => 'H',
+            DiffLineKind::Hunk => '@',
+            DiffLineKind::Context => ' ',
+            DiffLineKind::Added => '+',
+            DiffLineKind::Removed => '-',
+        })
+        .collect::<String>();
+    
+    // One change
+    println!("1. Diffing a Single Change...");
+    let new = old.replace("line 6\n", "line six\n");
+    let diff = unified_diff(&old, &new, "a/jail.local", "b/jail.local", 3);
+    report(diff.len() >= 2 && diff[0].text == "--- a/jail.local" && diff[1].text == "+++ b/jail.local", "file headers");
+    report(diff.get(2).is_some_and(|l| l.kind == DiffLineKind::Hunk && l.text == "@@ -3,7 +3,7 @@"), "hunk header with one-based start and length");
+    report(kinds(&old, &new, 3) == "HH@   -+   ", "three context lines on each side");
+    report(diff.iter().any(|l| l.text == "-line 6") && diff.iter().any(|l| l.text == "+line six") && diff.iter().any(|l| l.text == " line 5"), "lines prefixed by kind");
+    report(kinds(&old, &new, 0) == "HH@-+", "no context when none asked for");
+    
+    // Several changes
+    println!("\n2. Diffing Several Changes...");
+    let new = old.replace("line 2\n", "").replace("line 11\n", "line 11\nline 11b\n");
+    let diff = unified_diff(&old, &new, "a/jail.local", "b/jail.local", 1);
+    let hunks: Vec<&str> = diff.iter().filter(|l| l.kind == DiffLineKind::Hunk).map(|l| l.text.as_str()).collect();
+    report(hunks == ["@@ -1,3 +1,2 @@", "@@ -11,2 +10,3 @@"], "distant changes in separate hunks");
+    let close = old.replace("line 4\n", "line four\n").replace("line 7\n", "line seven\n");
+    report(kinds(&old, &close, 3).matches('@').count() == 1, "changes within the context joined into one hunk");
+    let start = unified_diff("", "[sshd]\nenabled = true\n", "a/new.local", "b/new.local", 3);
+    report(start.get(2).is_some_and(|l| l.text == "@@ -1,0 +1,2 @@") && start.len() == 5, "new file");
+    report(unified_diff("a\r\nb\r\n", "a\r\nc\r\n", "a", "b", 3).iter().all(|l| !l.text.ends_with('\r')), "line endings stripped");
+    
+    // Nothing to show
+    println!("\n3. Diffing Identical Text...");
+    report(unified_diff(&old, &old, "a/jail.local", "b/jail.local", 3).is_empty(), "no lines, not even headers");
+    report(diff_to_string(&[]).is_empty(), "renders as empty text");
+    
+    // Rendering
+    println!("\n4. Rendering as Text...");
+    let new = old.replace("line 12\n", "line 12\nline 13\n");
+    let text = diff_to_string(&unified_diff(&old, &new, "a/jail.local", "b/jail.local", 1));
+    report(text == "--- a/jail.local\n+++ b/jail.local\n@@ -12,1 +12,2 @@\n line 12\n+line 13", "one line per diff line");
+    
+    println!("\n=== Text Diff Test Complete ===");
+}
+
+fn report(ok: bool, what: &str) {
+    if ok {
+        println!("   ✓ {}", what);
+    } else {
+        println!("   ✗ {}", what);
+    }
+}
@@ -1,4 +1,5 @@
 use std::io;
+use std::path::PathBuf;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
@@ -8,13 +9,10 @@ use ratatui::{
     backend::CrosstermBackend,
     Terminal,
 };
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use anyhow::Result;
 
-mod app;
-mod services;
-mod utils;
-
+use f2b_buxjr::{app, services, utils};
 use app::App;
 
 #[derive(Parser)]
@@ -24,14 +22,91 @@ struct Cli {
     #[arg(short, long)]
     debug: bool,
     
-    /// Configuration file path
-    #[arg(short, long)]
+    /// f2b-buxjr settings file (notification rules, GeoIP, reverse DNS) [default: /etc/f2b-buxjr/config.toml]
+    #[arg(short, long, value_name = "FILE")]
     config: Option<String>,
+    
+    /// fail2ban configuration root; jail.local, filter.d, backups etc. are resolved from it
+    #[arg(long, value_name = "DIR", default_value = utils::paths::DEFAULT_ROOT)]
+    fail2ban_root: PathBuf,
+    
+    /// fail2ban log file (default: first of /var/log/fail2ban.log, /var/log/fail2ban/fail2ban.log)
+    #[arg(long, value_name = "FILE")]
+    log_file: Option<PathBuf>,
+    
+    /// fail2ban server socket, passed on to fail2ban-client
+    #[arg(long, value_name = "FILE")]
+    socket: Option<PathBuf>,
+    
+    /// Audit trail of administrative actions
+    #[arg(long, value_name = "FILE", default_value = services::audit_log::DEFAULT_AUDIT_LOG)]
+    audit_log: PathBuf,
+    
+    /// Init system used to start and stop fail2ban (auto, systemd, openrc, sysv, runit, direct)
+    #[arg(long, value_name = "MANAGER", default_value = "auto")]
+    service_manager: String,
+    
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Serve Prometheus metrics instead of starting the TUI
+    Exporter {
+        /// Address to listen on
+        #[arg(long, value_name = "ADDR", default_value = services::exporter::DEFAULT_LISTEN)]
+        listen: std::net::SocketAddr,
+    },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
     
+    let service_backend = match cli.service_manager.as_str() {
+        "auto" => None,
+        name => match services::service_backend::ServiceBackend::from_name(name) {
+            Some(backend) => Some(backend),
+            None => {
+                eprintln!("Unknown service manager: {} (expected auto, {})", name, services::service_backend::SERVICE_BACKEND_NAMES.join(", "));
+                std::process::exit(1);
+            }
+        },
+    };
+    
+    // A mistyped --fail2ban-root would otherwise look like an empty installation
+    if cli.fail2ban_root != std::path::Path::new(utils::paths::DEFAULT_ROOT) && !cli.fail2ban_root.is_dir() {
+        eprintln!("fail2ban root not found: {}", cli.fail2ban_root.display());
+        std::process::exit(1);
+    }
+    utils::paths::Fail2banPaths::new(Some(cli.fail2ban_root.clone()), cli.log_file.clone(), cli.socket.clone()).install();
+    
+    let settings_file = PathBuf::from(cli.config.clone().unwrap_or_else(|| utils::settings::DEFAULT_SETTINGS_FILE.to_string()));
+    let settings = match utils::settings::Settings::load(&settings_file) {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let notifier = services::notifications::Notifier::new(settings.notifications);
+    let rdns = services::reverse_dns::ReverseDns::new(settings.rdns);
+    let geoip = match services::geoip::GeoIp::open(&settings.geoip) {
+        Ok(geoip) => geoip,
+        Err(e) => {
+            eprintln!("GeoIP database: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let presets_file = settings_file.with_file_name(services::ban_query::PRESETS_FILE_NAME);
+    let filter_presets = match services::ban_query::QueryPresets::load(&presets_file) {
+        Ok(presets) => presets,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    
     // Initialize logging - redirect to file to avoid interfering with TUI
     let log_level = if cli.debug {
         log::LevelFilter::Debug
@@ -75,26 +150,48 @@ fn main() -> Result<()> {
         }
     }
     
+    let paths = utils::paths::fail2ban_paths();
+    log::info!("fail2ban root {}, log {}, socket {}", paths.root().display(), paths.log_file().display(),
+        paths.socket().map(|s| s.display().to_string()).unwrap_or_else(|| "default".to_string()));
+    
+    if let Some(Command::Exporter { listen }) = cli.command {
+        let listener = std::net::TcpListener::bind(listen)?;
+        eprintln!("Serving metrics on http://{}/metrics", listener.local_addr()?);
+        log::info!("Starting f2b-buxjr v{} exporter on {}", env!("CARGO_PKG_VERSION"), listen);
+        let log_monitor = services::file_monitor::LogMonitor::new(&paths.log_file().display().to_string());
+        services::exporter::Exporter::new(log_monitor, notifier).serve(listener)?;
+        return Ok(());
+    }
+    
     // Check privileges FIRST - before any other operations
-    match utils::privileges::check_privileges()? {
+    let privilege_status = utils::privileges::check_privileges()?;
+    let capabilities = utils::privileges::Capabilities::for_status(privilege_status, paths);
+    match privilege_status {
         utils::privileges::PrivilegeStatus::Root => {
             log::info!("Starting f2b-buxjr v{} as root - full functionality available", env!("CARGO_PKG_VERSION"));
         },
         utils::privileges::PrivilegeStatus::Sudo => {
             log::info!("Starting f2b-buxjr v{} with sudo access - full functionality available", env!("CARGO_PKG_VERSION"));
         },
+        utils::privileges::PrivilegeStatus::User if capabilities.can_observe() => {
+            log::info!("Starting f2b-buxjr v{} read-only - missing: {}", env!("CARGO_PKG_VERSION"), capabilities.missing().join(", "));
+        },
         utils::privileges::PrivilegeStatus::User => {
-            // Don't initialize ANY interface components - just show error and exit
+            // Nothing to observe either - don't initialize ANY interface components
             eprintln!();
             eprintln!("┌─────────────────────────────────────────────────────────────┐");
             eprintln!("│  🔒 PRIVILEGE ERROR: Root Access Required                   │");
             eprintln!("└─────────────────────────────────────────────────────────────┘");
             eprintln!();
             eprintln!("f2b-buxjr requires root privileges for fail2ban management:");
-            eprintln!("  • Read/write /etc/fail2ban/ configuration files");
+            eprintln!("  • Read/write {}/ configuration files", paths.root().display());
             eprintln!("  • Control fail2ban service via fail2ban-client");  
             eprintln!("  • Manage iptables/firewall rules");
             eprintln!();
+            eprintln!("Read-only mode needs access to either of:");
+            eprintln!("  • {} (read/write)", paths.socket_path().display());
+            eprintln!("  • {} (read)", paths.log_file().display());
+            eprintln!();
             eprintln!("Please run with elevated privileges:");
             eprintln!("  sudo {}", std::env::args().collect::<Vec<_>>().join(" "));
             eprintln!();
@@ -110,7 +207,16 @@ fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
     
     // Create app and run
-    let app = App::new(cli.config)?;
+    let mut app = App::new(cli.config)?;
+    app.set_capabilities(capabilities);
+    app.set_audit_log(services::audit_log::AuditLog::new(cli.audit_log));
+    app.set_notifier(notifier);
+    app.set_geoip(geoip);
+    app.set_rdns(rdns);
+    app.set_filter_presets(filter_presets);
+    if let Some(backend) = service_backend {
+        app.set_system_service(services::system_service::SystemService::with_backend(backend, "fail2ban"));
+    }
     let result = run_app(&mut terminal, app);
     
     // Restore terminal
@@ -140,7 +246,28 @@ fn run_app<B: ratatui::backend::Backend>(
         if app.handle_events()? {
             break;
         }
+        
+        // Hand the terminal to $VISUAL/$EDITOR and take it back afterwards
+        if let Some(request) = app.take_external_edit_request() {
+            suspend_terminal()?;
+            let result = utils::external_editor::edit_in_external_editor(&request.content, &request.file_name);
+            resume_terminal()?;
+            terminal.clear()?;
+            app.finish_external_edit(request, result);
+        }
     }
     
     Ok(())
 }
+
+fn suspend_terminal() -> Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show)?;
+    Ok(())
+}
+
+fn resume_terminal() -> Result<()> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    Ok(())
+}
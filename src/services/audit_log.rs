@@ -0,0 +1,239 @@
+use std::fs::{DirBuilder, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::errors::{AppError, Result};
+use crate::utils::text_diff::{diff_to_string, unified_diff};
+
+/// Root-only location; unlike the debug log it is never world-writable
+pub const DEFAULT_AUDIT_LOG: &str = "/var/log/f2b-buxjr/audit.log";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Ban,
+    Unban,
+    WhitelistAdd,
+    WhitelistRemove,
+    JailEnable,
+    JailDisable,
+    JailCreate,
+    ConfigSave,
+    ConfigBackup,
+    ServiceStart,
+    ServiceStop,
+    ServiceRestart,
+    ServiceReload,
+}
+
+impl AuditAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuditAction::Ban => "Ban",
+            AuditAction::Unban => "Unban",
+            AuditAction::WhitelistAdd => "Whitelist add",
+            AuditAction::WhitelistRemove => "Whitelist remove",
+            AuditAction::JailEnable => "Jail enable",
+            AuditAction::JailDisable => "Jail disable",
+            AuditAction::JailCreate => "Jail create",
+            AuditAction::ConfigSave => "Config save",
+            AuditAction::ConfigBackup => "Config backup",
+            AuditAction::ServiceStart => "Service start",
+            AuditAction::ServiceStop => "Service stop",
+            AuditAction::ServiceRestart => "Service restart",
+            AuditAction::ServiceReload => "Service reload",
+        }
+    }
+}
+
+/// One administrative action, stored as a JSON line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: chrono::DateTime<chrono::Local>,
+    pub user: String,           // The person behind sudo, not root
+    pub action: AuditAction,
+    pub target: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,   // Unified diff of a file edit, with hunk positions and context
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(action: AuditAction, target: impl Into<String>) -> Self {
+        Self {
+            timestamp: chrono::Local::now(),
+            user: real_user(),
+            action,
+            target: target.into(),
+            before: None,
+            after: None,
+            diff: None,
+            success: true,
+            error: None,
+        }
+    }
+    
+    pub fn change(mut self, before: impl Into<String>, after: impl Into<String>) -> Self {
+        self.before = Some(before.into());
+        self.after = Some(after.into());
+        self
+    }
+    
+    /// A file edit as the unified diff shown when it was reviewed
+    pub fn file_change(mut self, old: &str, new: &str) -> Self {
+        let diff = unified_diff(old, new, &format!("a{}", self.target), &format!("b{}", self.target), 3);
+        self.diff = Some(diff_to_string(&diff));
+        self
+    }
+    
+    pub fn outcome<T, E: std::fmt::Display>(mut self, result: &std::result::Result<T, E>) -> Self {
+        if let Err(e) = result {
+            self.success = false;
+            self.error = Some(e.to_string());
+        }
+        self
+    }
+    
+    pub fn failed(mut self, error: impl Into<String>) -> Self {
+        self.success = false;
+        self.error = Some(error.into());
+        self
+    }
+    
+    pub fn result_label(&self) -> String {
+        match self.error {
+            Some(ref error) if !self.success => format!("failed: {}", error),
+            _ => "ok".to_string(),
+        }
+    }
+}
+
+/// The login behind sudo (SUDO_USER), else the current user
+pub fn real_user() -> String {
+    if let Ok(user) = std::env::var("SUDO_USER") {
+        if !user.is_empty() {
+            return user;
+        }
+    }
+    let uid = nix::unistd::getuid();
+    nix::unistd::User::from_uid(uid).ok().flatten()
+        .map(|u| u.name)
+        .unwrap_or_else(|| uid.to_string())
+}
+
+/// Append-only audit trail.
+///
+/// Every entry is one JSON line written with O_APPEND in a single write, so
+/// concurrent instances never interleave. The directory is created 0700 and
+/// the file 0600; symlinks are refused so the path cannot be redirected.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_AUDIT_LOG)
+    }
+}
+
+impl AuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+    
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    
+    pub fn record(&self, entry: &AuditEntry) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            if !dir.as_os_str().is_empty() && !dir.exists() {
+                DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+            }
+        }
+        
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(0o600)
+            .custom_flags(nix::libc::O_NOFOLLOW)
+            .open(&self.path)?;
+        
+        // An existing file keeps its owner, but never stays world-accessible
+        let permissions = file.metadata()?.permissions();
+        if permissions.mode() & 0o077 != 0 {
+            file.set_permissions(std::fs::Permissions::from_mode(permissions.mode() & 0o7700))?;
+        }
+        
+        let mut line = serde_json::to_string(entry)
+            .map_err(|e| AppError::Internal(format!("Failed to encode audit entry: {}", e)))?;
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+    
+    /// All entries, oldest first; a missing log is empty and unreadable lines are skipped
+    pub fn read_entries(&self) -> Result<Vec<AuditEntry>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        
+        let mut entries = Vec::new();
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => log::warn!("Skipping malformed audit line {} in {}: {}", number + 1, self.path.display(), e),
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Write entries as CSV (0600, the trail may name internal hosts)
+pub fn export_csv(entries: &[AuditEntry], path: &Path) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    
+    let mut content = String::from("Timestamp,User,Action,Target,Before,After,Diff,Result\n");
+    for entry in entries {
+        let fields = [
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S %z").to_string(),
+            entry.user.clone(),
+            entry.action.label().to_string(),
+            entry.target.clone(),
+            entry.before.clone().unwrap_or_default(),
+            entry.after.clone().unwrap_or_default(),
+            entry.diff.clone().unwrap_or_default(),
+            entry.result_label(),
+        ];
+        content.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        content.push('\n');
+    }
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// Quote a CSV field when it needs it
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,464 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::app::BannedIP;
+use crate::services::geoip::GeoInfo;
+use crate::utils::cidr::Cidr;
+use crate::utils::errors::{AppError, ConfigError, Result};
+
+/// Field names, in the order completion offers them
+pub const FIELDS: [&str; 6] = ["jail", "ip", "age", "remaining", "country", "asn"];
+
+const KEYWORDS: [&str; 3] = ["AND", "OR", "NOT"];
+
+/// Presets file next to the settings file
+pub const PRESETS_FILE_NAME: &str = "filters.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+}
+
+impl Comparison {
+    fn holds<T: PartialOrd>(&self, value: T, limit: T) -> bool {
+        match self {
+            Comparison::Less => value < limit,
+            Comparison::LessOrEqual => value <= limit,
+            Comparison::Greater => value > limit,
+            Comparison::GreaterOrEqual => value >= limit,
+            Comparison::Equal => value == limit,
+        }
+    }
+}
+
+/// One `field:value` condition
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    IpRange(Cidr),                     // ip:203.0.113.0/24 or ip:203.0.113.7
+    IpPattern(String),                 // ip:203.0. (prefix) or ip:*.7 (wildcard)
+    Jail(String),                      // Exact, or with * wildcards
+    Age(Comparison, Duration),         // Time since the ban
+    Remaining(Comparison, Duration),   // Time until the unban; permanent bans never end
+    Permanent,
+    Country(String),
+    Asn(u32),
+    Text(String),                      // A bare word: part of the address or jail
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Term(Term),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+}
+
+/// A parsed filter such as `jail:sshd ip:203.0.113.0/24 age:<2h remaining:>1d`.
+/// Terms next to each other must all match; AND, OR, NOT (or !) and
+/// parentheses combine them, NOT binding tightest and OR loosest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BanQuery {
+    text: String,
+    expr: Expr,
+}
+
+impl BanQuery {
+    pub fn parse(text: &str) -> std::result::Result<Self, String> {
+        let tokens = tokenize(text);
+        if tokens.is_empty() {
+            return Err("empty filter".to_string());
+        }
+        let mut parser = Parser { tokens: &tokens, pos: 0 };
+        let expr = parser.or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("unexpected '{}'", token));
+        }
+        Ok(Self {
+            text: text.trim().to_string(),
+            expr,
+        })
+    }
+    
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    
+    /// Country and ASN terms need a GeoIP lookup per address
+    pub fn uses_geo(&self) -> bool {
+        fn walk(expr: &Expr) -> bool {
+            match expr {
+                Expr::Term(term) => matches!(term, Term::Country(_) | Term::Asn(_)),
+                Expr::Not(inner) => walk(inner),
+                Expr::And(parts) | Expr::Or(parts) => parts.iter().any(walk),
+            }
+        }
+        walk(&self.expr)
+    }
+    
+    /// `geo` is only asked for addresses that reach a country or ASN term
+    pub fn matches(&self, banned: &BannedIP, now: DateTime<Utc>, geo: &mut dyn FnMut(&str) -> GeoInfo) -> bool {
+        eval(&self.expr, banned, now, geo)
+    }
+}
+
+fn eval(expr: &Expr, banned: &BannedIP, now: DateTime<Utc>, geo: &mut dyn FnMut(&str) -> GeoInfo) -> bool {
+    match expr {
+        Expr::Term(term) => term_matches(term, banned, now, geo),
+        Expr::Not(inner) => !eval(inner, banned, now, geo),
+        Expr::And(parts) => parts.iter().all(|part| eval(part, banned, now, geo)),
+        Expr::Or(parts) => parts.iter().any(|part| eval(part, banned, now, geo)),
+    }
+}
+
+fn term_matches(term: &Term, banned: &BannedIP, now: DateTime<Utc>, geo: &mut dyn FnMut(&str) -> GeoInfo) -> bool {
+    match term {
+        Term::IpRange(range) => Cidr::parse(&banned.ip)
+            .is_some_and(|ban| ban.prefix_len() >= range.prefix_len() && range.contains(&ban.network())),
+        Term::IpPattern(pattern) => wildcard_match(pattern, &banned.ip),
+        Term::Jail(pattern) => wildcard_match(pattern, &banned.jail),
+        Term::Age(comparison, limit) => comparison.holds(now - banned.ban_time, *limit),
+        Term::Remaining(comparison, limit) => match unban_time(banned) {
+            Some(unban) => comparison.holds((unban - now).max(Duration::zero()), *limit),
+            None => matches!(comparison, Comparison::Greater | Comparison::GreaterOrEqual),
+        },
+        Term::Permanent => unban_time(banned).is_none(),
+        Term::Country(code) => geo(&banned.ip).country.is_some_and(|country| country.eq_ignore_ascii_case(code)),
+        Term::Asn(asn) => geo(&banned.ip).asn == Some(*asn),
+        Term::Text(text) => {
+            let text = text.to_lowercase();
+            banned.ip.to_lowercase().contains(&text) || banned.jail.to_lowercase().contains(&text)
+        },
+    }
+}
+
+/// None for permanent bans: no unban time, or one within a second of the ban
+pub fn unban_time(banned: &BannedIP) -> Option<DateTime<Utc>> {
+    banned.unban_time.filter(|unban| (unban.timestamp() - banned.ban_time.timestamp()).abs() > 1)
+}
+
+/// Case-insensitive match where * stands for any run of characters
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let text = text.to_lowercase();
+    let parts = pattern.split('*').collect::<Vec<_>>();
+    if parts.len() == 1 {
+        return text == pattern;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !text.starts_with(first) || text.len() < first.len() + last.len() || !text.ends_with(last) {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Words, with parentheses and a leading ! split off
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in text.split_whitespace() {
+        let mut word = word;
+        while let Some(rest) = word.strip_prefix('(').or_else(|| word.strip_prefix('!')) {
+            tokens.push(word[..1].to_string());
+            word = rest;
+        }
+        let mut closing = 0;
+        while word.ends_with(')') {
+            word = &word[..word.len() - 1];
+            closing += 1;
+        }
+        if !word.is_empty() {
+            tokens.push(word.to_string());
+        }
+        tokens.extend(std::iter::repeat(")".to_string()).take(closing));
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: &'a [String],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+    
+    fn keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|token| token.eq_ignore_ascii_case(keyword))
+    }
+    
+    fn or(&mut self) -> std::result::Result<Expr, String> {
+        let mut parts = vec![self.and()?];
+        while self.keyword("OR") {
+            self.pos += 1;
+            parts.push(self.and()?);
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { Expr::Or(parts) })
+    }
+    
+    fn and(&mut self) -> std::result::Result<Expr, String> {
+        let mut parts = vec![self.unary()?];
+        loop {
+            if self.keyword("AND") {
+                self.pos += 1;
+            } else if self.peek().map_or(true, |token| token == ")" || token.eq_ignore_ascii_case("OR")) {
+                break;
+            }
+            parts.push(self.unary()?);
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { Expr::And(parts) })
+    }
+    
+    fn unary(&mut self) -> std::result::Result<Expr, String> {
+        let Some(token) = self.peek().map(str::to_string) else {
+            return Err("filter ends too early".to_string());
+        };
+        self.pos += 1;
+        if token == "!" || token.eq_ignore_ascii_case("NOT") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if token == "(" {
+            let inner = self.or()?;
+            if self.peek() != Some(")") {
+                return Err("missing ')'".to_string());
+            }
+            self.pos += 1;
+            return Ok(inner);
+        }
+        if token == ")" || token.eq_ignore_ascii_case("AND") || token.eq_ignore_ascii_case("OR") {
+            return Err(format!("unexpected '{}'", token));
+        }
+        parse_term(&token).map(Expr::Term)
+    }
+}
+
+fn parse_term(token: &str) -> std::result::Result<Term, String> {
+    // A bare address or range, which for IPv6 would otherwise look like field:value
+    if let Some(range) = Cidr::parse(token) {
+        return Ok(Term::IpRange(range));
+    }
+    let Some((field, value)) = token.split_once(':').filter(|(field, _)| field.chars().all(|c| c.is_ascii_alphabetic())) else {
+        return Ok(Term::Text(token.to_string()));
+    };
+    if value.is_empty() {
+        return Err(format!("{}: needs a value", field));
+    }
+    match field.to_lowercase().as_str() {
+        "ip" => Ok(match Cidr::parse(value) {
+            Some(range) if !value.contains('*') => Term::IpRange(range),
+            _ if value.contains('*') => Term::IpPattern(value.to_string()),
+            _ => Term::IpPattern(format!("{}*", value)),
+        }),
+        "jail" => Ok(Term::Jail(value.to_string())),
+        "age" => {
+            let (comparison, duration) = parse_comparison(value)?;
+            Ok(Term::Age(comparison, duration))
+        },
+        "remaining" if value.eq_ignore_ascii_case("permanent") || value.eq_ignore_ascii_case("never") => Ok(Term::Permanent),
+        "remaining" => {
+            let (comparison, duration) = parse_comparison(value)?;
+            Ok(Term::Remaining(comparison, duration))
+        },
+        "country" => Ok(Term::Country(value.to_uppercase())),
+        "asn" => {
+            let number = value.strip_prefix("AS").or_else(|| value.strip_prefix("as")).unwrap_or(value);
+            number.parse().map(Term::Asn).map_err(|_| format!("asn:{} is not an AS number", value))
+        },
+        _ => Err(format!("unknown field '{}' (use {})", field, FIELDS.join(", "))),
+    }
+}
+
+/// "<2h", ">=1d", "30m" (at most 30 minutes)
+fn parse_comparison(value: &str) -> std::result::Result<(Comparison, Duration), String> {
+    let (comparison, amount) = if let Some(rest) = value.strip_prefix("<=") {
+        (Comparison::LessOrEqual, rest)
+    } else if let Some(rest) = value.strip_prefix(">=") {
+        (Comparison::GreaterOrEqual, rest)
+    } else if let Some(rest) = value.strip_prefix('<') {
+        (Comparison::Less, rest)
+    } else if let Some(rest) = value.strip_prefix('>') {
+        (Comparison::Greater, rest)
+    } else if let Some(rest) = value.strip_prefix('=') {
+        (Comparison::Equal, rest)
+    } else {
+        (Comparison::LessOrEqual, value)
+    };
+    parse_duration(amount).map(|duration| (comparison, duration))
+        .ok_or_else(|| format!("'{}' is not a duration like 30m, 2h, 1d or 1w", amount))
+}
+
+fn parse_duration(text: &str) -> Option<Duration> {
+    let unit = text.chars().last()?;
+    let number: i64 = text[..text.len() - unit.len_utf8()].parse().ok()?;
+    // The try_ forms, so a number too large for a duration is a parse error and not a panic
+    match unit {
+        's' => Duration::try_seconds(number),
+        'm' => Duration::try_minutes(number),
+        'h' => Duration::try_hours(number),
+        'd' => Duration::try_days(number),
+        'w' => Duration::try_weeks(number),
+        _ => None,
+    }
+}
+
+/// Values offered after `field:` by completion
+#[derive(Debug, Clone, Default)]
+pub struct CompletionSource {
+    pub jails: Vec<String>,
+    pub countries: Vec<String>,  // Most bans first
+    pub asns: Vec<u32>,
+}
+
+/// Candidates for the word being typed, as whole words
+pub fn complete(input: &str, source: &CompletionSource) -> Vec<String> {
+    let word = last_word(input);
+    let lead = word.len() - word.trim_start_matches(['(', '!']).len();
+    let (prefix, partial) = word.split_at(lead);
+    
+    let candidates: Vec<String> = match partial.split_once(':') {
+        Some((field, _)) => {
+            let field = field.to_lowercase();
+            let values: Vec<String> = match field.as_str() {
+                "jail" => source.jails.clone(),
+                "country" => source.countries.clone(),
+                "asn" => source.asns.iter().map(|asn| asn.to_string()).collect(),
+                "age" => ["<1h", "<24h", "<7d", ">1d"].iter().map(|v| v.to_string()).collect(),
+                "remaining" => ["<1h", "<1d", ">1d", "permanent"].iter().map(|v| v.to_string()).collect(),
+                _ => Vec::new(),
+            };
+            values.into_iter().map(|value| format!("{}:{}", field, value)).collect()
+        },
+        None => FIELDS.iter().map(|field| format!("{}:", field))
+            .chain(KEYWORDS.iter().map(|keyword| keyword.to_string()))
+            .collect(),
+    };
+    candidates.into_iter()
+        .filter(|candidate| candidate.to_lowercase().starts_with(&partial.to_lowercase()) && candidate.len() > partial.len())
+        .map(|candidate| format!("{}{}", prefix, candidate))
+        .collect()
+}
+
+/// The longest start all candidates share
+pub fn common_prefix(candidates: &[String]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+    let mut len = first.len();
+    for candidate in &candidates[1..] {
+        len = first.char_indices()
+            .zip(candidate.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8())
+            .min(len);
+    }
+    first[..len].to_string()
+}
+
+fn last_word(input: &str) -> &str {
+    if input.ends_with(char::is_whitespace) {
+        return "";
+    }
+    input.split_whitespace().last().unwrap_or("")
+}
+
+/// `input` with the word being typed replaced
+pub fn replace_last_word(input: &str, word: &str) -> String {
+    let keep = input.len() - last_word(input).len();
+    format!("{}{}", &input[..keep], word)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PresetsFile {
+    #[serde(default)]
+    presets: BTreeMap<String, String>,
+}
+
+/// Named filters, kept in a TOML file of their own as `name = "query"`
+#[derive(Debug, Clone, Default)]
+pub struct QueryPresets {
+    path: PathBuf,
+    presets: BTreeMap<String, String>,
+}
+
+impl QueryPresets {
+    /// A missing file means no presets yet
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut presets = Self {
+            path: path.to_path_buf(),
+            presets: BTreeMap::new(),
+        };
+        if path.exists() {
+            let text = std::fs::read_to_string(path)?;
+            let file: PresetsFile = toml::from_str(&text).map_err(|e| AppError::Config(ConfigError::InvalidFile(
+                format!("{}: {}", path.display(), e)
+            )))?;
+            presets.presets = file.presets;
+        }
+        Ok(presets)
+    }
+    
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    
+    pub fn names(&self) -> Vec<String> {
+        self.presets.keys().cloned().collect()
+    }
+    
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.presets.get(name).map(String::as_str)
+    }
+    
+    /// Save or replace a preset; the query must parse
+    pub fn save(&mut self, name: &str, query: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::Parse("preset name is empty".to_string()));
+        }
+        BanQuery::parse(query).map_err(AppError::Parse)?;
+        self.presets.insert(name.to_string(), query.trim().to_string());
+        self.write()
+    }
+    
+    pub fn remove(&mut self, name: &str) -> Result<bool> {
+        if self.presets.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.write()?;
+        Ok(true)
+    }
+    
+    fn write(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string(&PresetsFile { presets: self.presets.clone() })
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&self.path)?;
+        file.write_all(text.as_bytes())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+use crate::app::BannedIP;
+use crate::services::fail2ban_client::Fail2banClient;
+use crate::services::file_monitor::{BanAction, BanEvent};
+use crate::services::table_sort::{compare_banned_ips, sort_banned_ips, BannedIpColumn, SortOrder};
+use crate::utils::errors::{AppError, Result};
+
+/// A full reload still running after this long is given up, so a hung
+/// fail2ban-client does not stop the next one from starting
+const RECONCILE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// One full read of every jail's bans, taken off the UI thread
+#[derive(Debug, Clone, Default)]
+pub struct BanSnapshot {
+    pub bans: Vec<BannedIP>,
+    pub bantimes: HashMap<String, Option<chrono::Duration>>,  // Used for the unban time of live bans
+    pub failed_jails: Vec<String>,
+    pub took: Duration,
+}
+
+impl BanSnapshot {
+    pub fn load(client: &Fail2banClient, jails: &[String]) -> Self {
+        let start = Instant::now();
+        let mut snapshot = BanSnapshot::default();
+        for jail in jails {
+            match client.get_banned_ips(jail) {
+                Ok(mut ips) => snapshot.bans.append(&mut ips),
+                Err(e) => {
+                    log::warn!("Failed to get banned IPs for jail {}: {}", jail, e);
+                    snapshot.failed_jails.push(jail.clone());
+                }
+            }
+            snapshot.bantimes.insert(jail.clone(), client.get_jail_bantime_duration(jail));
+        }
+        snapshot.took = start.elapsed();
+        snapshot
+    }
+}
+
+/// (ip, jail)
+type BanKey = (String, String);
+
+/// Ban and unban time of a row, the sort keys besides its address and jail
+type BanTimes = (DateTime<Utc>, Option<DateTime<Utc>>);
+
+/// Events logged since a reload began
+#[derive(Debug, Clone)]
+struct Journal {
+    began: DateTime<Utc>,
+    events: Vec<(BanEvent, DateTime<Utc>)>,
+}
+
+/// What a full reload corrected in the live set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReconcileChanges {
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// The banned addresses in table order, indexed by (ip, jail) so Ban and
+/// Unban lines from the log apply in place instead of reloading every jail.
+/// Events from before the last full reload began are already in it and are
+/// ignored, which also keeps old lines of a freshly opened log out. Events
+/// seen while a reload runs are replayed on top of its result, since the
+/// reload may have read fail2ban before they happened.
+#[derive(Debug, Clone, Default)]
+pub struct BanSet {
+    bans: Vec<BannedIP>,
+    index: HashMap<BanKey, BanTimes>,  // To find a row by binary search
+    order: SortOrder<BannedIpColumn>,
+    bantimes: HashMap<String, Option<chrono::Duration>>,
+    covered_until: Option<DateTime<Utc>>,  // Start of the reload the rows come from
+    journal: Option<Journal>,
+}
+
+impl BanSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.bans.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.bans.is_empty()
+    }
+    
+    pub fn iter(&self) -> std::slice::Iter<'_, BannedIP> {
+        self.bans.iter()
+    }
+    
+    pub fn as_slice(&self) -> &[BannedIP] {
+        &self.bans
+    }
+    
+    pub fn contains(&self, ip: &str, jail: &str) -> bool {
+        self.index.contains_key(&(ip.to_string(), jail.to_string()))
+    }
+    
+    pub fn order(&self) -> SortOrder<BannedIpColumn> {
+        self.order
+    }
+    
+    pub fn set_order(&mut self, order: SortOrder<BannedIpColumn>) {
+        self.order = order;
+        sort_banned_ips(&mut self.bans, order);
+    }
+    
+    /// Replace every row, keeping the first of any duplicate (ip, jail)
+    pub fn replace(&mut self, bans: Vec<BannedIP>) {
+        self.index.clear();
+        self.bans = Vec::with_capacity(bans.len());
+        for banned in bans {
+            if let Entry::Vacant(slot) = self.index.entry((banned.ip.clone(), banned.jail.clone())) {
+                slot.insert((banned.ban_time, banned.unban_time));
+                self.bans.push(banned);
+            }
+        }
+        self.set_order(self.order);
+    }
+    
+    pub fn clear(&mut self) {
+        self.bans.clear();
+        self.index.clear();
+        self.covered_until = None;
+    }
+    
+    /// Apply a Ban or Unban line logged at `at` (real UTC); true when the set changed
+    pub fn apply(&mut self, event: &BanEvent, at: DateTime<Utc>) -> bool {
+        if let Some(ref mut journal) = self.journal {
+            journal.events.push((event.clone(), at));
+        }
+        // Log times are whole seconds
+        let covered = self.covered_until.map_or(true, |until| at < until - chrono::Duration::seconds(1));
+        if covered {
+            return false;
+        }
+        match event.action {
+            BanAction::Ban => self.insert(&event.ip, &event.jail, at),
+            BanAction::Unban => self.remove(&event.ip, &event.jail),
+        }
+    }
+    
+    fn insert(&mut self, ip: &str, jail: &str, at: DateTime<Utc>) -> bool {
+        let key = (ip.to_string(), jail.to_string());
+        if self.index.contains_key(&key) {
+            return false;
+        }
+        // Until a reload has read the jail's bantime the ban shows as permanent
+        let bantime = self.bantimes.get(jail).copied().flatten().filter(|d| *d > chrono::Duration::zero());
+        let banned = BannedIP {
+            ip: key.0.clone(),
+            jail: key.1.clone(),
+            ban_time: at,
+            unban_time: bantime.map(|d| at + d),
+            reason: "Active ban".to_string(),
+        };
+        let position = self.bans.partition_point(|b| compare_banned_ips(b, &banned, self.order).is_lt());
+        self.index.insert(key, (banned.ban_time, banned.unban_time));
+        self.bans.insert(position, banned);
+        true
+    }
+    
+    fn remove(&mut self, ip: &str, jail: &str) -> bool {
+        let Some((ban_time, unban_time)) = self.index.remove(&(ip.to_string(), jail.to_string())) else {
+            return false;
+        };
+        let probe = BannedIP {
+            ip: ip.to_string(),
+            jail: jail.to_string(),
+            ban_time,
+            unban_time,
+            reason: String::new(),
+        };
+        match self.bans.binary_search_by(|b| compare_banned_ips(b, &probe, self.order)) {
+            Ok(position) => {
+                self.bans.remove(position);
+            },
+            // Not expected while the rows stay sorted; fall back to a scan
+            Err(_) => self.bans.retain(|b| b.ip != ip || b.jail != jail),
+        }
+        true
+    }
+    
+    pub fn is_reconciling(&self) -> bool {
+        self.journal.is_some()
+    }
+    
+    /// Start recording events for replay on top of the snapshot taken from `now` on
+    pub fn begin_reconcile(&mut self, now: DateTime<Utc>) {
+        self.journal = Some(Journal { began: now, events: Vec::new() });
+    }
+    
+    pub fn abandon_reconcile(&mut self) {
+        self.journal = None;
+    }
+    
+    /// Take the snapshot as the new set, then replay what the log said since the
+    /// reload began. Jails the reload could not read keep the rows they had.
+    pub fn finish_reconcile(&mut self, snapshot: BanSnapshot) -> ReconcileChanges {
+        let Some(journal) = self.journal.take() else {
+            return ReconcileChanges::default();
+        };
+        let before = std::mem::take(&mut self.index);
+        self.bantimes.extend(snapshot.bantimes);
+        let mut bans = snapshot.bans;
+        bans.extend(std::mem::take(&mut self.bans).into_iter().filter(|b| snapshot.failed_jails.contains(&b.jail)));
+        self.replace(bans);
+        self.covered_until = Some(journal.began);
+        for (event, at) in &journal.events {
+            self.apply(event, *at);
+        }
+        ReconcileChanges {
+            added: self.index.keys().filter(|key| !before.contains_key(*key)).count(),
+            removed: before.keys().filter(|key| !self.index.contains_key(*key)).count(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a BanSet {
+    type Item = &'a BannedIP;
+    type IntoIter = std::slice::Iter<'a, BannedIP>;
+    
+    fn into_iter(self) -> Self::IntoIter {
+        self.bans.iter()
+    }
+}
+
+/// Runs `BanSnapshot::load` on a background thread, one reload at a time
+#[derive(Default)]
+pub struct BanReconciler {
+    running: Option<(Receiver<BanSnapshot>, Instant)>,
+}
+
+impl BanReconciler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+    
+    /// False when a reload is already running
+    pub fn start(&mut self, jails: Vec<String>) -> bool {
+        if self.running.is_some() {
+            return false;
+        }
+        let (sender, receiver) = channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(BanSnapshot::load(&Fail2banClient::new(), &jails));
+        });
+        self.running = Some((receiver, Instant::now()));
+        true
+    }
+    
+    /// The finished snapshot, an error if the reload died or timed out, or None while it runs
+    pub fn poll(&mut self) -> Option<Result<BanSnapshot>> {
+        let (receiver, started) = self.running.as_ref()?;
+        let result = match receiver.try_recv() {
+            Ok(snapshot) => Ok(snapshot),
+            Err(TryRecvError::Disconnected) => Err(AppError::Internal("banned IP reload stopped unexpectedly".to_string())),
+            Err(TryRecvError::Empty) if started.elapsed() > RECONCILE_TIMEOUT => {
+                Err(AppError::Internal(format!("banned IP reload took longer than {}s", RECONCILE_TIMEOUT.as_secs())))
+            },
+            Err(TryRecvError::Empty) => return None,
+        };
+        self.running = None;
+        Some(result)
+    }
+}
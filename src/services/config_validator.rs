@@ -0,0 +1,763 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::utils::paths::fail2ban_paths;
+
+/// Sections in jail files that are not jails
+const NON_JAIL_SECTIONS: [&str; 3] = ["DEFAULT", "INCLUDES", "Definition"];
+
+/// Keys whose values are fail2ban time specs
+const TIME_KEYS: [&str; 4] = ["bantime", "findtime", "bantime.maxtime", "bantime.rndtime"];
+
+/// Log backends fail2ban knows; systemd may carry options, as in systemd[journalflags=1]
+const BACKENDS: [&str; 5] = ["auto", "pyinotify", "gamin", "polling", "systemd"];
+
+/// Settings fail2ban reads from jail sections; custom variables normally live in [DEFAULT]
+const JAIL_KEYS: [&str; 46] = [
+    "enabled", "filter", "action", "logpath", "backend", "logencoding", "journalmatch",
+    "maxretry", "maxmatches", "findtime", "bantime", "bantime.increment", "bantime.factor",
+    "bantime.formula", "bantime.multipliers", "bantime.maxtime", "bantime.rndtime",
+    "bantime.overalljails", "ignoreip", "ignoreself", "ignorecommand", "ignorecache", "usedns",
+    "port", "protocol", "chain", "banaction", "banaction_allports", "mta", "destemail",
+    "sender", "sendername", "mode", "prefregex", "failregex", "ignoreregex", "datepattern",
+    "maxlines", "logtimezone", "fail2ban_agent", "timezone", "dbfile", "action_", "action_mw",
+    "action_mwl", "action_xarf",
+];
+
+/// Settings in fail2ban.conf / fail2ban.local, by section
+const DAEMON_KEYS: [&str; 9] = [
+    "loglevel", "logtarget", "syslogsocket", "socket", "pidfile", "allowipv6", "dbfile",
+    "dbpurgeage", "dbmaxmatches",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    pub severity: IssueSeverity,
+    pub file: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn error(file: &str, line: Option<usize>, message: String) -> Self {
+        Self { severity: IssueSeverity::Error, file: file.to_string(), line, message }
+    }
+    
+    fn warning(file: &str, line: Option<usize>, message: String) -> Self {
+        Self { severity: IssueSeverity::Warning, file: file.to_string(), line, message }
+    }
+    
+    /// "file:line" or just "file" when the line is unknown
+    pub fn location(&self) -> String {
+        match self.line {
+            Some(line) => format!("{}:{}", self.file, line),
+            None => self.file.clone(),
+        }
+    }
+}
+
+/// A single `key = value` entry together with where it was defined
+#[derive(Debug, Clone)]
+pub struct IniEntry {
+    pub key: String,
+    pub value: String,
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct IniSection {
+    pub name: String,
+    pub file: String,
+    pub line: usize,
+    pub entries: Vec<IniEntry>,
+}
+
+impl IniSection {
+    pub fn get(&self, key: &str) -> Option<&IniEntry> {
+        self.entries.iter().rev().find(|e| e.key == key)
+    }
+}
+
+/// Parse fail2ban's INI dialect, keeping line numbers (1-based).
+/// Indented lines continue the previous value, as in Python's configparser.
+pub fn parse_ini(content: &str, file: &str) -> Vec<IniSection> {
+    let mut sections: Vec<IniSection> = Vec::new();
+    
+    for (idx, raw_line) in content.lines().enumerate() {
+        let line_num = idx + 1;
+        let trimmed = raw_line.trim();
+        
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+        
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            sections.push(IniSection {
+                name: trimmed[1..trimmed.len() - 1].trim().to_string(),
+                file: file.to_string(),
+                line: line_num,
+                entries: Vec::new(),
+            });
+            continue;
+        }
+        
+        let Some(section) = sections.last_mut() else {
+            continue;
+        };
+        
+        // Continuation of a multi-line value
+        if raw_line.starts_with(|c: char| c.is_whitespace()) {
+            if let Some(entry) = section.entries.last_mut() {
+                if !entry.value.is_empty() {
+                    entry.value.push('\n');
+                }
+                entry.value.push_str(trimmed);
+                continue;
+            }
+        }
+        
+        let split = trimmed.find(['=', ':']);
+        if let Some(pos) = split {
+            section.entries.push(IniEntry {
+                key: trimmed[..pos].trim().to_string(),
+                value: trimmed[pos + 1..].trim().to_string(),
+                file: file.to_string(),
+                line: line_num,
+            });
+        }
+    }
+    
+    sections
+}
+
+/// Parse a fail2ban time value ("600", "10m", "1h 30m", "1w", "-1") into a duration
+pub fn parse_time_spec(value: &str) -> Option<chrono::Duration> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    
+    // Out of range values are invalid rather than a panic, since the editor lints as you type
+    if let Ok(seconds) = value.parse::<i64>() {
+        return chrono::Duration::try_seconds(seconds);
+    }
+    
+    let chars: Vec<char> = value.chars().collect();
+    let mut pos = 0;
+    let mut total_seconds = 0.0_f64;
+    let mut parsed_any = false;
+    
+    while pos < chars.len() {
+        while pos < chars.len() && chars[pos].is_whitespace() {
+            pos += 1;
+        }
+        if pos >= chars.len() {
+            break;
+        }
+        
+        let number_start = pos;
+        if chars[pos] == '-' || chars[pos] == '+' {
+            pos += 1;
+        }
+        while pos < chars.len() && (chars[pos].is_ascii_digit() || chars[pos] == '.') {
+            pos += 1;
+        }
+        let number: String = chars[number_start..pos].iter().collect();
+        let amount = number.parse::<f64>().ok()?;
+        
+        while pos < chars.len() && chars[pos].is_whitespace() {
+            pos += 1;
+        }
+        let unit_start = pos;
+        while pos < chars.len() && chars[pos].is_ascii_alphabetic() {
+            pos += 1;
+        }
+        let unit: String = chars[unit_start..pos].iter().collect::<String>().to_lowercase();
+        
+        let multiplier = match unit.as_str() {
+            "" | "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+            "m" | "mi" | "min" | "mins" | "minute" | "minutes" => 60.0,
+            "h" | "hour" | "hours" => 3600.0,
+            "d" | "day" | "days" => 86400.0,
+            "w" | "week" | "weeks" => 7.0 * 86400.0,
+            "mo" | "mon" | "month" | "months" => 30.0 * 86400.0,
+            "y" | "year" | "years" => 365.0 * 86400.0,
+            _ => return None,
+        };
+        
+        total_seconds += amount * multiplier;
+        parsed_any = true;
+    }
+    
+    let limit = (i64::MAX / 1000) as f64;
+    if parsed_any && total_seconds.is_finite() && total_seconds.abs() <= limit {
+        chrono::Duration::try_seconds(total_seconds as i64)
+    } else {
+        None
+    }
+}
+
+/// A jail after merging jail.conf, jail.local and jail.d; located at its first section header
+struct MergedJail {
+    name: String,
+    file: String,
+    line: usize,
+    entries: HashMap<String, IniEntry>,
+}
+
+/// Dry-run validator for a fail2ban configuration directory.
+/// Never talks to the running daemon.
+pub struct ConfigValidator {
+    config_dir: PathBuf,
+}
+
+impl Default for ConfigValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigValidator {
+    pub fn new() -> Self {
+        Self::with_config_dir(fail2ban_paths().root())
+    }
+    
+    pub fn with_config_dir<P: AsRef<Path>>(config_dir: P) -> Self {
+        Self { config_dir: config_dir.as_ref().to_path_buf() }
+    }
+    
+    /// Run `fail2ban-client --test` and our own checks, errors first
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = self.run_client_test();
+        issues.extend(self.check_configuration());
+        issues.sort_by(|a, b| a.severity.cmp(&b.severity)
+            .then_with(|| a.file.cmp(&b.file))
+            .then_with(|| a.line.cmp(&b.line)));
+        issues
+    }
+    
+    /// Ask fail2ban-client to parse the configuration without starting or reloading anything
+    pub fn run_client_test(&self) -> Vec<ValidationIssue> {
+        let source = "fail2ban-client --test";
+        let output = match Command::new("fail2ban-client")
+            .arg("-c")
+            .arg(&self.config_dir)
+            .arg("--test")
+            .output() {
+            Ok(output) => output,
+            Err(e) => {
+                log::warn!("Could not run fail2ban-client --test: {}", e);
+                return vec![ValidationIssue::warning(source, None,
+                    format!("fail2ban-client not available ({}), only built-in checks were run", e))];
+            }
+        };
+        
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let mut issues = Vec::new();
+        
+        for line in stderr.lines().chain(stdout.lines()) {
+            if let Some(message) = Self::strip_log_prefix(line, "ERROR") {
+                issues.push(ValidationIssue::error(source, None, message));
+            } else if let Some(message) = Self::strip_log_prefix(line, "WARNING") {
+                issues.push(ValidationIssue::warning(source, None, message));
+            }
+        }
+        
+        if !output.status.success() && !issues.iter().any(|i| i.severity == IssueSeverity::Error) {
+            let last_line = stderr.lines().chain(stdout.lines())
+                .rfind(|l| !l.trim().is_empty())
+                .unwrap_or("configuration test failed")
+                .trim()
+                .to_string();
+            issues.push(ValidationIssue::error(source, None, last_line));
+        }
+        
+        issues
+    }
+    
+    /// Return the message part of a fail2ban log line at the given level
+    fn strip_log_prefix(line: &str, level: &str) -> Option<String> {
+        let pos = line.find(level)?;
+        let message = line[pos + level.len()..].trim();
+        if message.is_empty() {
+            None
+        } else {
+            Some(message.to_string())
+        }
+    }
+    
+    /// Static checks: time specs, numeric values, filters, actions and log paths
+    pub fn check_configuration(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        let sections = self.load_jail_sections(&mut issues);
+        
+        for section in &sections {
+            for entry in &section.entries {
+                issues.extend(Self::check_entry_value(entry));
+            }
+        }
+        
+        let (defaults, jails) = Self::merge_sections(&sections);
+        for jail in &jails {
+            issues.extend(self.check_jail(jail, &defaults));
+        }
+        
+        issues
+    }
+    
+    /// Validate a value in isolation (time specs, integer settings and the backend)
+    pub fn check_entry_value(entry: &IniEntry) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        if entry.value.contains("%(") {
+            return issues;
+        }
+        
+        if TIME_KEYS.contains(&entry.key.as_str()) && parse_time_spec(&entry.value).is_none() {
+            issues.push(ValidationIssue::error(&entry.file, Some(entry.line),
+                format!("invalid time value for {}: '{}'", entry.key, entry.value)));
+        }
+        
+        if entry.key == "maxretry" && entry.value.parse::<i64>().is_err() {
+            issues.push(ValidationIssue::error(&entry.file, Some(entry.line),
+                format!("maxretry must be an integer, got '{}'", entry.value)));
+        }
+        
+        let backend = entry.value.split('[').next().unwrap_or_default().trim();
+        if entry.key == "backend" && !BACKENDS.contains(&backend) {
+            issues.push(ValidationIssue::error(&entry.file, Some(entry.line),
+                format!("unknown backend '{}' (expected {})", entry.value, BACKENDS.join(", "))));
+        }
+        
+        issues
+    }
+    
+    /// Line-level checks for text open in an editor: unknown and duplicate keys, bad
+    /// values and missing filters/actions. `section` names the section that lines
+    /// before the first header belong to (the jail editor shows a section body only).
+    pub fn lint_content(&self, content: &str, file: &str, section: Option<&str>) -> Vec<ValidationIssue> {
+        let (text, offset) = match section {
+            Some(name) => (format!("[{}]\n{}", name, content), 1),
+            None => (content.to_string(), 0),
+        };
+        let daemon_file = Path::new(file).file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with("fail2ban."));
+        // filter.d/action.d files have their own keys and <tag> values - only duplicates are checked
+        let definition_file = Path::new(file).parent()
+            .and_then(|dir| dir.file_name())
+            .is_some_and(|dir| dir == "filter.d" || dir == "action.d");
+        let sections = parse_ini(&text, file);
+        
+        // Variables defined in [DEFAULT] may be used as keys in jails too
+        let default_keys: Vec<&str> = sections.iter()
+            .filter(|s| s.name == "DEFAULT")
+            .flat_map(|s| s.entries.iter().map(|e| e.key.as_str()))
+            .collect();
+        
+        let mut issues = Vec::new();
+        for section in &sections {
+            let mut seen: HashMap<&str, usize> = HashMap::new();
+            for entry in &section.entries {
+                let line = entry.line - offset;
+                
+                if let Some(first) = seen.insert(entry.key.as_str(), line) {
+                    issues.push(ValidationIssue::warning(file, Some(line),
+                        format!("duplicate key '{}' (also set on line {})", entry.key, first)));
+                }
+                
+                if definition_file {
+                    continue;
+                }
+                
+                if !Self::key_allowed(&section.name, &entry.key, daemon_file, &default_keys) {
+                    issues.push(ValidationIssue::warning(file, Some(line),
+                        format!("unknown key '{}' in [{}]", entry.key, section.name)));
+                }
+                
+                issues.extend(Self::check_entry_value(entry).into_iter()
+                    .map(|issue| ValidationIssue { line: Some(line), ..issue }));
+                
+                if !daemon_file {
+                    issues.extend(self.check_references(entry, file, line));
+                }
+            }
+        }
+        
+        issues.sort_by_key(|i| (i.line, i.severity));
+        issues
+    }
+    
+    fn key_allowed(section: &str, key: &str, daemon_file: bool, default_keys: &[&str]) -> bool {
+        match section {
+            "DEFAULT" => true,
+            "INCLUDES" => matches!(key, "before" | "after"),
+            "Definition" if daemon_file => DAEMON_KEYS.contains(&key),
+            "Thread" if daemon_file => key == "stacksize",
+            _ if daemon_file => true,
+            _ => JAIL_KEYS.contains(&key)
+                || key.starts_with("known/")
+                || key.starts_with("action_")
+                || default_keys.contains(&key),
+        }
+    }
+    
+    /// Filter and action names that don't resolve to a file (interpolated values are skipped)
+    fn check_references(&self, entry: &IniEntry, file: &str, line: usize) -> Vec<ValidationIssue> {
+        let (kind, label) = match entry.key.as_str() {
+            "filter" => ("filter.d", "filter"),
+            "action" | "banaction" | "banaction_allports" => ("action.d", "action"),
+            _ => return Vec::new(),
+        };
+        
+        entry.value.lines()
+            .map(str::trim)
+            .filter(|v| !v.is_empty() && !v.contains("%("))
+            .map(Self::strip_options)
+            .filter(|name| !name.is_empty() && !self.definition_exists(kind, name))
+            .map(|name| ValidationIssue::error(file, Some(line),
+                format!("{} '{}' not found in {}", label, name, kind)))
+            .collect()
+    }
+    
+    /// Does `<kind>/<name>.conf` or `.local` exist (kind is "filter.d" or "action.d")?
+    pub fn definition_exists(&self, kind: &str, name: &str) -> bool {
+        let dir = self.config_dir.join(kind);
+        dir.join(format!("{}.conf", name)).exists() || dir.join(format!("{}.local", name)).exists()
+    }
+    
+    pub fn config_dir(&self) -> &Path {
+        &self.config_dir
+    }
+    
+    /// Names available in `filter.d` or `action.d`, from both .conf and .local files
+    pub fn list_definitions(&self, kind: &str) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(self.config_dir.join(kind))
+            .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect::<Vec<_>>())
+            .unwrap_or_default()
+            .iter()
+            .filter(|p| p.extension().is_some_and(|e| e == "conf" || e == "local"))
+            .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+    
+    /// Jail sections defined outside the stock jail.conf (jail.local and jail.d)
+    pub fn local_jail_names(&self) -> Vec<String> {
+        let stock = self.config_dir.join("jail.conf").display().to_string();
+        let mut names: Vec<String> = self.jail_files().iter()
+            .filter(|path| path.exists())
+            .flat_map(|path| {
+                let mut sections = Vec::new();
+                self.load_file_with_includes(path, &mut sections, &mut Vec::new(), 0);
+                sections
+            })
+            .filter(|section| section.file != stock && !NON_JAIL_SECTIONS.contains(&section.name.as_str()))
+            .map(|section| section.name)
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+    
+    /// Jail files in the order fail2ban reads them
+    fn jail_files(&self) -> Vec<PathBuf> {
+        let mut files = vec![self.config_dir.join("jail.conf")];
+        let drop_in_dir = self.config_dir.join("jail.d");
+        let mut drop_ins: Vec<PathBuf> = std::fs::read_dir(&drop_in_dir)
+            .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+            .unwrap_or_default();
+        drop_ins.sort();
+        
+        files.extend(drop_ins.iter().filter(|p| p.extension().is_some_and(|e| e == "conf")).cloned());
+        files.push(self.config_dir.join("jail.local"));
+        files.extend(drop_ins.iter().filter(|p| p.extension().is_some_and(|e| e == "local")).cloned());
+        files
+    }
+    
+    fn load_jail_sections(&self, issues: &mut Vec<ValidationIssue>) -> Vec<IniSection> {
+        let mut sections = Vec::new();
+        for path in self.jail_files() {
+            if path.exists() {
+                self.load_file_with_includes(&path, &mut sections, issues, 0);
+            }
+        }
+        sections
+    }
+    
+    /// Parse a file, pulling in its [INCLUDES] before/after files around it
+    fn load_file_with_includes(&self, path: &Path, sections: &mut Vec<IniSection>, issues: &mut Vec<ValidationIssue>, depth: usize) {
+        let display = path.display().to_string();
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                issues.push(ValidationIssue::error(&display, None, format!("cannot read file: {}", e)));
+                return;
+            }
+        };
+        
+        let parsed = parse_ini(&content, &display);
+        let includes = parsed.iter().find(|s| s.name == "INCLUDES");
+        let include_paths = |key: &str| -> Vec<(PathBuf, IniEntry)> {
+            includes
+                .and_then(|s| s.get(key))
+                .map(|entry| entry.value.split_whitespace()
+                    .map(|name| (path.parent().unwrap_or(&self.config_dir).join(name), entry.clone()))
+                    .collect())
+                .unwrap_or_default()
+        };
+        let before = include_paths("before");
+        let after = include_paths("after");
+        
+        for (include, entry) in before.iter() {
+            self.load_include(include, entry, sections, issues, depth);
+        }
+        sections.extend(parsed);
+        for (include, entry) in after.iter() {
+            self.load_include(include, entry, sections, issues, depth);
+        }
+    }
+    
+    fn load_include(&self, include: &Path, entry: &IniEntry, sections: &mut Vec<IniSection>, issues: &mut Vec<ValidationIssue>, depth: usize) {
+        if depth >= 5 {
+            return;
+        }
+        if include.exists() {
+            self.load_file_with_includes(include, sections, issues, depth + 1);
+        } else {
+            issues.push(ValidationIssue::warning(&entry.file, Some(entry.line),
+                format!("included file not found: {}", include.display())));
+        }
+    }
+    
+    /// Collapse repeated sections; later files override earlier ones key by key
+    fn merge_sections(sections: &[IniSection]) -> (HashMap<String, IniEntry>, Vec<MergedJail>) {
+        let mut defaults: HashMap<String, IniEntry> = HashMap::new();
+        let mut jails: Vec<MergedJail> = Vec::new();
+        
+        for section in sections {
+            if section.name == "DEFAULT" {
+                for entry in &section.entries {
+                    defaults.insert(entry.key.clone(), entry.clone());
+                }
+                continue;
+            }
+            if NON_JAIL_SECTIONS.contains(&section.name.as_str()) {
+                continue;
+            }
+            
+            let index = match jails.iter().position(|jail| jail.name == section.name) {
+                Some(index) => index,
+                None => {
+                    jails.push(MergedJail {
+                        name: section.name.clone(),
+                        file: section.file.clone(),
+                        line: section.line,
+                        entries: HashMap::new(),
+                    });
+                    jails.len() - 1
+                }
+            };
+            for entry in &section.entries {
+                jails[index].entries.insert(entry.key.clone(), entry.clone());
+            }
+        }
+        
+        (defaults, jails)
+    }
+    
+    fn check_jail(&self, jail: &MergedJail, defaults: &HashMap<String, IniEntry>) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        let name = jail.name.as_str();
+        let entries = &jail.entries;
+        let lookup = |key: &str| entries.get(key).or_else(|| defaults.get(key));
+        let resolve = |value: &str| Self::interpolate(value, name, entries, defaults, 0);
+        
+        if !Self::jail_enabled(jail, defaults) {
+            return issues;
+        }
+        
+        // Filter: "name[options]", defaults to the jail name
+        let filter_value = match lookup("filter") {
+            Some(entry) => resolve(&entry.value).map(|v| (v, Some(entry))),
+            None => Some((name.to_string(), None)),
+        };
+        if let Some((value, entry)) = filter_value {
+            let filter_name = Self::strip_options(&value);
+            if !filter_name.is_empty() && !self.definition_exists("filter.d", filter_name) {
+                let (file, line) = Self::position(entry, jail);
+                issues.push(ValidationIssue::error(&file, line,
+                    format!("[{}] filter '{}' not found in filter.d", name, filter_name)));
+            }
+        }
+        
+        // Actions: one per line, each "name[options]"
+        if let Some(entry) = lookup("action") {
+            if let Some(value) = resolve(&entry.value) {
+                for action in value.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                    let action_name = Self::strip_options(action);
+                    if !action_name.is_empty() && !self.definition_exists("action.d", action_name) {
+                        issues.push(ValidationIssue::error(&entry.file, Some(entry.line),
+                            format!("[{}] action '{}' not found in action.d", name, action_name)));
+                    }
+                }
+            }
+        }
+        
+        // Log paths are irrelevant for the journal backend
+        let backend = lookup("backend").and_then(|e| resolve(&e.value)).unwrap_or_default();
+        if !backend.starts_with("systemd") {
+            match lookup("logpath") {
+                Some(entry) => {
+                    if let Some(value) = resolve(&entry.value) {
+                        for path in value.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                            // Strip optional " tail"/" head" markers
+                            let path = path.split_whitespace().next().unwrap_or(path);
+                            if !Self::log_path_exists(path) {
+                                issues.push(ValidationIssue::error(&entry.file, Some(entry.line),
+                                    format!("[{}] log file not found: {}", name, path)));
+                            }
+                        }
+                    }
+                },
+                None => {
+                    let (file, line) = Self::position(None, jail);
+                    issues.push(ValidationIssue::warning(&file, line,
+                        format!("[{}] enabled jail has no logpath", name)));
+                }
+            }
+        }
+        
+        issues
+    }
+    
+    fn jail_enabled(jail: &MergedJail, defaults: &HashMap<String, IniEntry>) -> bool {
+        jail.entries.get("enabled").or_else(|| defaults.get("enabled"))
+            .and_then(|e| Self::interpolate(&e.value, &jail.name, &jail.entries, defaults, 0))
+            .map(|v| matches!(v.to_lowercase().as_str(), "true" | "yes" | "1" | "on"))
+            .unwrap_or(false)
+    }
+    
+    /// Enabled jails using each filter and action, keyed by "filter.d/<name>" or "action.d/<name>"
+    pub fn definition_references(&self) -> HashMap<String, Vec<String>> {
+        let sections = self.load_jail_sections(&mut Vec::new());
+        let (defaults, jails) = Self::merge_sections(&sections);
+        let mut references: HashMap<String, Vec<String>> = HashMap::new();
+        
+        for jail in jails.iter().filter(|jail| Self::jail_enabled(jail, &defaults)) {
+            let resolve = |key: &str| jail.entries.get(key).or_else(|| defaults.get(key))
+                .and_then(|e| Self::interpolate(&e.value, &jail.name, &jail.entries, &defaults, 0));
+            
+            let filter = resolve("filter").unwrap_or_else(|| jail.name.clone());
+            let mut used = vec![format!("filter.d/{}", Self::strip_options(&filter))];
+            if let Some(actions) = resolve("action") {
+                used.extend(actions.lines().map(str::trim).filter(|l| !l.is_empty())
+                    .map(|action| format!("action.d/{}", Self::strip_options(action))));
+            }
+            
+            for key in used {
+                let users = references.entry(key).or_default();
+                if !users.contains(&jail.name) {
+                    users.push(jail.name.clone());
+                }
+            }
+        }
+        
+        references
+    }
+    
+    /// Location of an entry, or of the jail's section header if the key is inherited
+    fn position(entry: Option<&IniEntry>, jail: &MergedJail) -> (String, Option<usize>) {
+        match entry {
+            Some(entry) => (entry.file.clone(), Some(entry.line)),
+            None => (jail.file.clone(), Some(jail.line)),
+        }
+    }
+    
+    fn strip_options(value: &str) -> &str {
+        value.split('[').next().unwrap_or(value).trim()
+    }
+    
+    /// Expand %(key)s references; None if something can't be resolved
+    fn interpolate(value: &str, jail: &str, entries: &HashMap<String, IniEntry>, defaults: &HashMap<String, IniEntry>, depth: usize) -> Option<String> {
+        if depth > 10 {
+            return None;
+        }
+        
+        let mut result = String::new();
+        let mut rest = value;
+        while let Some(start) = rest.find("%(") {
+            result.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let end = after.find(")s")?;
+            let key = &after[..end];
+            
+            let replacement = if key == "__name__" {
+                jail.to_string()
+            } else {
+                let entry = entries.get(key).or_else(|| defaults.get(key))?;
+                Self::interpolate(&entry.value, jail, entries, defaults, depth + 1)?
+            };
+            result.push_str(&replacement);
+            rest = &after[end + 2..];
+        }
+        result.push_str(rest);
+        
+        Some(result)
+    }
+    
+    /// Check a log path, allowing shell-style wildcards in the file name
+    pub fn log_path_exists(path: &str) -> bool {
+        if !path.contains(['*', '?']) {
+            return Path::new(path).exists();
+        }
+        
+        let path = Path::new(path);
+        let (Some(dir), Some(pattern)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+            return false;
+        };
+        if dir.to_string_lossy().contains(['*', '?']) {
+            // Wildcards in directories are left for fail2ban-client --test
+            return true;
+        }
+        
+        std::fs::read_dir(dir)
+            .map(|entries| entries.filter_map(|e| e.ok())
+                .any(|e| Self::wildcard_match(pattern, &e.file_name().to_string_lossy())))
+            .unwrap_or(false)
+    }
+    
+    fn wildcard_match(pattern: &str, text: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let text: Vec<char> = text.chars().collect();
+        let (mut p, mut t) = (0, 0);
+        let mut star: Option<(usize, usize)> = None;
+        
+        while t < text.len() {
+            if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+                p += 1;
+                t += 1;
+            } else if p < pattern.len() && pattern[p] == '*' {
+                star = Some((p, t));
+                p += 1;
+            } else if let Some((star_p, star_t)) = star {
+                p = star_p + 1;
+                t = star_t + 1;
+                star = Some((star_p, star_t + 1));
+            } else {
+                return false;
+            }
+        }
+        
+        pattern[p..].iter().all(|&c| c == '*')
+    }
+}
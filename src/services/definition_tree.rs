@@ -0,0 +1,118 @@
+use std::path::{Path, PathBuf};
+
+use crate::services::config_validator::{parse_ini, ConfigValidator};
+use crate::utils::config_writer::create_config_file;
+use crate::utils::errors::{AppError, ConfigError, Result};
+
+/// Directories shown in the definition browser, in display order
+pub const DEFINITION_DIRS: [&str; 3] = ["filter.d", "action.d", "jail.d"];
+
+/// A filter, action or jail drop-in, possibly split into a packaged `.conf`
+/// and a `.local` holding the site's overrides
+#[derive(Debug, Clone)]
+pub struct Definition {
+    pub dir: &'static str,
+    pub name: String,
+    pub has_conf: bool,
+    pub has_local: bool,
+    pub used_by: Vec<String>,  // Enabled jails using it, or the jails a jail.d file defines
+}
+
+impl Definition {
+    pub fn conf_path(&self, config_dir: &Path) -> PathBuf {
+        config_dir.join(self.dir).join(format!("{}.conf", self.name))
+    }
+    
+    pub fn local_path(&self, config_dir: &Path) -> PathBuf {
+        config_dir.join(self.dir).join(format!("{}.local", self.name))
+    }
+    
+    /// "conf", "conf+local" or "local"
+    pub fn files_label(&self) -> &'static str {
+        match (self.has_conf, self.has_local) {
+            (true, true) => "conf+local",
+            (true, false) => "conf",
+            _ => "local",
+        }
+    }
+}
+
+/// Scan the definition directories of `validator`'s configuration
+pub fn scan_definitions(validator: &ConfigValidator) -> Vec<Definition> {
+    let config_dir = validator.config_dir();
+    let references = validator.definition_references();
+    let mut definitions = Vec::new();
+    
+    for dir in DEFINITION_DIRS {
+        for name in validator.list_definitions(dir) {
+            let mut definition = Definition {
+                dir,
+                name,
+                has_conf: false,
+                has_local: false,
+                used_by: Vec::new(),
+            };
+            definition.has_conf = definition.conf_path(config_dir).exists();
+            definition.has_local = definition.local_path(config_dir).exists();
+            definition.used_by = if dir == "jail.d" {
+                jails_defined_in(&definition, config_dir)
+            } else {
+                references.get(&format!("{}/{}", dir, definition.name)).cloned().unwrap_or_default()
+            };
+            definitions.push(definition);
+        }
+    }
+    
+    definitions
+}
+
+fn jails_defined_in(definition: &Definition, config_dir: &Path) -> Vec<String> {
+    let mut jails: Vec<String> = [definition.conf_path(config_dir), definition.local_path(config_dir)].iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok().map(|content| parse_ini(&content, &path.display().to_string())))
+        .flatten()
+        .map(|section| section.name)
+        .filter(|name| name != "DEFAULT" && name != "INCLUDES")
+        .collect();
+    jails.sort();
+    jails.dedup();
+    jails
+}
+
+/// Create `<name>.local` next to a packaged `<name>.conf`.
+///
+/// fail2ban reads the `.local` after the `.conf` and only the keys set there
+/// replace packaged values, so the new file keeps the section headers and
+/// carries the packaged settings commented out for reference. Package
+/// upgrades then never overwrite local changes.
+pub fn create_local_override(definition: &Definition, config_dir: &Path) -> Result<PathBuf> {
+    let local_path = definition.local_path(config_dir);
+    let already_exists = || AppError::Config(ConfigError::InvalidFile(format!("{} already exists", local_path.display())));
+    if local_path.exists() {
+        return Err(already_exists());
+    }
+    
+    let conf_path = definition.conf_path(config_dir);
+    let packaged = std::fs::read_to_string(&conf_path)?;
+    let mut content = format!(
+        "# Local overrides for {}/{}.conf\n# Uncomment and change only the settings you need; the rest stay packaged.\n\n",
+        definition.dir, definition.name
+    );
+    for line in packaged.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') || trimmed.starts_with('[') {
+            content.push_str(line);
+        } else {
+            content.push_str("# ");
+            content.push_str(line);
+        }
+        content.push('\n');
+    }
+    
+    // Refuses a .local created since the check above, e.g. by another admin
+    create_config_file(&local_path, &content).map_err(|e| match e {
+        AppError::FileSystem(ref io) if io.kind() == std::io::ErrorKind::AlreadyExists => already_exists(),
+        e => e,
+    })?;
+    log::info!("Created override {} for {}", local_path.display(), conf_path.display());
+    Ok(local_path)
+}
@@ -0,0 +1,215 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::app::LogEntry;
+use crate::services::file_monitor::{ban_event, found_event, BanAction};
+
+/// Windows offered by the charts, in hours - the 1h/24h/week steps of the
+/// log screen's time filter
+pub const STATS_WINDOWS: [u32; 3] = [1, 24, 168];
+
+/// Events older than the longest window are dropped
+const HISTORY_MINUTES: i64 = 168 * 60;
+
+/// Upper bound on stored minute/jail buckets; the oldest go first
+pub const MAX_BUCKETS: usize = 50_000;
+
+/// Upper bound on remembered bans with their address; the oldest go first
+pub const MAX_BAN_RECORDS: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Ban,
+    Unban,
+    Found,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventCounts {
+    pub bans: u64,
+    pub unbans: u64,
+    pub found: u64,
+}
+
+impl EventCounts {
+    pub fn get(&self, kind: EventKind) -> u64 {
+        match kind {
+            EventKind::Ban => self.bans,
+            EventKind::Unban => self.unbans,
+            EventKind::Found => self.found,
+        }
+    }
+    
+    fn add(&mut self, other: &EventCounts) {
+        self.bans += other.bans;
+        self.unbans += other.unbans;
+        self.found += other.found;
+    }
+}
+
+/// One Ban line: when, where and whom
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BanRecord {
+    pub at: DateTime<Utc>,
+    pub jail: String,
+    pub ip: String,
+}
+
+/// "Now" on the clock LogMonitor stamps entries with: fail2ban writes local
+/// wall-clock time and the parser stores it as if it were UTC
+pub fn log_clock_now() -> DateTime<Utc> {
+    chrono::Local::now().naive_local().and_utc()
+}
+
+/// A real UTC time (such as `BannedIP::ban_time`) on the log clock
+pub fn to_log_clock(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.with_timezone(&chrono::Local).naive_local().and_utc()
+}
+
+/// A log entry's timestamp back on the real UTC clock
+pub fn from_log_clock(at: DateTime<Utc>) -> DateTime<Utc> {
+    chrono::Local.from_local_datetime(&at.naive_utc()).earliest()
+        .map_or(at, |local| local.with_timezone(&Utc))
+}
+
+/// Rolling per-minute Ban/Unban/Found counts per jail over the last week,
+/// plus the banned addresses for the offender tables
+#[derive(Debug, Clone, Default)]
+pub struct EventHistory {
+    buckets: BTreeMap<(i64, String), EventCounts>,  // (minute since epoch, jail)
+    bans: VecDeque<BanRecord>,
+}
+
+impl EventHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Count the Ban, Unban and Found lines among `entries`
+    pub fn record(&mut self, entries: &[LogEntry]) {
+        for entry in entries {
+            if let Some(event) = ban_event(&entry.message) {
+                match event.action {
+                    BanAction::Ban => self.record_ban(&event.jail, &event.ip, entry.timestamp),
+                    BanAction::Unban => self.record_event(&event.jail, EventKind::Unban, entry.timestamp),
+                }
+            } else if let Some((jail, _)) = found_event(&entry.message) {
+                self.record_event(&jail, EventKind::Found, entry.timestamp);
+            }
+        }
+        self.prune(log_clock_now());
+    }
+    
+    pub fn record_event(&mut self, jail: &str, kind: EventKind, at: DateTime<Utc>) {
+        let counts = self.buckets.entry((minute(at), jail.to_string())).or_default();
+        match kind {
+            EventKind::Ban => counts.bans += 1,
+            EventKind::Unban => counts.unbans += 1,
+            EventKind::Found => counts.found += 1,
+        }
+    }
+    
+    /// A Ban event together with the address it banned
+    pub fn record_ban(&mut self, jail: &str, ip: &str, at: DateTime<Utc>) {
+        self.record_event(jail, EventKind::Ban, at);
+        self.bans.push_back(BanRecord {
+            at,
+            jail: jail.to_string(),
+            ip: ip.to_string(),
+        });
+    }
+    
+    /// Drop what is older than a week, then the oldest beyond the caps
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        let oldest = minute(now) - HISTORY_MINUTES;
+        self.buckets.retain(|(bucket, _), _| *bucket > oldest);
+        while self.buckets.len() > MAX_BUCKETS {
+            self.buckets.pop_first();
+        }
+        self.bans.retain(|ban| minute(ban.at) > oldest);
+        while self.bans.len() > MAX_BAN_RECORDS {
+            self.bans.pop_front();
+        }
+    }
+    
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len()
+    }
+    
+    pub fn ban_record_count(&self) -> usize {
+        self.bans.len()
+    }
+    
+    /// Bans with their address within the window, in the order they were logged
+    pub fn ban_records(&self, window_hours: u32, now: DateTime<Utc>) -> impl Iterator<Item = &BanRecord> {
+        let end = minute(now) + 1;
+        let start = end - window_hours as i64 * 60;
+        self.bans.iter().filter(move |ban| (start..end).contains(&minute(ban.at)))
+    }
+    
+    /// `points` evenly sized bins covering the `window_hours` up to `now`,
+    /// oldest first, for one jail or all of them
+    pub fn series(&self, jail: Option<&str>, kind: EventKind, window_hours: u32, points: usize, now: DateTime<Utc>) -> Vec<u64> {
+        let mut bins = vec![0; points];
+        if points == 0 {
+            return bins;
+        }
+        let end = minute(now) + 1;
+        let span = window_hours as i64 * 60;
+        let start = end - span;
+        for ((bucket, bucket_jail), counts) in self.buckets.range((start, String::new())..) {
+            if *bucket >= end || jail.is_some_and(|jail| jail != bucket_jail) {
+                continue;
+            }
+            let bin = ((bucket - start) * points as i64 / span) as usize;
+            bins[bin.min(points - 1)] += counts.get(kind);
+        }
+        bins
+    }
+    
+    /// Totals per jail within the window, busiest (most bans) first
+    pub fn jail_totals(&self, window_hours: u32, now: DateTime<Utc>) -> Vec<(String, EventCounts)> {
+        let end = minute(now) + 1;
+        let start = end - window_hours as i64 * 60;
+        let mut totals: BTreeMap<&str, EventCounts> = BTreeMap::new();
+        for ((bucket, jail), counts) in self.buckets.range((start, String::new())..) {
+            if *bucket < end {
+                totals.entry(jail).or_default().add(counts);
+            }
+        }
+        let mut totals = totals.into_iter()
+            .map(|(jail, counts)| (jail.to_string(), counts))
+            .collect::<Vec<_>>();
+        totals.sort_by(|a, b| b.1.bans.cmp(&a.1.bans).then(b.1.found.cmp(&a.1.found)).then(a.0.cmp(&b.0)));
+        totals
+    }
+    
+    /// Sum over all jails within the window
+    pub fn totals(&self, window_hours: u32, now: DateTime<Utc>) -> EventCounts {
+        let mut sum = EventCounts::default();
+        for (_, counts) in self.jail_totals(window_hours, now) {
+            sum.add(&counts);
+        }
+        sum
+    }
+}
+
+/// "1h", "24h", "7d"
+pub fn window_label(hours: u32) -> String {
+    if hours >= 48 && hours % 24 == 0 {
+        format!("{}d", hours / 24)
+    } else {
+        format!("{}h", hours)
+    }
+}
+
+/// The window after `hours` in STATS_WINDOWS
+pub fn next_window(hours: u32) -> u32 {
+    let index = STATS_WINDOWS.iter().position(|w| *w == hours).unwrap_or(STATS_WINDOWS.len() - 1);
+    STATS_WINDOWS[(index + 1) % STATS_WINDOWS.len()]
+}
+
+fn minute(at: DateTime<Utc>) -> i64 {
+    at.timestamp().div_euclid(60)
+}
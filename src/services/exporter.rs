@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::services::fail2ban_client::{Fail2banClient, JailCounters};
+use crate::services::file_monitor::{ban_event, BanAction, BanEvent, LogMonitor};
+use crate::services::notifications::Notifier;
+use crate::utils::errors::Result;
+
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:9191";
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Connections handled at once; more are answered 503 straight away
+const MAX_CONNECTIONS: usize = 16;
+
+/// Request line and headers together; a scrape needs a few hundred bytes
+const MAX_REQUEST_BYTES: u64 = 8192;
+
+/// Prometheus exporter: every scrape of /metrics queries fail2ban-client and
+/// reads the log lines written since the previous scrape.
+///
+/// The ban/unban counters start with the events already in the current log
+/// file and grow from there for as long as the exporter runs, across log
+/// rotations; only a restart resets them, which Prometheus' rate() handles
+/// like any counter reset.
+pub struct Exporter {
+    client: Fail2banClient,
+    log_monitor: LogMonitor,
+    notifier: Notifier,  // Notification rules run here too when there is no TUI
+    ban_events: BTreeMap<String, u64>,
+    unban_events: BTreeMap<String, u64>,
+}
+
+impl Exporter {
+    pub fn new(log_monitor: LogMonitor, notifier: Notifier) -> Self {
+        Self {
+            client: Fail2banClient::new(),
+            log_monitor,
+            notifier,
+            ban_events: BTreeMap::new(),
+            unban_events: BTreeMap::new(),
+        }
+    }
+    
+    /// Answer HTTP requests until the listener fails. Each connection gets its
+    /// own thread, up to `MAX_CONNECTIONS`, so a client that connects and sends
+    /// nothing cannot hold up the scrapes behind it; only the scrapes
+    /// themselves take turns.
+    pub fn serve(self, listener: TcpListener) -> Result<()> {
+        let exporter = Arc::new(Mutex::new(self));
+        let open = Arc::new(AtomicUsize::new(0));
+        for stream in listener.incoming() {
+            match stream {
+                Ok(mut stream) => {
+                    if open.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                        open.fetch_sub(1, Ordering::SeqCst);
+                        log::warn!("Exporter refused a connection: {} already open", MAX_CONNECTIONS);
+                        let _ = stream.set_write_timeout(Some(Duration::from_secs(1)))
+                            .and_then(|()| respond(&mut stream, "503 Service Unavailable", "text/plain; charset=utf-8", "Too many connections\n"));
+                        continue;
+                    }
+                    let slot = ConnectionSlot(Arc::clone(&open));
+                    let exporter = Arc::clone(&exporter);
+                    std::thread::spawn(move || {
+                        if let Err(e) = handle(&exporter, stream) {
+                            log::warn!("Exporter request failed: {}", e);
+                        }
+                        drop(slot);
+                    });
+                },
+                Err(e) => log::warn!("Exporter failed to accept a connection: {}", e),
+            }
+        }
+        Ok(())
+    }
+    
+    /// Collect everything and render the Prometheus text format
+    pub fn scrape(&mut self) -> String {
+        let started = Instant::now();
+        self.count_log_events();
+        
+        let up = self.client.ping().is_ok();
+        let mut jails: BTreeMap<String, JailCounters> = BTreeMap::new();
+        let mut jail_errors = 0;
+        if up {
+            match self.client.get_jails() {
+                Ok(names) => {
+                    for name in names {
+                        match self.client.get_jail_counters(&name) {
+                            Ok(counters) => {
+                                jails.insert(name, counters);
+                            },
+                            Err(e) => {
+                                log::warn!("Exporter could not read jail {}: {}", name, e);
+                                jail_errors += 1;
+                            }
+                        }
+                    }
+                },
+                Err(e) => {
+                    log::warn!("Exporter could not list jails: {}", e);
+                    jail_errors += 1;
+                }
+            }
+        }
+        
+        let mut out = String::new();
+        gauge(&mut out, "fail2ban_up", "Whether the fail2ban server answers ping", &[(None, up as u64)]);
+        
+        let per_jail = |select: fn(&JailCounters) -> u64| jails.iter()
+            .map(|(name, counters)| (Some(name.as_str()), select(counters)))
+            .collect::<Vec<_>>();
+        gauge(&mut out, "fail2ban_jail_failed_current", "Failures currently tracked per jail", &per_jail(|c| c.currently_failed));
+        counter(&mut out, "fail2ban_jail_failed_total", "Failures seen per jail since the server started", &per_jail(|c| c.total_failed));
+        gauge(&mut out, "fail2ban_jail_banned_current", "Addresses currently banned per jail", &per_jail(|c| c.currently_banned));
+        counter(&mut out, "fail2ban_jail_banned_total", "Bans per jail since the server started", &per_jail(|c| c.total_banned));
+        
+        counter(&mut out, "fail2ban_log_bans_total", "Ban events read from the fail2ban log", &events(&self.ban_events));
+        counter(&mut out, "fail2ban_log_unbans_total", "Unban events read from the fail2ban log", &events(&self.unban_events));
+        
+        gauge(&mut out, "fail2ban_exporter_scrape_errors", "Jail queries that failed during this scrape", &[(None, jail_errors)]);
+        let _ = writeln!(out, "# HELP fail2ban_exporter_scrape_duration_seconds Time taken by this scrape");
+        let _ = writeln!(out, "# TYPE fail2ban_exporter_scrape_duration_seconds gauge");
+        let _ = writeln!(out, "fail2ban_exporter_scrape_duration_seconds {:.6}", started.elapsed().as_secs_f64());
+        out
+    }
+    
+    fn count_log_events(&mut self) {
+        let entries = match self.log_monitor.tail_new_lines() {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Exporter could not read the fail2ban log: {}", e);
+                return;
+            }
+        };
+        self.notifier.observe(&entries);
+        for entry in &entries {
+            match ban_event(&entry.message) {
+                Some(BanEvent { action: BanAction::Ban, jail, .. }) => *self.ban_events.entry(jail).or_default() += 1,
+                Some(BanEvent { action: BanAction::Unban, jail, .. }) => *self.unban_events.entry(jail).or_default() += 1,
+                None => {}
+            }
+        }
+    }
+}
+
+/// Frees its place among the open connections when the handler is done, even if it panicked
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn handle(exporter: &Mutex<Exporter>, mut stream: TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?.take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    
+    // Headers are not needed, but must be read before answering
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim() != "" {
+        header.clear();
+    }
+    if reader.get_ref().limit() == 0 {
+        return respond(&mut stream, "431 Request Header Fields Too Large", "text/plain; charset=utf-8", "Request too large\n");
+    }
+    
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => match exporter.lock() {
+            Ok(mut exporter) => ("200 OK", CONTENT_TYPE, exporter.scrape()),
+            Err(_) => ("500 Internal Server Error", "text/plain; charset=utf-8", "An earlier scrape failed\n".to_string()),
+        },
+        ("GET", "/") => ("200 OK", "text/plain; charset=utf-8", "f2b-buxjr exporter - metrics at /metrics\n".to_string()),
+        ("GET", _) => ("404 Not Found", "text/plain; charset=utf-8", "Not found\n".to_string()),
+        _ => ("405 Method Not Allowed", "text/plain; charset=utf-8", "Only GET is supported\n".to_string()),
+    };
+    respond(&mut stream, status, content_type, &body)
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, content_type, body.len(), body
+    )?;
+    stream.flush()
+}
+
+fn events(map: &BTreeMap<String, u64>) -> Vec<(Option<&str>, u64)> {
+    map.iter().map(|(name, count)| (Some(name.as_str()), *count)).collect()
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, samples: &[(Option<&str>, u64)]) {
+    metric(out, name, "gauge", help, samples);
+}
+
+fn counter(out: &mut String, name: &str, help: &str, samples: &[(Option<&str>, u64)]) {
+    metric(out, name, "counter", help, samples);
+}
+
+fn metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(Option<&str>, u64)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    for (jail, value) in samples {
+        match jail {
+            Some(jail) => {
+                let _ = writeln!(out, "{}{{jail=\"{}\"}} {}", name, escape_label(jail), value);
+            },
+            None => {
+                let _ = writeln!(out, "{} {}", name, value);
+            },
+        }
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
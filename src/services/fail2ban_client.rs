@@ -1,10 +1,60 @@
 use std::process::Command;
 use crate::utils::errors::{AppError, ServiceError, Result};
+use crate::utils::config_writer::write_config_file;
+use crate::utils::paths::fail2ban_paths;
 use crate::app::{JailState, BannedIP, JailConfig};
+use crate::services::config_validator::parse_time_spec;
 use chrono::{Utc, TimeZone};
 
 pub struct Fail2banClient;
 
+/// Counters from `fail2ban-client status <jail>`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JailCounters {
+    pub currently_failed: u64,
+    pub total_failed: u64,
+    pub currently_banned: u64,
+    pub total_banned: u64,
+}
+
+pub fn parse_jail_counters(status_output: &str) -> JailCounters {
+    let mut counters = JailCounters::default();
+    for line in status_output.lines() {
+        let Some((label, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().parse().unwrap_or(0);
+        match label.trim_start_matches(['|', '`', '-', ' ']).trim() {
+            "Currently failed" => counters.currently_failed = value,
+            "Total failed" => counters.total_failed = value,
+            "Currently banned" => counters.currently_banned = value,
+            "Total banned" => counters.total_banned = value,
+            _ => {}
+        }
+    }
+    counters
+}
+
+/// fail2ban-client pointed at the configured root and socket
+pub fn client_command() -> Command {
+    let mut command = Command::new("fail2ban-client");
+    command.args(fail2ban_paths().client_args());
+    command
+}
+
+/// Value from `fail2ban-client get ...` output, which comes either as
+/// "Current logging level is 'INFO'" or as a heading followed by "`- value"
+pub fn parse_setting_output(output: &str) -> String {
+    let line = output.lines().map(str::trim).rev().find(|l| !l.is_empty()).unwrap_or("");
+    let line = line.trim_start_matches(['`', '|', '-']).trim();
+    if let (Some(start), Some(end)) = (line.find('\''), line.rfind('\'')) {
+        if end > start {
+            return line[start + 1..end].to_string();
+        }
+    }
+    line.to_string()
+}
+
 impl Fail2banClient {
     pub fn new() -> Self {
         Self
@@ -12,7 +62,7 @@ impl Fail2banClient {
     
     #[allow(dead_code)] // Service health checking for Epic 4
     pub fn is_available() -> bool {
-        Command::new("fail2ban-client")
+        client_command()
             .arg("--help")
             .output()
             .map(|output| output.status.success())
@@ -20,7 +70,7 @@ impl Fail2banClient {
     }
     
     pub fn get_jails(&self) -> Result<Vec<String>> {
-        let output = Command::new("fail2ban-client")
+        let output = client_command()
             .args(["status"])
             .output()
             .map_err(|e| AppError::Service(ServiceError::CommunicationError(
@@ -54,7 +104,7 @@ impl Fail2banClient {
     }
     
     pub fn get_jail_status(&self, jail_name: &str) -> Result<JailState> {
-        let output = Command::new("fail2ban-client")
+        let output = client_command()
             .args(["status", jail_name])
             .output()
             .map_err(|e| AppError::Service(ServiceError::CommunicationError(
@@ -70,6 +120,7 @@ impl Fail2banClient {
         
         let stdout = String::from_utf8_lossy(&output.stdout);
         let mut banned_count = 0;
+        let mut failed_count = 0;
         let mut filter = "unknown".to_string();
         let mut action = "unknown".to_string();
         
@@ -78,6 +129,10 @@ impl Fail2banClient {
                 if let Some(count_str) = line.split(':').nth(1) {
                     banned_count = count_str.trim().parse().unwrap_or(0);
                 }
+            } else if line.contains("Currently failed:") {
+                if let Some(count_str) = line.split(':').nth(1) {
+                    failed_count = count_str.trim().parse().unwrap_or(0);
+                }
             } else if line.contains("Filter") {
                 if let Some(filter_str) = line.split(':').nth(1) {
                     filter = filter_str.trim().to_string();
@@ -93,16 +148,34 @@ impl Fail2banClient {
             name: jail_name.to_string(),
             enabled: true, // If we can get status, it's enabled
             banned_count,
+            failed_count,
             filter,
             action,
         })
     }
     
+    pub fn get_jail_counters(&self, jail_name: &str) -> Result<JailCounters> {
+        let output = client_command()
+            .args(["status", jail_name])
+            .output()
+            .map_err(|e| AppError::Service(ServiceError::CommunicationError(
+                format!("Failed to get jail status for {}: {}", jail_name, e)
+            )))?;
+        
+        if !output.status.success() {
+            let error_msg = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Service(ServiceError::OperationFailed(
+                format!("Failed to get jail status: {}", error_msg)
+            )));
+        }
+        Ok(parse_jail_counters(&String::from_utf8_lossy(&output.stdout)))
+    }
+    
     pub fn get_banned_ips(&self, jail_name: &str) -> Result<Vec<BannedIP>> {
         log::debug!("Getting banned IPs with times for jail: {}", jail_name);
         
         // Use the more accurate command that includes ban times
-        let output = Command::new("fail2ban-client")
+        let output = client_command()
             .args(["get", jail_name, "banip", "--with-time"])
             .output()
             .map_err(|e| AppError::Service(ServiceError::CommunicationError(
@@ -188,7 +261,7 @@ impl Fail2banClient {
         log::debug!("Using fallback method for getting banned IPs for jail: {}", jail_name);
         
         // Get the list of currently banned IPs without times
-        let output = Command::new("fail2ban-client")
+        let output = client_command()
             .args(["status", jail_name])
             .output()
             .map_err(|e| AppError::Service(ServiceError::CommunicationError(
@@ -255,50 +328,43 @@ impl Fail2banClient {
         use std::io::{BufRead, BufReader};
         
         // Try to read from fail2ban log file
-        let log_paths = [
-            "/var/log/fail2ban.log",
-            "/var/log/fail2ban/fail2ban.log",
-        ];
-        
-        for log_path in &log_paths {
-            if let Ok(file) = File::open(log_path) {
-                let reader = BufReader::new(file);
-                
-                // Read lines in reverse order to find the most recent ban
-                let lines: Vec<String> = reader.lines().filter_map(|l| l.ok()).collect();
-                
-                for line in lines.iter().rev() {
-                    // Look for ban entries like: "2024-07-25 14:30:05,123 fail2ban.actions[1234]: NOTICE [sshd] Ban 192.168.1.100"
-                    if line.contains(&format!("[{}]", jail_name)) && 
-                       line.contains(&format!("Ban {}", ip)) {
-                        
-                        // Parse the timestamp from the log line
-                        if let Some(timestamp_str) = line.split(',').next() {
-                            // Parse as naive datetime first, then assume local time
-                            if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(
-                                timestamp_str, "%Y-%m-%d %H:%M:%S"
-                            ) {
-                                // Convert local time to UTC
-                                let ban_time = chrono::Local.from_local_datetime(&naive_dt)
-                                    .single()
-                                    .map(|dt| dt.with_timezone(&chrono::Utc));
+        if let Ok(file) = File::open(fail2ban_paths().log_file()) {
+            let reader = BufReader::new(file);
+            
+            // Read lines in reverse order to find the most recent ban
+            let lines: Vec<String> = reader.lines().filter_map(|l| l.ok()).collect();
+            
+            for line in lines.iter().rev() {
+                // Look for ban entries like: "2024-07-25 14:30:05,123 fail2ban.actions[1234]: NOTICE [sshd] Ban 192.168.1.100"
+                if line.contains(&format!("[{}]", jail_name)) && 
+                   line.contains(&format!("Ban {}", ip)) {
+                    
+                    // Parse the timestamp from the log line
+                    if let Some(timestamp_str) = line.split(',').next() {
+                        // Parse as naive datetime first, then assume local time
+                        if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(
+                            timestamp_str, "%Y-%m-%d %H:%M:%S"
+                        ) {
+                            // Convert local time to UTC
+                            let ban_time = chrono::Local.from_local_datetime(&naive_dt)
+                                .single()
+                                .map(|dt| dt.with_timezone(&chrono::Utc));
+                            
+                            if let Some(ban_time) = ban_time {
+                                // Calculate unban time using jail's bantime duration
+                                let unban_time = if let Some(duration) = self.get_jail_bantime_duration(jail_name) {
+                                    Some(ban_time + duration)
+                                } else {
+                                    self.calculate_unban_time(jail_name, ban_time)
+                                };
                                 
-                                if let Some(ban_time) = ban_time {
-                                    // Calculate unban time using jail's bantime duration
-                                    let unban_time = if let Some(duration) = self.get_jail_bantime_duration(jail_name) {
-                                        Some(ban_time + duration)
-                                    } else {
-                                        self.calculate_unban_time(jail_name, ban_time)
-                                    };
-                                    
-                                    return Some(BannedIP {
-                                        ip: ip.to_string(),
-                                        jail: jail_name.to_string(),
-                                        ban_time,
-                                        unban_time,
-                                        reason: "Log analysis".to_string(),
-                                    });
-                                }
+                                return Some(BannedIP {
+                                    ip: ip.to_string(),
+                                    jail: jail_name.to_string(),
+                                    ban_time,
+                                    unban_time,
+                                    reason: "Log analysis".to_string(),
+                                });
                             }
                         }
                     }
@@ -334,7 +400,7 @@ impl Fail2banClient {
     }
     
     /// Get jail's bantime duration in chrono::Duration format
-    fn get_jail_bantime_duration(&self, jail_name: &str) -> Option<chrono::Duration> {
+    pub fn get_jail_bantime_duration(&self, jail_name: &str) -> Option<chrono::Duration> {
         // First try to get bantime directly from fail2ban-client
         if let Ok(bantime_seconds) = self.get_bantime(jail_name) {
             log::debug!("Got bantime for {} from fail2ban-client: {} seconds", jail_name, bantime_seconds);
@@ -357,29 +423,10 @@ impl Fail2banClient {
         let bantime = bantime_str.trim();
         log::debug!("Parsing bantime string: '{}'", bantime);
         
-        // Handle different bantime formats
-        if bantime.ends_with('h') {
-            if let Ok(hours) = bantime[..bantime.len()-1].parse::<i64>() {
-                log::debug!("Parsed as hours: {}", hours);
-                return Some(chrono::Duration::hours(hours));
-            }
-        } else if bantime.ends_with('d') {
-            if let Ok(days) = bantime[..bantime.len()-1].parse::<i64>() {
-                log::debug!("Parsed as days: {}", days);
-                return Some(chrono::Duration::days(days));
-            }
-        } else if bantime.ends_with('m') {
-            if let Ok(minutes) = bantime[..bantime.len()-1].parse::<i64>() {
-                log::debug!("Parsed as minutes: {}", minutes);
-                return Some(chrono::Duration::minutes(minutes));
-            }
-        } else {
-            // Assume it's seconds
-            if let Ok(seconds) = bantime.parse::<i64>() {
-                let hours = seconds / 3600;
-                log::debug!("Parsed as seconds: {} (= {} hours)", seconds, hours);
-                return Some(chrono::Duration::seconds(seconds));
-            }
+        // Shared with the configuration validator so both accept the same formats
+        if let Some(duration) = parse_time_spec(bantime) {
+            log::debug!("Parsed bantime as {} seconds", duration.num_seconds());
+            return Some(duration);
         }
         
         log::warn!("Failed to parse bantime: '{}'", bantime);
@@ -387,7 +434,7 @@ impl Fail2banClient {
     }
     
     pub fn ban_ip(&self, jail_name: &str, ip: &str) -> Result<()> {
-        let output = Command::new("fail2ban-client")
+        let output = client_command()
             .args(["set", jail_name, "banip", ip])
             .output()
             .map_err(|e| AppError::Service(ServiceError::OperationFailed(
@@ -407,7 +454,7 @@ impl Fail2banClient {
 
     /// Get current bantime for a jail in seconds
     fn get_bantime(&self, jail_name: &str) -> Result<u64> {
-        let output = Command::new("fail2ban-client")
+        let output = client_command()
             .args(["get", jail_name, "bantime"])
             .output()
             .map_err(|e| AppError::Service(ServiceError::OperationFailed(
@@ -432,7 +479,7 @@ impl Fail2banClient {
     // Removed unused set_bantime and parse_duration helper methods
     
     pub fn unban_ip(&self, jail_name: &str, ip: &str) -> Result<()> {
-        let output = Command::new("fail2ban-client")
+        let output = client_command()
             .args(["set", jail_name, "unbanip", ip])
             .output()
             .map_err(|e| AppError::Service(ServiceError::OperationFailed(
@@ -449,11 +496,81 @@ impl Fail2banClient {
         }
     }
     
-    // Removed unused reload_jail and get_version methods
+    // Removed unused reload_jail method
+    
+    /// Round trip of `fail2ban-client ping`; an error means the socket did not answer
+    pub fn ping(&self) -> Result<std::time::Duration> {
+        let started = std::time::Instant::now();
+        let output = client_command()
+            .arg("ping")
+            .output()
+            .map_err(|e| AppError::Service(ServiceError::CommunicationError(
+                format!("Failed to run fail2ban-client: {}", e)
+            )))?;
+        let elapsed = started.elapsed();
+        
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if output.status.success() && stdout.contains("pong") {
+            Ok(elapsed)
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = stderr.lines().chain(stdout.lines()).map(str::trim).find(|l| !l.is_empty()).unwrap_or("no reply");
+            Err(AppError::Service(ServiceError::CommunicationError(reason.to_string())))
+        }
+    }
+    
+    /// Version reported by the running server
+    pub fn version(&self) -> Result<String> {
+        let value = self.server_output(&["version"])?;
+        Ok(parse_setting_output(&value).trim_start_matches("Fail2Ban v").to_string())
+    }
+    
+    /// A server-wide setting such as dbfile, dbpurgeage, loglevel or logtarget
+    pub fn get_server_setting(&self, name: &str) -> Result<String> {
+        self.server_output(&["get", name]).map(|output| parse_setting_output(&output))
+    }
+    
+    fn server_output(&self, args: &[&str]) -> Result<String> {
+        let output = client_command()
+            .args(args)
+            .output()
+            .map_err(|e| AppError::Service(ServiceError::CommunicationError(
+                format!("Failed to run fail2ban-client {}: {}", args.join(" "), e)
+            )))?;
+        
+        if !output.status.success() {
+            let error_msg = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Service(ServiceError::OperationFailed(
+                format!("fail2ban-client {} failed: {}", args.join(" "), error_msg.trim())
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+    
+    /// Start a jail that was just added to the configuration.
+    /// Reloading a jail the server doesn't know yet makes it read and start it.
+    pub fn start_jail(&self, jail_name: &str) -> Result<()> {
+        let output = client_command()
+            .args(["reload", jail_name])
+            .output()
+            .map_err(|e| AppError::Service(ServiceError::CommunicationError(
+                format!("Failed to start jail {}: {}", jail_name, e)
+            )))?;
+        
+        if !output.status.success() {
+            let error_msg = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Service(ServiceError::OperationFailed(
+                format!("Starting jail {} failed: {}", jail_name, error_msg.trim())
+            )));
+        }
+        
+        // Confirm the server is actually running it
+        self.get_jail_status(jail_name).map(|_| ())
+    }
     
     /// Get all available jails from configuration files (both enabled and disabled)
     pub fn get_all_available_jails(&self) -> Result<Vec<JailConfig>> {
-        let jail_config_path = "/etc/fail2ban/jail.local";
+        let jail_config_path = &fail2ban_paths().jail_local();
         
         let content = match std::fs::read_to_string(jail_config_path) {
             Ok(content) => {
@@ -463,7 +580,7 @@ impl Fail2banClient {
             Err(e) => {
                 log::warn!("Failed to read jail.local: {}, trying jail.conf", e);
                 // jail.local doesn't exist, try to read from jail.conf
-                let jail_conf_path = "/etc/fail2ban/jail.conf";
+                let jail_conf_path = &fail2ban_paths().jail_conf();
                 match std::fs::read_to_string(jail_conf_path) {
                     Ok(conf_content) => {
                         log::info!("Successfully read jail.conf file ({} bytes)", conf_content.len());
@@ -597,18 +714,18 @@ impl Fail2banClient {
     
     /// Enable or disable a jail by updating its configuration
     pub fn set_jail_enabled(&self, jail_name: &str, enabled: bool) -> Result<()> {
-        let jail_config_path = "/etc/fail2ban/jail.local";
+        let jail_config_path = &fail2ban_paths().jail_local();
         
         // Read existing configuration or create jail.local from jail.conf
         let content = match std::fs::read_to_string(jail_config_path) {
             Ok(content) => content,
             Err(_) => {
                 // jail.local doesn't exist, copy from jail.conf
-                let jail_conf_path = "/etc/fail2ban/jail.conf";
+                let jail_conf_path = &fail2ban_paths().jail_conf();
                 match std::fs::read_to_string(jail_conf_path) {
                     Ok(conf_content) => {
                         // Copy jail.conf to jail.local
-                        std::fs::write(jail_config_path, &conf_content)?;
+                        write_config_file(jail_config_path, &conf_content)?;
                         conf_content
                     },
                     Err(e) => {
@@ -624,7 +741,7 @@ impl Fail2banClient {
         let updated_content = self.update_jail_enabled_in_config(&content, jail_name, enabled)?;
         
         // Write the updated content back to jail.local
-        std::fs::write(jail_config_path, updated_content)?;
+        write_config_file(jail_config_path, &updated_content)?;
         
         // Reload fail2ban configuration to apply changes
         self.reload_config()
@@ -694,13 +811,13 @@ impl Fail2banClient {
     /// Get currently whitelisted IPs from fail2ban configuration
     pub fn get_whitelist_ips(&self) -> Result<Vec<String>> {
         // Read from the jail.local file or create one if it doesn't exist
-        let jail_config_path = "/etc/fail2ban/jail.local";
+        let jail_config_path = &fail2ban_paths().jail_local();
         
         let content = match std::fs::read_to_string(jail_config_path) {
             Ok(content) => content,
             Err(_) => {
                 // jail.local doesn't exist, try to read from jail.conf
-                let jail_conf_path = "/etc/fail2ban/jail.conf";
+                let jail_conf_path = &fail2ban_paths().jail_conf();
                 match std::fs::read_to_string(jail_conf_path) {
                     Ok(conf_content) => conf_content,
                     Err(_) => {
@@ -748,18 +865,18 @@ impl Fail2banClient {
     
     /// Save whitelist IPs to fail2ban configuration
     pub fn save_whitelist_ips(&self, whitelist_ips: &[String]) -> Result<()> {
-        let jail_config_path = "/etc/fail2ban/jail.local";
+        let jail_config_path = &fail2ban_paths().jail_local();
         
         // Read existing configuration or create jail.local from jail.conf
         let config_content = match std::fs::read_to_string(jail_config_path) {
             Ok(content) => content,
             Err(_) => {
                 // jail.local doesn't exist, copy from jail.conf
-                let jail_conf_path = "/etc/fail2ban/jail.conf";
+                let jail_conf_path = &fail2ban_paths().jail_conf();
                 match std::fs::read_to_string(jail_conf_path) {
                     Ok(conf_content) => {
                         // Copy jail.conf to jail.local
-                        std::fs::write(jail_config_path, &conf_content)
+                        write_config_file(jail_config_path, &conf_content)
                             .map_err(|e| AppError::Service(ServiceError::OperationFailed(
                                 format!("Failed to copy jail.conf to jail.local: {}", e)
                             )))?;
@@ -826,7 +943,7 @@ impl Fail2banClient {
         
         // Write updated configuration
         let updated_content = updated_lines.join("\n");
-        std::fs::write(jail_config_path, &updated_content)
+        write_config_file(jail_config_path, &updated_content)
             .map_err(|e| AppError::Service(ServiceError::OperationFailed(
                 format!("Failed to write whitelist configuration: {}", e)
             )))?;
@@ -839,7 +956,7 @@ impl Fail2banClient {
     
     /// Reload fail2ban configuration to apply changes
     fn reload_config(&self) -> Result<()> {
-        let output = Command::new("fail2ban-client")
+        let output = client_command()
             .args(["reload"])
             .output()
             .map_err(|e| AppError::Service(ServiceError::CommunicationError(
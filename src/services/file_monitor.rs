@@ -1,9 +1,12 @@
+use std::cell::Cell;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use std::path::Path;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
 use std::time::SystemTime;
-// Removed unused notify imports after FileWatcher removal
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use crate::utils::errors::{AppError, Result};
+use crate::utils::paths::fail2ban_paths;
 use crate::app::LogEntry;
 use chrono::{DateTime, Utc, NaiveDateTime};
 
@@ -23,21 +26,8 @@ impl LogMonitor {
     }
     
     pub fn get_fail2ban_log_path() -> String {
-        // Common fail2ban log locations
-        let possible_paths = vec![
-            "/var/log/fail2ban.log",
-            "/var/log/fail2ban/fail2ban.log",
-            "/usr/local/var/log/fail2ban.log",
-        ];
-        
-        for path in possible_paths {
-            if Path::new(path).exists() {
-                return path.to_string();
-            }
-        }
-        
-        // Default to most common location
-        "/var/log/fail2ban.log".to_string()
+        // --log-file, or the first common location that exists
+        fail2ban_paths().log_file().display().to_string()
     }
     
     pub fn tail_new_lines(&mut self) -> Result<Vec<LogEntry>> {
@@ -61,6 +51,11 @@ impl LogMonitor {
             return Ok(Vec::new());
         }
         
+        // Rotated or truncated - start over from the top of the new file
+        if metadata.len() < self.last_position {
+            self.last_position = 0;
+        }
+        
         let mut file = File::open(path)
             .map_err(|e| AppError::FileSystem(e))?;
         
@@ -98,7 +93,9 @@ impl LogMonitor {
         Ok(new_entries)
     }
     
-    pub fn get_recent_lines(&mut self, max_lines: usize) -> Result<Vec<LogEntry>> {
+    /// Last `max_lines` entries, newest first. The tailing position is left
+    /// alone so lines not yet tailed still reach `tail_new_lines`.
+    pub fn get_recent_lines(&self, max_lines: usize) -> Result<Vec<LogEntry>> {
         let path = Path::new(&self.file_path);
         
         if !path.exists() {
@@ -131,17 +128,35 @@ impl LogMonitor {
         // Sort entries by timestamp in descending order (newest first)
         entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
         
-        // Update position to end of file
-        if let Ok(metadata) = path.metadata() {
-            self.last_position = metadata.len();
-            if let Ok(modified) = metadata.modified() {
-                self.last_modified = modified;
-            }
-        }
-        
         Ok(entries)
     }
     
+    /// Last `limit` ERROR entries, newest first. Only the tail of the file is
+    /// scanned and the tailing position is left alone.
+    pub fn recent_errors(&self, limit: usize) -> Result<Vec<LogEntry>> {
+        const TAIL_BYTES: u64 = 256 * 1024;
+        
+        let mut file = match File::open(&self.file_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AppError::FileSystem(e)),
+        };
+        let length = file.metadata().map_err(AppError::FileSystem)?.len();
+        file.seek(SeekFrom::Start(length.saturating_sub(TAIL_BYTES))).map_err(AppError::FileSystem)?;
+        
+        // Lossy: the cut may land inside a multi-byte character
+        let mut tail = Vec::new();
+        file.read_to_end(&mut tail).map_err(AppError::FileSystem)?;
+        let tail = String::from_utf8_lossy(&tail);
+        let skip = if length > TAIL_BYTES { 1 } else { 0 };  // Started mid-line
+        
+        Ok(tail.lines().skip(skip).collect::<Vec<_>>().into_iter().rev()
+            .filter_map(|line| self.parse_log_line(line))
+            .filter(|entry| entry.level == "ERROR")
+            .take(limit)
+            .collect())
+    }
+    
     fn parse_log_line(&self, line: &str) -> Option<LogEntry> {
         // Parse fail2ban log format: TIMESTAMP LEVEL MESSAGE
         // Example: 2025-07-19 16:16:35,393 fail2ban.actions [12345]: NOTICE [sshd] Ban 192.168.1.100
@@ -226,4 +241,99 @@ impl LogMonitor {
     }
 }
 
-// Removed unused FileWatcher struct and methods
\ No newline at end of file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BanAction {
+    Ban,
+    Unban,
+}
+
+/// A Ban or Unban line from fail2ban.actions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BanEvent {
+    pub action: BanAction,
+    pub jail: String,
+    pub ip: String,
+}
+
+/// Parse "... [812]: NOTICE  [sshd] Ban 203.0.113.7". The jail is the bracket
+/// right before the verb, not the PID. "Restore Ban" re-applies an old ban
+/// after a restart and is not an event.
+pub fn ban_event(message: &str) -> Option<BanEvent> {
+    let (action, (jail, ip)) = match jail_verb(message, "Ban") {
+        Some(found) => (BanAction::Ban, found),
+        None => (BanAction::Unban, jail_verb(message, "Unban")?),
+    };
+    Some(BanEvent { action, jail, ip })
+}
+
+/// Jail and address from a fail2ban.filter "[sshd] Found 203.0.113.7 - ..." line
+pub fn found_event(message: &str) -> Option<(String, String)> {
+    jail_verb(message, "Found")
+}
+
+fn jail_verb(message: &str, verb: &str) -> Option<(String, String)> {
+    let end = message.find(&format!("] {} ", verb))?;
+    let start = message[..end].rfind('[')?;
+    let ip = message[end + verb.len() + 3..].split_whitespace().next()?;
+    Some((message[start + 1..end].to_string(), ip.to_string()))
+}
+
+/// Watches a single config file for changes made outside the editor.
+/// The parent directory is watched because atomic writers (ours, vim, Ansible)
+/// replace the file with a rename rather than modifying it in place.
+pub struct ConfigFileWatcher {
+    file_path: PathBuf,
+    receiver: Receiver<notify::Result<Event>>,
+    _watcher: RecommendedWatcher,
+    recheck: Cell<bool>,  // A change that could not be read yet
+}
+
+impl ConfigFileWatcher {
+    pub fn new(file_path: &str) -> Result<Self> {
+        let file_path = PathBuf::from(file_path);
+        let watch_dir = file_path.parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+        
+        let (sender, receiver) = channel();
+        let mut watcher = notify::recommended_watcher(sender)
+            .map_err(|e| AppError::Internal(format!("Failed to create file watcher: {}", e)))?;
+        watcher.watch(&watch_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| AppError::Internal(format!("Failed to watch {}: {}", watch_dir.display(), e)))?;
+        
+        log::info!("Watching {} for external changes", file_path.display());
+        Ok(Self { file_path, receiver, _watcher: watcher, recheck: Cell::new(false) })
+    }
+    
+    pub fn file_path(&self) -> &Path {
+        &self.file_path
+    }
+    
+    /// Drain pending events; true if any of them touched the watched file
+    pub fn has_changed(&self) -> bool {
+        let file_name = self.file_path.file_name();
+        let mut changed = self.recheck.replace(false);
+        
+        while let Ok(event) = self.receiver.try_recv() {
+            match event {
+                Ok(event) => {
+                    if matches!(event.kind, EventKind::Access(_)) {
+                        continue;
+                    }
+                    if event.paths.iter().any(|p| p.file_name() == file_name) {
+                        changed = true;
+                    }
+                },
+                Err(e) => log::warn!("File watcher error for {}: {}", self.file_path.display(), e),
+            }
+        }
+        
+        changed
+    }
+    
+    /// Report a change on the next `has_changed` too, when the file could not be read this time
+    pub fn recheck(&self) {
+        self.recheck.set(true);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use crate::utils::errors::{AppError, ConfigError, Result};
+use crate::utils::mmdb::{Mmdb, MmdbValue};
+use crate::utils::settings::GeoIpSettings;
+
+/// Looked-up addresses kept before the cache starts over
+const MAX_CACHED: usize = 100_000;
+
+/// Where an address is registered; fields the databases do not cover stay empty
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GeoInfo {
+    pub country: Option<String>,       // ISO 3166 code, e.g. "CN"
+    pub country_name: Option<String>,
+    pub asn: Option<u32>,
+    pub org: Option<String>,           // Organisation owning the AS
+}
+
+impl GeoInfo {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+    
+    /// "AS14061 DigitalOcean, LLC", or just the number
+    pub fn asn_label(&self) -> Option<String> {
+        let asn = self.asn?;
+        Some(match self.org {
+            Some(ref org) => format!("AS{} {}", asn, org),
+            None => format!("AS{}", asn),
+        })
+    }
+    
+    /// Fill the gaps from another source
+    fn merge(&mut self, other: GeoInfo) {
+        self.country = self.country.take().or(other.country);
+        self.country_name = self.country_name.take().or(other.country_name);
+        self.asn = self.asn.or(other.asn);
+        self.org = self.org.take().or(other.org);
+    }
+}
+
+/// One IP2Location LITE CSV file: DB1 (country) or ASN, IPv4 or IPv6
+struct RangeTable {
+    ranges: Vec<(u128, u128, GeoInfo)>,  // Sorted by first address
+}
+
+impl RangeTable {
+    /// Rows are "from","to","CC","Country" or "from","to","cidr","asn","AS name",
+    /// with addresses as decimal numbers; rows that do not parse are skipped
+    fn parse(text: &str) -> std::result::Result<Self, String> {
+        let mut ranges = Vec::new();
+        for line in text.lines() {
+            let fields = split_csv(line);
+            let (Some(from), Some(to)) = (
+                fields.first().and_then(|f| f.parse::<u128>().ok()),
+                fields.get(1).and_then(|f| f.parse::<u128>().ok()),
+            ) else {
+                continue;
+            };
+            let known = |field: &String| field != "-" && !field.is_empty();
+            let info = match fields.len() {
+                4 => GeoInfo {
+                    country: Some(fields[2].clone()).filter(known),
+                    country_name: Some(fields[3].clone()).filter(known),
+                    ..GeoInfo::default()
+                },
+                5 => GeoInfo {
+                    asn: fields[3].parse().ok(),
+                    org: Some(fields[4].clone()).filter(known),
+                    ..GeoInfo::default()
+                },
+                _ => continue,
+            };
+            if !info.is_empty() {
+                ranges.push((from, to, info));
+            }
+        }
+        if ranges.is_empty() {
+            return Err("no IP2Location country or ASN rows".to_string());
+        }
+        ranges.sort_by_key(|range| range.0);
+        Ok(Self { ranges })
+    }
+    
+    fn lookup(&self, addr: IpAddr) -> Option<&GeoInfo> {
+        let keys = match addr {
+            // IPv4 files number addresses as IPv4, IPv6 files as ::ffff:a.b.c.d
+            IpAddr::V4(v4) => vec![u32::from(v4) as u128, u32::from(v4) as u128 | 0xffff_0000_0000],
+            IpAddr::V6(v6) => vec![u128::from(v6)],
+        };
+        keys.into_iter().find_map(|key| {
+            let index = self.ranges.partition_point(|range| range.0 <= key).checked_sub(1)?;
+            let (_, to, ref info) = self.ranges[index];
+            (key <= to).then_some(info)
+        })
+    }
+}
+
+enum Source {
+    Mmdb(Mmdb),
+    Csv(RangeTable),
+}
+
+/// Country and AS of banned addresses from local database files, read once
+/// at startup so lookups never touch the network
+#[derive(Default)]
+pub struct GeoIp {
+    sources: Vec<(PathBuf, Source)>,
+    cache: HashMap<String, GeoInfo>,
+}
+
+impl GeoIp {
+    /// Every configured database must load; `.mmdb` files are MaxMind DB, anything else IP2Location CSV
+    pub fn open(settings: &GeoIpSettings) -> Result<Self> {
+        let mut geoip = Self::default();
+        for path in &settings.databases {
+            geoip.add_database(path)?;
+        }
+        Ok(geoip)
+    }
+    
+    pub fn add_database(&mut self, path: &Path) -> Result<()> {
+        let source = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("mmdb")) {
+            Source::Mmdb(Mmdb::open(path)?)
+        } else {
+            let text = std::fs::read_to_string(path)?;
+            Source::Csv(RangeTable::parse(&text).map_err(|e| AppError::Config(ConfigError::InvalidFile(
+                format!("{}: {}", path.display(), e)
+            )))?)
+        };
+        self.sources.push((path.to_path_buf(), source));
+        self.cache.clear();
+        Ok(())
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        !self.sources.is_empty()
+    }
+    
+    /// "GeoLite2-ASN (/var/lib/GeoIP/GeoLite2-ASN.mmdb)" per database
+    pub fn describe(&self) -> Vec<String> {
+        self.sources.iter().map(|(path, source)| match source {
+            Source::Mmdb(db) if !db.database_type().is_empty() => format!("{} ({})", db.database_type(), path.display()),
+            Source::Mmdb(_) => format!("MaxMind DB ({})", path.display()),
+            Source::Csv(table) => format!("IP2Location CSV, {} ranges ({})", table.ranges.len(), path.display()),
+        }).collect()
+    }
+    
+    /// Ask every database, earlier ones winning; ranges and hostnames have no location
+    pub fn lookup(&self, ip: &str) -> GeoInfo {
+        let mut info = GeoInfo::default();
+        let Ok(addr) = ip.trim().parse::<IpAddr>() else {
+            return info;
+        };
+        for (path, source) in &self.sources {
+            match source {
+                Source::Mmdb(db) => match db.lookup(addr) {
+                    Ok(Some(record)) => info.merge(from_record(&record)),
+                    Ok(None) => {},
+                    Err(e) => log::debug!("GeoIP lookup of {} in {} failed: {}", ip, path.display(), e),
+                },
+                Source::Csv(table) => {
+                    if let Some(found) = table.lookup(addr) {
+                        info.merge(found.clone());
+                    }
+                },
+            }
+        }
+        info
+    }
+    
+    /// Like `lookup`, remembering the answer
+    pub fn cached(&mut self, ip: &str) -> GeoInfo {
+        if let Some(info) = self.cache.get(ip) {
+            return info.clone();
+        }
+        if self.cache.len() >= MAX_CACHED {
+            self.cache.clear();
+        }
+        let info = self.lookup(ip);
+        self.cache.insert(ip.to_string(), info.clone());
+        info
+    }
+    
+    pub fn cached_count(&self) -> usize {
+        self.cache.len()
+    }
+}
+
+/// GeoLite2/GeoIP2 Country, City and ASN record layouts
+fn from_record(record: &MmdbValue) -> GeoInfo {
+    let text = |path: &[&str]| record.get(path).and_then(MmdbValue::as_str).map(str::to_string);
+    GeoInfo {
+        country: text(&["country", "iso_code"]).or_else(|| text(&["registered_country", "iso_code"])),
+        country_name: text(&["country", "names", "en"]).or_else(|| text(&["registered_country", "names", "en"])),
+        asn: record.get(&["autonomous_system_number"]).and_then(MmdbValue::as_u64).and_then(|n| u32::try_from(n).ok()),
+        org: text(&["autonomous_system_organization"]),
+    }
+}
+
+/// Split one CSV line, honouring double quotes ("Korea, Republic of")
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                push_char(&mut fields, '"');
+            },
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => push_char(&mut fields, c),
+        }
+    }
+    fields
+}
+
+fn push_char(fields: &mut [String], c: char) {
+    if let Some(field) = fields.last_mut() {
+        field.push(c);
+    }
+}
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use crate::app::{LogEntry, ServiceStatus};
+use crate::services::fail2ban_client::Fail2banClient;
+
+/// ERROR entries kept for the health view
+pub const RECENT_ERROR_LIMIT: usize = 10;
+
+/// A ping slower than this marks the daemon as degraded
+const SLOW_PING: Duration = Duration::from_secs(1);
+
+/// Errors this recent mark the daemon as degraded
+const ERROR_WINDOW_MINUTES: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthLevel {
+    Healthy,
+    Degraded,  // Answers, but slowly or while logging errors
+    Critical,  // Failed, or running with a dead socket
+    Stopped,
+    Unknown,   // Not probed, e.g. read-only without socket access
+}
+
+/// Daemon-wide settings read back from the running server
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DaemonInfo {
+    pub version: Option<String>,
+    pub dbfile: Option<String>,
+    pub dbpurgeage: Option<String>,
+    pub loglevel: Option<String>,
+    pub logtarget: Option<String>,
+}
+
+impl DaemonInfo {
+    pub fn query(client: &Fail2banClient) -> Self {
+        let setting = |name: &str| client.get_server_setting(name)
+            .map_err(|e| log::warn!("Failed to read fail2ban {}: {}", name, e))
+            .ok();
+        Self {
+            version: client.version().map_err(|e| log::warn!("Failed to read fail2ban version: {}", e)).ok(),
+            dbfile: setting("dbfile"),
+            dbpurgeage: setting("dbpurgeage"),
+            loglevel: setting("loglevel"),
+            logtarget: setting("logtarget"),
+        }
+    }
+}
+
+/// Service manager state combined with what the daemon itself says
+#[derive(Debug, Clone)]
+pub struct ServiceHealth {
+    pub service: ServiceStatus,
+    pub ping: Option<std::result::Result<Duration, String>>,  // None when not probed
+    pub daemon: DaemonInfo,
+    pub recent_errors: Vec<LogEntry>,  // Newest first
+    pub checked_at: Option<chrono::DateTime<chrono::Local>>,
+}
+
+impl Default for ServiceHealth {
+    fn default() -> Self {
+        Self {
+            service: ServiceStatus::Unknown,
+            ping: None,
+            daemon: DaemonInfo::default(),
+            recent_errors: Vec::new(),
+            checked_at: None,
+        }
+    }
+}
+
+impl ServiceHealth {
+    pub fn responding(&self) -> bool {
+        matches!(self.ping, Some(Ok(_)))
+    }
+    
+    pub fn level(&self) -> HealthLevel {
+        match (&self.service, &self.ping) {
+            (ServiceStatus::Failed, _) => HealthLevel::Critical,
+            (ServiceStatus::Running, Some(Err(_))) => HealthLevel::Critical,
+            (_, Some(Ok(latency))) if *latency > SLOW_PING || self.recent_error_count() > 0 => HealthLevel::Degraded,
+            // A container without a service manager is judged by the socket alone
+            (_, Some(Ok(_))) => HealthLevel::Healthy,
+            (ServiceStatus::Stopped, _) => HealthLevel::Stopped,
+            (ServiceStatus::Unknown, Some(Err(_))) => HealthLevel::Stopped,
+            _ => HealthLevel::Unknown,
+        }
+    }
+    
+    /// ERROR entries within the last hour
+    pub fn recent_error_count(&self) -> usize {
+        let since = chrono::Utc::now() - chrono::Duration::minutes(ERROR_WINDOW_MINUTES);
+        self.recent_errors.iter().filter(|entry| entry.timestamp >= since).count()
+    }
+    
+    /// Short text for the header badge
+    pub fn headline(&self) -> String {
+        match (self.level(), &self.ping) {
+            (HealthLevel::Critical, Some(Err(_))) if matches!(self.service, ServiceStatus::Running) => "SOCKET DEAD".to_string(),
+            (HealthLevel::Critical, _) => "FAILED".to_string(),
+            (HealthLevel::Degraded, Some(Ok(latency))) if *latency > SLOW_PING => format!("slow {:.1}s", latency.as_secs_f64()),
+            (HealthLevel::Degraded, _) => {
+                let count = self.recent_error_count();
+                format!("{} error{}", count, if count == 1 { "" } else { "s" })
+            },
+            (HealthLevel::Healthy, Some(Ok(latency))) => format!("ok {}ms", latency.as_millis()),
+            (HealthLevel::Stopped, _) => "stopped".to_string(),
+            _ => "unknown".to_string(),
+        }
+    }
+    
+    /// One sentence explaining the level
+    pub fn explanation(&self) -> String {
+        match (self.level(), &self.ping) {
+            (HealthLevel::Critical, Some(Err(e))) if matches!(self.service, ServiceStatus::Running) =>
+                format!("Service is running but the socket does not answer: {}", e),
+            (HealthLevel::Critical, _) => "The service manager reports the service as failed".to_string(),
+            (HealthLevel::Degraded, Some(Ok(latency))) if *latency > SLOW_PING =>
+                format!("Socket answers slowly ({:.1}s round trip)", latency.as_secs_f64()),
+            (HealthLevel::Degraded, _) => format!("{} ERROR entries in the log within the last hour", self.recent_error_count()),
+            (HealthLevel::Healthy, _) => "Service running and socket answering".to_string(),
+            (HealthLevel::Stopped, _) => "fail2ban is not running".to_string(),
+            _ => "Socket not probed".to_string(),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::path::{Path, PathBuf};
+
+use crate::services::config_validator::{ConfigValidator, IssueSeverity};
+use crate::utils::config_writer::write_config_file;
+use crate::utils::errors::{AppError, ConfigError, Result};
+
+/// Log files tried in order for services that log through syslog
+const AUTH_LOGS: [&str; 2] = ["/var/log/auth.log", "/var/log/secure"];
+const MAIL_LOGS: [&str; 2] = ["/var/log/mail.log", "/var/log/maillog"];
+const NGINX_ERROR_LOGS: [&str; 1] = ["/var/log/nginx/error.log"];
+const NGINX_ACCESS_LOGS: [&str; 1] = ["/var/log/nginx/access.log"];
+
+/// Suggested settings for a jail using a given filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JailTemplate {
+    pub port: String,
+    pub logpath: String,
+    pub backend: String,
+}
+
+/// Defaults for the common services; anything else gets an empty template.
+/// Services that only log to the journal on this host get the systemd backend.
+pub fn template_for(filter: &str) -> JailTemplate {
+    let (port, logs): (&str, &[&str]) = match filter {
+        "sshd" => ("ssh", &AUTH_LOGS),
+        "nginx-botsearch" => ("http,https", &NGINX_ACCESS_LOGS),
+        f if f.starts_with("nginx") => ("http,https", &NGINX_ERROR_LOGS),
+        f if f.starts_with("postfix") => ("smtp,465,submission", &MAIL_LOGS),
+        "dovecot" => ("pop3,pop3s,imap,imaps,submission,465,sieve", &MAIL_LOGS),
+        _ => ("", &[]),
+    };
+    
+    let existing = logs.iter().find(|path| Path::new(path).exists());
+    let (logpath, backend) = match existing {
+        Some(path) => (path.to_string(), "auto"),
+        None if !logs.is_empty() && Path::new("/run/systemd/journal").exists() => (String::new(), "systemd"),
+        None => (logs.first().map(|p| p.to_string()).unwrap_or_default(), "auto"),
+    };
+    
+    JailTemplate { port: port.to_string(), logpath, backend: backend.to_string() }
+}
+
+/// Where the wizard writes the new section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JailDestination {
+    JailLocal,  // Appended to jail.local
+    DropIn,     // jail.d/<name>.local
+}
+
+/// A jail to be created by the new jail wizard
+#[derive(Debug, Clone)]
+pub struct NewJail {
+    pub name: String,
+    pub filter: String,
+    pub port: String,
+    pub logpath: String,
+    pub backend: String,
+    pub maxretry: String,
+    pub bantime: String,
+    pub destination: JailDestination,
+}
+
+impl NewJail {
+    /// Start from the template for `filter`, named after it
+    pub fn from_filter(filter: &str) -> Self {
+        let template = template_for(filter);
+        Self {
+            name: filter.to_string(),
+            filter: filter.to_string(),
+            port: template.port,
+            logpath: template.logpath,
+            backend: template.backend,
+            maxretry: "5".to_string(),
+            bantime: "10m".to_string(),
+            destination: JailDestination::DropIn,
+        }
+    }
+    
+    /// Switch to another filter, keeping the name if the user changed it
+    pub fn apply_filter(&mut self, filter: &str) {
+        let keep_name = !self.name.is_empty() && self.name != self.filter;
+        let template = template_for(filter);
+        if !keep_name {
+            self.name = filter.to_string();
+        }
+        self.filter = filter.to_string();
+        self.port = template.port;
+        self.logpath = template.logpath;
+        self.backend = template.backend;
+    }
+    
+    /// The section as written to disk; empty fields are left out
+    pub fn section_text(&self) -> String {
+        let mut text = format!("[{}]\nenabled = true\nfilter = {}\n", self.name.trim(), self.filter);
+        let fields = [
+            ("port", &self.port),
+            ("logpath", &self.logpath),
+            ("backend", &self.backend),
+            ("maxretry", &self.maxretry),
+            ("bantime", &self.bantime),
+        ];
+        for (key, value) in fields {
+            if !value.trim().is_empty() {
+                text.push_str(&format!("{} = {}\n", key, value.trim()));
+            }
+        }
+        text
+    }
+    
+    pub fn target_path(&self, config_dir: &Path) -> PathBuf {
+        match self.destination {
+            JailDestination::JailLocal => config_dir.join("jail.local"),
+            JailDestination::DropIn => config_dir.join("jail.d").join(format!("{}.local", self.name.trim())),
+        }
+    }
+    
+    /// Problems that would stop the jail from starting (empty when it is fine to write)
+    pub fn validate(&self, validator: &ConfigValidator) -> Vec<String> {
+        let mut problems = Vec::new();
+        let name = self.name.trim();
+        
+        if name.is_empty() {
+            problems.push("jail name is required".to_string());
+        } else if !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+            problems.push(format!("jail name '{}' may only use letters, digits, '-', '_' and '.'", name));
+        } else if matches!(name, "DEFAULT" | "INCLUDES" | "Definition") {
+            problems.push(format!("'{}' is a reserved section name", name));
+        } else if validator.local_jail_names().iter().any(|existing| existing == name) {
+            problems.push(format!("jail '{}' is already defined in jail.local or jail.d", name));
+        }
+        
+        if self.destination == JailDestination::DropIn && self.target_path(validator.config_dir()).exists() {
+            problems.push(format!("{} already exists", self.target_path(validator.config_dir()).display()));
+        }
+        
+        if self.filter.is_empty() {
+            problems.push("choose a filter".to_string());
+        }
+        
+        let logpath = self.logpath.trim();
+        if self.backend.trim().starts_with("systemd") {
+            // The journal is read instead of logpath
+        } else if logpath.is_empty() {
+            problems.push("logpath is required unless backend is systemd".to_string());
+        } else {
+            problems.extend(logpath.split_whitespace()
+                .filter(|path| !ConfigValidator::log_path_exists(path))
+                .map(|path| format!("log file not found: {}", path)));
+        }
+        
+        // Value checks (maxretry, bantime, backend, filter reference) are shared with the editor lint
+        let target = self.target_path(validator.config_dir()).display().to_string();
+        problems.extend(validator.lint_content(&self.section_text(), &target, None).into_iter()
+            .filter(|issue| issue.severity == IssueSeverity::Error)
+            .map(|issue| issue.message));
+        
+        problems
+    }
+    
+    /// Write the section to its destination and return the file written
+    pub fn write(&self, config_dir: &Path) -> Result<PathBuf> {
+        let path = self.target_path(config_dir);
+        let content = match self.destination {
+            JailDestination::JailLocal => {
+                let existing = std::fs::read_to_string(&path).unwrap_or_default();
+                let separator = match existing.as_str() {
+                    "" => "",
+                    e if e.ends_with("\n\n") => "",
+                    e if e.ends_with('\n') => "\n",
+                    _ => "\n\n",
+                };
+                format!("{}{}{}", existing, separator, self.section_text())
+            },
+            JailDestination::DropIn => {
+                if path.exists() {
+                    return Err(AppError::Config(ConfigError::InvalidFile(
+                        format!("{} already exists", path.display())
+                    )));
+                }
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                self.section_text()
+            },
+        };
+        
+        write_config_file(&path, &content)?;
+        log::info!("Created jail [{}] in {}", self.name.trim(), path.display());
+        Ok(path)
+    }
+}
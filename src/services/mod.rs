@@ -1,4 +1,21 @@
 // System integration layer
+pub mod audit_log;
+pub mod ban_query;
+pub mod ban_set;
+pub mod config_validator;
+pub mod definition_tree;
+pub mod event_history;
+pub mod exporter;
 pub mod fail2ban_client;
+pub mod geoip;
+pub mod health;
+pub mod jail_templates;
 pub mod file_monitor;
-pub mod system_service;
\ No newline at end of file
+pub mod notifications;
+pub mod reverse_dns;
+pub mod service_backend;
+pub mod statistics;
+pub mod subnets;
+pub mod system_service;
+pub mod systemd_dbus;
+pub mod table_sort;
\ No newline at end of file
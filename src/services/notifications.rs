@@ -0,0 +1,268 @@
+use std::collections::VecDeque;
+use std::io::Write;
+use std::os::unix::net::UnixDatagram;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::app::LogEntry;
+use crate::services::file_monitor::{ban_event, BanAction, BanEvent};
+use crate::utils::settings::{NotificationRule, NotificationSettings, RuleEvent};
+
+/// Addresses named in a message; the payload carries all of them
+const LISTED_ADDRESSES: usize = 5;
+
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// A rule that went past its threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub rule: String,
+    pub action: BanAction,
+    pub jails: Vec<String>,
+    pub addresses: Vec<String>,
+    pub window_secs: u64,
+    pub suppressed: usize,  // Triggers swallowed by the cooldown since the last one sent
+}
+
+impl Notification {
+    pub fn text(&self) -> String {
+        let verb = match self.action {
+            BanAction::Ban => "bans",
+            BanAction::Unban => "unbans",
+        };
+        let mut listed = self.addresses.iter().take(LISTED_ADDRESSES).cloned().collect::<Vec<_>>().join(", ");
+        if self.addresses.len() > LISTED_ADDRESSES {
+            listed.push_str(&format!(", +{} more", self.addresses.len() - LISTED_ADDRESSES));
+        }
+        let mut text = format!(
+            "{} on {}: {} {} in {} within {} ({})",
+            self.rule, hostname(), self.addresses.len(), verb, self.jails.join(", "), format_window(self.window_secs), listed
+        );
+        if self.suppressed > 0 {
+            text.push_str(&format!(" - {} earlier trigger(s) suppressed by the cooldown", self.suppressed));
+        }
+        text
+    }
+    
+    /// Slack and Mattermost read `text` and `username` and ignore the rest
+    pub fn payload(&self) -> serde_json::Value {
+        serde_json::json!({
+            "text": self.text(),
+            "username": "f2b-buxjr",
+            "rule": self.rule,
+            "event": match self.action { BanAction::Ban => "ban", BanAction::Unban => "unban" },
+            "jails": self.jails,
+            "addresses": self.addresses,
+            "count": self.addresses.len(),
+            "window_secs": self.window_secs,
+            "host": hostname(),
+        })
+    }
+}
+
+#[derive(Default)]
+struct RuleState {
+    window: VecDeque<(Instant, BanEvent)>,  // Matching events, one per jail/address/action
+    last_sent: Option<Instant>,
+    suppressed: usize,
+}
+
+/// Evaluates notification rules against Ban/Unban log lines and delivers
+/// the ones that fire on background threads.
+///
+/// Lines already in the log when monitoring starts (the first batch) are
+/// history and never trigger anything.
+pub struct Notifier {
+    settings: NotificationSettings,
+    states: Vec<RuleState>,
+    primed: bool,
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        Self::new(NotificationSettings::default())
+    }
+}
+
+impl Notifier {
+    pub fn new(settings: NotificationSettings) -> Self {
+        let states = settings.rules.iter().map(|_| RuleState::default()).collect();
+        Self {
+            settings,
+            states,
+            primed: false,
+        }
+    }
+    
+    pub fn rule_count(&self) -> usize {
+        self.settings.rules.len()
+    }
+    
+    /// Feed new log lines; returns what was sent
+    pub fn observe(&mut self, entries: &[LogEntry]) -> Vec<Notification> {
+        let fired = self.evaluate(entries, Instant::now());
+        for (index, notification) in &fired {
+            let rule = self.settings.rules[*index].clone();
+            let syslog_socket = self.settings.syslog_socket.clone();
+            let notification = notification.clone();
+            std::thread::spawn(move || {
+                for result in deliver(&rule, &syslog_socket, &notification) {
+                    if let Err(e) = result {
+                        log::warn!("Notification '{}' not delivered: {}", rule.name, e);
+                    }
+                }
+            });
+        }
+        fired.into_iter().map(|(_, notification)| notification).collect()
+    }
+    
+    /// Rule evaluation without delivery. `entries` come newest first, as
+    /// `LogMonitor` returns them.
+    pub fn observe_at(&mut self, entries: &[LogEntry], now: Instant) -> Vec<Notification> {
+        self.evaluate(entries, now).into_iter().map(|(_, notification)| notification).collect()
+    }
+    
+    fn evaluate(&mut self, entries: &[LogEntry], now: Instant) -> Vec<(usize, Notification)> {
+        if !self.primed {
+            self.primed = true;
+            return Vec::new();
+        }
+        
+        let mut notifications = Vec::new();
+        for event in entries.iter().rev().filter_map(|entry| ban_event(&entry.message)) {
+            for (index, (rule, state)) in self.settings.rules.iter().zip(self.states.iter_mut()).enumerate() {
+                if !matches_rule(rule, &event) {
+                    continue;
+                }
+                let window = Duration::from_secs(rule.window_secs);
+                state.window.retain(|(seen, _)| now.duration_since(*seen) < window);
+                // The same address banned again within the window counts once
+                if state.window.iter().any(|(_, seen)| *seen == event) {
+                    continue;
+                }
+                state.window.push_back((now, event.clone()));
+                if state.window.len() <= rule.threshold {
+                    continue;
+                }
+                
+                let events = state.window.drain(..).map(|(_, event)| event).collect::<Vec<_>>();
+                let cooling = state.last_sent
+                    .is_some_and(|sent| now.duration_since(sent) < Duration::from_secs(rule.cooldown_secs));
+                if cooling {
+                    state.suppressed += 1;
+                    log::info!("Notification '{}' suppressed by its cooldown", rule.name);
+                    continue;
+                }
+                
+                let mut jails = events.iter().map(|event| event.jail.clone()).collect::<Vec<_>>();
+                jails.sort();
+                jails.dedup();
+                notifications.push((index, Notification {
+                    rule: rule.name.clone(),
+                    action: event.action,
+                    jails,
+                    addresses: events.into_iter().map(|event| event.ip).collect(),
+                    window_secs: rule.window_secs,
+                    suppressed: std::mem::take(&mut state.suppressed),
+                }));
+                state.last_sent = Some(now);
+                log::info!("Notification '{}' fired", rule.name);
+            }
+        }
+        notifications
+    }
+}
+
+fn matches_rule(rule: &NotificationRule, event: &BanEvent) -> bool {
+    let action = matches!(
+        (rule.event, event.action),
+        (RuleEvent::Any, _) | (RuleEvent::Ban, BanAction::Ban) | (RuleEvent::Unban, BanAction::Unban)
+    );
+    action && rule.jail.as_ref().map_or(true, |jail| *jail == event.jail)
+}
+
+/// Send `notification` through every channel `rule` names, waiting for each
+pub fn deliver(rule: &NotificationRule, syslog_socket: &str, notification: &Notification) -> Vec<std::result::Result<(), String>> {
+    let mut results = Vec::new();
+    if let Some(ref url) = rule.webhook {
+        results.push(post_webhook(url, &notification.payload()));
+    }
+    if let Some(ref command) = rule.command {
+        results.push(run_command(command, notification));
+    }
+    if rule.syslog {
+        results.push(send_syslog(syslog_socket, notification));
+    }
+    results
+}
+
+/// POST through curl, which brings TLS and proxy support along
+fn post_webhook(url: &str, payload: &serde_json::Value) -> std::result::Result<(), String> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--max-time", &WEBHOOK_TIMEOUT_SECS.to_string()])
+        .args(["-X", "POST", "-H", "Content-Type: application/json", "--data-binary", "@-", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(payload.to_string().as_bytes()).map_err(|e| format!("curl: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("curl: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("webhook {}: {}", url, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+fn run_command(command: &str, notification: &Notification) -> std::result::Result<(), String> {
+    let output = Command::new("sh")
+        .args(["-c", command])
+        .env("F2B_RULE", &notification.rule)
+        .env("F2B_EVENT", match notification.action { BanAction::Ban => "ban", BanAction::Unban => "unban" })
+        .env("F2B_JAILS", notification.jails.join(" "))
+        .env("F2B_ADDRESSES", notification.addresses.join(" "))
+        .env("F2B_COUNT", notification.addresses.len().to_string())
+        .env("F2B_MESSAGE", notification.text())
+        .env("F2B_PAYLOAD", notification.payload().to_string())
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("{}: {}", command, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("{}: {} {}", command, output.status, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// RFC 3164 datagram, facility auth: warning for bans, notice for unbans
+fn send_syslog(socket: &str, notification: &Notification) -> std::result::Result<(), String> {
+    let priority = match notification.action {
+        BanAction::Ban => 4 * 8 + 4,
+        BanAction::Unban => 4 * 8 + 5,
+    };
+    let message = format!(
+        "<{}>{} f2b-buxjr[{}]: {}",
+        priority, chrono::Local::now().format("%b %e %H:%M:%S"), std::process::id(), notification.text()
+    );
+    UnixDatagram::unbound()
+        .and_then(|sock| sock.send_to(message.as_bytes(), socket))
+        .map(|_| ())
+        .map_err(|e| format!("syslog {}: {}", socket, e))
+}
+
+fn hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|name| name.trim().to_string())
+        .unwrap_or_else(|_| "localhost".to_string())
+}
+
+fn format_window(secs: u64) -> String {
+    match secs {
+        s if s % 3600 == 0 => format!("{}h", s / 3600),
+        s if s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
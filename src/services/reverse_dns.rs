@@ -0,0 +1,192 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::IpAddr;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use crate::utils::settings::RdnsSettings;
+
+/// Addresses waiting for a free worker; the oldest requests are dropped first
+const MAX_QUEUED: usize = 256;
+
+/// Cached answers kept before expired ones are swept
+const MAX_CACHED: usize = 50_000;
+
+/// What is known about an address's PTR name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hostname {
+    Pending,
+    Found(String),
+    NotFound,  // No PTR record, resolver error or timeout
+}
+
+struct CachedName {
+    name: Option<String>,
+    expires: Instant,
+}
+
+/// PTR lookups through the system resolver (`getent hosts` by default, so
+/// /etc/hosts and nsswitch.conf apply), run on background threads so the UI
+/// never waits. At most `workers` lookups run at once; answers, including
+/// failures, are cached for `ttl_secs`.
+pub struct ReverseDns {
+    settings: RdnsSettings,
+    cache: HashMap<IpAddr, CachedName>,
+    queue: VecDeque<IpAddr>,
+    in_flight: HashSet<IpAddr>,
+    results_tx: Sender<(IpAddr, Option<String>)>,
+    results_rx: Receiver<(IpAddr, Option<String>)>,
+}
+
+impl Default for ReverseDns {
+    fn default() -> Self {
+        Self::new(RdnsSettings::default())
+    }
+}
+
+impl ReverseDns {
+    pub fn new(settings: RdnsSettings) -> Self {
+        let (results_tx, results_rx) = channel();
+        Self {
+            settings,
+            cache: HashMap::new(),
+            queue: VecDeque::new(),
+            in_flight: HashSet::new(),
+            results_tx,
+            results_rx,
+        }
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        self.settings.enabled
+    }
+    
+    /// The cached name, or Pending after queueing a lookup; ranges and hostnames are NotFound
+    pub fn lookup(&mut self, ip: &str) -> Hostname {
+        if !self.settings.enabled {
+            return Hostname::NotFound;
+        }
+        let Ok(addr) = ip.trim().parse::<IpAddr>() else {
+            return Hostname::NotFound;
+        };
+        if let Some(known) = self.fresh(addr) {
+            return known;
+        }
+        if !self.in_flight.contains(&addr) && !self.queue.contains(&addr) {
+            if self.queue.len() >= MAX_QUEUED {
+                self.queue.pop_front();
+            }
+            self.queue.push_back(addr);
+        }
+        self.dispatch();
+        Hostname::Pending
+    }
+    
+    /// Like `lookup` without starting anything
+    pub fn peek(&self, ip: &str) -> Hostname {
+        match ip.trim().parse::<IpAddr>() {
+            Ok(addr) if self.settings.enabled => self.fresh(addr).unwrap_or(Hostname::Pending),
+            _ => Hostname::NotFound,
+        }
+    }
+    
+    fn fresh(&self, addr: IpAddr) -> Option<Hostname> {
+        self.cache.get(&addr)
+            .filter(|cached| cached.expires > Instant::now())
+            .map(|cached| match cached.name {
+                Some(ref name) => Hostname::Found(name.clone()),
+                None => Hostname::NotFound,
+            })
+    }
+    
+    /// Collect finished lookups and start queued ones; returns the number collected
+    pub fn poll(&mut self) -> usize {
+        let mut collected = 0;
+        while let Ok((addr, name)) = self.results_rx.try_recv() {
+            self.in_flight.remove(&addr);
+            if self.cache.len() >= MAX_CACHED {
+                let now = Instant::now();
+                self.cache.retain(|_, cached| cached.expires > now);
+                if self.cache.len() >= MAX_CACHED {
+                    self.cache.clear();
+                }
+            }
+            self.cache.insert(addr, CachedName {
+                name,
+                expires: Instant::now() + Duration::from_secs(self.settings.ttl_secs),
+            });
+            collected += 1;
+        }
+        self.dispatch();
+        collected
+    }
+    
+    /// Most recently requested first, so the rows on screen resolve before ones scrolled past
+    fn dispatch(&mut self) {
+        while self.in_flight.len() < self.settings.workers {
+            let Some(addr) = self.queue.pop_back() else {
+                break;
+            };
+            self.in_flight.insert(addr);
+            let command = self.settings.command.clone();
+            let timeout = Duration::from_millis(self.settings.timeout_ms);
+            let results = self.results_tx.clone();
+            std::thread::spawn(move || {
+                let name = resolve(&command, addr, timeout);
+                let _ = results.send((addr, name));
+            });
+        }
+    }
+    
+    pub fn cached_count(&self) -> usize {
+        self.cache.len()
+    }
+    
+    pub fn pending_count(&self) -> usize {
+        self.queue.len() + self.in_flight.len()
+    }
+}
+
+/// Run the resolver command with the address as its last argument and take
+/// the first name from "<address> <name> [aliases...]"
+pub fn resolve(command: &str, addr: IpAddr, timeout: Duration) -> Option<String> {
+    let mut words = command.split_whitespace();
+    let mut child = Command::new(words.next()?)
+        .args(words)
+        .arg(addr.to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| log::debug!("Reverse DNS command '{}' failed to start: {}", command, e))
+        .ok()?;
+    
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() < timeout => std::thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                log::debug!("Reverse DNS lookup of {} timed out", addr);
+                return None;
+            },
+        }
+    }
+    
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_hosts_line(&String::from_utf8_lossy(&output.stdout), addr)
+}
+
+/// The name in a hosts(5)-style line, unless it is just the address again
+pub fn parse_hosts_line(output: &str, addr: IpAddr) -> Option<String> {
+    let name = output.lines().next()?.split_whitespace().nth(1)?.trim_end_matches('.');
+    if name.is_empty() || name.parse::<IpAddr>() == Ok(addr) {
+        return None;
+    }
+    Some(name.to_string())
+}
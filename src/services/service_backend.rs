@@ -0,0 +1,300 @@
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use zbus::zvariant::OwnedObjectPath;
+
+use crate::app::{ServiceAction, ServiceStatus};
+use crate::services::fail2ban_client::client_command;
+use crate::utils::paths::fail2ban_paths;
+
+/// Names accepted by --service-manager besides "auto"
+pub const SERVICE_BACKEND_NAMES: [&str; 5] = ["systemd", "openrc", "sysv", "runit", "direct"];
+
+/// runit service directories, in the order distributions use them
+const RUNIT_SERVICE_DIRS: [&str; 3] = ["etc/service", "var/service", "etc/runit/runsvdir/default"];
+
+/// What starts and stops fail2ban on this host
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceBackend {
+    Systemd,  // D-Bus, see systemd_dbus
+    OpenRc,   // rc-service
+    SysV,     // service / /etc/init.d scripts
+    Runit,    // sv
+    Direct,   // fail2ban-client start/stop, for containers without an init system
+}
+
+impl ServiceBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ServiceBackend::Systemd => "systemd",
+            ServiceBackend::OpenRc => "openrc",
+            ServiceBackend::SysV => "sysv",
+            ServiceBackend::Runit => "runit",
+            ServiceBackend::Direct => "direct",
+        }
+    }
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            ServiceBackend::Systemd => "systemd (D-Bus)",
+            ServiceBackend::OpenRc => "OpenRC (rc-service)",
+            ServiceBackend::SysV => "SysV init (service)",
+            ServiceBackend::Runit => "runit (sv)",
+            ServiceBackend::Direct => "fail2ban-client (no init system)",
+        }
+    }
+    
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "systemd" => Some(ServiceBackend::Systemd),
+            "openrc" => Some(ServiceBackend::OpenRc),
+            "sysv" => Some(ServiceBackend::SysV),
+            "runit" => Some(ServiceBackend::Runit),
+            "direct" => Some(ServiceBackend::Direct),
+            _ => None,
+        }
+    }
+    
+    pub fn detect(service_name: &str) -> Self {
+        Self::detect_at(Path::new("/"), service_name)
+    }
+    
+    /// Detect the init system of the tree under `root`.
+    ///
+    /// systemd and OpenRC leave runtime directories behind once booted; runit
+    /// and SysV are recognised by a service directory or init script for
+    /// `service_name`. Anything else is treated as a container without init.
+    pub fn detect_at(root: &Path, service_name: &str) -> Self {
+        if root.join("run/systemd/system").is_dir() {
+            ServiceBackend::Systemd
+        } else if root.join("run/openrc").is_dir() {
+            ServiceBackend::OpenRc
+        } else if RUNIT_SERVICE_DIRS.iter().any(|dir| root.join(dir).join(service_name).exists()) {
+            ServiceBackend::Runit
+        } else if root.join("etc/init.d").join(service_name).exists() {
+            ServiceBackend::SysV
+        } else {
+            ServiceBackend::Direct
+        }
+    }
+    
+    /// Commands carrying out `action`, run in order until one fails.
+    /// Empty for systemd, which is driven over D-Bus instead.
+    pub fn action_commands(&self, service_name: &str, action: &ServiceAction) -> Vec<Command> {
+        let verb = match action {
+            ServiceAction::Start => "start",
+            ServiceAction::Stop => "stop",
+            ServiceAction::Restart => "restart",
+            ServiceAction::Reload => "reload",
+        };
+        match (self, action) {
+            (ServiceBackend::Systemd, _) => Vec::new(),
+            (ServiceBackend::OpenRc, _) => vec![command("rc-service", &[service_name, verb])],
+            (ServiceBackend::SysV, _) => vec![sysv_command(service_name, verb)],
+            // runit's reload only sends SIGHUP; ask the server itself to re-read its configuration
+            (ServiceBackend::Runit, ServiceAction::Reload) => vec![client(&["reload"])],
+            (ServiceBackend::Runit, _) => vec![command("sv", &[verb, service_name])],
+            // fail2ban-client 0.9 has no restart
+            (ServiceBackend::Direct, ServiceAction::Restart) => vec![client(&["stop"]), client(&["start"])],
+            (ServiceBackend::Direct, _) => vec![client(&[verb])],
+        }
+    }
+    
+    /// Command reporting whether the service runs; None for systemd
+    pub fn status_command(&self, service_name: &str) -> Option<Command> {
+        match self {
+            ServiceBackend::Systemd => None,
+            ServiceBackend::OpenRc => Some(command("rc-service", &[service_name, "status"])),
+            ServiceBackend::SysV => Some(sysv_command(service_name, "status")),
+            ServiceBackend::Runit => Some(command("sv", &["status", service_name])),
+            ServiceBackend::Direct => Some(client(&["ping"])),
+        }
+    }
+    
+    /// Map the status command's exit code and output onto systemd's vocabulary
+    pub fn parse_status(&self, exit_code: Option<i32>, output: &str) -> UnitState {
+        let output = output.trim();
+        let (active, sub, failure) = match self {
+            ServiceBackend::Systemd => ("unknown", "unknown".to_string(), None),
+            // " * status: started" / "stopped" / "crashed"
+            ServiceBackend::OpenRc => {
+                let status = output.rsplit(':').next().unwrap_or("").trim().to_string();
+                match status.as_str() {
+                    "started" => ("active", status, None),
+                    "stopped" => ("inactive", status, None),
+                    "crashed" => ("failed", status, Some("crashed".to_string())),
+                    _ if exit_code == Some(0) => ("active", "started".to_string(), None),
+                    _ => ("unknown", status, None),
+                }
+            },
+            // LSB init script exit codes
+            ServiceBackend::SysV => match exit_code {
+                Some(0) => ("active", "running".to_string(), None),
+                Some(1) => ("failed", "dead".to_string(), Some("program is dead but the pid file exists".to_string())),
+                Some(2) => ("failed", "dead".to_string(), Some("program is dead but the lock file exists".to_string())),
+                Some(3) => ("inactive", "dead".to_string(), None),
+                _ => ("unknown", "unknown".to_string(), None),
+            },
+            // "run: fail2ban: (pid 123) 456s; run: log: ..." / "down: fail2ban: 7s, normally up"
+            ServiceBackend::Runit => {
+                let status = output.split(':').next().unwrap_or("").trim().to_string();
+                match status.as_str() {
+                    "run" => ("active", status, None),
+                    "down" | "finish" => ("inactive", status, None),
+                    "fail" => ("failed", status, Some(output.to_string())),
+                    _ => ("unknown", status, None),
+                }
+            },
+            ServiceBackend::Direct if exit_code == Some(0) && output.contains("pong") => ("active", "pong".to_string(), None),
+            ServiceBackend::Direct => ("inactive", "no reply".to_string(), None),
+        };
+        
+        let mut state = UnitState {
+            active_state: active.to_string(),
+            sub_state: sub,
+            main_pid: 0,
+            active_since: None,
+            memory_bytes: None,
+            restarts: None,
+            failure,
+        };
+        if *self == ServiceBackend::Runit && state.active_state == "active" {
+            // "(pid 123) 456s"
+            let service_part = output.split(';').next().unwrap_or("");
+            state.main_pid = between(service_part, "(pid ", ")").and_then(|pid| pid.parse().ok()).unwrap_or(0);
+            state.active_since = between(service_part, ") ", "s")
+                .and_then(|secs| secs.trim().parse::<i64>().ok())
+                .map(|secs| chrono::Local::now() - chrono::Duration::seconds(secs));
+        }
+        state
+    }
+}
+
+fn command(program: &str, args: &[&str]) -> Command {
+    let mut command = Command::new(program);
+    command.args(args);
+    command
+}
+
+fn client(args: &[&str]) -> Command {
+    let mut command = client_command();
+    command.args(args);
+    command
+}
+
+/// `service` where installed (Debian/Devuan), else the init script itself
+fn sysv_command(service_name: &str, verb: &str) -> Command {
+    if on_path("service") {
+        command("service", &[service_name, verb])
+    } else {
+        command(&format!("/etc/init.d/{}", service_name), &[verb])
+    }
+}
+
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+fn between<'a>(text: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let rest = &text[text.find(start)? + start.len()..];
+    Some(&rest[..rest.find(end)?])
+}
+
+/// PID and start time of the server from its pid file, next to the socket
+pub fn pid_file_process() -> Option<(u32, chrono::DateTime<chrono::Local>)> {
+    let pid_file = fail2ban_paths().socket_path().with_file_name("fail2ban.pid");
+    let pid = std::fs::read_to_string(&pid_file).ok()?.trim().parse().ok()?;
+    let started = std::fs::metadata(&pid_file).and_then(|m| m.modified()).ok()?;
+    Some((pid, started.into()))
+}
+
+/// Runtime state of the fail2ban service, in systemd's terms whatever the backend
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitState {
+    pub active_state: String,   // active, inactive, failed, activating, ...
+    pub sub_state: String,      // running, dead, start-pre, ...
+    pub main_pid: u32,
+    pub active_since: Option<chrono::DateTime<chrono::Local>>,
+    pub memory_bytes: Option<u64>,
+    pub restarts: Option<u32>,    // Only systemd counts automatic restarts
+    pub failure: Option<String>,  // Why the last run ended, unless it succeeded
+}
+
+impl UnitState {
+    pub fn service_status(&self) -> ServiceStatus {
+        match self.active_state.as_str() {
+            "active" | "reloading" => ServiceStatus::Running,
+            "inactive" | "deactivating" => ServiceStatus::Stopped,
+            "failed" => ServiceStatus::Failed,
+            _ => ServiceStatus::Unknown,
+        }
+    }
+    
+    pub fn uptime(&self) -> Option<Duration> {
+        if self.active_state != "active" {
+            return None;
+        }
+        self.active_since.and_then(|since| (chrono::Local::now() - since).to_std().ok())
+    }
+    
+    /// Whether the unit ended up where `action` should have left it
+    pub fn satisfies(&self, action: &ServiceAction) -> bool {
+        match action {
+            ServiceAction::Stop => matches!(self.active_state.as_str(), "inactive" | "failed"),
+            _ => self.active_state == "active",
+        }
+    }
+    
+    /// "PID 812, up 3h 12m, 24.1 MB, 0 restarts"
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.main_pid > 0 {
+            parts.push(format!("PID {}", self.main_pid));
+        }
+        if let Some(uptime) = self.uptime() {
+            parts.push(format!("up {}", format_uptime(uptime)));
+        }
+        if let Some(bytes) = self.memory_bytes {
+            parts.push(format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0)));
+        }
+        if let Some(restarts) = self.restarts {
+            parts.push(format!("{} restart{}", restarts, if restarts == 1 { "" } else { "s" }));
+        }
+        parts.join(", ")
+    }
+}
+
+pub fn format_uptime(uptime: Duration) -> String {
+    let minutes = uptime.as_secs() / 60;
+    match (minutes / (24 * 60), minutes / 60 % 24, minutes % 60) {
+        (0, 0, m) => format!("{}m", m),
+        (0, h, m) => format!("{}h {}m", h, m),
+        (d, h, _) => format!("{}d {}h", d, h),
+    }
+}
+
+/// A start/stop/restart/reload submitted to the service manager
+#[derive(Debug, Clone)]
+pub struct ServiceJob {
+    pub action: ServiceAction,
+    pub submitted_at: Instant,
+    pub handle: JobHandle,
+}
+
+#[derive(Debug, Clone)]
+pub enum JobHandle {
+    Systemd(OwnedObjectPath),
+    /// Init commands running on a worker thread; holds their outcome once they exit
+    Script(Arc<Mutex<Option<std::result::Result<(), String>>>>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    Waiting,  // Queued behind other jobs
+    Running,
+    Done,     // Finished - check the unit for the outcome
+}
@@ -0,0 +1,256 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::net::IpAddr;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Timelike, Utc};
+
+use crate::app::BannedIP;
+use crate::services::audit_log::csv_field;
+use crate::services::event_history::{to_log_clock, EventCounts, EventHistory};
+use crate::utils::cidr::Cidr;
+use crate::utils::errors::Result;
+
+/// Rows in the top IP and top prefix tables
+pub const TOP_ROWS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsTable {
+    TopIps,
+    Prefixes,
+    Jails,
+    Hours,
+    RepeatOffenders,
+}
+
+impl StatsTable {
+    pub const ALL: [StatsTable; 5] = [
+        StatsTable::TopIps,
+        StatsTable::Prefixes,
+        StatsTable::Jails,
+        StatsTable::Hours,
+        StatsTable::RepeatOffenders,
+    ];
+    
+    pub fn title(&self) -> &'static str {
+        match self {
+            StatsTable::TopIps => "Top IPs",
+            StatsTable::Prefixes => "Prefixes",
+            StatsTable::Jails => "Jails",
+            StatsTable::Hours => "Hours",
+            StatsTable::RepeatOffenders => "Repeat Offenders",
+        }
+    }
+    
+    /// Used in export file names
+    pub fn slug(&self) -> &'static str {
+        match self {
+            StatsTable::TopIps => "top-ips",
+            StatsTable::Prefixes => "prefixes",
+            StatsTable::Jails => "jails",
+            StatsTable::Hours => "hours",
+            StatsTable::RepeatOffenders => "repeat-offenders",
+        }
+    }
+    
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|t| t == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// One table as shown and exported, with the address each row drills down to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableData {
+    pub headers: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+    pub ips: Vec<Option<String>>,
+}
+
+/// Bans of one address within the window
+#[derive(Debug, Clone, PartialEq)]
+pub struct OffenderStats {
+    pub bans: u64,
+    pub jails: BTreeMap<String, u64>,  // Bans per jail
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+}
+
+/// Bans from the log, plus current bans the log no longer covers, over one window
+#[derive(Debug, Clone, Default)]
+pub struct BanStatistics {
+    offenders: HashMap<String, OffenderStats>,
+    banned_now: BTreeMap<String, u64>,        // Current bans per jail
+    log_counts: BTreeMap<String, EventCounts>, // Unbans and failures per jail
+    hours: [u64; 24],
+}
+
+impl BanStatistics {
+    /// Times are on the log clock, like `EventHistory`
+    pub fn collect(banned_ips: &[BannedIP], history: &EventHistory, window_hours: u32, now: DateTime<Utc>) -> Self {
+        let mut stats = Self::default();
+        let mut logged = HashSet::new();
+        for ban in history.ban_records(window_hours, now) {
+            logged.insert((ban.ip.as_str(), ban.jail.as_str()));
+            stats.add(&ban.ip, &ban.jail, ban.at);
+        }
+        
+        // Bans older than the log file still count while they are in effect
+        let start = now - Duration::hours(window_hours as i64);
+        for banned in banned_ips {
+            *stats.banned_now.entry(banned.jail.clone()).or_default() += 1;
+            let at = to_log_clock(banned.ban_time);
+            if at > start && !logged.contains(&(banned.ip.as_str(), banned.jail.as_str())) {
+                stats.add(&banned.ip, &banned.jail, at);
+            }
+        }
+        
+        stats.log_counts = history.jail_totals(window_hours, now).into_iter().collect();
+        stats
+    }
+    
+    fn add(&mut self, ip: &str, jail: &str, at: DateTime<Utc>) {
+        let offender = self.offenders.entry(ip.to_string()).or_insert_with(|| OffenderStats {
+            bans: 0,
+            jails: BTreeMap::new(),
+            first: at,
+            last: at,
+        });
+        offender.bans += 1;
+        *offender.jails.entry(jail.to_string()).or_default() += 1;
+        offender.first = offender.first.min(at);
+        offender.last = offender.last.max(at);
+        self.hours[at.hour() as usize] += 1;
+    }
+    
+    pub fn offender(&self, ip: &str) -> Option<&OffenderStats> {
+        self.offenders.get(ip)
+    }
+    
+    pub fn total_bans(&self) -> u64 {
+        self.hours.iter().sum()
+    }
+    
+    /// Most bans first, then the most recent
+    fn ranked(&self) -> Vec<(&String, &OffenderStats)> {
+        let mut ranked = self.offenders.iter().collect::<Vec<_>>();
+        ranked.sort_by(|a, b| b.1.bans.cmp(&a.1.bans).then(b.1.last.cmp(&a.1.last)).then(a.0.cmp(b.0)));
+        ranked
+    }
+    
+    pub fn table(&self, kind: StatsTable) -> TableData {
+        let mut table = TableData::default();
+        match kind {
+            StatsTable::TopIps => {
+                table.headers = vec!["IP", "Bans", "Jails", "Last Ban"];
+                for (ip, offender) in self.ranked().into_iter().take(TOP_ROWS) {
+                    table.push(Some(ip), vec![ip.clone(), offender.bans.to_string(), jail_list(offender), format_time(offender.last)]);
+                }
+            },
+            StatsTable::Prefixes => {
+                // (bans, addresses, busiest address and its bans)
+                let mut prefixes: HashMap<String, (u64, u64, &String, u64)> = HashMap::new();
+                for (ip, offender) in self.ranked() {
+                    if let Some(prefix) = network_prefix(ip) {
+                        let entry = prefixes.entry(prefix).or_insert((0, 0, ip, offender.bans));
+                        entry.0 += offender.bans;
+                        entry.1 += 1;
+                    }
+                }
+                let mut prefixes = prefixes.into_iter().collect::<Vec<_>>();
+                prefixes.sort_by(|a, b| b.1.0.cmp(&a.1.0).then(b.1.1.cmp(&a.1.1)).then(a.0.cmp(&b.0)));
+                table.headers = vec!["Prefix", "Bans", "IPs", "Top IP"];
+                for (prefix, (bans, ips, top, top_bans)) in prefixes.into_iter().take(TOP_ROWS) {
+                    table.push(Some(top), vec![prefix, bans.to_string(), ips.to_string(), format!("{} ({})", top, top_bans)]);
+                }
+            },
+            StatsTable::Jails => {
+                // (bans, addresses)
+                let mut jails: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+                for offender in self.offenders.values() {
+                    for (jail, bans) in &offender.jails {
+                        let entry = jails.entry(jail).or_default();
+                        entry.0 += bans;
+                        entry.1 += 1;
+                    }
+                }
+                for jail in self.banned_now.keys().chain(self.log_counts.keys()) {
+                    jails.entry(jail).or_default();
+                }
+                let mut jails = jails.into_iter().collect::<Vec<_>>();
+                let found = |jail: &str| self.log_counts.get(jail).map_or(0, |counts| counts.found);
+                jails.sort_by(|a, b| b.1.0.cmp(&a.1.0).then(found(b.0).cmp(&found(a.0))).then(a.0.cmp(b.0)));
+                table.headers = vec!["Jail", "Bans", "IPs", "Banned Now", "Unbans", "Found"];
+                for (jail, (bans, ips)) in jails {
+                    let counts = self.log_counts.get(jail).copied().unwrap_or_default();
+                    table.push(None, vec![
+                        jail.to_string(),
+                        bans.to_string(),
+                        ips.to_string(),
+                        self.banned_now.get(jail).copied().unwrap_or(0).to_string(),
+                        counts.unbans.to_string(),
+                        counts.found.to_string(),
+                    ]);
+                }
+            },
+            StatsTable::Hours => {
+                let total = self.total_bans().max(1) as f64;
+                table.headers = vec!["Hour", "Bans", "Share"];
+                for (hour, bans) in self.hours.iter().enumerate() {
+                    table.push(None, vec![format!("{:02}:00", hour), bans.to_string(), format!("{:.1}%", *bans as f64 * 100.0 / total)]);
+                }
+            },
+            StatsTable::RepeatOffenders => {
+                // Banned in several jails first, then by ban count
+                let mut repeat = self.ranked().into_iter().filter(|(_, offender)| offender.bans > 1).collect::<Vec<_>>();
+                repeat.sort_by_key(|(_, offender)| std::cmp::Reverse(offender.jails.len()));
+                table.headers = vec!["IP", "Bans", "Jails", "First Ban", "Last Ban"];
+                for (ip, offender) in repeat {
+                    table.push(Some(ip), vec![ip.clone(), offender.bans.to_string(), jail_list(offender), format_time(offender.first), format_time(offender.last)]);
+                }
+            },
+        }
+        table
+    }
+}
+
+impl TableData {
+    fn push(&mut self, ip: Option<&String>, row: Vec<String>) {
+        self.ips.push(ip.cloned());
+        self.rows.push(row);
+    }
+}
+
+/// The /24 of an IPv4 or the /64 of an IPv6 address
+pub fn network_prefix(ip: &str) -> Option<String> {
+    let addr: IpAddr = ip.parse().ok()?;
+    Cidr::new(addr, if addr.is_ipv4() { 24 } else { 64 }).map(|range| range.to_string())
+}
+
+/// Write one table as CSV (0600, like the audit export)
+pub fn export_table(table: &TableData, path: &Path) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    
+    let mut content = table.headers.join(",");
+    content.push('\n');
+    for row in &table.rows {
+        content.push_str(&row.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        content.push('\n');
+    }
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+fn jail_list(offender: &OffenderStats) -> String {
+    offender.jails.keys().cloned().collect::<Vec<_>>().join(" ")
+}
+
+fn format_time(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M").to_string()
+}
@@ -0,0 +1,126 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+
+use crate::app::BannedIP;
+use crate::utils::cidr::Cidr;
+
+/// Prefix lengths offered for grouping, widest first
+pub const IPV4_PREFIXES: [u8; 5] = [16, 20, 22, 24, 28];
+pub const IPV6_PREFIXES: [u8; 4] = [32, 48, 56, 64];
+pub const DEFAULT_IPV4_PREFIX: u8 = 24;
+pub const DEFAULT_IPV6_PREFIX: u8 = 64;
+
+/// Banned hosts in one range from which a range ban is suggested
+pub const SUGGEST_MIN_HOSTS: usize = 5;
+
+/// Banned addresses that fall in one range
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubnetGroup {
+    pub range: Cidr,
+    pub host_bans: BTreeMap<IpAddr, BTreeSet<String>>,  // Address -> jails banning it
+    pub range_bans: BTreeMap<Cidr, BTreeSet<String>>,   // Existing range bans inside it -> jails
+}
+
+impl SubnetGroup {
+    pub fn host_count(&self) -> usize {
+        self.host_bans.len()
+    }
+    
+    /// Share of the range's addresses that are banned
+    pub fn density(&self) -> f64 {
+        self.host_count() as f64 / self.range.size() as f64
+    }
+    
+    /// Banned hosts per jail, most first
+    pub fn jails(&self) -> Vec<(String, usize)> {
+        let mut jails: BTreeMap<&str, usize> = BTreeMap::new();
+        for jail in self.host_bans.values().flatten() {
+            *jails.entry(jail).or_default() += 1;
+        }
+        let mut jails = jails.into_iter().map(|(jail, hosts)| (jail.to_string(), hosts)).collect::<Vec<_>>();
+        jails.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        jails
+    }
+    
+    /// Jails that already ban this exact range
+    pub fn banned_as_range_in(&self) -> Vec<String> {
+        self.range_bans.get(&self.range).map(|jails| jails.iter().cloned().collect()).unwrap_or_default()
+    }
+    
+    /// Worth replacing with one range ban
+    pub fn is_suggested(&self) -> bool {
+        self.host_count() >= SUGGEST_MIN_HOSTS && self.banned_as_range_in().is_empty()
+    }
+}
+
+/// Group bans by the given prefix lengths, most banned hosts first. Range bans
+/// narrower than the prefix are listed with their group; wider ones are left out.
+pub fn aggregate(banned_ips: &[BannedIP], ipv4_prefix: u8, ipv6_prefix: u8) -> Vec<SubnetGroup> {
+    let mut groups: BTreeMap<Cidr, SubnetGroup> = BTreeMap::new();
+    for banned in banned_ips {
+        let Some(ban) = Cidr::parse(&banned.ip) else {
+            continue;
+        };
+        let prefix = if ban.network().is_ipv4() { ipv4_prefix } else { ipv6_prefix };
+        if ban.prefix_len() < prefix {
+            continue;
+        }
+        let Some(range) = Cidr::new(ban.network(), prefix) else {
+            continue;
+        };
+        let group = groups.entry(range).or_insert_with(|| SubnetGroup {
+            range,
+            host_bans: BTreeMap::new(),
+            range_bans: BTreeMap::new(),
+        });
+        let jails = if ban.is_host() {
+            group.host_bans.entry(ban.network()).or_default()
+        } else {
+            group.range_bans.entry(ban).or_default()
+        };
+        jails.insert(banned.jail.clone());
+    }
+    let mut groups = groups.into_values().collect::<Vec<_>>();
+    groups.sort_by(|a, b| b.host_count().cmp(&a.host_count()).then(a.range.cmp(&b.range)));
+    groups
+}
+
+/// The prefix length after `current` in `prefixes`
+pub fn next_prefix(prefixes: &[u8], current: u8) -> u8 {
+    let index = prefixes.iter().position(|p| *p == current).map_or(0, |i| i + 1);
+    prefixes[index % prefixes.len()]
+}
+
+/// Whitelist entries that overlap the range; hostnames cannot be checked and are skipped
+pub fn whitelist_overlaps(range: &Cidr, whitelist: &[String]) -> Vec<String> {
+    whitelist.iter()
+        .flat_map(|entry| entry.split_whitespace())
+        .filter(|entry| Cidr::parse(entry).is_some_and(|allowed| allowed.overlaps(range)))
+        .map(|entry| entry.to_string())
+        .collect()
+}
+
+/// One range ban in `jail` that replaces the host bans it covers there
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeBan {
+    pub range: Cidr,
+    pub jail: String,
+    pub replaces: Vec<String>,
+}
+
+/// Plan a range ban, refused when it would cover a whitelisted address
+pub fn plan_range_ban(group: &SubnetGroup, jail: &str, whitelist: &[String]) -> Result<RangeBan, String> {
+    let overlaps = whitelist_overlaps(&group.range, whitelist);
+    if !overlaps.is_empty() {
+        return Err(format!("{} overlaps the whitelist: {}", group.range, overlaps.join(", ")));
+    }
+    let replaces = group.host_bans.iter()
+        .filter(|(_, jails)| jails.contains(jail))
+        .map(|(ip, _)| ip.to_string())
+        .collect::<Vec<_>>();
+    Ok(RangeBan {
+        range: group.range,
+        jail: jail.to_string(),
+        replaces,
+    })
+}
@@ -1,116 +1,161 @@
 use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use crate::utils::errors::{AppError, ServiceError, Result};
-use crate::app::ServiceStatus;
+use crate::app::{ServiceAction, ServiceStatus};
+use crate::services::service_backend::{pid_file_process, JobHandle, JobState, ServiceBackend, ServiceJob, UnitState};
+use crate::services::systemd_dbus::SystemdController;
 
 pub struct SystemService {
     service_name: String,
+    backend: ServiceBackend,
+    controller: Option<SystemdController>,  // systemd only; None when the system bus is unreachable
 }
 
 impl SystemService {
+    /// Manage `service_name` through whichever init system this host runs
     pub fn new(service_name: &str) -> Self {
+        Self::with_backend(ServiceBackend::detect(service_name), service_name)
+    }
+    
+    pub fn with_backend(backend: ServiceBackend, service_name: &str) -> Self {
+        let controller = match backend {
+            ServiceBackend::Systemd => match SystemdController::system(service_name) {
+                Ok(controller) => Some(controller),
+                Err(e) => {
+                    log::warn!("Service control unavailable for {}: {}", service_name, e);
+                    None
+                }
+            },
+            _ => None,
+        };
+        log::info!("Managing {} through {}", service_name, backend.label());
         Self {
             service_name: service_name.to_string(),
+            backend,
+            controller,
+        }
+    }
+    
+    pub fn with_controller(controller: SystemdController) -> Self {
+        Self {
+            service_name: controller.unit_name().to_string(),
+            backend: ServiceBackend::Systemd,
+            controller: Some(controller),
         }
     }
     
+    pub fn backend(&self) -> ServiceBackend {
+        self.backend
+    }
+    
+    fn controller(&self) -> Result<&SystemdController> {
+        self.controller.as_ref().ok_or_else(|| AppError::Service(ServiceError::CommunicationError(
+            format!("systemd is not reachable over D-Bus - cannot manage {}", self.service_name)
+        )))
+    }
+    
     pub fn get_status(&self) -> Result<ServiceStatus> {
-        let output = Command::new("systemctl")
-            .args(["is-active", &self.service_name])
-            .output()
-            .map_err(|e| AppError::Service(ServiceError::CommunicationError(
-                format!("Failed to check service status: {}", e)
-            )))?;
+        Ok(self.unit_state()?.service_status())
+    }
+    
+    /// State, sub-state, PID, uptime, memory, restarts and last failure,
+    /// as far as the backend knows them
+    pub fn unit_state(&self) -> Result<UnitState> {
+        let Some(mut status_command) = self.backend.status_command(&self.service_name) else {
+            return self.controller()?.unit_state();
+        };
         
-        let binding = String::from_utf8_lossy(&output.stdout);
-        let status_str = binding.trim();
+        let output = status_command.output().map_err(|e| AppError::Service(ServiceError::CommunicationError(
+            format!("Failed to run {} status check: {}", self.backend.name(), e)
+        )))?;
+        let text = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+        let mut state = self.backend.parse_status(output.status.code(), &text);
         
-        match status_str {
-            "active" => Ok(ServiceStatus::Running),
-            "inactive" => Ok(ServiceStatus::Stopped),
-            "failed" => Ok(ServiceStatus::Failed),
-            _ => Ok(ServiceStatus::Unknown),
+        if state.active_state == "active" && state.main_pid == 0 {
+            if let Some((pid, started)) = pid_file_process() {
+                state.main_pid = pid;
+                state.active_since = Some(started);
+            }
         }
+        Ok(state)
     }
     
-    pub fn start(&self) -> Result<()> {
-        let output = Command::new("sudo")
-            .args(["systemctl", "start", &self.service_name])
-            .output()
-            .map_err(|e| {
-                AppError::Service(ServiceError::OperationFailed(
-                    format!("Failed to start service: {}", e)
-                ))
-            })?;
-        
-        if output.status.success() {
-            Ok(())
+    /// Start `action` without waiting; follow it with `job_state`
+    pub fn submit(&self, action: &ServiceAction) -> Result<ServiceJob> {
+        let handle = if self.backend == ServiceBackend::Systemd {
+            JobHandle::Systemd(self.controller()?.submit(action)?)
         } else {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            Err(AppError::Service(ServiceError::OperationFailed(
-                format!("Start failed: {}", error_msg)
-            )))
-        }
+            let commands = self.backend.action_commands(&self.service_name, action);
+            let outcome = Arc::new(Mutex::new(None));
+            let slot = outcome.clone();
+            std::thread::spawn(move || {
+                let result = run_commands(commands);
+                if let Ok(mut slot) = slot.lock() {
+                    *slot = Some(result);
+                }
+            });
+            JobHandle::Script(outcome)
+        };
+        
+        Ok(ServiceJob {
+            action: action.clone(),
+            submitted_at: Instant::now(),
+            handle,
+        })
     }
     
-    pub fn stop(&self) -> Result<()> {
-        let output = Command::new("sudo")
-            .args(["systemctl", "stop", &self.service_name])
-            .output()
-            .map_err(|e| {
-                AppError::Service(ServiceError::OperationFailed(
-                    format!("Failed to stop service: {}", e)
-                ))
-            })?;
-        
-        if output.status.success() {
-            Ok(())
-        } else {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            Err(AppError::Service(ServiceError::OperationFailed(
-                format!("Stop failed: {}", error_msg)
-            )))
+    /// A failed init command is reported as an error carrying its output
+    pub fn job_state(&self, job: &ServiceJob) -> Result<JobState> {
+        match job.handle {
+            JobHandle::Systemd(ref path) => self.controller()?.job_state(path),
+            JobHandle::Script(ref outcome) => match outcome.lock().ok().and_then(|outcome| outcome.clone()) {
+                None => Ok(JobState::Running),
+                Some(Ok(())) => Ok(JobState::Done),
+                Some(Err(e)) => Err(AppError::Service(ServiceError::OperationFailed(e))),
+            },
         }
     }
     
-    pub fn restart(&self) -> Result<()> {
-        let output = Command::new("sudo")
-            .args(["systemctl", "restart", &self.service_name])
-            .output()
-            .map_err(|e| {
-                AppError::Service(ServiceError::OperationFailed(
-                    format!("Failed to restart service: {}", e)
-                ))
-            })?;
+    /// Run `action` to completion (for callers without an event loop)
+    pub fn run(&self, action: &ServiceAction, timeout: Duration) -> Result<UnitState> {
+        let job = self.submit(action)?;
+        while self.job_state(&job)? != JobState::Done {
+            if job.submitted_at.elapsed() > timeout {
+                return Err(AppError::Service(ServiceError::Timeout));
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
         
-        if output.status.success() {
-            Ok(())
+        let state = self.unit_state()?;
+        if state.satisfies(action) {
+            Ok(state)
         } else {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            Err(AppError::Service(ServiceError::OperationFailed(
-                format!("Restart failed: {}", error_msg)
-            )))
+            Err(AppError::Service(ServiceError::OperationFailed(job_failure(&state))))
         }
     }
-    
-    pub fn reload(&self) -> Result<()> {
-        let output = Command::new("sudo")
-            .args(["systemctl", "reload", &self.service_name])
-            .output()
-            .map_err(|e| {
-                AppError::Service(ServiceError::OperationFailed(
-                    format!("Failed to reload service: {}", e)
-                ))
-            })?;
-        
-        if output.status.success() {
-            Ok(())
-        } else {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            Err(AppError::Service(ServiceError::OperationFailed(
-                format!("Reload failed: {}", error_msg)
-            )))
+}
+
+/// Run init commands in order, stopping at the first failure
+fn run_commands(commands: Vec<Command>) -> std::result::Result<(), String> {
+    for mut command in commands {
+        let program = command.get_program().to_string_lossy().to_string();
+        let output = command.output().map_err(|e| format!("{}: {}", program, e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            let detail = if !stderr.is_empty() { stderr } else if !stdout.is_empty() { stdout } else { output.status.to_string() };
+            return Err(format!("{}: {}", program, detail));
         }
     }
-    
-    // Removed unused get_uptime method
-}
\ No newline at end of file
+    Ok(())
+}
+
+/// Why a finished job did not leave the unit where it should be
+pub fn job_failure(state: &UnitState) -> String {
+    match state.failure {
+        Some(ref failure) => format!("unit is {} ({}): {}", state.active_state, state.sub_state, failure),
+        None => format!("unit is {} ({})", state.active_state, state.sub_state),
+    }
+}
@@ -0,0 +1,124 @@
+use zbus::blocking::{Connection, Proxy, ProxyBuilder};
+use zbus::zvariant::OwnedObjectPath;
+use zbus::CacheProperties;
+
+use crate::app::ServiceAction;
+use crate::services::service_backend::{JobState, UnitState};
+use crate::utils::errors::{AppError, ServiceError, Result};
+
+const DESTINATION: &str = "org.freedesktop.systemd1";
+const MANAGER_PATH: &str = "/org/freedesktop/systemd1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.systemd1.Manager";
+const UNIT_INTERFACE: &str = "org.freedesktop.systemd1.Unit";
+const SERVICE_INTERFACE: &str = "org.freedesktop.systemd1.Service";
+
+/// Entry of Manager.ListJobs: id, unit, job type, state, job path, unit path
+type JobListing = (u32, String, String, String, OwnedObjectPath, OwnedObjectPath);
+
+/// Controls one unit through org.freedesktop.systemd1.
+///
+/// Works on whatever bus it is given, so tests can serve a stand-in manager
+/// on a session bus instead of touching the real systemd.
+pub struct SystemdController {
+    connection: Connection,
+    unit: String,
+}
+
+impl SystemdController {
+    pub fn system(service_name: &str) -> Result<Self> {
+        let connection = Connection::system().map_err(|e| bus_error("Cannot connect to the system bus", e))?;
+        Ok(Self::with_connection(connection, service_name))
+    }
+    
+    pub fn with_connection(connection: Connection, service_name: &str) -> Self {
+        let unit = if service_name.contains('.') {
+            service_name.to_string()
+        } else {
+            format!("{}.service", service_name)
+        };
+        Self { connection, unit }
+    }
+    
+    pub fn unit_name(&self) -> &str {
+        &self.unit
+    }
+    
+    pub fn unit_state(&self) -> Result<UnitState> {
+        let unit_path: OwnedObjectPath = self.manager()?.call("LoadUnit", &(self.unit.as_str(),))
+            .map_err(|e| bus_error(&format!("Cannot load {}", self.unit), e))?;
+        let unit = self.proxy(unit_path.clone(), UNIT_INTERFACE)?;
+        let service = self.proxy(unit_path, SERVICE_INTERFACE)?;
+        
+        let active_state: String = property(&unit, "ActiveState")?;
+        let active_enter: u64 = property(&unit, "ActiveEnterTimestamp")?;
+        let memory: u64 = property(&service, "MemoryCurrent")?;
+        let result: String = property(&service, "Result")?;
+        let exit_status: i32 = property(&service, "ExecMainStatus")?;
+        
+        Ok(UnitState {
+            active_state,
+            sub_state: property(&unit, "SubState")?,
+            main_pid: property(&service, "MainPID")?,
+            active_since: (active_enter > 0)
+                .then(|| chrono::DateTime::from_timestamp_micros(active_enter as i64))
+                .flatten()
+                .map(|t| t.with_timezone(&chrono::Local)),
+            // systemd reports u64::MAX when memory accounting is off
+            memory_bytes: (memory != u64::MAX).then_some(memory),
+            restarts: Some(property(&service, "NRestarts")?),
+            failure: (result != "success").then(|| format!("{} (exit status {})", result, exit_status)),
+        })
+    }
+    
+    /// Queue a job and return its object path; poll it with `job_state`
+    pub fn submit(&self, action: &ServiceAction) -> Result<OwnedObjectPath> {
+        let method = match action {
+            ServiceAction::Start => "StartUnit",
+            ServiceAction::Stop => "StopUnit",
+            ServiceAction::Restart => "RestartUnit",
+            ServiceAction::Reload => "ReloadUnit",
+        };
+        let path: OwnedObjectPath = self.manager()?.call(method, &(self.unit.as_str(), "replace"))
+            .map_err(|e| AppError::Service(ServiceError::OperationFailed(format!("{} {}: {}", method, self.unit, e))))?;
+        log::info!("Queued systemd job {} ({} {})", path.as_str(), method, self.unit);
+        Ok(path)
+    }
+    
+    pub fn job_state(&self, job: &OwnedObjectPath) -> Result<JobState> {
+        let jobs: Vec<JobListing> = self.manager()?.call("ListJobs", &())
+            .map_err(|e| bus_error("Cannot list systemd jobs", e))?;
+        
+        Ok(match jobs.iter().find(|listing| listing.4 == *job) {
+            Some(listing) if listing.3 == "waiting" => JobState::Waiting,
+            Some(_) => JobState::Running,
+            None => JobState::Done,
+        })
+    }
+    
+    fn manager(&self) -> Result<Proxy<'static>> {
+        self.proxy(OwnedObjectPath::try_from(MANAGER_PATH).expect("valid object path"), MANAGER_INTERFACE)
+    }
+    
+    fn proxy(&self, path: OwnedObjectPath, interface: &'static str) -> Result<Proxy<'static>> {
+        // Uncached - every refresh must see the unit as it is now
+        ProxyBuilder::new_bare(&self.connection)
+            .destination(DESTINATION)
+            .and_then(|builder| builder.path(path))
+            .and_then(|builder| builder.interface(interface))
+            .map(|builder| builder.cache_properties(CacheProperties::No))
+            .and_then(|builder| builder.build())
+            .map_err(|e| bus_error("Cannot create systemd proxy", e))
+    }
+}
+
+fn property<T>(proxy: &Proxy<'_>, name: &str) -> Result<T>
+where
+    T: TryFrom<zbus::zvariant::OwnedValue>,
+    T::Error: Into<zbus::Error>,
+{
+    proxy.get_property(name).map_err(|e| bus_error(&format!("Cannot read {}", name), e))
+}
+
+fn bus_error(context: &str, e: zbus::Error) -> AppError {
+    AppError::Service(ServiceError::CommunicationError(format!("{}: {}", context, e)))
+}
@@ -0,0 +1,140 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::app::{BannedIP, JailConfig, JailState};
+use crate::services::ban_query::unban_time;
+use crate::utils::cidr::Cidr;
+
+/// Sortable columns of the dashboard's Banned IPs table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BannedIpColumn {
+    #[default]
+    Ip,
+    Jail,
+    BannedAt,
+    Remaining,  // Also the Unbans At column; permanent bans come last
+}
+
+impl BannedIpColumn {
+    pub const ALL: [BannedIpColumn; 4] = [BannedIpColumn::Ip, BannedIpColumn::Jail, BannedIpColumn::BannedAt, BannedIpColumn::Remaining];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            BannedIpColumn::Ip => "IP address",
+            BannedIpColumn::Jail => "jail",
+            BannedIpColumn::BannedAt => "ban time",
+            BannedIpColumn::Remaining => "remaining time",
+        }
+    }
+}
+
+/// Sortable columns of the dashboard's Jails table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JailColumn {
+    #[default]
+    Status,  // Enabled first, then by name
+    Name,
+    Bans,
+    Failed,
+}
+
+impl JailColumn {
+    pub const ALL: [JailColumn; 4] = [JailColumn::Status, JailColumn::Name, JailColumn::Bans, JailColumn::Failed];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            JailColumn::Status => "status",
+            JailColumn::Name => "name",
+            JailColumn::Bans => "banned count",
+            JailColumn::Failed => "failed count",
+        }
+    }
+}
+
+/// A column and direction; picking the same column again flips the direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SortOrder<C> {
+    pub column: C,
+    pub descending: bool,
+}
+
+impl<C: Copy + PartialEq> SortOrder<C> {
+    pub fn select(&mut self, column: C) {
+        if self.column == column {
+            self.descending = !self.descending;
+        } else {
+            self.column = column;
+            self.descending = false;
+        }
+    }
+    
+    /// The column after the current one in `columns`, ascending
+    pub fn next_column(&mut self, columns: &[C]) {
+        let index = columns.iter().position(|c| *c == self.column).unwrap_or(0);
+        self.column = columns[(index + 1) % columns.len()];
+        self.descending = false;
+    }
+    
+    pub fn reverse(&mut self) {
+        self.descending = !self.descending;
+    }
+    
+    /// "▲"/"▼" next to the header of the sorted column
+    pub fn indicator(&self, column: C) -> &'static str {
+        match (self.column == column, self.descending) {
+            (false, _) => "",
+            (true, false) => " ▲",
+            (true, true) => " ▼",
+        }
+    }
+    
+    fn apply(&self, ordering: Ordering) -> Ordering {
+        if self.descending { ordering.reverse() } else { ordering }
+    }
+}
+
+/// Numeric address order: IPv4 before IPv6, a range right after its network
+/// address, and anything that is not an address (hostnames) last by text
+pub fn compare_ips(a: &str, b: &str) -> Ordering {
+    match (Cidr::parse(a), Cidr::parse(b)) {
+        (Some(a), Some(b)) => a.network().cmp(&b.network()).then_with(|| b.prefix_len().cmp(&a.prefix_len())),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// Ties fall back to address then jail, so the order is the same on every refresh
+pub fn compare_banned_ips(a: &BannedIP, b: &BannedIP, order: SortOrder<BannedIpColumn>) -> Ordering {
+    let primary = match order.column {
+        BannedIpColumn::Ip => Ordering::Equal,
+        BannedIpColumn::Jail => a.jail.cmp(&b.jail),
+        BannedIpColumn::BannedAt => a.ban_time.cmp(&b.ban_time),
+        // None is permanent, which never ends
+        BannedIpColumn::Remaining => match (unban_time(a), unban_time(b)) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (x, y) => x.is_none().cmp(&y.is_none()),
+        },
+    };
+    order.apply(primary
+        .then_with(|| compare_ips(&a.ip, &b.ip))
+        .then_with(|| a.jail.cmp(&b.jail)))
+}
+
+pub fn sort_banned_ips(ips: &mut [BannedIP], order: SortOrder<BannedIpColumn>) {
+    ips.sort_by(|a, b| compare_banned_ips(a, b, order));
+}
+
+/// Counts come from the running jails; configured but stopped jails count as 0
+pub fn sort_jails(jails: &mut [JailConfig], states: &HashMap<String, JailState>, order: SortOrder<JailColumn>) {
+    let count = |jail: &JailConfig, field: fn(&JailState) -> usize| states.get(&jail.name).map_or(0, field);
+    jails.sort_by(|a, b| {
+        let primary = match order.column {
+            JailColumn::Status => b.enabled.cmp(&a.enabled),
+            JailColumn::Name => Ordering::Equal,
+            JailColumn::Bans => count(a, |s| s.banned_count).cmp(&count(b, |s| s.banned_count)),
+            JailColumn::Failed => count(a, |s| s.failed_count).cmp(&count(b, |s| s.failed_count)),
+        };
+        order.apply(primary.then_with(|| a.name.cmp(&b.name)))
+    });
+}
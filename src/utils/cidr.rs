@@ -0,0 +1,79 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// An address range such as 203.0.113.0/24; a bare address is a /32 or /128
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// The range of `prefix_len` bits around `addr`; host bits are cleared
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Option<Self> {
+        let network = match addr {
+            IpAddr::V4(v4) if prefix_len <= 32 => {
+                IpAddr::V4(Ipv4Addr::from(u32::from(v4) & v4_mask(prefix_len)))
+            },
+            IpAddr::V6(v6) if prefix_len <= 128 => {
+                IpAddr::V6(Ipv6Addr::from(u128::from(v6) & v6_mask(prefix_len)))
+            },
+            _ => return None,
+        };
+        Some(Self { network, prefix_len })
+    }
+    
+    /// "203.0.113.7", "203.0.113.0/24" or "2001:db8::/32"; hostnames are not ranges
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        match text.split_once('/') {
+            Some((addr, len)) => Self::new(addr.parse().ok()?, len.parse().ok()?),
+            None => {
+                let addr: IpAddr = text.parse().ok()?;
+                Self::new(addr, if addr.is_ipv4() { 32 } else { 128 })
+            },
+        }
+    }
+    
+    pub fn network(&self) -> IpAddr {
+        self.network
+    }
+    
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+    
+    /// A single address rather than a range
+    pub fn is_host(&self) -> bool {
+        self.prefix_len == if self.network.is_ipv4() { 32 } else { 128 }
+    }
+    
+    /// Addresses in the range, saturating for IPv6 ranges wider than /1
+    pub fn size(&self) -> u128 {
+        let host_bits = if self.network.is_ipv4() { 32 } else { 128 } - self.prefix_len as u32;
+        1u128.checked_shl(host_bits).unwrap_or(u128::MAX)
+    }
+    
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        Self::new(*addr, self.prefix_len).is_some_and(|range| range.network == self.network)
+    }
+    
+    /// The ranges share at least one address
+    pub fn overlaps(&self, other: &Cidr) -> bool {
+        self.contains(&other.network) || other.contains(&self.network)
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+fn v4_mask(prefix_len: u8) -> u32 {
+    u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0)
+}
+
+fn v6_mask(prefix_len: u8) -> u128 {
+    u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0)
+}
@@ -0,0 +1,161 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::{MetadataExt, OpenOptionsExt, PermissionsExt};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use nix::fcntl::{flock, FlockArg};
+use nix::unistd::{fchown, Gid, Uid};
+
+use crate::utils::errors::{AppError, Result};
+
+/// Atomically replace a configuration file.
+///
+/// The content goes to a temp file in the same directory which is fsynced and
+/// renamed over the target, so readers see either the old or the new file.
+/// Mode, owner and SELinux context of an existing file are kept, as are its
+/// line endings and trailing newline. An advisory lock on the directory keeps
+/// concurrent f2b-buxjr instances from interleaving writes. A symlinked path is
+/// written through to the file it points at, so the link itself stays.
+pub fn write_config_file<P: AsRef<Path>>(path: P, content: &str) -> Result<()> {
+    write_atomically(&resolve_symlink(path.as_ref()), content, true)
+}
+
+/// Like `write_config_file`, but for a file that must not exist yet: the
+/// finished temp file is hard-linked into place, which fails with
+/// `AlreadyExists` if anything took the name since the caller looked.
+pub fn create_config_file<P: AsRef<Path>>(path: P, content: &str) -> Result<()> {
+    write_atomically(path.as_ref(), content, false)
+}
+
+fn write_atomically(path: &Path, content: &str, replace: bool) -> Result<()> {
+    let dir = parent_dir(path);
+    let file_name = path.file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| AppError::Internal(format!("Invalid config path: {}", path.display())))?;
+    
+    let dir_handle = File::open(&dir)?;
+    flock(dir_handle.as_raw_fd(), FlockArg::LockExclusive)
+        .map_err(|e| AppError::FileSystem(e.into()))?;
+    
+    let existing = replace.then(|| fs::metadata(path).ok()).flatten();
+    let original = replace.then(|| fs::read_to_string(path).ok()).flatten();
+    let content = match original {
+        Some(ref original) => match_line_endings(content, original),
+        None => ensure_trailing_newline(content),
+    };
+    
+    let temp_path = dir.join(format!(".{}.f2b-buxjr.{}.tmp", file_name, std::process::id()));
+    let result = write_and_rename(path, &temp_path, &content, existing.as_ref(), replace);
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    
+    // The lock is released when dir_handle is dropped
+    result
+}
+
+/// Atomically copy a file over a configuration file (used for restores and rollbacks)
+pub fn copy_config_file<P: AsRef<Path>, Q: AsRef<Path>>(source: P, target: Q) -> Result<()> {
+    let content = fs::read_to_string(source)?;
+    write_config_file(target, &content)
+}
+
+fn write_and_rename(path: &Path, temp_path: &Path, content: &str, existing: Option<&fs::Metadata>, replace: bool) -> Result<()> {
+    let mode = existing.map(|m| m.permissions().mode() & 0o7777).unwrap_or(0o644);
+    
+    let mut temp_file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(mode)
+        .open(temp_path)?;
+    temp_file.write_all(content.as_bytes())?;
+    
+    if let Some(metadata) = existing {
+        // Umask may have stripped bits at creation
+        temp_file.set_permissions(fs::Permissions::from_mode(mode))?;
+        if let Err(e) = fchown(temp_file.as_raw_fd(), Some(Uid::from_raw(metadata.uid())), Some(Gid::from_raw(metadata.gid()))) {
+            log::warn!("Could not preserve owner of {}: {}", path.display(), e);
+        }
+        copy_selinux_context(path, temp_path);
+    }
+    
+    temp_file.sync_all()?;
+    drop(temp_file);
+    
+    if replace {
+        fs::rename(temp_path, path)?;
+    } else {
+        fs::hard_link(temp_path, path)?;
+        fs::remove_file(temp_path)?;
+    }
+    
+    // Make the rename itself durable
+    if let Ok(dir_handle) = File::open(parent_dir(path)) {
+        let _ = dir_handle.sync_all();
+    }
+    
+    log::info!("Wrote {} ({} bytes, mode {:o})", path.display(), content.len(), mode);
+    Ok(())
+}
+
+/// Carry the SELinux label over when SELinux is active
+fn copy_selinux_context(reference: &Path, target: &Path) {
+    if !Path::new("/sys/fs/selinux/enforce").exists() {
+        return;
+    }
+    
+    match std::process::Command::new("chcon")
+        .arg("--reference")
+        .arg(reference)
+        .arg(target)
+        .output() {
+        Ok(output) if output.status.success() => {},
+        Ok(output) => log::warn!("chcon failed for {}: {}", reference.display(), String::from_utf8_lossy(&output.stderr).trim()),
+        Err(e) => log::warn!("Could not run chcon for {}: {}", reference.display(), e),
+    }
+}
+
+/// The file a symlink points at (e.g. jail.local linked into a config
+/// management tree), or the path itself when it is not a link
+fn resolve_symlink(path: &Path) -> PathBuf {
+    let is_link = fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink());
+    if !is_link {
+        return path.to_path_buf();
+    }
+    fs::canonicalize(path).unwrap_or_else(|_| {
+        // A dangling link: create the file it names
+        fs::read_link(path).map(|target| parent_dir(path).join(target)).unwrap_or_else(|_| path.to_path_buf())
+    })
+}
+
+fn parent_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Use the original file's line endings and keep its final newline
+fn match_line_endings(content: &str, original: &str) -> String {
+    let normalized = content.replace("\r\n", "\n");
+    let mut result = if original.contains("\r\n") {
+        normalized.replace('\n', "\r\n")
+    } else {
+        normalized
+    };
+    
+    let newline = if original.contains("\r\n") { "\r\n" } else { "\n" };
+    if original.ends_with('\n') && !result.ends_with('\n') {
+        result.push_str(newline);
+    }
+    result
+}
+
+fn ensure_trailing_newline(content: &str) -> String {
+    if content.is_empty() || content.ends_with('\n') {
+        content.to_string()
+    } else {
+        format!("{}\n", content)
+    }
+}
@@ -0,0 +1,59 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::utils::errors::{AppError, Result};
+
+/// Editor command from $VISUAL, then $EDITOR, falling back to vi.
+/// The value may carry arguments ("code --wait").
+pub fn editor_command() -> Vec<String> {
+    ["VISUAL", "EDITOR"].iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|value| value.split_whitespace().map(|s| s.to_string()).collect::<Vec<_>>())
+        .find(|parts| !parts.is_empty())
+        .unwrap_or_else(|| vec!["vi".to_string()])
+}
+
+/// Edit `content` in the user's editor and return the result.
+///
+/// Works on a private temp copy (mode 0600) named after `file_name` so the
+/// editor picks the right syntax; the real file is never touched here. The
+/// caller must have released the terminal before calling this.
+pub fn edit_in_external_editor(content: &str, file_name: &str) -> Result<String> {
+    let temp_path = temp_copy_path(file_name);
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&temp_path)?;
+    file.write_all(content.as_bytes())?;
+    drop(file);
+    
+    let result = run_editor(&temp_path).and_then(|()| fs::read_to_string(&temp_path).map_err(AppError::from));
+    let _ = fs::remove_file(&temp_path);
+    result
+}
+
+fn run_editor(path: &Path) -> Result<()> {
+    let command = editor_command();
+    log::info!("Launching external editor: {} {}", command.join(" "), path.display());
+    
+    let status = Command::new(&command[0])
+        .args(&command[1..])
+        .arg(path)
+        .status()
+        .map_err(|e| AppError::Internal(format!("could not start '{}': {}", command[0], e)))?;
+    
+    if status.success() {
+        Ok(())
+    } else {
+        Err(AppError::Internal(format!("'{}' exited with {}", command[0], status)))
+    }
+}
+
+fn temp_copy_path(file_name: &str) -> PathBuf {
+    let stamp = chrono::Local::now().format("%H%M%S%3f");
+    std::env::temp_dir().join(format!("f2b-buxjr-{}-{}-{}", std::process::id(), stamp, file_name))
+}
@@ -0,0 +1,65 @@
+/// Syntax classes for fail2ban's INI dialect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IniToken {
+    Plain,
+    Section,        // [sshd]
+    Key,            // bantime
+    Separator,      // = or :
+    Value,
+    Comment,        // # or ; at the start of a line
+    Interpolation,  // %(name)s
+    Tag,            // <HOST>, <ip>, <F-USER>
+}
+
+/// Token class for every character of a line (one entry per char)
+pub fn classify_line(line: &str) -> Vec<IniToken> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens = vec![IniToken::Plain; chars.len()];
+    let start = chars.iter().position(|c| !c.is_whitespace()).unwrap_or(chars.len());
+    
+    match chars.get(start) {
+        None => {},
+        Some('#') | Some(';') => tokens[start..].fill(IniToken::Comment),
+        Some('[') if start == 0 => tokens.fill(IniToken::Section),
+        // Indented lines continue the previous value
+        Some(_) if start > 0 => classify_value(&chars, &mut tokens, start),
+        Some(_) => {
+            if let Some(sep) = chars.iter().position(|&c| c == '=' || c == ':') {
+                tokens[..sep].fill(IniToken::Key);
+                tokens[sep] = IniToken::Separator;
+                classify_value(&chars, &mut tokens, sep + 1);
+            }
+        },
+    }
+    
+    tokens
+}
+
+fn classify_value(chars: &[char], tokens: &mut [IniToken], from: usize) {
+    tokens[from..].fill(IniToken::Value);
+    
+    let mut i = from;
+    while i < chars.len() {
+        let end = if chars[i] == '%' && chars.get(i + 1) == Some(&'(') {
+            find_from(chars, i + 2, ')').filter(|&close| chars.get(close + 1) == Some(&'s')).map(|close| (close + 2, IniToken::Interpolation))
+        } else if chars[i] == '<' {
+            find_from(chars, i + 1, '>')
+                .filter(|&close| close > i + 1 && chars[i + 1..close].iter().all(|c| c.is_alphanumeric() || *c == '_' || *c == '-'))
+                .map(|close| (close + 1, IniToken::Tag))
+        } else {
+            None
+        };
+        
+        match end {
+            Some((end, token)) => {
+                tokens[i..end].fill(token);
+                i = end;
+            },
+            None => i += 1,
+        }
+    }
+}
+
+fn find_from(chars: &[char], from: usize, target: char) -> Option<usize> {
+    chars.iter().skip(from).position(|&c| c == target).map(|p| p + from)
+}
@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::utils::errors::{AppError, ConfigError, Result};
+
+/// Marks the start of the metadata section, searched for from the end of the file
+const METADATA_MARKER: &[u8] = b"\xAB\xCD\xEFMaxMind.com";
+
+/// Pointers chasing pointers, or maps nested deeper than this, mean a broken file
+const MAX_DEPTH: usize = 32;
+
+/// A decoded value from the data section
+#[derive(Debug, Clone, PartialEq)]
+pub enum MmdbValue {
+    String(String),
+    Double(f64),
+    Bytes(Vec<u8>),
+    Uint(u128),
+    Int(i32),
+    Map(BTreeMap<String, MmdbValue>),
+    Array(Vec<MmdbValue>),
+    Bool(bool),
+    Float(f32),
+}
+
+impl MmdbValue {
+    /// Follow map keys, e.g. ["country", "iso_code"]
+    pub fn get(&self, path: &[&str]) -> Option<&MmdbValue> {
+        let mut value = self;
+        for key in path {
+            match value {
+                MmdbValue::Map(map) => value = map.get(*key)?,
+                _ => return None,
+            }
+        }
+        Some(value)
+    }
+    
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            MmdbValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+    
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            MmdbValue::Uint(n) => u64::try_from(*n).ok(),
+            MmdbValue::Int(n) => u64::try_from(*n).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// A MaxMind DB (.mmdb) file held in memory: GeoLite2/GeoIP2 Country, City
+/// and ASN, or any other database in the same format
+pub struct Mmdb {
+    data: Vec<u8>,
+    node_count: usize,
+    record_size: usize,
+    ip_version: u64,
+    database_type: String,
+    tree_size: usize,
+}
+
+impl Mmdb {
+    pub fn open(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::from_bytes(data).map_err(|e| AppError::Config(ConfigError::InvalidFile(
+            format!("{}: {}", path.display(), e)
+        )))
+    }
+    
+    pub fn from_bytes(data: Vec<u8>) -> std::result::Result<Self, String> {
+        let marker = data.windows(METADATA_MARKER.len())
+            .rposition(|window| window == METADATA_MARKER)
+            .ok_or("not a MaxMind DB file (no metadata)")?;
+        let metadata_start = marker + METADATA_MARKER.len();
+        let (metadata, _) = Decoder { data: &data, base: metadata_start }.decode(metadata_start, 0)?;
+        let field = |name: &str| metadata.get(&[name]).and_then(MmdbValue::as_u64)
+            .ok_or(format!("metadata has no {}", name));
+        let node_count = field("node_count")? as usize;
+        let record_size = field("record_size")? as usize;
+        let ip_version = field("ip_version")?;
+        if ![24, 28, 32].contains(&record_size) {
+            return Err(format!("unsupported record size {}", record_size));
+        }
+        // Both come from the file, so a hostile one must not overflow past the bounds check
+        let tree_size = node_count.checked_mul(record_size).map(|n| n / 4);
+        let tree_size = match tree_size.and_then(|size| size.checked_add(16).map(|end| (size, end))) {
+            Some((size, end)) if end <= marker => size,
+            _ => return Err("search tree runs past the end of the file".to_string()),
+        };
+        Ok(Self {
+            database_type: metadata.get(&["database_type"]).and_then(MmdbValue::as_str).unwrap_or_default().to_string(),
+            data,
+            node_count,
+            record_size,
+            ip_version,
+            tree_size,
+        })
+    }
+    
+    /// e.g. "GeoLite2-Country" or "GeoLite2-ASN"
+    pub fn database_type(&self) -> &str {
+        &self.database_type
+    }
+    
+    /// The record for the network containing `addr`
+    pub fn lookup(&self, addr: IpAddr) -> std::result::Result<Option<MmdbValue>, String> {
+        let (bits, len) = match addr {
+            // IPv4 addresses live under ::/96 in an IPv6 tree
+            IpAddr::V4(v4) if self.ip_version == 6 => (u32::from(v4) as u128, 128),
+            IpAddr::V4(v4) => ((u32::from(v4) as u128) << 96, 32),
+            IpAddr::V6(_) if self.ip_version == 4 => return Ok(None),
+            IpAddr::V6(v6) => (u128::from(v6), 128),
+        };
+        
+        let mut node = 0;
+        for i in 0..len {
+            if node >= self.node_count {
+                break;
+            }
+            let bit = (bits >> (127 - i)) & 1;
+            node = self.record(node, bit as usize)?;
+        }
+        
+        if node <= self.node_count {
+            return Ok(None);  // Equal to node_count means no data
+        }
+        let offset = self.tree_size + node - self.node_count;
+        let decoder = Decoder { data: &self.data, base: self.tree_size + 16 };
+        decoder.decode(offset, 0).map(|(value, _)| Some(value))
+    }
+    
+    fn record(&self, node: usize, side: usize) -> std::result::Result<usize, String> {
+        let start = node * self.record_size / 4;
+        let bytes = self.data.get(start..start + self.record_size / 4).ok_or("search tree truncated")?;
+        let be = |slice: &[u8]| slice.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        Ok(match (self.record_size, side) {
+            (24, 0) => be(&bytes[0..3]),
+            (24, _) => be(&bytes[3..6]),
+            (28, 0) => ((bytes[3] as usize & 0xF0) << 20) | be(&bytes[0..3]),
+            (28, _) => ((bytes[3] as usize & 0x0F) << 24) | be(&bytes[4..7]),
+            (_, 0) => be(&bytes[0..4]),
+            (_, _) => be(&bytes[4..8]),
+        })
+    }
+}
+
+/// Reads values from the data section; pointers are relative to `base`
+struct Decoder<'a> {
+    data: &'a [u8],
+    base: usize,
+}
+
+impl Decoder<'_> {
+    /// The value at `offset` and the offset just past it
+    fn decode(&self, offset: usize, depth: usize) -> std::result::Result<(MmdbValue, usize), String> {
+        if depth > MAX_DEPTH {
+            return Err("data section nested too deeply".to_string());
+        }
+        let control = self.byte(offset)?;
+        let mut pos = offset + 1;
+        let mut kind = control >> 5;
+        
+        if kind == 1 {
+            let pointer = self.pointer(control, &mut pos)?;
+            let (value, _) = self.decode(self.base + pointer, depth + 1)?;
+            return Ok((value, pos));
+        }
+        if kind == 0 {
+            kind = 7 + self.byte(pos)?;
+            pos += 1;
+        }
+        
+        let size = match control & 0x1F {
+            29 => 29 + self.uint(pos, 1)? as usize,
+            30 => 285 + self.uint(pos, 2)? as usize,
+            31 => 65_821 + self.uint(pos, 3)? as usize,
+            size => size as usize,
+        };
+        pos += match control & 0x1F { 29 => 1, 30 => 2, 31 => 3, _ => 0 };
+        
+        let value = match kind {
+            2 => MmdbValue::String(String::from_utf8_lossy(self.slice(pos, size)?).into_owned()),
+            3 => MmdbValue::Double(f64::from_bits(self.uint(pos, size)? as u64)),
+            4 => MmdbValue::Bytes(self.slice(pos, size)?.to_vec()),
+            5 | 6 | 9 | 10 => MmdbValue::Uint(self.uint(pos, size)?),
+            8 => MmdbValue::Int(self.uint(pos, size)? as u32 as i32),
+            14 => return Ok((MmdbValue::Bool(size != 0), pos)),
+            15 => MmdbValue::Float(f32::from_bits(self.uint(pos, size)? as u32)),
+            7 => {
+                let mut map = BTreeMap::new();
+                for _ in 0..size {
+                    let (key, next) = self.decode(pos, depth + 1)?;
+                    let (value, next) = self.decode(next, depth + 1)?;
+                    let MmdbValue::String(key) = key else {
+                        return Err(format!("map key at {} is not a string", pos));
+                    };
+                    map.insert(key, value);
+                    pos = next;
+                }
+                return Ok((MmdbValue::Map(map), pos));
+            },
+            11 => {
+                let mut array = Vec::with_capacity(size.min(1024));
+                for _ in 0..size {
+                    let (value, next) = self.decode(pos, depth + 1)?;
+                    array.push(value);
+                    pos = next;
+                }
+                return Ok((MmdbValue::Array(array), pos));
+            },
+            other => return Err(format!("unknown data type {} at {}", other, offset)),
+        };
+        Ok((value, pos + size))
+    }
+    
+    fn pointer(&self, control: u8, pos: &mut usize) -> std::result::Result<usize, String> {
+        let high = (control & 0x07) as usize;
+        let (len, bias) = match (control >> 3) & 0x03 {
+            0 => (1, 0),
+            1 => (2, 2048),
+            2 => (3, 526_336),
+            _ => (4, 0),
+        };
+        let low = self.uint(*pos, len)? as usize;
+        *pos += len;
+        Ok(if len == 4 { low } else { ((high << (8 * len)) | low) + bias })
+    }
+    
+    fn byte(&self, pos: usize) -> std::result::Result<u8, String> {
+        self.data.get(pos).copied().ok_or_else(|| format!("data section truncated at {}", pos))
+    }
+    
+    fn slice(&self, pos: usize, len: usize) -> std::result::Result<&[u8], String> {
+        self.data.get(pos..pos + len).ok_or_else(|| format!("data section truncated at {}", pos))
+    }
+    
+    /// Big-endian unsigned integer of up to 16 bytes
+    fn uint(&self, pos: usize, len: usize) -> std::result::Result<u128, String> {
+        if len > 16 {
+            return Err(format!("integer of {} bytes at {}", len, pos));
+        }
+        Ok(self.slice(pos, len)?.iter().fold(0u128, |acc, b| (acc << 8) | *b as u128))
+    }
+}
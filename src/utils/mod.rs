@@ -1,4 +1,13 @@
 // Utility modules
+pub mod cidr;
+pub mod config_writer;
 pub mod errors;
+pub mod external_editor;
+pub mod ini_syntax;
 pub mod logging;
-pub mod privileges;
\ No newline at end of file
+pub mod mmdb;
+pub mod paths;
+pub mod privileges;
+pub mod settings;
+pub mod text_buffer;
+pub mod text_diff;
\ No newline at end of file
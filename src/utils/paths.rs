@@ -0,0 +1,110 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const DEFAULT_ROOT: &str = "/etc/fail2ban";
+
+/// Where fail2ban-server listens unless told otherwise
+pub const DEFAULT_SOCKET: &str = "/var/run/fail2ban/fail2ban.sock";
+
+/// Log locations tried when no --log-file is given
+const LOG_CANDIDATES: [&str; 3] = [
+    "/var/log/fail2ban.log",
+    "/var/log/fail2ban/fail2ban.log",
+    "/usr/local/var/log/fail2ban.log",
+];
+
+static PATHS: OnceLock<Fail2banPaths> = OnceLock::new();
+
+/// Locations of the managed fail2ban: configuration root, log file and socket.
+///
+/// Set once at startup from the command line (--fail2ban-root, --log-file,
+/// --socket) so a fail2ban inside a container bind mount or a test fixture
+/// directory can be managed; every config path is derived from the root.
+#[derive(Debug, Clone)]
+pub struct Fail2banPaths {
+    root: PathBuf,
+    log_file: Option<PathBuf>,
+    socket: Option<PathBuf>,
+}
+
+impl Default for Fail2banPaths {
+    fn default() -> Self {
+        Self::new(None, None, None)
+    }
+}
+
+impl Fail2banPaths {
+    pub fn new(root: Option<PathBuf>, log_file: Option<PathBuf>, socket: Option<PathBuf>) -> Self {
+        Self {
+            root: root.unwrap_or_else(|| PathBuf::from(DEFAULT_ROOT)),
+            log_file,
+            socket,
+        }
+    }
+    
+    /// Make these the process-wide paths. Only the first call counts;
+    /// returns false if paths were already in use.
+    pub fn install(self) -> bool {
+        PATHS.set(self).is_ok()
+    }
+    
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+    
+    /// A file directly under the root, e.g. "jail.local"
+    pub fn config_file(&self, name: &str) -> PathBuf {
+        self.root.join(name)
+    }
+    
+    pub fn jail_local(&self) -> PathBuf {
+        self.config_file("jail.local")
+    }
+    
+    pub fn jail_conf(&self) -> PathBuf {
+        self.config_file("jail.conf")
+    }
+    
+    pub fn backup_dir(&self) -> PathBuf {
+        self.config_file("backups")
+    }
+    
+    /// The --log-file given, else the first common location that exists
+    pub fn log_file(&self) -> PathBuf {
+        if let Some(ref log_file) = self.log_file {
+            return log_file.clone();
+        }
+        LOG_CANDIDATES.iter()
+            .map(PathBuf::from)
+            .find(|path| path.exists())
+            .unwrap_or_else(|| PathBuf::from(LOG_CANDIDATES[0]))
+    }
+    
+    pub fn socket(&self) -> Option<&Path> {
+        self.socket.as_deref()
+    }
+    
+    /// The --socket given, else fail2ban's default
+    pub fn socket_path(&self) -> PathBuf {
+        self.socket.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_SOCKET))
+    }
+    
+    /// Options pointing fail2ban-client at this configuration and socket
+    pub fn client_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.root != Path::new(DEFAULT_ROOT) {
+            args.push("-c".to_string());
+            args.push(self.root.display().to_string());
+        }
+        if let Some(ref socket) = self.socket {
+            args.push("-s".to_string());
+            args.push(socket.display().to_string());
+        }
+        args
+    }
+}
+
+/// The process-wide paths (defaults if none were installed)
+pub fn fail2ban_paths() -> &'static Fail2banPaths {
+    PATHS.get_or_init(Fail2banPaths::default)
+}
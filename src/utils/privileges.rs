@@ -1,5 +1,8 @@
-use nix::unistd::{getuid, geteuid};
+use std::path::Path;
+
+use nix::unistd::{access, getuid, geteuid, AccessFlags};
 use crate::utils::errors::Result;
+use crate::utils::paths::Fail2banPaths;
 
 pub fn check_privileges() -> Result<PrivilegeStatus> {
     let uid = getuid();
@@ -27,4 +30,77 @@ pub enum PrivilegeStatus {
     User,
 }
 
+/// What this process can reach of the managed fail2ban.
+///
+/// Root and sudo get everything. A regular user (e.g. in the fail2ban or adm
+/// group) may still read the socket or log, which is enough to observe;
+/// anything that changes fail2ban stays disabled for them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Capabilities {
+    pub privileged: bool,   // Ban/unban, editing and service control
+    pub socket: bool,       // fail2ban-client can reach the server
+    pub log: bool,
+    pub config: bool,       // Configuration root readable
+}
+
+impl Capabilities {
+    pub fn full() -> Self {
+        Self {
+            privileged: true,
+            socket: true,
+            log: true,
+            config: true,
+        }
+    }
+    
+    /// Probe access for a non-root user
+    pub fn probe(paths: &Fail2banPaths) -> Self {
+        Self {
+            privileged: false,
+            // Connecting to a unix socket needs write permission on it
+            socket: accessible(&paths.socket_path(), AccessFlags::R_OK | AccessFlags::W_OK),
+            log: accessible(&paths.log_file(), AccessFlags::R_OK),
+            config: accessible(&paths.jail_conf(), AccessFlags::R_OK) || accessible(&paths.jail_local(), AccessFlags::R_OK),
+        }
+    }
+    
+    pub fn for_status(status: PrivilegeStatus, paths: &Fail2banPaths) -> Self {
+        match status {
+            PrivilegeStatus::Root | PrivilegeStatus::Sudo => Self::full(),
+            PrivilegeStatus::User => Self::probe(paths),
+        }
+    }
+    
+    pub fn read_only(&self) -> bool {
+        !self.privileged
+    }
+    
+    /// Enough to be useful: live jail data or the ban log
+    pub fn can_observe(&self) -> bool {
+        self.socket || self.log
+    }
+    
+    /// Human-readable list of what is not available
+    pub fn missing(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if !self.privileged {
+            missing.push("ban/unban, editing and service control (root)");
+        }
+        if !self.socket {
+            missing.push("fail2ban socket (live jail status)");
+        }
+        if !self.log {
+            missing.push("fail2ban log (ban history)");
+        }
+        if !self.config {
+            missing.push("configuration files");
+        }
+        missing
+    }
+}
+
+fn accessible(path: &Path, mode: AccessFlags) -> bool {
+    access(path, mode).is_ok()
+}
+
 // Unused privilege utility methods removed
\ No newline at end of file
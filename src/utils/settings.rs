@@ -0,0 +1,191 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::utils::errors::{AppError, ConfigError, Result};
+
+/// f2b-buxjr's own settings, as opposed to fail2ban's configuration
+pub const DEFAULT_SETTINGS_FILE: &str = "/etc/f2b-buxjr/config.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    #[serde(default)]
+    pub geoip: GeoIpSettings,
+    #[serde(default)]
+    pub rdns: RdnsSettings,
+}
+
+impl Settings {
+    /// A missing file means defaults; an unreadable or invalid one is an error
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| AppError::Config(ConfigError::InvalidFile(
+            format!("{}: {}", path.display(), e)
+        )))
+    }
+    
+    pub fn parse(text: &str) -> std::result::Result<Self, String> {
+        let settings: Self = toml::from_str(text).map_err(|e| e.to_string())?;
+        for rule in &settings.notifications.rules {
+            rule.validate()?;
+        }
+        settings.rdns.validate()?;
+        Ok(settings)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationSettings {
+    #[serde(default = "default_syslog_socket")]
+    pub syslog_socket: String,
+    #[serde(default)]
+    pub rules: Vec<NotificationRule>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            syslog_socket: default_syslog_socket(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// Local country/ASN databases; none means no enrichment
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GeoIpSettings {
+    #[serde(default)]
+    pub databases: Vec<PathBuf>,   // MaxMind .mmdb or IP2Location LITE .csv
+}
+
+/// Reverse DNS names for banned addresses; off unless enabled
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RdnsSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_rdns_command")]
+    pub command: String,           // Gets the address as its last argument
+    #[serde(default = "default_rdns_workers")]
+    pub workers: usize,            // Lookups running at once
+    #[serde(default = "default_rdns_timeout")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_rdns_ttl")]
+    pub ttl_secs: u64,             // How long names and failures are cached
+}
+
+impl Default for RdnsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            command: default_rdns_command(),
+            workers: default_rdns_workers(),
+            timeout_ms: default_rdns_timeout(),
+            ttl_secs: default_rdns_ttl(),
+        }
+    }
+}
+
+impl RdnsSettings {
+    fn validate(&self) -> std::result::Result<(), String> {
+        if self.command.trim().is_empty() {
+            return Err("rdns: command must not be empty".to_string());
+        }
+        if self.workers == 0 {
+            return Err("rdns: workers must be at least 1".to_string());
+        }
+        if self.timeout_ms == 0 {
+            return Err("rdns: timeout_ms must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleEvent {
+    Ban,
+    Unban,
+    Any,
+}
+
+/// "jail=sshd, more than 20 bans in 5 min" and what to do about it
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationRule {
+    pub name: String,
+    pub jail: Option<String>,      // Any jail when unset
+    #[serde(default = "default_event")]
+    pub event: RuleEvent,
+    #[serde(default = "default_threshold")]
+    pub threshold: usize,          // Fires on more distinct addresses than this within the window
+    #[serde(default = "default_window")]
+    pub window_secs: u64,
+    #[serde(default = "default_cooldown")]
+    pub cooldown_secs: u64,        // Quiet time after the rule fired
+    pub webhook: Option<String>,   // Slack/Mattermost incoming webhook URL
+    pub command: Option<String>,   // Run with sh -c and F2B_* variables
+    #[serde(default)]
+    pub syslog: bool,
+}
+
+impl NotificationRule {
+    fn validate(&self) -> std::result::Result<(), String> {
+        if self.window_secs == 0 {
+            return Err(format!("rule '{}': window_secs must be at least 1", self.name));
+        }
+        if self.webhook.is_none() && self.command.is_none() && !self.syslog {
+            return Err(format!("rule '{}': needs a webhook, command or syslog = true", self.name));
+        }
+        if let Some(ref url) = self.webhook {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(format!("rule '{}': webhook must be an http(s) URL", self.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn default_syslog_socket() -> String {
+    "/dev/log".to_string()
+}
+
+fn default_event() -> RuleEvent {
+    RuleEvent::Ban
+}
+
+fn default_threshold() -> usize {
+    0
+}
+
+fn default_window() -> u64 {
+    300
+}
+
+fn default_cooldown() -> u64 {
+    60
+}
+
+fn default_rdns_command() -> String {
+    "getent hosts".to_string()
+}
+
+fn default_rdns_workers() -> usize {
+    4
+}
+
+fn default_rdns_timeout() -> u64 {
+    2000
+}
+
+fn default_rdns_ttl() -> u64 {
+    3600
+}
@@ -0,0 +1,569 @@
+use regex::Regex;
+
+/// Undo history is capped so long editing sessions don't grow without bound
+const MAX_UNDO_STEPS: usize = 200;
+
+/// Cursor position; `col` counts characters, not bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, col: usize) -> Self {
+        Self { line, col }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Snapshot {
+    lines: Vec<String>,
+    cursor: Position,
+}
+
+/// Consecutive edits of the same kind are undone as one step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    Insert,
+    Delete,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    Find,    // Incremental plain-text search
+    Replace, // Regex search and replace
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchField {
+    Query,
+    Replacement,
+}
+
+/// Search prompt shown in the editor footer
+#[derive(Debug, Clone)]
+pub struct SearchState {
+    pub mode: SearchMode,
+    pub query: String,
+    pub replacement: String,
+    pub field: SearchField,
+    origin: Position, // Incremental search restarts from here as the query changes
+}
+
+/// Line-based text buffer shared by the jail and config editors
+#[derive(Debug, Clone)]
+pub struct TextBuffer {
+    lines: Vec<String>,
+    cursor: Position,
+    anchor: Option<Position>,       // Other end of the selection
+    preferred_col: Option<usize>,   // Column kept while moving up/down through short lines
+    undo_stack: Vec<Snapshot>,
+    redo_stack: Vec<Snapshot>,
+    last_edit: EditKind,
+    last_query: String,
+    pub scroll_offset: usize,
+    pub search: Option<SearchState>,
+}
+
+impl Default for TextBuffer {
+    fn default() -> Self {
+        Self::from_text("")
+    }
+}
+
+impl TextBuffer {
+    pub fn from_text(text: &str) -> Self {
+        Self {
+            lines: text.split('\n').map(|l| l.to_string()).collect(),
+            cursor: Position::default(),
+            anchor: None,
+            preferred_col: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            last_edit: EditKind::Other,
+            last_query: String::new(),
+            scroll_offset: 0,
+            search: None,
+        }
+    }
+    
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+    
+    /// Replace the whole content as one undoable step (merges, reloads)
+    pub fn set_text(&mut self, text: &str) {
+        self.record(EditKind::Other);
+        self.lines = text.split('\n').map(|l| l.to_string()).collect();
+        self.anchor = None;
+        self.cursor = self.clamp(self.cursor);
+    }
+    
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+    
+    pub fn cursor(&self) -> Position {
+        self.cursor
+    }
+    
+    /// Ordered, non-empty selection range
+    pub fn selection(&self) -> Option<(Position, Position)> {
+        let anchor = self.anchor?;
+        if anchor == self.cursor {
+            return None;
+        }
+        Some((anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+    
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection()?;
+        Some(self.text_between(start, end))
+    }
+    
+    fn line_len(&self, line: usize) -> usize {
+        self.lines[line].chars().count()
+    }
+    
+    fn clamp(&self, pos: Position) -> Position {
+        let line = pos.line.min(self.lines.len() - 1);
+        Position::new(line, pos.col.min(self.line_len(line)))
+    }
+    
+    fn text_between(&self, start: Position, end: Position) -> String {
+        if start.line == end.line {
+            return char_slice(&self.lines[start.line], start.col, end.col).to_string();
+        }
+        let mut parts = vec![char_slice(&self.lines[start.line], start.col, usize::MAX).to_string()];
+        parts.extend(self.lines[start.line + 1..end.line].iter().cloned());
+        parts.push(char_slice(&self.lines[end.line], 0, end.col).to_string());
+        parts.join("\n")
+    }
+    
+    // --- Undo history ---
+    
+    fn record(&mut self, kind: EditKind) {
+        if kind == EditKind::Other || kind != self.last_edit {
+            self.undo_stack.push(Snapshot { lines: self.lines.clone(), cursor: self.cursor });
+            if self.undo_stack.len() > MAX_UNDO_STEPS {
+                self.undo_stack.remove(0);
+            }
+        }
+        self.redo_stack.clear();
+        self.last_edit = kind;
+        self.preferred_col = None;
+    }
+    
+    pub fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.undo_stack.pop() else {
+            return false;
+        };
+        self.redo_stack.push(Snapshot { lines: std::mem::replace(&mut self.lines, snapshot.lines), cursor: self.cursor });
+        self.cursor = snapshot.cursor;
+        self.after_history_step();
+        true
+    }
+    
+    pub fn redo(&mut self) -> bool {
+        let Some(snapshot) = self.redo_stack.pop() else {
+            return false;
+        };
+        self.undo_stack.push(Snapshot { lines: std::mem::replace(&mut self.lines, snapshot.lines), cursor: self.cursor });
+        self.cursor = snapshot.cursor;
+        self.after_history_step();
+        true
+    }
+    
+    fn after_history_step(&mut self) {
+        self.anchor = None;
+        self.preferred_col = None;
+        self.last_edit = EditKind::Other;
+        self.cursor = self.clamp(self.cursor);
+    }
+    
+    // --- Editing ---
+    
+    /// Remove the selected text, if any; the caller has already recorded undo
+    fn remove_selection(&mut self) -> bool {
+        let Some((start, end)) = self.selection() else {
+            self.anchor = None;
+            return false;
+        };
+        let tail = char_slice(&self.lines[end.line], end.col, usize::MAX).to_string();
+        let head = char_slice(&self.lines[start.line], 0, start.col).to_string();
+        self.lines.splice(start.line..=end.line, std::iter::once(head + &tail));
+        self.cursor = start;
+        self.anchor = None;
+        true
+    }
+    
+    pub fn insert_char(&mut self, c: char) {
+        if self.selection().is_some() {
+            self.record(EditKind::Other);
+            self.remove_selection();
+        } else {
+            self.record(EditKind::Insert);
+        }
+        let idx = byte_index(&self.lines[self.cursor.line], self.cursor.col);
+        self.lines[self.cursor.line].insert(idx, c);
+        self.cursor.col += 1;
+    }
+    
+    pub fn insert_newline(&mut self) {
+        self.insert_str("\n");
+    }
+    
+    /// Insert text that may span several lines (paste)
+    pub fn insert_str(&mut self, text: &str) {
+        self.record(EditKind::Other);
+        self.remove_selection();
+        
+        let line = &self.lines[self.cursor.line];
+        let idx = byte_index(line, self.cursor.col);
+        let tail = line[idx..].to_string();
+        let head = line[..idx].to_string();
+        
+        let mut new_lines: Vec<String> = text.split('\n').map(|l| l.to_string()).collect();
+        let last = new_lines.len() - 1;
+        let end_col = if last == 0 { self.cursor.col } else { 0 } + new_lines[last].chars().count();
+        new_lines[0] = head + &new_lines[0];
+        new_lines[last].push_str(&tail);
+        
+        let end_line = self.cursor.line + last;
+        self.lines.splice(self.cursor.line..=self.cursor.line, new_lines);
+        self.cursor = Position::new(end_line, end_col);
+    }
+    
+    pub fn backspace(&mut self) {
+        if self.selection().is_some() {
+            self.record(EditKind::Other);
+            self.remove_selection();
+            return;
+        }
+        self.anchor = None;
+        if self.cursor.col > 0 {
+            self.record(EditKind::Delete);
+            let idx = byte_index(&self.lines[self.cursor.line], self.cursor.col - 1);
+            self.lines[self.cursor.line].remove(idx);
+            self.cursor.col -= 1;
+        } else if self.cursor.line > 0 {
+            self.record(EditKind::Delete);
+            let line = self.lines.remove(self.cursor.line);
+            self.cursor.line -= 1;
+            self.cursor.col = self.line_len(self.cursor.line);
+            self.lines[self.cursor.line].push_str(&line);
+        }
+    }
+    
+    pub fn delete(&mut self) {
+        if self.selection().is_some() {
+            self.record(EditKind::Other);
+            self.remove_selection();
+            return;
+        }
+        self.anchor = None;
+        if self.cursor.col < self.line_len(self.cursor.line) {
+            self.record(EditKind::Delete);
+            let idx = byte_index(&self.lines[self.cursor.line], self.cursor.col);
+            self.lines[self.cursor.line].remove(idx);
+        } else if self.cursor.line + 1 < self.lines.len() {
+            self.record(EditKind::Delete);
+            let next = self.lines.remove(self.cursor.line + 1);
+            self.lines[self.cursor.line].push_str(&next);
+        }
+    }
+    
+    /// Remove the selection and return it for the clipboard
+    pub fn cut(&mut self) -> Option<String> {
+        let text = self.selected_text()?;
+        self.record(EditKind::Other);
+        self.remove_selection();
+        Some(text)
+    }
+    
+    // --- Motions (`select` extends the selection instead of clearing it) ---
+    
+    fn begin_motion(&mut self, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.last_edit = EditKind::Other;
+    }
+    
+    pub fn move_left(&mut self, select: bool) {
+        self.begin_motion(select);
+        self.preferred_col = None;
+        if self.cursor.col > 0 {
+            self.cursor.col -= 1;
+        } else if self.cursor.line > 0 {
+            self.cursor.line -= 1;
+            self.cursor.col = self.line_len(self.cursor.line);
+        }
+    }
+    
+    pub fn move_right(&mut self, select: bool) {
+        self.begin_motion(select);
+        self.preferred_col = None;
+        if self.cursor.col < self.line_len(self.cursor.line) {
+            self.cursor.col += 1;
+        } else if self.cursor.line + 1 < self.lines.len() {
+            self.cursor.line += 1;
+            self.cursor.col = 0;
+        }
+    }
+    
+    /// Move up (negative) or down by a number of lines, keeping the column where possible
+    pub fn move_lines(&mut self, delta: isize, select: bool) {
+        self.begin_motion(select);
+        let col = *self.preferred_col.get_or_insert(self.cursor.col);
+        let line = self.cursor.line.saturating_add_signed(delta).min(self.lines.len() - 1);
+        self.cursor = Position::new(line, col.min(self.line_len(line)));
+    }
+    
+    pub fn move_home(&mut self, select: bool) {
+        self.begin_motion(select);
+        self.preferred_col = None;
+        // First press goes to the indentation, second to column 0
+        let indent = self.lines[self.cursor.line].chars().take_while(|c| c.is_whitespace()).count();
+        self.cursor.col = if self.cursor.col == indent { 0 } else { indent };
+    }
+    
+    pub fn move_end(&mut self, select: bool) {
+        self.begin_motion(select);
+        self.preferred_col = None;
+        self.cursor.col = self.line_len(self.cursor.line);
+    }
+    
+    pub fn move_to_start(&mut self, select: bool) {
+        self.begin_motion(select);
+        self.preferred_col = None;
+        self.cursor = Position::default();
+    }
+    
+    pub fn move_to_end(&mut self, select: bool) {
+        self.begin_motion(select);
+        self.preferred_col = None;
+        let line = self.lines.len() - 1;
+        self.cursor = Position::new(line, self.line_len(line));
+    }
+    
+    pub fn word_left(&mut self, select: bool) {
+        self.begin_motion(select);
+        self.preferred_col = None;
+        if self.cursor.col == 0 {
+            if self.cursor.line > 0 {
+                self.cursor.line -= 1;
+                self.cursor.col = self.line_len(self.cursor.line);
+            }
+            return;
+        }
+        let chars: Vec<char> = self.lines[self.cursor.line].chars().collect();
+        let mut col = self.cursor.col;
+        while col > 0 && chars[col - 1].is_whitespace() {
+            col -= 1;
+        }
+        if col > 0 {
+            let class = char_class(chars[col - 1]);
+            while col > 0 && char_class(chars[col - 1]) == class {
+                col -= 1;
+            }
+        }
+        self.cursor.col = col;
+    }
+    
+    pub fn word_right(&mut self, select: bool) {
+        self.begin_motion(select);
+        self.preferred_col = None;
+        let chars: Vec<char> = self.lines[self.cursor.line].chars().collect();
+        if self.cursor.col >= chars.len() {
+            if self.cursor.line + 1 < self.lines.len() {
+                self.cursor.line += 1;
+                self.cursor.col = 0;
+            }
+            return;
+        }
+        let mut col = self.cursor.col;
+        let class = char_class(chars[col]);
+        while col < chars.len() && char_class(chars[col]) == class {
+            col += 1;
+        }
+        while col < chars.len() && chars[col].is_whitespace() {
+            col += 1;
+        }
+        self.cursor.col = col;
+    }
+    
+    pub fn select_all(&mut self) {
+        self.move_to_start(false);
+        self.move_to_end(true);
+    }
+    
+    /// Keep the cursor inside a viewport of `height` lines
+    pub fn ensure_visible(&mut self, height: usize) {
+        let height = height.max(1);
+        if self.cursor.line < self.scroll_offset {
+            self.scroll_offset = self.cursor.line;
+        } else if self.cursor.line >= self.scroll_offset + height {
+            self.scroll_offset = self.cursor.line + 1 - height;
+        }
+    }
+    
+    // --- Search and replace ---
+    
+    pub fn open_search(&mut self, mode: SearchMode) {
+        let query = self.selected_text()
+            .filter(|t| !t.contains('\n'))
+            .unwrap_or_else(|| self.last_query.clone());
+        self.search = Some(SearchState {
+            mode,
+            query,
+            replacement: String::new(),
+            field: SearchField::Query,
+            origin: self.cursor,
+        });
+    }
+    
+    pub fn close_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            if !search.query.is_empty() {
+                self.last_query = search.query;
+            }
+        }
+    }
+    
+    /// Re-run the incremental search from where it started
+    pub fn search_update(&mut self) -> bool {
+        let Some(origin) = self.search.as_ref().map(|s| s.origin) else {
+            return false;
+        };
+        self.find_from(origin, true)
+    }
+    
+    /// Jump to the next (or previous) match of the current or last query
+    pub fn search_next(&mut self, forward: bool) -> bool {
+        let from = match (forward, self.selection()) {
+            (true, Some((_, end))) => end,
+            (false, Some((start, _))) => start,
+            _ => self.cursor,
+        };
+        self.find_from(from, forward)
+    }
+    
+    fn find_from(&mut self, from: Position, forward: bool) -> bool {
+        let ranges = self.all_matches();
+        let found = if forward {
+            ranges.iter().find(|(start, _)| *start >= from).or(ranges.first())
+        } else {
+            ranges.iter().rev().find(|(start, _)| *start < from).or(ranges.last())
+        };
+        match found {
+            Some(&(start, end)) => {
+                self.anchor = Some(start);
+                self.cursor = end;
+                self.preferred_col = None;
+                true
+            },
+            None => {
+                self.anchor = None;
+                false
+            }
+        }
+    }
+    
+    /// Replace mode searches by regex; find mode (and F3 after closing the prompt) by plain text
+    fn search_regex(&self) -> Option<Regex> {
+        match self.search {
+            Some(ref search) if search.mode == SearchMode::Replace => {
+                if search.query.is_empty() {
+                    None
+                } else {
+                    Regex::new(&search.query).ok()
+                }
+            },
+            Some(ref search) => literal_regex(&search.query),
+            None => literal_regex(&self.last_query),
+        }
+    }
+    
+    /// Match ranges (in characters) on one line, highlighted while the prompt is open
+    pub fn line_matches(&self, line: usize) -> Vec<(usize, usize)> {
+        if self.search.is_none() {
+            return Vec::new();
+        }
+        match self.search_regex() {
+            Some(regex) => char_ranges(&regex, &self.lines[line]),
+            None => Vec::new(),
+        }
+    }
+    
+    fn all_matches(&self) -> Vec<(Position, Position)> {
+        let Some(regex) = self.search_regex() else {
+            return Vec::new();
+        };
+        self.lines.iter().enumerate()
+            .flat_map(|(line, text)| {
+                char_ranges(&regex, text).into_iter()
+                    .map(move |(start, end)| (Position::new(line, start), Position::new(line, end)))
+            })
+            .collect()
+    }
+    
+    /// Regex replace of every match in the buffer as a single undo step
+    pub fn replace_all(&mut self) -> std::result::Result<usize, regex::Error> {
+        let Some(search) = self.search.clone() else {
+            return Ok(0);
+        };
+        if search.query.is_empty() {
+            return Ok(0);
+        }
+        let regex = Regex::new(&search.query)?;
+        let text = self.text();
+        let count = regex.find_iter(&text).filter(|m| !m.is_empty()).count();
+        if count > 0 {
+            let replaced = regex.replace_all(&text, search.replacement.as_str()).into_owned();
+            self.set_text(&replaced);
+        }
+        Ok(count)
+    }
+}
+
+/// Smart case: an all-lowercase query matches any case
+fn literal_regex(query: &str) -> Option<Regex> {
+    if query.is_empty() {
+        return None;
+    }
+    let flags = if query.chars().any(|c| c.is_uppercase()) { "" } else { "(?i)" };
+    Regex::new(&format!("{}{}", flags, regex::escape(query))).ok()
+}
+
+fn char_ranges(regex: &Regex, text: &str) -> Vec<(usize, usize)> {
+    regex.find_iter(text)
+        .filter(|m| !m.is_empty())
+        .map(|m| (text[..m.start()].chars().count(), text[..m.end()].chars().count()))
+        .collect()
+}
+
+fn byte_index(text: &str, col: usize) -> usize {
+    text.char_indices().nth(col).map(|(i, _)| i).unwrap_or(text.len())
+}
+
+fn char_slice(text: &str, start: usize, end: usize) -> &str {
+    let start = byte_index(text, start);
+    let end = if end == usize::MAX { text.len() } else { byte_index(text, end) };
+    &text[start..end.max(start)]
+}
+
+/// Word motions stop where the character class changes
+fn char_class(c: char) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
@@ -0,0 +1,201 @@
+use similar::{DiffOp, DiffTag, TextDiff};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Header,
+    Hunk,
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+}
+
+impl DiffLine {
+    fn new(kind: DiffLineKind, text: String) -> Self {
+        Self { kind, text }
+    }
+}
+
+/// Line-based unified diff; empty when the texts are identical
+pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str, context: usize) -> Vec<DiffLine> {
+    let diff = TextDiff::from_lines(old, new);
+    let groups = diff.grouped_ops(context);
+    if groups.is_empty() {
+        return Vec::new();
+    }
+    
+    let mut lines = vec![
+        DiffLine::new(DiffLineKind::Header, format!("--- {}", old_name)),
+        DiffLine::new(DiffLineKind::Header, format!("+++ {}", new_name)),
+    ];
+    
+    for group in groups {
+        let (first, last) = (&group[0], &group[group.len() - 1]);
+        let old_start = first.old_range().start;
+        let old_len = last.old_range().end - old_start;
+        let new_start = first.new_range().start;
+        let new_len = last.new_range().end - new_start;
+        lines.push(DiffLine::new(DiffLineKind::Hunk,
+            format!("@@ -{},{} +{},{} @@", old_start + 1, old_len, new_start + 1, new_len)));
+        
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                let (kind, prefix) = match change.tag() {
+                    similar::ChangeTag::Equal => (DiffLineKind::Context, ' '),
+                    similar::ChangeTag::Insert => (DiffLineKind::Added, '+'),
+                    similar::ChangeTag::Delete => (DiffLineKind::Removed, '-'),
+                };
+                let text = change.value().trim_end_matches(['\r', '\n']);
+                lines.push(DiffLine::new(kind, format!("{}{}", prefix, text)));
+            }
+        }
+    }
+    
+    lines
+}
+
+/// Plain-text rendering of a unified diff (for logs and the audit trail)
+pub fn diff_to_string(lines: &[DiffLine]) -> String {
+    lines.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join("\n")
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    pub text: String,
+    pub conflicts: usize,
+}
+
+/// A change to the base: base lines [start, end) replaced by `lines`
+struct Hunk<'a> {
+    start: usize,
+    end: usize,
+    lines: Vec<&'a str>,
+}
+
+fn changes_from_base<'a>(base: &[&'a str], other: &[&'a str]) -> Vec<Hunk<'a>> {
+    let diff = similar::capture_diff_slices(similar::Algorithm::Myers, base, other);
+    let mut hunks: Vec<Hunk> = Vec::new();
+    
+    for op in diff {
+        if op.tag() == DiffTag::Equal {
+            continue;
+        }
+        let (old, new) = match op {
+            DiffOp::Delete { old_index, old_len, new_index } => (old_index..old_index + old_len, new_index..new_index),
+            DiffOp::Insert { old_index, new_index, new_len } => (old_index..old_index, new_index..new_index + new_len),
+            DiffOp::Replace { old_index, old_len, new_index, new_len } => (old_index..old_index + old_len, new_index..new_index + new_len),
+            DiffOp::Equal { .. } => continue,
+        };
+        
+        // Adjacent operations form one hunk
+        if let Some(last) = hunks.last_mut() {
+            if last.end == old.start {
+                last.end = old.end;
+                last.lines.extend_from_slice(&other[new]);
+                continue;
+            }
+        }
+        hunks.push(Hunk { start: old.start, end: old.end, lines: other[new].to_vec() });
+    }
+    
+    hunks
+}
+
+/// Apply the hunks that fall inside base[start, end) to that slice of base
+fn apply_hunks<'a>(base: &[&'a str], hunks: &[&Hunk<'a>], start: usize, end: usize) -> Vec<&'a str> {
+    let mut result = Vec::new();
+    let mut pos = start;
+    for hunk in hunks {
+        result.extend_from_slice(&base[pos..hunk.start]);
+        result.extend_from_slice(&hunk.lines);
+        pos = hunk.end;
+    }
+    result.extend_from_slice(&base[pos..end]);
+    result
+}
+
+/// Line-based three-way merge (diff3 style). Overlapping edits are kept
+/// between `<<<<<<<`/`=======`/`>>>>>>>` markers and counted as conflicts.
+pub fn three_way_merge(base: &str, ours: &str, theirs: &str) -> MergeResult {
+    let base_lines: Vec<&str> = base.lines().collect();
+    let our_lines: Vec<&str> = ours.lines().collect();
+    let their_lines: Vec<&str> = theirs.lines().collect();
+    
+    let our_hunks = changes_from_base(&base_lines, &our_lines);
+    let their_hunks = changes_from_base(&base_lines, &their_lines);
+    
+    let mut output: Vec<&str> = Vec::new();
+    let mut conflicts = 0;
+    let mut pos = 0;
+    let (mut i, mut j) = (0, 0);
+    
+    while i < our_hunks.len() || j < their_hunks.len() {
+        // Start a cluster with whichever hunk comes first
+        let take_ours = match (our_hunks.get(i), their_hunks.get(j)) {
+            (Some(a), Some(b)) => a.start <= b.start,
+            (Some(_), None) => true,
+            _ => false,
+        };
+        let first = if take_ours { &our_hunks[i] } else { &their_hunks[j] };
+        let (start, mut end) = (first.start, first.end);
+        let mut ours_in: Vec<&Hunk> = Vec::new();
+        let mut theirs_in: Vec<&Hunk> = Vec::new();
+        
+        // Grow the cluster while hunks from either side overlap or touch it (adjacent edits conflict, as in git)
+        loop {
+            if let Some(h) = our_hunks.get(i) {
+                if h.start <= end {
+                    end = end.max(h.end);
+                    ours_in.push(h);
+                    i += 1;
+                    continue;
+                }
+            }
+            if let Some(h) = their_hunks.get(j) {
+                if h.start <= end {
+                    end = end.max(h.end);
+                    theirs_in.push(h);
+                    j += 1;
+                    continue;
+                }
+            }
+            break;
+        }
+        
+        output.extend_from_slice(&base_lines[pos..start]);
+        let our_version = apply_hunks(&base_lines, &ours_in, start, end);
+        let their_version = apply_hunks(&base_lines, &theirs_in, start, end);
+        
+        if theirs_in.is_empty() || our_version == their_version {
+            output.extend(our_version);
+        } else if ours_in.is_empty() {
+            output.extend(their_version);
+        } else {
+            conflicts += 1;
+            output.push("<<<<<<< editor");
+            output.extend(our_version);
+            output.push("=======");
+            output.extend(their_version);
+            output.push(">>>>>>> on disk");
+        }
+        pos = end;
+    }
+    output.extend_from_slice(&base_lines[pos..]);
+    
+    let mut text = output.join("\n");
+    if theirs.ends_with('\n') || ours.ends_with('\n') {
+        text.push('\n');
+    }
+    
+    MergeResult { text, conflicts }
+}
+
+/// True if the text still contains unresolved merge markers
+pub fn has_conflict_markers(text: &str) -> bool {
+    text.lines().any(|l| l.starts_with("<<<<<<< ") || l.starts_with(">>>>>>> "))
+}